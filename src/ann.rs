@@ -0,0 +1,293 @@
+//! Approximate nearest-neighbour search over semantic index embeddings.
+//!
+//! An inverted-file (IVF) index: spherical k-means centroids stored in the
+//! `ivf_centroids` table of the semantic index database, and a `cluster` column
+//! on each chunk naming its nearest centroid. `cassio search --semantic` scores
+//! only the chunks in the clusters nearest the query instead of decoding every
+//! embedding in the archive; `--exact` skips the probe for verification.
+//!
+//! `build_index` keeps the index current: freshly embedded chunks get their
+//! `cluster` reset and are assigned to the nearest existing centroid, deleted
+//! chunks simply drop out, and the centroids are retrained once the chunk count
+//! has doubled or halved since the last training.
+//!
+//! # TRADE-OFFS
+//!
+//! - IVF rather than HNSW: a graph index would need its own file format and
+//!   locking beside SQLite, while clusters are one indexed integer column that
+//!   shares the index's transactions and incremental updates.
+//! - Recall is approximate. A chunk whose nearest neighbours straddle cluster
+//!   boundaries can be missed when its cluster is not probed; the probe count
+//!   scales with the cluster count to keep recall high on large archives.
+//! - Archives below `ANN_MIN_CHUNKS` keep no centroids; scoring every chunk is
+//!   already cheap there.
+
+use rusqlite::{Connection, params};
+
+use crate::error::CassioError;
+use crate::index::{decode_embedding, encode_embedding};
+
+/// Below this many chunks, semantic search scores every chunk.
+pub(crate) const ANN_MIN_CHUNKS: usize = 2_000;
+/// Upper bound on vectors held in memory while training centroids.
+const TRAINING_SAMPLE: usize = 20_000;
+const KMEANS_ITERATIONS: usize = 8;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AnnReport {
+    /// Centroids in the index (0 when the archive is below `ANN_MIN_CHUNKS`).
+    pub clusters: usize,
+    /// Chunks assigned to a cluster during this run.
+    pub assigned: usize,
+    pub retrained: bool,
+}
+
+/// Add the IVF table and `chunks.cluster` column to an existing index.
+pub(crate) fn prepare_schema(conn: &Connection) -> Result<(), CassioError> {
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS ivf_centroids (
+            id INTEGER PRIMARY KEY,
+            centroid BLOB NOT NULL
+        );
+        "#,
+    )
+    .map_err(sql_error)?;
+    let has_cluster = conn
+        .prepare("SELECT 1 FROM pragma_table_info('chunks') WHERE name = 'cluster'")
+        .and_then(|mut stmt| stmt.exists(params![]))
+        .map_err(sql_error)?;
+    if !has_cluster {
+        conn.execute_batch(
+            r#"
+            ALTER TABLE chunks ADD COLUMN cluster INTEGER;
+            CREATE INDEX IF NOT EXISTS idx_chunks_cluster ON chunks(cluster);
+            "#,
+        )
+        .map_err(sql_error)?;
+    }
+    Ok(())
+}
+
+/// Train or extend the IVF index after chunks were embedded or deleted.
+pub(crate) fn update_ann_index(
+    conn: &Connection,
+    min_chunks: usize,
+) -> Result<AnnReport, CassioError> {
+    let total: i64 = conn
+        .query_row("SELECT COUNT(*) FROM chunks", params![], |row| row.get(0))
+        .map_err(sql_error)?;
+    let total = total as usize;
+    if total < min_chunks {
+        conn.execute_batch("DELETE FROM ivf_centroids; UPDATE chunks SET cluster = NULL;")
+            .map_err(sql_error)?;
+        return Ok(AnnReport::default());
+    }
+
+    let trained: usize = conn
+        .query_row(
+            "SELECT value FROM metadata WHERE key = 'ivf_trained_chunks'",
+            params![],
+            |row| row.get::<_, String>(0),
+        )
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(0);
+    let mut centroids = load_centroids(conn)?;
+    let retrained = centroids.is_empty() || total >= trained * 2 || total * 2 <= trained;
+    if retrained {
+        centroids = train_centroids(conn, total)?;
+        let tx = conn.unchecked_transaction().map_err(sql_error)?;
+        tx.execute("DELETE FROM ivf_centroids", params![])
+            .map_err(sql_error)?;
+        for (id, centroid) in centroids.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO ivf_centroids(id, centroid) VALUES (?1, ?2)",
+                params![id as i64, encode_embedding(centroid)],
+            )
+            .map_err(sql_error)?;
+        }
+        tx.execute("UPDATE chunks SET cluster = NULL", params![])
+            .map_err(sql_error)?;
+        tx.execute(
+            r#"
+            INSERT INTO metadata(key, value) VALUES ('ivf_trained_chunks', ?1)
+            ON CONFLICT(key) DO UPDATE SET value = excluded.value
+            "#,
+            params![total.to_string()],
+        )
+        .map_err(sql_error)?;
+        tx.commit().map_err(sql_error)?;
+    }
+
+    let assigned = assign_unclustered(conn, &centroids)?;
+    Ok(AnnReport {
+        clusters: centroids.len(),
+        assigned,
+        retrained,
+    })
+}
+
+/// Clusters to score for `query`, nearest first, or `None` when the index has
+/// no centroids and every chunk must be scored.
+pub(crate) fn probe_clusters(
+    conn: &Connection,
+    query: &[f32],
+) -> Result<Option<Vec<i64>>, CassioError> {
+    // Indexes built before IVF support have no centroid table until the next
+    // `cassio index`.
+    let has_ivf = conn
+        .prepare("SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'ivf_centroids'")
+        .and_then(|mut stmt| stmt.exists(params![]))
+        .map_err(sql_error)?;
+    if !has_ivf {
+        return Ok(None);
+    }
+    let centroids = load_centroids(conn)?;
+    if centroids.is_empty() {
+        return Ok(None);
+    }
+    let query = normalized(query);
+    let mut ranked: Vec<(usize, f32)> = centroids
+        .iter()
+        .enumerate()
+        .map(|(id, centroid)| (id, dot(&query, centroid)))
+        .collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+    ranked.truncate(probe_count(centroids.len()));
+    Ok(Some(ranked.into_iter().map(|(id, _)| id as i64).collect()))
+}
+
+/// How many clusters to probe: a tenth of them, never fewer than eight.
+fn probe_count(clusters: usize) -> usize {
+    clusters.div_ceil(10).max(8).min(clusters)
+}
+
+/// `sqrt(n)` clusters, bounded so tiny and huge archives stay sensible.
+fn cluster_count(total: usize) -> usize {
+    ((total as f64).sqrt().round() as usize).clamp(16, 4_096)
+}
+
+fn load_centroids(conn: &Connection) -> Result<Vec<Vec<f32>>, CassioError> {
+    let mut stmt = conn
+        .prepare("SELECT centroid FROM ivf_centroids ORDER BY id")
+        .map_err(sql_error)?;
+    let rows = stmt
+        .query_map(params![], |row| row.get::<_, Vec<u8>>(0))
+        .map_err(sql_error)?;
+    let mut centroids = Vec::new();
+    for row in rows {
+        centroids.push(decode_embedding(&row.map_err(sql_error)?)?);
+    }
+    Ok(centroids)
+}
+
+/// Spherical k-means over an evenly strided sample of the chunk embeddings.
+/// Chunk ids are SHA-256 hashes, so striding in id order is an unbiased sample.
+fn train_centroids(conn: &Connection, total: usize) -> Result<Vec<Vec<f32>>, CassioError> {
+    let stride = total.div_ceil(TRAINING_SAMPLE).max(1);
+    let mut stmt = conn
+        .prepare("SELECT embedding FROM chunks ORDER BY id")
+        .map_err(sql_error)?;
+    let rows = stmt
+        .query_map(params![], |row| row.get::<_, Vec<u8>>(0))
+        .map_err(sql_error)?;
+    let mut sample = Vec::new();
+    for (index, row) in rows.enumerate() {
+        let blob = row.map_err(sql_error)?;
+        if index % stride == 0 {
+            sample.push(normalized(&decode_embedding(&blob)?));
+        }
+    }
+    Ok(kmeans(&sample, cluster_count(total).min(sample.len())))
+}
+
+pub(crate) fn kmeans(sample: &[Vec<f32>], k: usize) -> Vec<Vec<f32>> {
+    if k == 0 {
+        return Vec::new();
+    }
+    let step = sample.len() / k;
+    let mut centroids: Vec<Vec<f32>> = (0..k).map(|i| sample[i * step].clone()).collect();
+    let dims = centroids[0].len();
+    for _ in 0..KMEANS_ITERATIONS {
+        let mut sums = vec![vec![0.0f32; dims]; k];
+        let mut counts = vec![0usize; k];
+        for vector in sample {
+            let nearest = nearest_centroid(&centroids, vector);
+            counts[nearest] += 1;
+            for (sum, value) in sums[nearest].iter_mut().zip(vector) {
+                *sum += value;
+            }
+        }
+        for ((centroid, sum), count) in centroids.iter_mut().zip(sums).zip(counts) {
+            // An empty cluster keeps its previous centroid.
+            if count > 0 {
+                *centroid = normalized(&sum);
+            }
+        }
+    }
+    centroids
+}
+
+fn assign_unclustered(conn: &Connection, centroids: &[Vec<f32>]) -> Result<usize, CassioError> {
+    let Some(dims) = centroids.first().map(Vec::len) else {
+        return Ok(0);
+    };
+    let mut stmt = conn
+        .prepare("SELECT id, embedding FROM chunks WHERE cluster IS NULL")
+        .map_err(sql_error)?;
+    let rows = stmt
+        .query_map(params![], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, Vec<u8>>(1)?))
+        })
+        .map_err(sql_error)?;
+    let mut assignments = Vec::new();
+    for row in rows {
+        let (id, blob) = row.map_err(sql_error)?;
+        let embedding = normalized(&decode_embedding(&blob)?);
+        if embedding.len() == dims {
+            assignments.push((id, nearest_centroid(centroids, &embedding) as i64));
+        }
+    }
+    let tx = conn.unchecked_transaction().map_err(sql_error)?;
+    for (id, cluster) in &assignments {
+        tx.execute(
+            "UPDATE chunks SET cluster = ?1 WHERE id = ?2",
+            params![cluster, id],
+        )
+        .map_err(sql_error)?;
+    }
+    tx.commit().map_err(sql_error)?;
+    Ok(assignments.len())
+}
+
+pub(crate) fn nearest_centroid(centroids: &[Vec<f32>], vector: &[f32]) -> usize {
+    let mut best = (0, f32::NEG_INFINITY);
+    for (index, centroid) in centroids.iter().enumerate() {
+        let score = dot(centroid, vector);
+        if score > best.1 {
+            best = (index, score);
+        }
+    }
+    best.0
+}
+
+pub(crate) fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+pub(crate) fn normalized(values: &[f32]) -> Vec<f32> {
+    let norm = values.iter().map(|v| v * v).sum::<f32>().sqrt();
+    if norm == 0.0 {
+        return values.to_vec();
+    }
+    values.iter().map(|v| v / norm).collect()
+}
+
+fn sql_error(e: rusqlite::Error) -> CassioError {
+    CassioError::Other(format!("ANN index error: {e}"))
+}
+
+#[cfg(test)]
+#[path = "ann_test.rs"]
+mod tests;
@@ -0,0 +1,90 @@
+use super::*;
+
+fn temp_db(label: &str) -> Connection {
+    let path =
+        std::env::temp_dir().join(format!("cassio_ann_{label}_{}.sqlite", std::process::id()));
+    std::fs::remove_file(&path).ok();
+    let conn = Connection::open(&path).unwrap();
+    conn.execute_batch(
+        r#"
+        CREATE TABLE metadata (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+        CREATE TABLE chunks (id TEXT PRIMARY KEY, embedding BLOB NOT NULL);
+        "#,
+    )
+    .unwrap();
+    prepare_schema(&conn).unwrap();
+    conn
+}
+
+/// A unit vector along `axis` with a small per-item wobble.
+fn vector(axis: usize, wobble: usize) -> Vec<f32> {
+    let mut values = vec![0.0f32; 4];
+    values[axis] = 1.0;
+    values[(axis + 1) % 4] = wobble as f32 * 0.01;
+    values
+}
+
+fn insert(conn: &Connection, id: &str, embedding: &[f32]) {
+    conn.execute(
+        "INSERT INTO chunks(id, embedding) VALUES (?1, ?2)",
+        params![id, encode_embedding(embedding)],
+    )
+    .unwrap();
+}
+
+fn cluster_of(conn: &Connection, id: &str) -> Option<i64> {
+    conn.query_row(
+        "SELECT cluster FROM chunks WHERE id = ?1",
+        params![id],
+        |row| row.get(0),
+    )
+    .unwrap()
+}
+
+#[test]
+fn probe_finds_the_query_neighbourhood_and_new_chunks_join_incrementally() {
+    let conn = temp_db("probe");
+    for axis in 0..3 {
+        for wobble in 0..20 {
+            insert(&conn, &format!("{axis}-{wobble:02}"), &vector(axis, wobble));
+        }
+    }
+    let report = update_ann_index(&conn, 10).unwrap();
+    assert!(report.retrained);
+    assert_eq!(report.clusters, 16);
+    assert_eq!(report.assigned, 60);
+
+    let probed = probe_clusters(&conn, &vector(1, 5)).unwrap().unwrap();
+    assert_eq!(probed.len(), 8);
+    for wobble in 0..20 {
+        let cluster = cluster_of(&conn, &format!("1-{wobble:02}")).unwrap();
+        assert!(probed.contains(&cluster), "1-{wobble:02} not probed");
+    }
+
+    insert(&conn, "1-new", &vector(1, 3));
+    let report = update_ann_index(&conn, 10).unwrap();
+    assert!(!report.retrained, "61 chunks do not double the trained 60");
+    assert_eq!(report.assigned, 1);
+    assert_eq!(cluster_of(&conn, "1-new"), cluster_of(&conn, "1-03"));
+}
+
+#[test]
+fn small_and_legacy_indexes_score_every_chunk() {
+    let conn = temp_db("small");
+    insert(&conn, "a", &vector(0, 0));
+    let report = update_ann_index(&conn, 10).unwrap();
+    assert_eq!(report, AnnReport::default());
+    assert!(probe_clusters(&conn, &vector(0, 0)).unwrap().is_none());
+
+    let legacy = Connection::open_in_memory().unwrap();
+    assert!(probe_clusters(&legacy, &vector(0, 0)).unwrap().is_none());
+}
+
+#[test]
+fn probe_count_scales_with_cluster_count() {
+    assert_eq!(probe_count(3), 3);
+    assert_eq!(probe_count(16), 8);
+    assert_eq!(probe_count(1_000), 100);
+    assert_eq!(cluster_count(10), 16);
+    assert_eq!(cluster_count(1_000_000), 1_000);
+}
@@ -0,0 +1,264 @@
+//! Read-only archive queries shared by `cassio mcp` and `cassio serve`.
+//!
+//! Both servers take loosely typed arguments from their clients (MCP tool
+//! arguments, HTTP query strings), so every query here reads a JSON object of
+//! arguments and returns JSON or text. Transcript text, summaries, and search
+//! hits pass through `redact::redact_text`, the same redaction applied to
+//! exports, before they leave the archive.
+//!
+//! # TRADE-OFFS
+//!
+//! - `list_sessions` reads the manifest, so transcripts written before the
+//!   archive had one are searchable but not listed.
+//! - Arguments are validated where they are used, not against a schema; an
+//!   argument of the wrong JSON type is treated as absent.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json::{Value, json};
+
+use crate::error::CassioError;
+use crate::manifest::Manifest;
+use crate::redact::redact_text;
+use crate::related::{resolve_target, transcript_for_key};
+use crate::search::{DateBounds, SearchOptions, SemanticSearchOptions, Speaker, search};
+use crate::summary::stats_by_month;
+
+const DEFAULT_SEARCH_LIMIT: usize = 20;
+const DEFAULT_LIST_LIMIT: usize = 50;
+
+/// An archive root opened for queries.
+pub struct Archive {
+    root: PathBuf,
+    /// Embedding settings for `semantic` and `hybrid` search modes.
+    semantic: SemanticSearchOptions,
+}
+
+impl Archive {
+    pub fn new(root: PathBuf, semantic: SemanticSearchOptions) -> Self {
+        Self { root, semantic }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// `cassio search` over the archive. Arguments: `query` (required),
+    /// `mode` (`lexical`, `semantic`, `hybrid`), `limit`, `from`, `to`, `tool`,
+    /// `project`, `speaker`, `regex`.
+    pub fn search(&self, args: &Value) -> Result<Value, CassioError> {
+        let query = required_arg(args, "query")?;
+        let mode = string_arg(args, "mode").unwrap_or("lexical");
+        let (semantic, hybrid) = match mode {
+            "lexical" => (None, false),
+            "semantic" => (Some(self.semantic.clone()), false),
+            "hybrid" => (Some(self.semantic.clone()), true),
+            other => {
+                return Err(CassioError::Other(format!(
+                    "Unknown search mode '{other}' (expected lexical, semantic, or hybrid)"
+                )));
+            }
+        };
+        let speaker = string_arg(args, "speaker")
+            .map(str::parse::<Speaker>)
+            .transpose()
+            .map_err(|e| CassioError::Other(e.to_string()))?;
+        let options = SearchOptions {
+            from: string_arg(args, "from").map(str::to_string),
+            to: string_arg(args, "to").map(str::to_string),
+            tool: string_arg(args, "tool").map(str::to_string),
+            project: string_arg(args, "project").map(str::to_string),
+            session_model: None,
+            speaker,
+            limit: limit_arg(args, DEFAULT_SEARCH_LIMIT),
+            summaries_only: false,
+            include_training: false,
+            include_paths: false,
+            json: false,
+            regex: args.get("regex").and_then(Value::as_bool).unwrap_or(false),
+            case_sensitive: false,
+            context: 0,
+            files_with_matches: false,
+            count: false,
+            oldest_first: false,
+            semantic,
+            training_root: None,
+            scan: false,
+            hybrid,
+            rerank_model: None,
+            sessions: false,
+        };
+        // Line scans stop at the file that reaches the limit, not at the hit.
+        let hits: Vec<Value> = search(&self.root, query, &options)?
+            .into_iter()
+            .take(options.limit)
+            .map(|hit| {
+                json!({
+                    "path": self.relative(&hit.path),
+                    "line": hit.line,
+                    "artifact": hit.artifact,
+                    "score": hit.score,
+                    "text": redact_text(&hit.text),
+                })
+            })
+            .collect();
+        Ok(json!({"query": query, "mode": mode, "hits": hits}))
+    }
+
+    /// Root-relative path and redacted text of the transcript for `target`, a
+    /// path or session id prefix (see `related::resolve_target`).
+    pub fn session(&self, target: &str) -> Result<(String, String), CassioError> {
+        let rel = resolve_target(&self.root, target)?;
+        let content = fs::read_to_string(self.root.join(&rel))?;
+        Ok((rel, redact_text(&content)))
+    }
+
+    /// Manifest sessions, newest first. Arguments: `from`, `to`, `tool`,
+    /// `project` (case-insensitive substring of the project path), `limit`.
+    pub fn list_sessions(&self, args: &Value) -> Result<Value, CassioError> {
+        let bounds = DateBounds::parse(string_arg(args, "from"), string_arg(args, "to"))?;
+        let tool = string_arg(args, "tool");
+        let project = string_arg(args, "project").map(str::to_lowercase);
+        let manifest = Manifest::load(&self.root)?;
+        let mut entries: Vec<_> = manifest
+            .sessions
+            .iter()
+            .filter(|(_, entry)| {
+                let date = entry.started_at.format("%Y-%m-%d").to_string();
+                bounds.contains(&date[..7], Some(&date))
+                    && tool.is_none_or(|tool| entry.tool.eq_ignore_ascii_case(tool))
+                    && project
+                        .as_deref()
+                        .is_none_or(|needle| entry.project_path.to_lowercase().contains(needle))
+            })
+            .filter_map(|(key, entry)| Some((transcript_for_key(&self.root, key)?, entry)))
+            .collect();
+        entries.sort_by(|a, b| b.1.started_at.cmp(&a.1.started_at).then(a.0.cmp(&b.0)));
+        let total = entries.len();
+        let sessions: Vec<Value> = entries
+            .into_iter()
+            .take(limit_arg(args, DEFAULT_LIST_LIMIT))
+            .map(|(path, entry)| {
+                json!({
+                    "path": path,
+                    "session_id": entry.session_id,
+                    "tool": entry.tool,
+                    "project": entry.project_path,
+                    "started_at": entry.started_at.to_rfc3339(),
+                })
+            })
+            .collect();
+        Ok(json!({"total": total, "sessions": sessions}))
+    }
+
+    /// Redacted daily summary for `date` (`YYYY-MM-DD`), including legacy
+    /// `*.compaction.md` files.
+    pub fn daily(&self, date: &str) -> Result<String, CassioError> {
+        if date.len() != 10 || chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").is_err() {
+            return Err(CassioError::Other(format!(
+                "Invalid date '{date}' (expected YYYY-MM-DD)"
+            )));
+        }
+        daily_summary_path(&self.root, date)
+            .map(|path| read_redacted(&path))
+            .unwrap_or_else(|| {
+                Err(CassioError::Other(format!(
+                    "No daily summary for {date} (run `cassio compact dailies`)"
+                )))
+            })
+    }
+
+    /// Redacted monthly summary for `month` (`YYYY-MM`).
+    pub fn monthly(&self, month: &str) -> Result<String, CassioError> {
+        if month.len() != 7
+            || chrono::NaiveDate::parse_from_str(&format!("{month}-01"), "%Y-%m-%d").is_err()
+        {
+            return Err(CassioError::Other(format!(
+                "Invalid month '{month}' (expected YYYY-MM)"
+            )));
+        }
+        monthly_summary_path(&self.root, month)
+            .map(|path| read_redacted(&path))
+            .unwrap_or_else(|| {
+                Err(CassioError::Other(format!(
+                    "No monthly summary for {month} (run `cassio compact monthly`)"
+                )))
+            })
+    }
+
+    /// The `cassio summary` month × tool table with totals. Arguments: `from`,
+    /// `to`.
+    pub fn summary_stats(&self, args: &Value) -> Result<Value, CassioError> {
+        let months = stats_by_month(&self.root, string_arg(args, "from"), string_arg(args, "to"))?;
+        let totals = json!({
+            "sessions": months.iter().map(|row| row.sessions).sum::<u32>(),
+            "input_tokens": months.iter().map(|row| row.input_tokens).sum::<u64>(),
+            "output_tokens": months.iter().map(|row| row.output_tokens).sum::<u64>(),
+            "duration_secs": months.iter().map(|row| row.duration_secs).sum::<i64>(),
+            "estimated_cost_usd": months.iter().map(|row| row.estimated_cost_usd).sum::<f64>(),
+        });
+        Ok(json!({"months": months, "totals": totals}))
+    }
+
+    /// `path` relative to the archive root.
+    fn relative(&self, path: &Path) -> String {
+        path.strip_prefix(&self.root)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/")
+    }
+}
+
+/// The daily summary for `date` (`YYYY-MM-DD`), including legacy
+/// `*.compaction.md` files.
+pub(crate) fn daily_summary_path(root: &Path, date: &str) -> Option<PathBuf> {
+    let month_dir = root.join(date.get(..7)?);
+    [".daily.md", ".compaction.md"]
+        .iter()
+        .map(|suffix| month_dir.join(format!("{date}{suffix}")))
+        .find(|path| path.is_file())
+}
+
+/// The weekly summary for ISO week `week` (`YYYY-Www`), written under its
+/// Monday's month.
+pub(crate) fn weekly_summary_path(root: &Path, week: &str) -> Option<PathBuf> {
+    let monday = crate::metrics::iso_week_monday_public(week).ok()?;
+    let path = root
+        .join(monday.format("%Y-%m").to_string())
+        .join(format!("{week}.weekly.md"));
+    path.is_file().then_some(path)
+}
+
+/// The monthly summary for `month` (`YYYY-MM`).
+pub(crate) fn monthly_summary_path(root: &Path, month: &str) -> Option<PathBuf> {
+    let path = root.join(month).join(format!("{month}.monthly.md"));
+    path.is_file().then_some(path)
+}
+
+fn read_redacted(path: &Path) -> Result<String, CassioError> {
+    Ok(redact_text(&fs::read_to_string(path)?))
+}
+
+/// Non-empty string argument `name`.
+pub(crate) fn string_arg<'a>(args: &'a Value, name: &str) -> Option<&'a str> {
+    args.get(name)
+        .and_then(Value::as_str)
+        .filter(|value| !value.is_empty())
+}
+
+pub(crate) fn required_arg<'a>(args: &'a Value, name: &str) -> Result<&'a str, CassioError> {
+    string_arg(args, name)
+        .ok_or_else(|| CassioError::Other(format!("Missing required argument '{name}'")))
+}
+
+fn limit_arg(args: &Value, default: usize) -> usize {
+    args.get("limit")
+        .and_then(Value::as_u64)
+        .map_or(default, |limit| limit as usize)
+        .max(1)
+}
+
+#[cfg(test)]
+#[path = "archive_test.rs"]
+mod tests;
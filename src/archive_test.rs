@@ -0,0 +1,173 @@
+use super::*;
+use crate::manifest::ManifestEntry;
+use chrono::{TimeZone, Utc};
+
+fn temp_root(label: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("cassio_archive_{label}_{}", std::process::id()))
+}
+
+fn archive(root: &Path) -> Archive {
+    Archive::new(
+        root.to_path_buf(),
+        SemanticSearchOptions {
+            provider: "ollama".to_string(),
+            model: "cassio-embedding".to_string(),
+            base_url: "http://127.0.0.1:1".to_string(),
+            timeout_secs: 1,
+            exact: false,
+        },
+    )
+}
+
+fn entry(tool: &str, session_id: &str, month: u32, day: u32, project: &str) -> ManifestEntry {
+    let started_at = Utc.with_ymd_and_hms(2026, month, day, 10, 0, 0).unwrap();
+    ManifestEntry {
+        tool: tool.to_string(),
+        source_path: format!("/logs/{session_id}.jsonl"),
+        session_id: session_id.to_string(),
+        started_at,
+        project_path: project.to_string(),
+        format: "emoji-text".to_string(),
+        training: false,
+        written_at: started_at,
+        fingerprint: None,
+    }
+}
+
+/// Four manifest sessions, three with transcripts on disk.
+fn sample_archive(label: &str) -> PathBuf {
+    let root = temp_root(label);
+    let _ = fs::remove_dir_all(&root);
+    let mut manifest = Manifest::default();
+    for (key, entry) in [
+        (
+            "2026-03/2026-03-28T10-00-00-codex",
+            entry("codex", "march-1", 3, 28, "/work/Faber"),
+        ),
+        (
+            "2026-04/2026-04-02T10-00-00-claude",
+            entry("claude", "april-1", 4, 2, "/work/cassio"),
+        ),
+        (
+            "2026-04/2026-04-20T10-00-00-codex",
+            entry("codex", "april-2", 4, 20, "/work/faber/web"),
+        ),
+        (
+            "2026-04/2026-04-25T10-00-00-grok",
+            entry("grok", "april-3", 4, 25, "/work/cassio"),
+        ),
+    ] {
+        if !key.ends_with("grok") {
+            let path = root.join(format!("{key}.md"));
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "👤 hi\nOPENAI_API_KEY=sk-secret\n").unwrap();
+        }
+        manifest.record(key.to_string(), entry);
+    }
+    manifest.save(&root).unwrap();
+    root
+}
+
+fn listed(archive: &Archive, args: Value) -> (u64, Vec<String>) {
+    let listing = archive.list_sessions(&args).unwrap();
+    let ids = listing["sessions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|s| s["session_id"].as_str().unwrap().to_string())
+        .collect();
+    (listing["total"].as_u64().unwrap(), ids)
+}
+
+#[test]
+fn list_sessions_filters_and_orders_newest_first() {
+    let root = sample_archive("list");
+    let archive = archive(&root);
+
+    // The grok session has no transcript on disk, so it is not listed.
+    let (total, ids) = listed(&archive, json!({}));
+    assert_eq!(total, 3);
+    assert_eq!(ids, ["april-2", "april-1", "march-1"]);
+
+    let (_, ids) = listed(&archive, json!({"from": "2026-04-01", "to": "2026-04-10"}));
+    assert_eq!(ids, ["april-1"]);
+    let (_, ids) = listed(&archive, json!({"from": "2026-04"}));
+    assert_eq!(ids, ["april-2", "april-1"]);
+    let (_, ids) = listed(&archive, json!({"tool": "CODEX"}));
+    assert_eq!(ids, ["april-2", "march-1"]);
+    let (_, ids) = listed(&archive, json!({"project": "faber", "tool": "codex"}));
+    assert_eq!(ids, ["april-2", "march-1"]);
+
+    // `total` counts every match; `limit` (at least 1) only trims the page.
+    let (total, ids) = listed(&archive, json!({"limit": 1}));
+    assert_eq!((total, ids.len()), (3, 1));
+    let (total, ids) = listed(&archive, json!({"limit": 0}));
+    assert_eq!((total, ids.len()), (3, 1));
+
+    assert!(archive.list_sessions(&json!({"from": "April"})).is_err());
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn daily_and_monthly_validate_their_arguments() {
+    let root = sample_archive("summaries");
+    let archive = archive(&root);
+    fs::write(
+        root.join("2026-04/2026-04-02.compaction.md"),
+        "legacy day\nOPENAI_API_KEY=sk-secret\n",
+    )
+    .unwrap();
+    fs::write(root.join("2026-04/2026-04.monthly.md"), "the month\n").unwrap();
+
+    let daily = archive.daily("2026-04-02").unwrap();
+    assert!(daily.starts_with("legacy day\n") && !daily.contains("sk-secret"));
+    assert_eq!(archive.monthly("2026-04").unwrap(), "the month\n");
+
+    for bad in ["2026-4-02", "2026-02-30", "../../etc", "2026-04-02x"] {
+        let err = archive.daily(bad).unwrap_err().to_string();
+        assert!(err.contains("expected YYYY-MM-DD"), "{bad}: {err}");
+    }
+    for bad in ["2026-13", "2026-4", "../etc"] {
+        let err = archive.monthly(bad).unwrap_err().to_string();
+        assert!(err.contains("expected YYYY-MM"), "{bad}: {err}");
+    }
+    assert!(
+        archive
+            .daily("2026-04-03")
+            .unwrap_err()
+            .to_string()
+            .contains("cassio compact dailies")
+    );
+    assert!(archive.monthly("2026-03").is_err());
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn session_resolves_inside_the_archive_only() {
+    let root = sample_archive("session");
+    let archive = archive(&root);
+
+    let (rel, text) = archive.session("april-1").unwrap();
+    assert_eq!(rel, "2026-04/2026-04-02T10-00-00-claude.md");
+    assert!(text.starts_with("👤 hi\n") && !text.contains("sk-secret"));
+    let (rel, _) = archive
+        .session("2026-04/2026-04-20T10-00-00-codex.md")
+        .unwrap();
+    assert_eq!(rel, "2026-04/2026-04-20T10-00-00-codex.md");
+
+    let outside = temp_root("session_outside.md");
+    fs::write(&outside, "not archived\n").unwrap();
+    for target in [
+        outside.to_string_lossy().into_owned(),
+        format!("../{}", outside.file_name().unwrap().to_string_lossy()),
+    ] {
+        let err = archive.session(&target).unwrap_err().to_string();
+        assert!(err.contains("is not under the archive"), "{target}: {err}");
+    }
+    // Ambiguous prefixes and sessions without a transcript are errors too.
+    assert!(archive.session("april").is_err());
+    assert!(archive.session("april-3").is_err());
+
+    let _ = fs::remove_file(&outside);
+    let _ = fs::remove_dir_all(&root);
+}
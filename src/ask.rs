@@ -0,0 +1,281 @@
+//! Retrieval-augmented answers over the archive (`cassio ask`).
+//!
+//! `cassio search --semantic` returns chunks; `cassio ask` turns them into an
+//! answer. The question is embedded and matched against the semantic index
+//! (optionally fused with the lexical index, as `--hybrid` does for search).
+//! The top chunks are re-read from their transcripts by line range, redacted,
+//! numbered, and placed in a grounded prompt (`prompts/ask.md`) that tells the
+//! model to answer only from them and to cite excerpt numbers. The prompt goes
+//! to the configured compaction provider through the same retrying invocation
+//! compaction uses, and the answer is printed with its numbered sources as
+//! `path:start-end`.
+//!
+//! When retrieval comes up empty, `ask` refuses before calling the model: an
+//! answer with nothing to ground it would be the model's guess.
+//!
+//! # TRADE-OFFS
+//!
+//! - Grounding is enforced by the prompt, not checked afterwards. The printed
+//!   sources are the excerpts the model saw, whether or not it cited each one.
+//! - Excerpts are whole index chunks, not the 500-character snippets search
+//!   prints, capped at 80 lines each and at `max_input_bytes` in total; lower
+//!   ranked passages are dropped first.
+//! - Hybrid hits found only by the lexical index carry a single line; they are
+//!   widened by a few lines on either side so the model sees some context.
+//! - `--min-score` compares against the retriever's score: cosine similarity
+//!   for semantic retrieval, fused rank scores (much smaller) for hybrid.
+
+use std::fs;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::compact::{CompactOptions, invoke_prompt};
+use crate::error::CassioError;
+use crate::manifest::STATE_DIR;
+use crate::redact::redact_text;
+use crate::search::{SearchHit, SearchOptions, SemanticSearchOptions, search};
+
+const ASK_PROMPT: &str = include_str!("prompts/ask.md");
+/// Passages retrieved when `--limit` is not given.
+pub const DEFAULT_PASSAGES: usize = 8;
+/// Lines added on each side of a hit that has no line range.
+const CONTEXT_LINES: usize = 3;
+const MAX_PASSAGE_LINES: usize = 80;
+/// Progress log directory under the archive's state directory.
+const ASK_DIR: &str = "ask";
+
+#[derive(Debug, Clone)]
+pub struct AskOptions {
+    /// Embedding settings for retrieval.
+    pub semantic: SemanticSearchOptions,
+    /// Fuse lexical and semantic retrieval.
+    pub hybrid: bool,
+    /// Passages to retrieve.
+    pub passages: usize,
+    /// Drop passages whose retriever score is below this.
+    pub min_score: Option<f32>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub tool: Option<String>,
+    pub project: Option<String>,
+    /// Answering model, provider, and base URL (the compaction settings).
+    pub model: String,
+    pub provider: String,
+    pub base_url: Option<String>,
+    /// Timeout, retries, and the prompt byte budget.
+    pub compact: CompactOptions,
+}
+
+/// A retrieved excerpt given to the model.
+#[derive(Debug, Clone, Serialize)]
+pub struct Passage {
+    /// Path relative to the archive root.
+    pub path: String,
+    pub line_start: usize,
+    pub line_end: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<f32>,
+    #[serde(skip)]
+    pub text: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Answer {
+    pub question: String,
+    pub answer: String,
+    /// Excerpts the model saw; `[n]` in the answer is `sources[n - 1]`.
+    pub sources: Vec<Passage>,
+}
+
+/// Answer `question` from the archive at `root`. With `dry_run`, print the
+/// grounded prompt instead of calling the model.
+pub fn run_ask(
+    root: &Path,
+    question: &str,
+    options: &AskOptions,
+    dry_run: bool,
+    json: bool,
+) -> Result<(), CassioError> {
+    let search_options = SearchOptions {
+        from: options.from.clone(),
+        to: options.to.clone(),
+        tool: options.tool.clone(),
+        project: options.project.clone(),
+        session_model: None,
+        speaker: None,
+        limit: options.passages.max(1),
+        summaries_only: false,
+        include_training: false,
+        include_paths: false,
+        json: false,
+        regex: false,
+        case_sensitive: false,
+        context: 0,
+        files_with_matches: false,
+        count: false,
+        oldest_first: false,
+        semantic: Some(options.semantic.clone()),
+        training_root: None,
+        scan: false,
+        hybrid: options.hybrid,
+        rerank_model: None,
+        sessions: false,
+    };
+    let hits = search(root, question, &search_options)?;
+    let retrieved = hits.len();
+    let mut passages = passages_from_hits(root, &hits, options.min_score);
+    if passages.is_empty() {
+        let detail = match options.min_score {
+            Some(min) if retrieved > 0 => {
+                format!("none of {retrieved} retrieved passages scored at least {min}")
+            }
+            _ => "no indexed passages matched (is the semantic index built? see `cassio index`)"
+                .to_string(),
+        };
+        return Err(CassioError::Other(format!(
+            "cassio ask: nothing in the archive to ground an answer on: {detail}"
+        )));
+    }
+    let (prompt, used) = build_prompt(question, &passages, options.compact.max_input_bytes);
+    passages.truncate(used);
+    if dry_run {
+        print!("{prompt}");
+        return Ok(());
+    }
+
+    eprintln!(
+        "ask: {} passages -> {} ({})",
+        passages.len(),
+        options.model,
+        options.provider
+    );
+    let answer = invoke_prompt(
+        &prompt,
+        &options.model,
+        &options.provider,
+        options.base_url.as_deref(),
+        &options.compact,
+        &root.join(STATE_DIR).join(ASK_DIR),
+        "ask",
+    )?;
+    let answer = Answer {
+        question: question.to_string(),
+        answer: answer.trim().to_string(),
+        sources: passages,
+    };
+    if json {
+        let body = serde_json::to_string_pretty(&answer)
+            .map_err(|e| CassioError::Other(format!("ask serialize: {e}")))?;
+        println!("{body}");
+    } else {
+        print!("{}", render_answer(&answer));
+    }
+    Ok(())
+}
+
+/// Excerpts for `hits`, in rank order: each hit's line range re-read from its
+/// file and redacted. Hits under `min_score`, and hits overlapping an earlier
+/// excerpt of the same file, are dropped.
+pub(crate) fn passages_from_hits(
+    root: &Path,
+    hits: &[SearchHit],
+    min_score: Option<f32>,
+) -> Vec<Passage> {
+    let mut passages: Vec<Passage> = Vec::new();
+    for hit in hits {
+        if let (Some(min), Some(score)) = (min_score, hit.score)
+            && score < min
+        {
+            continue;
+        }
+        let (start, mut end) = match hit.line_end {
+            Some(end) => (hit.line, end.max(hit.line)),
+            None => (
+                hit.line.saturating_sub(CONTEXT_LINES).max(1),
+                hit.line + CONTEXT_LINES,
+            ),
+        };
+        let path = hit
+            .path
+            .strip_prefix(root)
+            .unwrap_or(&hit.path)
+            .to_string_lossy()
+            .replace('\\', "/");
+        if passages
+            .iter()
+            .any(|p| p.path == path && start <= p.line_end && p.line_start <= end)
+        {
+            continue;
+        }
+        let text = match fs::read_to_string(&hit.path) {
+            Ok(content) => {
+                let lines: Vec<&str> = content.lines().collect();
+                end = end.min(lines.len()).min(start + MAX_PASSAGE_LINES - 1);
+                lines
+                    .get(start.saturating_sub(1)..end)
+                    .unwrap_or_default()
+                    .join("\n")
+            }
+            Err(_) => hit.text.clone(),
+        };
+        if text.trim().is_empty() {
+            continue;
+        }
+        passages.push(Passage {
+            path,
+            line_start: start,
+            line_end: end,
+            score: hit.score,
+            text: redact_text(&text),
+        });
+    }
+    passages
+}
+
+/// The grounded prompt and how many passages fit in `max_bytes`. The first
+/// passage is always included.
+pub(crate) fn build_prompt(
+    question: &str,
+    passages: &[Passage],
+    max_bytes: usize,
+) -> (String, usize) {
+    let mut prompt = format!("{ASK_PROMPT}\n## Question\n\n{question}\n\n## Excerpts\n");
+    let mut used = 0;
+    for (index, passage) in passages.iter().enumerate() {
+        let excerpt = format!(
+            "\n[{}] {}:{}-{}\n```\n{}\n```\n",
+            index + 1,
+            passage.path,
+            passage.line_start,
+            passage.line_end,
+            passage.text
+        );
+        if used > 0 && prompt.len() + excerpt.len() > max_bytes {
+            break;
+        }
+        prompt.push_str(&excerpt);
+        used += 1;
+    }
+    prompt.push_str("\n## Answer\n");
+    (prompt, used)
+}
+
+/// The answer followed by its numbered sources.
+pub(crate) fn render_answer(answer: &Answer) -> String {
+    let mut out = format!("{}\n\nSources:\n", answer.answer);
+    for (index, source) in answer.sources.iter().enumerate() {
+        out.push_str(&format!(
+            "  [{}] {}:{}-{}\n",
+            index + 1,
+            source.path,
+            source.line_start,
+            source.line_end
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+#[path = "ask_test.rs"]
+mod tests;
@@ -0,0 +1,104 @@
+use super::*;
+use crate::search::SearchArtifact;
+use std::path::PathBuf;
+
+fn temp_root(label: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("cassio_ask_{label}_{}", std::process::id()))
+}
+
+fn hit(root: &Path, rel: &str, line: usize, line_end: Option<usize>, score: f32) -> SearchHit {
+    SearchHit {
+        artifact: SearchArtifact::Session,
+        path: root.join(rel),
+        line,
+        line_end,
+        text: "snippet".to_string(),
+        score: Some(score),
+        context: None,
+        retrievers: None,
+        rerank_score: None,
+        archive: None,
+    }
+}
+
+fn passage(path: &str, text: &str) -> Passage {
+    Passage {
+        path: path.to_string(),
+        line_start: 2,
+        line_end: 4,
+        score: Some(0.8),
+        text: text.to_string(),
+    }
+}
+
+#[test]
+fn passages_reread_line_ranges_and_skip_overlaps() {
+    let root = temp_root("passages");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("2026-04")).unwrap();
+    let transcript: String = (1..=12)
+        .map(|n| format!("👤 line {n}\n"))
+        .collect::<String>()
+        .replace("line 3", "export OPENAI_API_KEY=hunter2hunter2");
+    fs::write(root.join("2026-04/a.md"), transcript).unwrap();
+
+    let hits = vec![
+        hit(&root, "2026-04/a.md", 2, Some(4), 0.9),
+        // Overlaps the first passage.
+        hit(&root, "2026-04/a.md", 4, Some(6), 0.8),
+        // No range: widened by CONTEXT_LINES on each side, clamped to the file.
+        hit(&root, "2026-04/a.md", 11, None, 0.7),
+        hit(&root, "2026-04/a.md", 7, Some(7), 0.1),
+    ];
+    let passages = passages_from_hits(&root, &hits, Some(0.5));
+    let ranges: Vec<(usize, usize)> = passages
+        .iter()
+        .map(|p| (p.line_start, p.line_end))
+        .collect();
+    assert_eq!(ranges, vec![(2, 4), (8, 12)]);
+    assert_eq!(passages[0].path, "2026-04/a.md");
+    assert!(passages[0].text.starts_with("👤 line 2\n"));
+    assert!(
+        !passages[0].text.contains("hunter2"),
+        "excerpts are redacted"
+    );
+    assert!(passages_from_hits(&root, &hits, Some(0.95)).is_empty());
+
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn prompt_numbers_excerpts_within_the_byte_budget() {
+    let passages = vec![
+        passage("2026-04/a.md", "👤 drop launchd, use a cron entry"),
+        passage("2026-04/b.md", &"🤖 long answer ".repeat(200)),
+    ];
+    let (prompt, used) = build_prompt("why did we drop launchd?", &passages, 100_000);
+    assert_eq!(used, 2);
+    assert!(prompt.starts_with(ASK_PROMPT));
+    assert!(prompt.contains("## Question\n\nwhy did we drop launchd?\n"));
+    assert!(
+        prompt.contains("\n[1] 2026-04/a.md:2-4\n```\n👤 drop launchd, use a cron entry\n```\n")
+    );
+    assert!(prompt.contains("\n[2] 2026-04/b.md:2-4\n"));
+    assert!(prompt.ends_with("## Answer\n"));
+
+    // The first passage always fits; later ones are dropped past the budget.
+    let (prompt, used) = build_prompt("why?", &passages, 10);
+    assert_eq!(used, 1);
+    assert!(!prompt.contains("\n[2] "));
+}
+
+#[test]
+fn answers_are_printed_with_numbered_sources() {
+    let answer = Answer {
+        question: "why?".to_string(),
+        answer: "launchd kept unloading the job after sleep [1].".to_string(),
+        sources: vec![passage("2026-04/a.md", "x"), passage("2026-04/b.md", "y")],
+    };
+    assert_eq!(
+        render_answer(&answer),
+        "launchd kept unloading the job after sleep [1].\n\nSources:\n  \
+         [1] 2026-04/a.md:2-4\n  [2] 2026-04/b.md:2-4\n"
+    );
+}
@@ -236,11 +236,17 @@ pub enum ContentBlock {
     ///
     /// WHY: Storing a human-readable `summary` here (rather than the raw result
     /// content) avoids embedding potentially large tool outputs in the AST.
+    /// Only failed calls keep a bounded prefix of their output, for formatters
+    /// that show an excerpt of what went wrong.
     ToolResult {
         tool_use_id: String,
         name: String,
         success: bool,
         summary: String,
+        /// Leading output of a failed call; `None` for successful calls and for
+        /// sources that do not record tool output.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        output: Option<String>,
     },
     /// Synthetic event recording that the active model changed during the session.
     ModelChange { model: String },
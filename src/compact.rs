@@ -19,7 +19,9 @@
 //! The `extract_session` function reduces each transcript to a compact signal:
 //! metadata lines, user messages, the first 5 lines of each assistant response,
 //! and no tool call details. This compression is intentional — the LLM receives
-//! the human-level conversation, not raw tool I/O.
+//! the human-level conversation, not raw tool I/O. Transcripts with a
+//! frontmatter block also contribute their cost and per-model usage, which the
+//! emoji header does not carry.
 //!
 //! # Monthly compaction
 //!
@@ -66,6 +68,9 @@ use wait_timeout::ChildExt;
 use walkdir::WalkDir;
 
 use crate::error::CassioError;
+use crate::formatter::emoji_text::shorten_model_name;
+use crate::frontmatter;
+use crate::manifest::SessionCatalog;
 
 const COMPACT_PROMPT: &str = include_str!("prompts/compact.md");
 const DAILY_MERGE_PROMPT: &str = include_str!("prompts/daily_merge.md");
@@ -751,14 +756,20 @@ fn find_pending_months(dir: &Path) -> Result<Vec<String>, CassioError> {
 ///
 /// WHY: Using `BTreeMap` ensures days are returned in chronological order without
 /// a separate sort. The date prefix is extracted from the filename (first 10 chars
-/// must be `YYYY-MM-DD`) rather than filesystem metadata to be portable.
+/// must be `YYYY-MM-DD`) rather than filesystem metadata to be portable; files
+/// written under a custom `output_template` take their date from the manifest.
 fn find_pending_days(
     input_dir: &Path,
     output_dir: &Path,
 ) -> Result<Vec<(String, Vec<PathBuf>)>, CassioError> {
     let mut by_date: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+    let catalog = SessionCatalog::load(input_dir);
 
-    for entry in WalkDir::new(input_dir).into_iter().filter_map(|e| e.ok()) {
+    for entry in WalkDir::new(input_dir)
+        .into_iter()
+        .filter_entry(|e| !crate::manifest::is_state_dir(e))
+        .filter_map(|e| e.ok())
+    {
         let path = entry.path();
         if !path.is_file() {
             continue;
@@ -776,6 +787,13 @@ fn find_pending_days(
                     .or_default()
                     .push(path.to_path_buf());
             }
+        } else if name.ends_with(".md")
+            && let Some(session) = catalog.lookup(path)
+        {
+            by_date
+                .entry(session.date.clone())
+                .or_default()
+                .push(path.to_path_buf());
         }
     }
 
@@ -1333,7 +1351,11 @@ fn find_pending_weeks(
 
     let mut by_week: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
 
-    for entry in WalkDir::new(input_dir).into_iter().filter_map(|e| e.ok()) {
+    for entry in WalkDir::new(input_dir)
+        .into_iter()
+        .filter_entry(|e| !crate::manifest::is_state_dir(e))
+        .filter_map(|e| e.ok())
+    {
         let path = entry.path();
         if !path.is_file() {
             continue;
@@ -1567,8 +1589,11 @@ fn extract_session(path: &Path) -> Result<String, CassioError> {
     let mut llm_lines = 0u32;
     const LLM_LINE_LIMIT: u32 = 5;
 
-    for line in content.lines() {
-        if line.starts_with("📋") {
+    for line in frontmatter::body(&content).lines() {
+        if line.starts_with("📋 Usage: ") {
+            // Per-turn usage annotations; the totals are in the summary block.
+            in_llm = false;
+        } else if line.starts_with("📋") {
             out.push_str(line);
             out.push('\n');
             in_llm = false;
@@ -1582,9 +1607,13 @@ fn extract_session(path: &Path) -> Result<String, CassioError> {
             out.push('\n');
             in_llm = true;
             llm_lines = 0;
-        } else if line.starts_with("✅") || line.starts_with("❌") {
+        } else if ["✅", "❌", "🔧", "💭"]
+            .iter()
+            .any(|emoji| line.starts_with(emoji))
+        {
             // WHY: Tool call lines reset the assistant context — the next non-tool
-            // content may be a continuation that we want to capture.
+            // content may be a continuation that we want to capture. Thinking
+            // and tool input lines (opt-in formatter output) are left out too.
             in_llm = false;
         } else if in_llm && !line.trim().is_empty() {
             llm_lines += 1;
@@ -1595,6 +1624,29 @@ fn extract_session(path: &Path) -> Result<String, CassioError> {
         }
     }
 
+    if let Some(fm) = frontmatter::parse(&content) {
+        if let Some(cost) = fm.cost_usd
+            && !out.contains("📋 Cost: ")
+        {
+            out.push_str(&format!("📋 Cost: ${cost:.4}\n"));
+        }
+        if !fm.models.is_empty() {
+            let models: Vec<String> = fm
+                .models
+                .iter()
+                .map(|(model, usage)| match usage.cost_usd {
+                    Some(cost) => format!(
+                        "{} ({} msgs, ${cost:.4})",
+                        shorten_model_name(model),
+                        usage.messages
+                    ),
+                    None => format!("{} ({} msgs)", shorten_model_name(model), usage.messages),
+                })
+                .collect();
+            out.push_str(&format!("📋 Models: {}\n", models.join(", ")));
+        }
+    }
+
     Ok(out)
 }
 
@@ -1736,6 +1788,35 @@ fn invoke_llm(
     }))
 }
 
+/// One prompt through `invoke_llm`, for callers outside the compaction pipeline
+/// (`cassio ask`). Retries follow `options`; progress events are appended
+/// under `checkpoint_dir`, labelled `label`.
+pub(crate) fn invoke_prompt(
+    input: &str,
+    model: &str,
+    provider: &str,
+    base_url: Option<&str>,
+    options: &CompactOptions,
+    checkpoint_dir: &Path,
+    label: &str,
+) -> Result<String, CassioError> {
+    std::fs::create_dir_all(checkpoint_dir)?;
+    let context = InvocationContext {
+        checkpoint_dir,
+        day: label,
+        phase: label,
+        chunk_index: None,
+        total_chunks: 1,
+    };
+    invoke_llm(input, model, provider, base_url, options, context).map_err(|err| {
+        CassioError::Other(format!(
+            "{provider} call failed [{}]: {}",
+            err.class.as_str(),
+            err.detail
+        ))
+    })
+}
+
 fn invoke_llm_once(
     input: &str,
     model: &str,
@@ -158,6 +158,29 @@ seventh line that should be cut
     std::fs::remove_dir_all(&dir).ok();
 }
 
+#[test]
+fn test_extract_session_reads_frontmatter_usage() {
+    let content = "---\nsession_id: abc\ntool: claude\nproject_path: /proj\n\
+         started_at: 2026-04-01T09:00:00Z\nsession_kind: human\ncost_usd: 0.25\n\
+         models:\n  claude-opus-4-5-20251101:\n    messages: 3\n    cost_usd: 0.25\n---\n\
+         📋 Session: abc\n👤 hi\n";
+    let dir = std::env::temp_dir().join(format!(
+        "cassio_test_extract_frontmatter_{}",
+        std::process::id()
+    ));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("test.md");
+    std::fs::write(&path, content).unwrap();
+
+    let result = extract_session(&path).unwrap();
+    assert!(result.starts_with("📋 Session: abc\n👤 hi\n"));
+    assert!(!result.contains("session_id"));
+    assert!(result.contains("📋 Cost: $0.2500\n"));
+    assert!(result.contains("📋 Models: opus-4.5 (3 msgs, $0.2500)\n"));
+
+    std::fs::remove_dir_all(&dir).ok();
+}
+
 #[test]
 fn test_daily_chunk_checkpoint_path_format() {
     let dir = Path::new("/tmp/checkpoints");
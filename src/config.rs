@@ -38,7 +38,7 @@ use crate::error::CassioError;
 ///
 /// WHY: Isolating git options in a sub-struct keeps the top-level `Config` flat and
 /// maps cleanly to the `[git]` TOML table, making the config file readable.
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Clone, Deserialize, Default)]
 pub struct GitConfig {
     /// Auto-commit output files after processing.
     #[serde(default)]
@@ -67,6 +67,97 @@ pub struct SourcesConfig {
     pub kimi: Option<String>,
 }
 
+/// One `[[routes]]` rule sending matching sessions to a separate archive.
+///
+/// WHY: Client and personal work often live in separate git repositories with
+/// different push targets. Every criterion that is set must match; the first
+/// matching route wins and unmatched sessions go to the top-level `output`.
+#[derive(Debug, Default, Deserialize)]
+pub struct RouteConfig {
+    /// Label for progress output. Defaults to the route's output path.
+    pub name: Option<String>,
+    /// Project path prefix, compared by path component (`~/work/acme`).
+    pub project: Option<String>,
+    /// Tool name as written in transcript names (`claude`, `codex`, ...).
+    pub tool: Option<String>,
+    /// Substring of one of the project's git remote URLs (`github.com/acme/`).
+    pub remote: Option<String>,
+    /// Output root for matching sessions. Has its own manifest and index.
+    pub output: String,
+    /// Training-JSON root for matching sessions. When unset, training JSON is
+    /// co-located under this route's `output`.
+    pub training_output: Option<String>,
+    /// Git options for this archive. When unset, the top-level `[git]` applies.
+    pub git: Option<GitConfig>,
+}
+
+/// One `[[saved_search]]` query checked by `cassio watchlist run`.
+///
+/// WHY: Recurring queries (secrets in commands, known error strings, a client's
+/// name) are worth re-running after every batch, but only the hits that are new
+/// since the previous run need attention.
+#[derive(Debug, Default, Deserialize)]
+pub struct SavedSearchConfig {
+    /// Label for reports and the archive's watchlist state.
+    pub name: String,
+    /// Search query, with the same syntax as `cassio search`.
+    pub query: String,
+    /// Treat `query` as a regular expression.
+    #[serde(default)]
+    pub regex: bool,
+    #[serde(default)]
+    pub case_sensitive: bool,
+    /// Only sessions from this tool.
+    pub tool: Option<String>,
+    /// Only sessions whose project header contains this substring.
+    pub project: Option<String>,
+    /// Only `user`, `assistant`, or `tool` lines.
+    pub speaker: Option<String>,
+    /// New hits make `cassio watchlist run` (and the batch that ran it) exit
+    /// non-zero.
+    #[serde(default)]
+    pub critical: bool,
+}
+
+/// The `format` key: a format name, or a `[format]` table that also carries
+/// formatter verbosity options.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum FormatSetting {
+    Name(String),
+    Table(FormatConfig),
+}
+
+/// `[format]` options. Each overrides the output format's own default and is
+/// in turn overridden by the matching CLI flag.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct FormatConfig {
+    /// Default output format, as a plain `format = "..."` key sets it.
+    pub default: Option<String>,
+    /// Show thinking blocks.
+    pub show_thinking: Option<bool>,
+    /// Tool call input detail: `"summary"`, `"inline"`, or `"full"`.
+    pub tool_input: Option<String>,
+    /// Lines of output shown under a failed tool call.
+    pub failed_output_lines: Option<usize>,
+    /// Timestamp on every message.
+    pub timestamps: Option<bool>,
+    /// Token and cost annotation on each assistant turn.
+    pub turn_usage: Option<bool>,
+    /// Start emoji-text transcripts with a YAML frontmatter block carrying the
+    /// session metadata, stats, per-model usage and cost.
+    pub frontmatter: Option<bool>,
+}
+
+/// `[search]` options for `cassio search`.
+#[derive(Debug, Default, Deserialize)]
+pub struct SearchConfig {
+    /// Archive roots searched together when `-o` is not given. Replaces
+    /// `output` for search, so list it here too when it should be included.
+    #[serde(default)]
+    pub roots: Vec<String>,
+}
+
 /// Embedding provider options used by `cassio index`.
 #[derive(Debug, Default, Deserialize)]
 pub struct EmbeddingConfig {
@@ -91,6 +182,12 @@ pub struct EmbeddingConfig {
     pub batch_size: Option<usize>,
     /// Per-request embedding timeout, in seconds.
     pub timeout_secs: Option<u64>,
+    /// GGUF cross-encoder used by `cassio search --rerank`.
+    pub rerank_model: Option<String>,
+    /// After automatic indexing, write a Related footer to each newly embedded
+    /// emoji-text transcript.
+    #[serde(default)]
+    pub related_footer: bool,
 }
 
 /// Top-level config deserialized from `~/.config/cassio/config.toml`.
@@ -109,8 +206,13 @@ pub struct Config {
     /// instead of beside each transcript under `output`. When unset, training
     /// JSON is co-located with transcripts (legacy layout).
     pub training_output: Option<String>,
-    /// Default output format: `"emoji-text"`, `"jsonl"`, or `"training-json"`.
-    pub format: Option<String>,
+    /// Output path template for batch mode, relative to `output` and without an
+    /// extension (e.g. `"{project}/{yyyy}/{mm}/{dd}T{HH}-{MM}-{tool}"`). When
+    /// unset, transcripts use the built-in `YYYY-MM/` layout.
+    pub output_template: Option<String>,
+    /// Default output format: `"emoji-text"`, `"jsonl"`, `"training-json"`, `"html"`,
+    /// or `"markdown"`; or a `[format]` table with `default` and formatter options.
+    pub format: Option<FormatSetting>,
     /// Default model name passed to the LLM provider during compaction.
     pub model: Option<String>,
     /// LLM provider for compaction: `"ollama"`, `"claude"`, `"codex"`,
@@ -126,9 +228,17 @@ pub struct Config {
     pub max_retries: Option<usize>,
     /// Embedding settings for semantic indexing.
     pub embedding: Option<EmbeddingConfig>,
+    /// Search settings (`[search]`).
+    pub search: Option<SearchConfig>,
     #[serde(default)]
     pub git: GitConfig,
     pub sources: Option<SourcesConfig>,
+    /// Per-project routing rules (`[[routes]]`), tried in order.
+    #[serde(default)]
+    pub routes: Vec<RouteConfig>,
+    /// Recurring queries (`[[saved_search]]`) checked after each batch.
+    #[serde(default, rename = "saved_search")]
+    pub saved_searches: Vec<SavedSearchConfig>,
 }
 
 impl Config {
@@ -149,6 +259,22 @@ impl Config {
         toml::from_str(&content).unwrap_or_default()
     }
 
+    /// The default output format, from `format = "..."` or `[format] default`.
+    pub fn format_name(&self) -> Option<&str> {
+        match self.format.as_ref()? {
+            FormatSetting::Name(name) => Some(name),
+            FormatSetting::Table(table) => table.default.as_deref(),
+        }
+    }
+
+    /// The `[format]` options; all unset when `format` is a plain name.
+    pub fn format_options(&self) -> FormatConfig {
+        match &self.format {
+            Some(FormatSetting::Table(table)) => table.clone(),
+            _ => FormatConfig::default(),
+        }
+    }
+
     /// Resolve the configured output path, expanding a leading `~` to the home directory.
     ///
     /// Returns `None` when no output path is configured, signalling to callers that
@@ -157,6 +283,22 @@ impl Config {
         self.output.as_deref().map(expand_tilde)
     }
 
+    /// Resolve `search.roots`, expanding a leading `~` in each.
+    pub fn search_roots(&self) -> Vec<PathBuf> {
+        self.search
+            .as_ref()
+            .map(|search| search.roots.iter().map(|root| expand_tilde(root)).collect())
+            .unwrap_or_default()
+    }
+
+    /// Resolve `embedding.rerank_model`, expanding a leading `~`.
+    pub fn rerank_model_path(&self) -> Option<PathBuf> {
+        self.embedding
+            .as_ref()
+            .and_then(|embedding| embedding.rerank_model.as_deref())
+            .map(expand_tilde)
+    }
+
     /// Resolve the configured training-JSON directory, expanding a leading `~`.
     ///
     /// Returns `None` when unset; callers then co-locate training files under
@@ -295,8 +437,23 @@ pub fn init() -> Result<(), CassioError> {
 # When unset, training JSON is written beside each transcript under output.
 # training_output = "~/training"
 
-# Default output format: "emoji-text", "jsonl", or "training-json"
+# Output path template, relative to output (extension is added per artifact).
+# Placeholders: {yyyy} {mm} {dd} {HH} {MM} {SS} {tool} {project} {session_id} {branch}
+# output_template = "{yyyy}-{mm}/{yyyy}-{mm}-{dd}T{HH}-{MM}-{SS}-{tool}"
+
+# Default output format: "emoji-text", "jsonl", "training-json", "html", or "markdown"
 # format = "emoji-text"
+#
+# Or, to also set formatter options, use a [format] table instead (at the end of
+# this file, since keys after a table header belong to that table):
+# [format]
+# default = "emoji-text"
+# show_thinking = true          # include thinking blocks
+# tool_input = "inline"         # "summary", "inline", or "full"
+# failed_output_lines = 5       # output excerpt under failed tool calls
+# timestamps = true             # [HH:MM:SS] on every message
+# turn_usage = true             # tokens and cost per assistant turn
+# frontmatter = true            # YAML frontmatter on emoji-text transcripts
 
 # LLM provider for compaction: "ollama", "claude", "codex", "openrouter", or "openai"
 # provider = "ollama"
@@ -335,6 +492,16 @@ pub fn init() -> Result<(), CassioError> {
 # batch_size = 16
 # timeout_secs = 120
 
+# Append related sessions to new transcripts after automatic indexing
+# related_footer = false
+
+# GGUF cross-encoder for `cassio search --rerank`
+# rerank_model = "~/models/bge-reranker-v2-m3-Q8_0.gguf"
+
+[search]
+# Archive roots `cassio search` covers when -o is not given (replaces `output`)
+# roots = ["~/transcripts", "~/work/acme-transcripts"]
+
 [git]
 # Auto-commit output files after processing
 # commit = false
@@ -353,6 +520,24 @@ pub fn init() -> Result<(), CassioError> {
 # grok = "~/.grok/sessions"
 # cursor = "~/.cursor/projects"
 # kimi = "~/.kimi-code/sessions"
+
+# Route matching sessions to separate archives (first match wins).
+# Criteria: project (path prefix), tool, remote (git remote URL substring).
+# [[routes]]
+# name = "acme"
+# project = "~/work/acme"
+# remote = "github.com/acme/"
+# output = "~/work/acme-transcripts"
+# training_output = "~/work/acme-training"
+# git = { commit = true, push = false }
+
+# Saved searches: after each batch, report hits that are new since the last
+# run (also `cassio watchlist run`). Critical matches exit non-zero.
+# [[saved_search]]
+# name = "aws-keys"
+# query = "AKIA[0-9A-Z]{16}"
+# regex = true
+# critical = true
 "#;
 
     if let Some(parent) = path.parent() {
@@ -98,6 +98,7 @@ fn test_config_deserialize() {
     let toml_str = r#"
 output = "~/transcripts"
 training_output = "~/training"
+output_template = "{project}/{yyyy}/{mm}/{dd}T{HH}-{MM}-{tool}"
 format = "emoji-text"
 provider = "openai"
 base_url = "http://127.0.0.1:18173/v1"
@@ -126,7 +127,11 @@ pi = "~/.pi/agent/sessions"
     let config: Config = toml::from_str(toml_str).unwrap();
     assert_eq!(config.output.as_deref(), Some("~/transcripts"));
     assert_eq!(config.training_output.as_deref(), Some("~/training"));
-    assert_eq!(config.format.as_deref(), Some("emoji-text"));
+    assert_eq!(
+        config.output_template.as_deref(),
+        Some("{project}/{yyyy}/{mm}/{dd}T{HH}-{MM}-{tool}")
+    );
+    assert_eq!(config.format_name(), Some("emoji-text"));
     assert_eq!(config.provider.as_deref(), Some("openai"));
     assert_eq!(
         config.base_url.as_deref(),
@@ -159,6 +164,53 @@ pi = "~/.pi/agent/sessions"
     );
 }
 
+#[test]
+fn test_config_deserialize_routes() {
+    let toml_str = r#"
+output = "~/transcripts"
+
+[[routes]]
+name = "acme"
+project = "~/work/acme"
+remote = "github.com/acme/"
+output = "~/work/acme-transcripts"
+git = { commit = true, push = false }
+
+[[routes]]
+tool = "claude-chat"
+output = "~/chat"
+"#;
+    let config: Config = toml::from_str(toml_str).unwrap();
+    assert_eq!(config.routes.len(), 2);
+    assert_eq!(config.routes[0].name.as_deref(), Some("acme"));
+    assert_eq!(config.routes[0].remote.as_deref(), Some("github.com/acme/"));
+    assert!(config.routes[0].git.as_ref().is_some_and(|git| git.commit));
+    assert_eq!(config.routes[1].tool.as_deref(), Some("claude-chat"));
+    assert!(config.routes[1].git.is_none());
+    assert!(Config::default().routes.is_empty());
+}
+
+#[test]
+fn test_config_deserialize_saved_searches() {
+    let toml_str = r#"
+[[saved_search]]
+name = "aws-keys"
+query = "AKIA[0-9A-Z]{16}"
+regex = true
+critical = true
+
+[[saved_search]]
+name = "acme"
+query = "acme"
+speaker = "user"
+"#;
+    let config: Config = toml::from_str(toml_str).unwrap();
+    assert_eq!(config.saved_searches.len(), 2);
+    assert!(config.saved_searches[0].regex && config.saved_searches[0].critical);
+    assert_eq!(config.saved_searches[1].speaker.as_deref(), Some("user"));
+    assert!(!config.saved_searches[1].critical);
+}
+
 #[test]
 fn test_config_default() {
     let config = Config::default();
@@ -181,6 +233,26 @@ fn test_config_output_path_expands_tilde() {
     assert_eq!(path, home.join("transcripts"));
 }
 
+#[test]
+fn test_config_search_roots_expand_tilde() {
+    let config: Config = toml::from_str(
+        r#"
+[search]
+roots = ["~/transcripts", "/srv/acme-transcripts"]
+"#,
+    )
+    .unwrap();
+    let home = dirs::home_dir().unwrap();
+    assert_eq!(
+        config.search_roots(),
+        vec![
+            home.join("transcripts"),
+            PathBuf::from("/srv/acme-transcripts")
+        ]
+    );
+    assert!(Config::default().search_roots().is_empty());
+}
+
 #[test]
 fn test_config_training_output_path_expands_tilde() {
     let config = Config {
@@ -198,6 +270,35 @@ fn test_config_training_output_path_none_when_unset() {
     assert!(config.training_output_path().is_none());
 }
 
+#[test]
+fn test_config_format_table() {
+    let config: Config = toml::from_str(
+        r#"
+output = "~/transcripts"
+
+[format]
+default = "markdown"
+show_thinking = true
+tool_input = "inline"
+failed_output_lines = 5
+frontmatter = true
+"#,
+    )
+    .unwrap();
+    assert_eq!(config.output.as_deref(), Some("~/transcripts"));
+    assert_eq!(config.format_name(), Some("markdown"));
+    let options = config.format_options();
+    assert_eq!(options.show_thinking, Some(true));
+    assert_eq!(options.tool_input.as_deref(), Some("inline"));
+    assert_eq!(options.failed_output_lines, Some(5));
+    assert_eq!(options.timestamps, None);
+    assert_eq!(options.frontmatter, Some(true));
+
+    let config: Config = toml::from_str("format = \"html\"").unwrap();
+    assert_eq!(config.format_name(), Some("html"));
+    assert_eq!(config.format_options().show_thinking, None);
+}
+
 // --- resolve_key tests ---
 
 #[test]
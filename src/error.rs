@@ -30,6 +30,10 @@ pub enum CassioError {
         details: String,
     },
 
+    /// New hits for `critical` saved searches (see `watchlist`).
+    #[error("critical saved search matched: {0}")]
+    CriticalMatch(String),
+
     #[error("{0}")]
     Other(String),
 }
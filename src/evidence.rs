@@ -319,17 +319,16 @@ pub fn merge_evidence(
                 quotes.push(q.clone());
             }
         }
-        if metrics_ref.is_none() {
-            if let Some(r) = &part.metrics_ref {
-                if !r.is_empty() {
-                    metrics_ref = Some(r.clone());
-                }
-            }
+        if metrics_ref.is_none()
+            && let Some(r) = &part.metrics_ref
+            && !r.is_empty()
+        {
+            metrics_ref = Some(r.clone());
         }
-        if let Some(n) = &part.notes {
-            if !n.trim().is_empty() {
-                notes.push(n.clone());
-            }
+        if let Some(n) = &part.notes
+            && !n.trim().is_empty()
+        {
+            notes.push(n.clone());
         }
     }
 
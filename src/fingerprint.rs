@@ -0,0 +1,252 @@
+//! Content fingerprints over normalized message streams, for duplicate detection.
+//!
+//! The same conversation reaches cassio more than once: Claude Desktop
+//! local-agent sessions mirror Claude Code logs, forked Grok sub-agents replay
+//! their parent's turns, and archives synced from two machines overlap. Source
+//! paths, session ids, and timestamps differ between those copies, so they are
+//! compared by content instead.
+//!
+//! A fingerprint is the chain of cumulative digests after each message of a
+//! normalized stream: message role, collapsed text, tool name plus input, and
+//! tool outcome. Thinking blocks, model changes, queue operations, tool-use ids,
+//! and tool-result summaries are left out because they vary between tools and
+//! parser versions without changing the conversation. Two sessions are exact
+//! duplicates when their chains are equal, and one is a prefix duplicate of
+//! another when its final digest appears at the same position in the longer
+//! chain, the shared prefix is at least [`MIN_PREFIX_MESSAGES`] long, and both
+//! sessions belong to the same project.
+//!
+//! # TRADE-OFFS
+//!
+//! - Digests are truncated to 64 bits. The manifest only stores a summary
+//!   (message count, first digest, final digest), so comparing against a
+//!   previously written session whose chain is needed means re-parsing its
+//!   source; the first digest limits that to sessions that open the same way.
+//! - A session that diverges after a shared prefix (a fork that continues
+//!   differently) is not a duplicate: both copies are kept.
+//! - Short sessions are never prefix duplicates. Unrelated sessions often open
+//!   with the same line ("continue"), so a copy shorter than two exchanges is
+//!   kept rather than risk dropping a real session.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::ast::{ContentBlock, Role, Session};
+
+/// Shortest stream (two exchanges) that can count as a prefix duplicate.
+pub const MIN_PREFIX_MESSAGES: usize = 4;
+
+/// Cumulative digests after each fingerprinted message of a session.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Fingerprint {
+    chain: Vec<u64>,
+}
+
+/// What the manifest keeps of a fingerprint.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FingerprintSummary {
+    pub messages: usize,
+    /// Digest after the first message (candidate filter for re-parsing).
+    pub head: String,
+    /// Digest after the last message.
+    pub digest: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateKind {
+    /// Same normalized message stream.
+    Exact,
+    /// The duplicate's stream is a strict prefix of the kept session's.
+    Prefix,
+}
+
+impl std::fmt::Display for DuplicateKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DuplicateKind::Exact => write!(f, "exact"),
+            DuplicateKind::Prefix => write!(f, "prefix"),
+        }
+    }
+}
+
+impl Fingerprint {
+    pub fn of(session: &Session) -> Self {
+        let mut chain = Vec::new();
+        let mut previous = 0u64;
+        for message in &session.messages {
+            let Some(normalized) = normalize_message(message.role, &message.content) else {
+                continue;
+            };
+            let mut hasher = Sha256::new();
+            hasher.update(previous.to_be_bytes());
+            hasher.update(normalized.as_bytes());
+            previous = truncate(&hasher.finalize());
+            chain.push(previous);
+        }
+        Self { chain }
+    }
+
+    /// Number of fingerprinted messages.
+    pub fn len(&self) -> usize {
+        self.chain.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chain.is_empty()
+    }
+
+    pub fn head(&self) -> Option<u64> {
+        self.chain.first().copied()
+    }
+
+    pub fn digest(&self) -> Option<u64> {
+        self.chain.last().copied()
+    }
+
+    pub fn summary(&self) -> Option<FingerprintSummary> {
+        Some(FingerprintSummary {
+            messages: self.len(),
+            head: format!("{:016x}", self.head()?),
+            digest: format!("{:016x}", self.digest()?),
+        })
+    }
+
+    /// How `self` duplicates `other`, if its whole stream is contained in it.
+    pub fn duplicate_of(&self, other: &Fingerprint) -> Option<DuplicateKind> {
+        let digest = self.digest()?;
+        if other.chain.get(self.len() - 1) != Some(&digest) {
+            return None;
+        }
+        Some(if self.len() == other.len() {
+            DuplicateKind::Exact
+        } else {
+            DuplicateKind::Prefix
+        })
+    }
+}
+
+impl FingerprintSummary {
+    pub fn head(&self) -> Option<u64> {
+        u64::from_str_radix(&self.head, 16).ok()
+    }
+}
+
+/// One session taking part in duplicate resolution.
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub fingerprint: Fingerprint,
+    /// Already written by an earlier run; preferred on exact ties so the
+    /// archive does not churn.
+    pub existing: bool,
+    /// Final tie-breaker, for a stable choice between equal copies.
+    pub source_path: String,
+    /// Prefix duplicates only match within one project.
+    pub project_path: String,
+}
+
+/// For each candidate, the index of the candidate it duplicates (and how), or
+/// `None` when it should be kept.
+///
+/// The most complete copy wins: candidates are visited longest first, then
+/// existing before new, then by source path, and each kept candidate claims
+/// every unclaimed candidate whose stream it contains. Claimed candidates never
+/// claim others, so a prefix always points at the longest copy containing it.
+/// A prefix must share at least [`MIN_PREFIX_MESSAGES`] messages and the
+/// keeper's project.
+pub fn resolve(candidates: &[Candidate]) -> Vec<Option<(usize, DuplicateKind)>> {
+    let mut by_position: HashMap<(usize, u64), Vec<usize>> = HashMap::new();
+    for (index, candidate) in candidates.iter().enumerate() {
+        if let Some(digest) = candidate.fingerprint.digest() {
+            by_position
+                .entry((candidate.fingerprint.len(), digest))
+                .or_default()
+                .push(index);
+        }
+    }
+
+    let mut order: Vec<usize> = (0..candidates.len()).collect();
+    order.sort_by(|&a, &b| {
+        let (a, b) = (&candidates[a], &candidates[b]);
+        b.fingerprint
+            .len()
+            .cmp(&a.fingerprint.len())
+            .then(b.existing.cmp(&a.existing))
+            .then(a.source_path.cmp(&b.source_path))
+    });
+
+    let mut resolution: Vec<Option<(usize, DuplicateKind)>> = vec![None; candidates.len()];
+    for &keeper in &order {
+        if resolution[keeper].is_some() {
+            continue;
+        }
+        let chain = &candidates[keeper].fingerprint.chain;
+        for (position, digest) in chain.iter().enumerate() {
+            let Some(matches) = by_position.get(&(position + 1, *digest)) else {
+                continue;
+            };
+            let kind = if position + 1 == chain.len() {
+                DuplicateKind::Exact
+            } else {
+                DuplicateKind::Prefix
+            };
+            let prefix_allowed = position + 1 >= MIN_PREFIX_MESSAGES;
+            for &other in matches {
+                if kind == DuplicateKind::Prefix
+                    && (!prefix_allowed
+                        || candidates[other].project_path != candidates[keeper].project_path)
+                {
+                    continue;
+                }
+                if other != keeper && resolution[other].is_none() {
+                    resolution[other] = Some((keeper, kind));
+                }
+            }
+        }
+    }
+    resolution
+}
+
+fn normalize_message(role: Role, content: &[ContentBlock]) -> Option<String> {
+    let role = match role {
+        Role::User => "user",
+        Role::Assistant => "assistant",
+        Role::System => return None,
+    };
+    let mut parts = Vec::new();
+    for block in content {
+        match block {
+            ContentBlock::Text { text } => {
+                let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+                if !text.is_empty() {
+                    parts.push(format!("text:{text}"));
+                }
+            }
+            ContentBlock::ToolUse { name, input, .. } => {
+                parts.push(format!("tool:{name}:{input}"));
+            }
+            ContentBlock::ToolResult { name, success, .. } => {
+                parts.push(format!("result:{name}:{success}"));
+            }
+            ContentBlock::Thinking { .. }
+            | ContentBlock::ModelChange { .. }
+            | ContentBlock::QueueOperation { .. } => {}
+        }
+    }
+    if parts.is_empty() {
+        return None;
+    }
+    Some(format!("{role}\n{}", parts.join("\n")))
+}
+
+fn truncate(digest: &[u8]) -> u64 {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(&digest[..8]);
+    u64::from_be_bytes(bytes)
+}
+
+#[cfg(test)]
+#[path = "fingerprint_test.rs"]
+mod tests;
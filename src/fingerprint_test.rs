@@ -0,0 +1,208 @@
+use super::*;
+use crate::ast::{Message, SessionKind, SessionMetadata, SessionStats, Tool};
+
+fn message(role: Role, content: Vec<ContentBlock>) -> Message {
+    Message {
+        role,
+        timestamp: None,
+        model: None,
+        content,
+        usage: None,
+    }
+}
+
+fn text(role: Role, text: &str) -> Message {
+    message(
+        role,
+        vec![ContentBlock::Text {
+            text: text.to_string(),
+        }],
+    )
+}
+
+fn session(tool: Tool, messages: Vec<Message>) -> Session {
+    Session {
+        metadata: SessionMetadata {
+            session_id: "s".to_string(),
+            tool,
+            project_path: "/tmp/project".to_string(),
+            started_at: "2025-01-15T10:00:00Z".parse().unwrap(),
+            session_kind: SessionKind::Human,
+            version: None,
+            git_branch: None,
+            model: None,
+            title: None,
+        },
+        messages,
+        stats: SessionStats::default(),
+    }
+}
+
+fn conversation(turns: usize) -> Vec<Message> {
+    (0..turns)
+        .flat_map(|turn| {
+            [
+                text(Role::User, &format!("question {turn}")),
+                text(Role::Assistant, &format!("answer {turn}")),
+            ]
+        })
+        .collect()
+}
+
+fn candidate(turns: usize, existing: bool, source: &str) -> Candidate {
+    Candidate {
+        fingerprint: Fingerprint::of(&session(Tool::Grok, conversation(turns))),
+        existing,
+        source_path: source.to_string(),
+        project_path: "/tmp/project".to_string(),
+    }
+}
+
+#[test]
+fn fingerprint_ignores_tool_ids_thinking_and_whitespace() {
+    let a = session(
+        Tool::Claude,
+        vec![
+            text(Role::User, "fix  the\nbuild"),
+            message(
+                Role::Assistant,
+                vec![
+                    ContentBlock::Thinking {
+                        text: "hmm".to_string(),
+                    },
+                    ContentBlock::ToolUse {
+                        id: "toolu_1".to_string(),
+                        name: "Bash".to_string(),
+                        input: serde_json::json!({"command": "cargo build"}),
+                    },
+                ],
+            ),
+            message(
+                Role::System,
+                vec![ContentBlock::ModelChange {
+                    model: "opus".to_string(),
+                }],
+            ),
+        ],
+    );
+    let b = session(
+        Tool::ClaudeDesktop,
+        vec![
+            text(Role::User, "fix the build"),
+            message(
+                Role::Assistant,
+                vec![ContentBlock::ToolUse {
+                    id: "call_9".to_string(),
+                    name: "Bash".to_string(),
+                    input: serde_json::json!({"command": "cargo build"}),
+                }],
+            ),
+        ],
+    );
+    let (a, b) = (Fingerprint::of(&a), Fingerprint::of(&b));
+    assert_eq!(a.len(), 2);
+    assert_eq!(a, b);
+    assert_eq!(a.duplicate_of(&b), Some(DuplicateKind::Exact));
+}
+
+#[test]
+fn prefix_and_divergent_streams() {
+    let short = Fingerprint::of(&session(Tool::Grok, conversation(1)));
+    let long = Fingerprint::of(&session(Tool::Grok, conversation(2)));
+    let mut forked = conversation(1);
+    forked.push(text(Role::User, "something else"));
+    let forked = Fingerprint::of(&session(Tool::Grok, forked));
+
+    assert_eq!(short.duplicate_of(&long), Some(DuplicateKind::Prefix));
+    assert_eq!(long.duplicate_of(&short), None);
+    assert_eq!(forked.duplicate_of(&long), None);
+    assert_eq!(short.duplicate_of(&forked), Some(DuplicateKind::Prefix));
+    assert_eq!(short.head(), long.head());
+}
+
+#[test]
+fn resolve_points_every_copy_at_the_longest() {
+    let candidates = vec![
+        candidate(2, false, "/a"),
+        candidate(4, false, "/b"),
+        candidate(3, false, "/c"),
+        candidate(4, false, "/d"),
+    ];
+    let resolution = resolve(&candidates);
+    assert_eq!(resolution[0], Some((1, DuplicateKind::Prefix)));
+    assert_eq!(resolution[1], None);
+    assert_eq!(resolution[2], Some((1, DuplicateKind::Prefix)));
+    assert_eq!(resolution[3], Some((1, DuplicateKind::Exact)));
+}
+
+#[test]
+fn resolve_prefers_existing_copy_on_exact_tie() {
+    let candidates = vec![candidate(2, false, "/a"), candidate(2, true, "/z")];
+    let resolution = resolve(&candidates);
+    assert_eq!(resolution[0], Some((1, DuplicateKind::Exact)));
+    assert_eq!(resolution[1], None);
+}
+
+#[test]
+fn empty_sessions_are_never_duplicates() {
+    let empty = Candidate {
+        fingerprint: Fingerprint::default(),
+        existing: false,
+        source_path: "/empty".to_string(),
+        project_path: "/tmp/project".to_string(),
+    };
+    let resolution = resolve(&[empty.clone(), empty]);
+    assert_eq!(resolution, vec![None, None]);
+    assert!(Fingerprint::default().summary().is_none());
+}
+
+#[test]
+fn short_or_cross_project_prefixes_are_kept() {
+    let opener = |turns: &[&str]| {
+        let messages = turns
+            .iter()
+            .enumerate()
+            .map(|(i, turn)| {
+                let role = if i % 2 == 0 {
+                    Role::User
+                } else {
+                    Role::Assistant
+                };
+                text(role, turn)
+            })
+            .collect();
+        Fingerprint::of(&session(Tool::Claude, messages))
+    };
+    let candidates = vec![
+        Candidate {
+            fingerprint: opener(&["continue"]),
+            existing: false,
+            source_path: "/short".to_string(),
+            project_path: "/tmp/project".to_string(),
+        },
+        Candidate {
+            fingerprint: opener(&["continue", "on it", "now the tests", "done", "thanks"]),
+            existing: false,
+            source_path: "/long".to_string(),
+            project_path: "/tmp/project".to_string(),
+        },
+        Candidate {
+            fingerprint: opener(&["continue", "on it", "now the tests", "done"]),
+            existing: false,
+            source_path: "/elsewhere".to_string(),
+            project_path: "/tmp/other".to_string(),
+        },
+    ];
+    assert_eq!(
+        candidates[0]
+            .fingerprint
+            .duplicate_of(&candidates[1].fingerprint),
+        Some(DuplicateKind::Prefix)
+    );
+    assert_eq!(resolve(&candidates), vec![None, None, None]);
+
+    let mut same_project = candidates[2].clone();
+    same_project.project_path = "/tmp/project".to_string();
+    let resolution = resolve(&[candidates[1].clone(), same_project]);
+    assert_eq!(resolution[1], Some((0, DuplicateKind::Prefix)));
+}
@@ -14,6 +14,8 @@
 //! | ✅    | Successful tool call             |
 //! | ❌    | Failed tool call                 |
 //! | ⏳    | Queue operation (sub-agent task) |
+//! | 💭    | Thinking (opt-in)                |
+//! | 🔧    | Tool call input (opt-in)         |
 //!
 //! # Design philosophy
 //!
@@ -21,11 +23,16 @@
 //! emoji is always the first character, making patterns like `grep "❌" file.md`
 //! instant to write and read.
 //!
-//! The formatter is intentionally lossy in some areas:
+//! The formatter is intentionally lossy by default:
 //! - `Thinking` blocks are suppressed (they contain internal LLM reasoning, not user-visible content)
 //! - `ToolUse` blocks are suppressed (they're paired with `ToolResult` which is shown)
 //! - Long tool summaries are pre-truncated by the parser, not the formatter
 //!
+//! `FormatterOptions` turns each of these back on: `💭` thinking lines, `🔧`
+//! input lines, indented excerpts under `❌` lines, `[HH:MM:SS]` after the
+//! speaker emoji, and a `📋 Usage:` line after each assistant message. Readers
+//! that scrape the header and summary ignore all of them.
+//!
 //! # TRADE-OFFS
 //!
 //! Using Unicode escape sequences for emoji constants (`\u{1f4cb}`) rather than
@@ -36,7 +43,10 @@ use std::io::Write;
 
 use crate::ast::*;
 use crate::error::CassioError;
-use crate::formatter::Formatter;
+use crate::formatter::{
+    Formatter, FormatterOptions, ToolInput, output_excerpt, tool_input_text, turn_usage_parts,
+};
+use crate::frontmatter::TranscriptFrontmatter;
 use crate::training::ParsedSession;
 
 // `pub(crate)` so search can gate matches on the speaker prefix of a line.
@@ -46,25 +56,35 @@ pub(crate) const EMOJI_ASSISTANT: &str = "\u{1f916}"; // 🤖
 pub(crate) const EMOJI_SUCCESS: &str = "\u{2705}"; // ✅
 pub(crate) const EMOJI_FAILURE: &str = "\u{274c}"; // ❌
 pub(crate) const EMOJI_QUEUE: &str = "\u{23f3}"; // ⏳
+pub(crate) const EMOJI_THINKING: &str = "\u{1f4ad}"; // 💭
+pub(crate) const EMOJI_TOOL_INPUT: &str = "\u{1f527}"; // 🔧
 
 /// Formatter that produces emoji-prefixed plain text transcripts.
-pub struct EmojiTextFormatter;
+#[derive(Debug, Clone, Default)]
+pub struct EmojiTextFormatter {
+    pub options: FormatterOptions,
+}
 
 impl Formatter for EmojiTextFormatter {
     /// Format a session as emoji-prefixed plain text.
     ///
     /// Output structure:
-    /// 1. Metadata header (session ID, project, start time, version, branch)
-    /// 2. Blank line
-    /// 3. All messages in chronological order
-    /// 4. Summary block (only when the session has at least one message)
+    /// 1. YAML frontmatter (only when `options.frontmatter` is set)
+    /// 2. Metadata header (session ID, project, start time, version, branch)
+    /// 3. Blank line
+    /// 4. All messages in chronological order
+    /// 5. Summary block (only when the session has at least one message)
     fn format(&self, parsed: &ParsedSession, writer: &mut dyn Write) -> Result<(), CassioError> {
         let session = &parsed.session;
+        if self.options.frontmatter {
+            let block = TranscriptFrontmatter::from_session(session).to_block()?;
+            writer.write_all(block.as_bytes())?;
+        }
         format_metadata(&session.metadata, writer)?;
         writeln!(writer)?;
 
         for msg in &session.messages {
-            format_message(msg, writer)?;
+            self.format_message(msg, session.metadata.model.as_deref(), writer)?;
         }
 
         format_summary(&session.stats, &session.metadata, writer)?;
@@ -122,54 +142,97 @@ fn format_metadata(meta: &SessionMetadata, w: &mut dyn Write) -> Result<(), Cass
     Ok(())
 }
 
-/// Emit all content blocks within a single message.
-///
-/// Role determines the emoji for text content. Thinking and ToolUse blocks are
-/// silently suppressed — thinking is internal LLM reasoning not intended for
-/// transcripts, and ToolUse is paired with the ToolResult which carries the
-/// visible output.
-fn format_message(msg: &Message, w: &mut dyn Write) -> Result<(), CassioError> {
-    for block in &msg.content {
-        match block {
-            ContentBlock::Text { text } => {
-                let emoji = match msg.role {
-                    Role::User => EMOJI_USER,
-                    Role::Assistant => EMOJI_ASSISTANT,
-                    Role::System => EMOJI_META,
-                };
-                writeln!(w, "{emoji} {text}")?;
-            }
-            ContentBlock::Thinking { .. } => {
-                // WHY: Thinking blocks contain extended reasoning tokens. They are
-                // not part of the conversation visible to the user and add noise.
-            }
-            ContentBlock::ToolUse { .. } => {
-                // WHY: Tool use is deferred — the ToolResult that follows contains
-                // both the tool name and the outcome, which is more informative.
-            }
-            ContentBlock::ToolResult {
-                name,
-                success,
-                summary,
-                ..
-            } => {
-                let emoji = if *success {
-                    EMOJI_SUCCESS
-                } else {
-                    EMOJI_FAILURE
-                };
-                writeln!(w, "{emoji} {name}: {summary}")?;
-            }
-            ContentBlock::ModelChange { model } => {
-                let short = shorten_model_name(model);
-                writeln!(w, "{EMOJI_META} Model: {short}")?;
-            }
-            ContentBlock::QueueOperation { summary } => {
-                writeln!(w, "{EMOJI_QUEUE} {summary}")?;
+impl EmojiTextFormatter {
+    /// Emit all content blocks within a single message.
+    ///
+    /// Role determines the emoji for text content. By default Thinking and
+    /// ToolUse blocks are silently suppressed — thinking is internal LLM
+    /// reasoning not intended for transcripts, and ToolUse is paired with the
+    /// ToolResult which carries the visible output.
+    fn format_message(
+        &self,
+        msg: &Message,
+        session_model: Option<&str>,
+        w: &mut dyn Write,
+    ) -> Result<(), CassioError> {
+        let options = &self.options;
+        let tool_input = options.tool_input.unwrap_or(ToolInput::Summary);
+        // The timestamp goes on the first line the message emits.
+        let mut stamp = msg
+            .timestamp
+            .filter(|_| options.timestamps.unwrap_or(false))
+            .map(|ts| format!("[{}] ", ts.format("%H:%M:%S")));
+
+        for block in &msg.content {
+            match block {
+                ContentBlock::Text { text } => {
+                    let emoji = match msg.role {
+                        Role::User => EMOJI_USER,
+                        Role::Assistant => EMOJI_ASSISTANT,
+                        Role::System => EMOJI_META,
+                    };
+                    let stamp = stamp.take().unwrap_or_default();
+                    writeln!(w, "{emoji} {stamp}{text}")?;
+                }
+                ContentBlock::Thinking { text } => {
+                    // WHY: Thinking blocks contain extended reasoning tokens. They are
+                    // not part of the conversation visible to the user and add noise.
+                    if options.show_thinking.unwrap_or(false) && !text.trim().is_empty() {
+                        let stamp = stamp.take().unwrap_or_default();
+                        writeln!(w, "{EMOJI_THINKING} {stamp}{}", text.trim())?;
+                    }
+                }
+                ContentBlock::ToolUse { name, input, .. } => {
+                    // WHY: Tool use is deferred — the ToolResult that follows contains
+                    // both the tool name and the outcome, which is more informative.
+                    if let Some(input) = tool_input_text(input, tool_input) {
+                        let stamp = stamp.take().unwrap_or_default();
+                        writeln!(w, "{EMOJI_TOOL_INPUT} {stamp}{name}: {input}")?;
+                    }
+                }
+                ContentBlock::ToolResult {
+                    name,
+                    success,
+                    summary,
+                    output,
+                    ..
+                } => {
+                    let emoji = if *success {
+                        EMOJI_SUCCESS
+                    } else {
+                        EMOJI_FAILURE
+                    };
+                    let stamp = stamp.take().unwrap_or_default();
+                    writeln!(w, "{emoji} {stamp}{name}: {summary}")?;
+                    if let Some(excerpt) =
+                        output_excerpt(output.as_deref(), options.failed_output_lines)
+                    {
+                        for line in excerpt.lines() {
+                            writeln!(w, "   {line}")?;
+                        }
+                    }
+                }
+                ContentBlock::ModelChange { model } => {
+                    let short = shorten_model_name(model);
+                    writeln!(w, "{EMOJI_META} Model: {short}")?;
+                }
+                ContentBlock::QueueOperation { summary } => {
+                    let stamp = stamp.take().unwrap_or_default();
+                    writeln!(w, "{EMOJI_QUEUE} {stamp}{summary}")?;
+                }
             }
         }
+
+        if options.turn_usage.unwrap_or(false)
+            && msg.role == Role::Assistant
+            && let Some(usage) = &msg.usage
+        {
+            let model = msg.model.as_deref().or(session_model);
+            let parts = turn_usage_parts(usage, model);
+            writeln!(w, "{EMOJI_META} Usage: {}", parts.join(", "))?;
+        }
+        Ok(())
     }
-    Ok(())
 }
 
 /// Emit the session summary block at the end of the transcript.
@@ -291,7 +354,7 @@ fn format_summary(
 ///
 /// WHY: Full Claude model identifiers include trailing date stamps (e.g.,
 /// `20251101`) that are not useful to readers and make the line visually noisy.
-fn shorten_model_name(model: &str) -> String {
+pub(crate) fn shorten_model_name(model: &str) -> String {
     if model == "<synthetic>" {
         return "synthetic".to_string();
     }
@@ -312,7 +375,7 @@ fn shorten_model_name(model: &str) -> String {
 ///
 /// Uses the largest non-zero unit: hours+minutes, minutes only, or seconds.
 /// Negative durations (clock skew) are clamped to zero.
-fn format_duration(seconds: i64) -> String {
+pub(crate) fn format_duration(seconds: i64) -> String {
     if seconds < 0 {
         return "0s".to_string();
     }
@@ -333,7 +396,7 @@ fn format_duration(seconds: i64) -> String {
 /// Format a token count with SI-style suffixes (K, M).
 ///
 /// Keeps the output compact — `1500` becomes `1.5K` rather than `1,500`.
-fn format_tokens(n: u64) -> String {
+pub(crate) fn format_tokens(n: u64) -> String {
     if n >= 1_000_000 {
         format!("{:.1}M", n as f64 / 1_000_000.0)
     } else if n >= 1_000 {
@@ -17,6 +17,7 @@ fn parsed_from_session(session: Session) -> ParsedSession {
                 source_record_count: Some(1),
                 source_format: Some("jsonl".to_string()),
                 source_root: None,
+                duplicates: Vec::new(),
             },
             TrainingMetadata {
                 project_path_raw: session.metadata.project_path.clone(),
@@ -161,7 +162,9 @@ fn make_test_session() -> Session {
 fn test_full_format_output() {
     let session = parsed_from_session(make_test_session());
     let mut buf = Vec::new();
-    EmojiTextFormatter.format(&session, &mut buf).unwrap();
+    EmojiTextFormatter::default()
+        .format(&session, &mut buf)
+        .unwrap();
     let output = String::from_utf8(buf).unwrap();
 
     assert!(output.contains("Session: test-session"));
@@ -179,6 +182,35 @@ fn test_full_format_output() {
     assert!(output.contains("Files: 1 read"));
 }
 
+#[test]
+fn test_frontmatter_precedes_emoji_header() {
+    let session = parsed_from_session(make_test_session());
+    let mut buf = Vec::new();
+    EmojiTextFormatter {
+        options: FormatterOptions {
+            frontmatter: true,
+            ..Default::default()
+        },
+    }
+    .format(&session, &mut buf)
+    .unwrap();
+    let output = String::from_utf8(buf).unwrap();
+
+    let fm = crate::frontmatter::parse(&output).unwrap();
+    assert_eq!(fm.session_id, "test-session");
+    assert_eq!(fm.project_path, "/home/user/project");
+    assert!(crate::frontmatter::body(&output).starts_with("📋 Session: test-session\n"));
+
+    let mut plain = Vec::new();
+    EmojiTextFormatter::default()
+        .format(&session, &mut plain)
+        .unwrap();
+    assert_eq!(
+        crate::frontmatter::body(&output),
+        String::from_utf8(plain).unwrap()
+    );
+}
+
 #[test]
 fn test_format_tool_result_success() {
     let session = parsed_from_session(Session {
@@ -202,6 +234,7 @@ fn test_format_tool_result_success() {
                 name: "Read".to_string(),
                 success: true,
                 summary: "file=\"test.rs\"".to_string(),
+                output: None,
             }],
             usage: None,
         }],
@@ -212,7 +245,9 @@ fn test_format_tool_result_success() {
         },
     });
     let mut buf = Vec::new();
-    EmojiTextFormatter.format(&session, &mut buf).unwrap();
+    EmojiTextFormatter::default()
+        .format(&session, &mut buf)
+        .unwrap();
     let output = String::from_utf8(buf).unwrap();
     assert!(output.contains("✅ Read: file=\"test.rs\""));
 }
@@ -240,6 +275,10 @@ fn test_format_tool_result_failure() {
                 name: "Bash".to_string(),
                 success: false,
                 summary: "exit code 1".to_string(),
+                output: Some(
+                    "error[E0425]: cannot find value\n --> src/lib.rs:3\nerror: aborting"
+                        .to_string(),
+                ),
             }],
             usage: None,
         }],
@@ -250,9 +289,78 @@ fn test_format_tool_result_failure() {
         },
     });
     let mut buf = Vec::new();
-    EmojiTextFormatter.format(&session, &mut buf).unwrap();
+    EmojiTextFormatter::default()
+        .format(&session, &mut buf)
+        .unwrap();
     let output = String::from_utf8(buf).unwrap();
-    assert!(output.contains("❌ Bash: exit code 1"));
+    assert!(output.contains("❌ Bash: exit code 1\n"));
+    assert!(!output.contains("E0425"), "no excerpt by default");
+}
+
+#[test]
+fn test_options_restore_suppressed_detail() {
+    let mut session = make_test_session();
+    session.messages[1].content = vec![
+        ContentBlock::Thinking {
+            text: "greet back".to_string(),
+        },
+        ContentBlock::Text {
+            text: "Hi there!".to_string(),
+        },
+        ContentBlock::ToolUse {
+            id: "t1".to_string(),
+            name: "Bash".to_string(),
+            input: serde_json::json!({"command": "cargo build"}),
+        },
+        ContentBlock::ToolResult {
+            tool_use_id: "t1".to_string(),
+            name: "Bash".to_string(),
+            success: false,
+            summary: "cargo build".to_string(),
+            output: Some("error: one\nerror: two\nerror: three".to_string()),
+        },
+    ];
+    session.messages[1].usage = Some(TokenUsage {
+        input_tokens: 1200,
+        output_tokens: 300,
+        ..Default::default()
+    });
+    let session = parsed_from_session(session);
+
+    let render = |options: FormatterOptions| {
+        let mut buf = Vec::new();
+        EmojiTextFormatter { options }
+            .format(&session, &mut buf)
+            .unwrap();
+        String::from_utf8(buf).unwrap()
+    };
+
+    let terse = render(FormatterOptions::default());
+    assert!(terse.contains("🤖 Hi there!\n❌ Bash: cargo build\n"));
+    assert!(!terse.contains("greet back") && !terse.contains("Usage:"));
+
+    let verbose = render(FormatterOptions {
+        show_thinking: Some(true),
+        tool_input: Some(ToolInput::Inline),
+        failed_output_lines: 2,
+        timestamps: Some(true),
+        turn_usage: Some(true),
+        ..Default::default()
+    });
+    assert!(verbose.contains("👤 [10:00:01] Hello!\n"));
+    assert!(verbose.contains(
+        "💭 [10:00:02] greet back\n🤖 Hi there!\n🔧 Bash: {\"command\":\"cargo build\"}\n"
+    ));
+    assert!(
+        verbose.contains("❌ Bash: cargo build\n   error: one\n   error: two\n   … 1 more line\n")
+    );
+    assert!(verbose.contains("📋 Usage: 1.2K in, 300 out, $"));
+
+    let full = render(FormatterOptions {
+        tool_input: Some(ToolInput::Full),
+        ..Default::default()
+    });
+    assert!(full.contains("🔧 Bash: {\n  \"command\": \"cargo build\"\n}\n"));
 }
 
 #[test]
@@ -273,7 +381,9 @@ fn test_format_empty_stats_no_summary() {
         stats: SessionStats::default(),
     });
     let mut buf = Vec::new();
-    EmojiTextFormatter.format(&session, &mut buf).unwrap();
+    EmojiTextFormatter::default()
+        .format(&session, &mut buf)
+        .unwrap();
     let output = String::from_utf8(buf).unwrap();
     assert!(!output.contains("Summary"));
 }
@@ -301,7 +411,9 @@ fn test_format_context_line() {
         },
     });
     let mut buf = Vec::new();
-    EmojiTextFormatter.format(&session, &mut buf).unwrap();
+    EmojiTextFormatter::default()
+        .format(&session, &mut buf)
+        .unwrap();
     let output = String::from_utf8(buf).unwrap();
     assert!(
         output.contains("Context: 43.3K / 1.0M (4%)"),
@@ -0,0 +1,405 @@
+//! Self-contained HTML formatter for reading and sharing single sessions.
+//!
+//! Produces one HTML page per session: a metadata header with session-level
+//! badges, one chat bubble per turn, and a footer with the session summary.
+//! Tool calls are rendered as collapsible `<details>` elements that pair each
+//! `ToolUse` input with its `ToolResult` output, so a long agentic run reads as
+//! a list of one-line summaries until expanded. Thinking blocks are kept, but
+//! collapsed, and assistant turns that carry usage get token and
+//! estimated-cost badges; `FormatterOptions` can turn either off, shorten the
+//! tool input, and add an output excerpt to failed calls.
+//!
+//! # TRADE-OFFS
+//!
+//! - CSS is inlined and there is no JavaScript or external asset, so a page can
+//!   be mailed or attached to a ticket and still render. The price is a few KB
+//!   of repeated styles per file.
+//! - Tool output is the parser's `summary`, not the raw tool output; the AST
+//!   only keeps the start of a failed call's output, for the excerpt.
+//! - Messages are rendered as preformatted text, not as markdown. Rendering
+//!   markdown would need a parser dependency and would make the page disagree
+//!   with what the model actually saw.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write as _;
+use std::io::Write;
+
+use crate::ast::*;
+use crate::error::CassioError;
+use crate::formatter::emoji_text::{format_duration, format_tokens, shorten_model_name};
+use crate::formatter::{
+    Formatter, FormatterOptions, ToolInput, output_excerpt, tool_input_text, turn_usage_parts,
+};
+use crate::pricing;
+use crate::training::ParsedSession;
+
+const STYLE: &str = r#"
+:root { --bg: #f6f7f9; --fg: #1d2330; --muted: #6b7280; --card: #fff; --user: #e8f0fe;
+  --border: #d9dde3; --ok: #15803d; --fail: #b91c1c; --code: #f1f3f5; }
+@media (prefers-color-scheme: dark) {
+  :root { --bg: #14171c; --fg: #e4e7eb; --muted: #9aa3ae; --card: #1d2128; --user: #1f2c44;
+    --border: #2f3540; --ok: #4ade80; --fail: #f87171; --code: #252a33; }
+}
+* { box-sizing: border-box; }
+body { margin: 0; background: var(--bg); color: var(--fg);
+  font: 15px/1.5 -apple-system, BlinkMacSystemFont, "Segoe UI", sans-serif; }
+header, main, footer { max-width: 920px; margin: 0 auto; padding: 16px; }
+h1 { font-size: 1.3em; margin: 0 0 8px; }
+dl { display: grid; grid-template-columns: max-content 1fr; gap: 2px 12px; margin: 0 0 8px; }
+dt { color: var(--muted); }
+dd { margin: 0; overflow-wrap: anywhere; }
+.badge { display: inline-block; font-size: 12px; padding: 1px 8px; margin: 0 4px 4px 0;
+  border: 1px solid var(--border); border-radius: 10px; color: var(--muted); }
+.turn { margin: 12px 0; }
+.turn.user { margin-left: 15%; }
+.turn.assistant { margin-right: 15%; }
+.turn-head { font-size: 12px; color: var(--muted); margin-bottom: 4px; }
+.turn-head .role { font-weight: 600; color: var(--fg); margin-right: 8px; }
+.bubble { background: var(--card); border: 1px solid var(--border); border-radius: 12px;
+  padding: 10px 14px; margin: 4px 0; white-space: pre-wrap; overflow-wrap: anywhere; }
+.user .bubble { background: var(--user); }
+.event { font-size: 13px; color: var(--muted); margin: 4px 0; }
+details { background: var(--card); border: 1px solid var(--border); border-radius: 8px;
+  margin: 4px 0; padding: 4px 10px; font-size: 13px; }
+summary { cursor: pointer; }
+details.failed { border-color: var(--fail); }
+.ok { color: var(--ok); }
+.fail { color: var(--fail); }
+.label { font-size: 11px; text-transform: uppercase; color: var(--muted); margin-top: 6px; }
+pre { background: var(--code); border-radius: 6px; padding: 8px; margin: 4px 0;
+  white-space: pre-wrap; overflow-wrap: anywhere; font-size: 12px; }
+details.thinking summary { color: var(--muted); font-style: italic; }
+footer { color: var(--muted); font-size: 13px; }
+"#;
+
+/// Formatter that produces a single-file HTML page per session.
+#[derive(Debug, Clone, Default)]
+pub struct HtmlFormatter {
+    pub options: FormatterOptions,
+}
+
+/// A tool call's outcome: success, the parser summary, and any failed output.
+type Outcome<'a> = (bool, &'a str, Option<&'a str>);
+
+impl Formatter for HtmlFormatter {
+    fn format(&self, parsed: &ParsedSession, writer: &mut dyn Write) -> Result<(), CassioError> {
+        let session = &parsed.session;
+        let meta = &session.metadata;
+        let title = meta.title.clone().unwrap_or_else(|| {
+            format!(
+                "{} session {}",
+                meta.tool,
+                meta.started_at.format("%Y-%m-%d %H:%M")
+            )
+        });
+
+        let mut page = String::new();
+        let _ = write!(
+            page,
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+             <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+             <title>{}</title>\n<style>{STYLE}</style>\n</head>\n<body>\n",
+            escape_html(&title)
+        );
+        render_header(&mut page, &title, session);
+        page.push_str("<main>\n");
+        let tool_results = tool_results(&session.messages);
+        let tool_use_ids: HashSet<&str> = session
+            .messages
+            .iter()
+            .flat_map(|msg| &msg.content)
+            .filter_map(|block| match block {
+                ContentBlock::ToolUse { id, .. } if !id.is_empty() => Some(id.as_str()),
+                _ => None,
+            })
+            .collect();
+        for msg in &session.messages {
+            self.render_message(&mut page, msg, meta, &tool_results, &tool_use_ids);
+        }
+        page.push_str("</main>\n");
+        render_footer(&mut page, &session.stats);
+        page.push_str("</body>\n</html>\n");
+
+        writer.write_all(page.as_bytes())?;
+        Ok(())
+    }
+}
+
+impl HtmlFormatter {
+    /// Emit one turn. Turns with nothing visible (e.g. a user message holding
+    /// only tool results already shown with their calls) are skipped.
+    fn render_message(
+        &self,
+        page: &mut String,
+        msg: &Message,
+        meta: &SessionMetadata,
+        tool_results: &HashMap<&str, Outcome>,
+        tool_use_ids: &HashSet<&str>,
+    ) {
+        let options = &self.options;
+        let excerpt_lines = options.failed_output_lines;
+        let mut body = String::new();
+        for block in &msg.content {
+            match block {
+                ContentBlock::Text { text } => {
+                    if !text.trim().is_empty() {
+                        let text = escape_html(text);
+                        let _ = writeln!(body, "<div class=\"bubble\">{text}</div>");
+                    }
+                }
+                ContentBlock::Thinking { text } => {
+                    if options.show_thinking.unwrap_or(true) && !text.trim().is_empty() {
+                        let _ = writeln!(
+                            body,
+                            "<details class=\"thinking\"><summary>Thinking</summary>\
+                             <pre>{}</pre></details>",
+                            escape_html(text)
+                        );
+                    }
+                }
+                ContentBlock::ToolUse { id, name, input } => {
+                    let result = tool_results.get(id.as_str()).copied();
+                    let input =
+                        tool_input_text(input, options.tool_input.unwrap_or(ToolInput::Full));
+                    render_tool(&mut body, name, input.as_deref(), result, excerpt_lines);
+                }
+                ContentBlock::ToolResult {
+                    tool_use_id,
+                    name,
+                    success,
+                    summary,
+                    output,
+                } => {
+                    // Shown with its call when the call is in the session.
+                    if !tool_use_ids.contains(tool_use_id.as_str()) {
+                        let result = (*success, summary.as_str(), output.as_deref());
+                        render_tool(&mut body, name, None, Some(result), excerpt_lines);
+                    }
+                }
+                ContentBlock::ModelChange { model } => {
+                    let _ = writeln!(
+                        body,
+                        "<div class=\"event\">Model: {}</div>",
+                        escape_html(&shorten_model_name(model))
+                    );
+                }
+                ContentBlock::QueueOperation { summary } => {
+                    let _ = writeln!(
+                        body,
+                        "<div class=\"event\">\u{23f3} {}</div>",
+                        escape_html(summary)
+                    );
+                }
+            }
+        }
+        if body.is_empty() {
+            return;
+        }
+
+        let (class, label) = match msg.role {
+            Role::User => ("user", "User"),
+            Role::Assistant => ("assistant", "Assistant"),
+            Role::System => ("system", "System"),
+        };
+        let _ = write!(
+            page,
+            "<section class=\"turn {class}\">\n<div class=\"turn-head\"><span class=\"role\">{label}</span>"
+        );
+        if let Some(ts) = msg.timestamp.filter(|_| options.timestamps.unwrap_or(true)) {
+            let _ = write!(
+                page,
+                "<time datetime=\"{}\">{}</time> ",
+                ts.to_rfc3339(),
+                ts.format("%H:%M:%S")
+            );
+        }
+        if let Some(model) = msg.model.as_deref() {
+            let _ = write!(
+                page,
+                "<span class=\"badge\">{}</span>",
+                escape_html(&shorten_model_name(model))
+            );
+        }
+        if let Some(usage) = msg
+            .usage
+            .as_ref()
+            .filter(|_| options.turn_usage.unwrap_or(true))
+        {
+            let model = msg.model.as_deref().or(meta.model.as_deref());
+            let (cost, tokens): (Vec<String>, Vec<String>) = turn_usage_parts(usage, model)
+                .into_iter()
+                .partition(|part| part.starts_with('$'));
+            for badge in std::iter::once(tokens.join(" \u{b7} ")).chain(cost) {
+                let _ = write!(page, "<span class=\"badge\">{badge}</span>");
+            }
+        }
+        let _ = write!(page, "</div>\n{body}</section>\n");
+    }
+}
+
+/// `ToolResult` outcomes keyed by the `ToolUse` id they answer.
+fn tool_results(messages: &[Message]) -> HashMap<&str, Outcome<'_>> {
+    messages
+        .iter()
+        .flat_map(|msg| &msg.content)
+        .filter_map(|block| match block {
+            ContentBlock::ToolResult {
+                tool_use_id,
+                success,
+                summary,
+                output,
+                ..
+            } if !tool_use_id.is_empty() => Some((
+                tool_use_id.as_str(),
+                (*success, summary.as_str(), output.as_deref()),
+            )),
+            _ => None,
+        })
+        .collect()
+}
+
+/// A collapsible tool call: status and name in the summary line, input and
+/// output inside, and up to `excerpt_lines` of a failed call's output.
+fn render_tool(
+    body: &mut String,
+    name: &str,
+    input: Option<&str>,
+    result: Option<Outcome>,
+    excerpt_lines: usize,
+) {
+    let (class, status) = match result {
+        Some((true, ..)) => ("tool", "<span class=\"ok\">\u{2705}</span>"),
+        Some((false, ..)) => ("tool failed", "<span class=\"fail\">\u{274c}</span>"),
+        None => ("tool", "<span>\u{2026}</span>"),
+    };
+    let _ = write!(
+        body,
+        "<details class=\"{class}\"><summary>{status} <code>{}</code></summary>",
+        escape_html(name)
+    );
+    if let Some(input) = input {
+        let _ = write!(
+            body,
+            "<div class=\"label\">Input</div><pre>{}</pre>",
+            escape_html(input)
+        );
+    }
+    if let Some((_, summary, _)) = result.filter(|(_, summary, _)| !summary.is_empty()) {
+        let _ = write!(
+            body,
+            "<div class=\"label\">Output</div><pre>{}</pre>",
+            escape_html(summary)
+        );
+    }
+    if let Some(excerpt) = result.and_then(|(_, _, output)| output_excerpt(output, excerpt_lines)) {
+        let _ = write!(
+            body,
+            "<div class=\"label\">Error</div><pre>{}</pre>",
+            escape_html(&excerpt)
+        );
+    }
+    body.push_str("</details>\n");
+}
+
+fn render_header(page: &mut String, title: &str, session: &Session) {
+    let meta = &session.metadata;
+    let stats = &session.stats;
+    let _ = write!(page, "<header>\n<h1>{}</h1>\n<dl>\n", escape_html(title));
+    let mut field = |name: &str, value: &str| {
+        let _ = writeln!(page, "<dt>{name}</dt><dd>{}</dd>", escape_html(value));
+    };
+    field("Session", &meta.session_id);
+    field("Tool", &meta.tool.to_string());
+    field("Project", &meta.project_path);
+    field("Started", &meta.started_at.to_rfc3339());
+    field("Session Kind", &meta.session_kind.to_string());
+    if let Some(model) = &meta.model {
+        field("Model", &shorten_model_name(model));
+    }
+    if let Some(version) = &meta.version {
+        field("Version", version);
+    }
+    if let Some(branch) = &meta.git_branch {
+        field("Branch", branch);
+    }
+    page.push_str("</dl>\n<div>");
+
+    let mut badge = |text: String| {
+        let _ = write!(page, "<span class=\"badge\">{}</span>", escape_html(&text));
+    };
+    if let Some(secs) = stats.duration_seconds {
+        badge(format_duration(secs));
+    }
+    badge(format!(
+        "{} user \u{b7} {} assistant",
+        stats.user_messages, stats.assistant_messages
+    ));
+    badge(format!(
+        "{} tool calls \u{b7} {} failed",
+        stats.tool_calls, stats.tool_errors
+    ));
+    let tokens = &stats.total_tokens;
+    if tokens.input_tokens > 0 || tokens.output_tokens > 0 {
+        badge(format!(
+            "{} in \u{b7} {} out",
+            format_tokens(tokens.input_tokens),
+            format_tokens(tokens.output_tokens)
+        ));
+    }
+    let cost = stats.cost.or_else(|| {
+        pricing::estimate_cost(
+            meta.model.as_deref(),
+            tokens.input_tokens,
+            tokens.output_tokens,
+            tokens.cache_read_tokens,
+            tokens.cache_creation_tokens,
+            None,
+        )
+    });
+    if let Some(cost) = cost {
+        badge(format!("${cost:.2}"));
+    }
+    page.push_str("</div>\n</header>\n");
+}
+
+fn render_footer(page: &mut String, stats: &SessionStats) {
+    let files = [
+        ("read", stats.files_read.len()),
+        ("written", stats.files_written.len()),
+        ("edited", stats.files_edited.len()),
+    ];
+    let files: Vec<String> = files
+        .iter()
+        .filter(|(_, count)| *count > 0)
+        .map(|(label, count)| format!("{count} {label}"))
+        .collect();
+    page.push_str("<footer>");
+    if !files.is_empty() {
+        let _ = write!(page, "Files: {}. ", files.join(", "));
+    }
+    let _ = writeln!(
+        page,
+        "Generated by cassio {}.</footer>",
+        env!("CARGO_PKG_VERSION")
+    );
+}
+
+/// Escape `text` for HTML element content and quoted attribute values.
+pub(crate) fn escape_html(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+#[path = "html_test.rs"]
+mod tests;
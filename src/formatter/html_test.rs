@@ -0,0 +1,200 @@
+use super::*;
+use crate::training::{
+    ParsedSession, TrainingMetadata, TrainingSession, TrainingSource, training_stats_from_session,
+};
+use chrono::{TimeZone, Utc};
+
+fn parsed_from_session(session: Session) -> ParsedSession {
+    ParsedSession {
+        training: TrainingSession::new(
+            "test.v1",
+            TrainingSource {
+                tool: session.metadata.tool.to_string(),
+                source_path: "/tmp/source".to_string(),
+                session_id: session.metadata.session_id.clone(),
+                source_hash: "sha256:test".to_string(),
+                source_record_count: Some(1),
+                source_format: Some("jsonl".to_string()),
+                source_root: None,
+                duplicates: Vec::new(),
+            },
+            TrainingMetadata {
+                project_path_raw: session.metadata.project_path.clone(),
+                project_path_sanitized: session.metadata.project_path.clone(),
+                started_at: session.metadata.started_at,
+                ended_at: None,
+                git_branch: session.metadata.git_branch.clone(),
+                title: session.metadata.title.clone(),
+                session_kind: session.metadata.session_kind.to_string(),
+                models_seen: session.metadata.model.clone().into_iter().collect(),
+                version: session.metadata.version.clone(),
+            },
+            training_stats_from_session(&session.stats),
+        ),
+        session,
+    }
+}
+
+fn message(role: Role, content: Vec<ContentBlock>, usage: Option<TokenUsage>) -> Message {
+    Message {
+        role,
+        timestamp: Some(Utc.with_ymd_and_hms(2026, 4, 1, 9, 0, 5).unwrap()),
+        model: (role == Role::Assistant).then(|| "claude-opus-4-5-20251101".to_string()),
+        content,
+        usage,
+    }
+}
+
+fn sample_session() -> Session {
+    Session {
+        metadata: SessionMetadata {
+            session_id: "s-42".to_string(),
+            tool: Tool::Claude,
+            project_path: "/work/faber".to_string(),
+            started_at: Utc.with_ymd_and_hms(2026, 4, 1, 9, 0, 0).unwrap(),
+            session_kind: SessionKind::Human,
+            version: Some("2.1.0".to_string()),
+            git_branch: Some("main".to_string()),
+            model: Some("claude-opus-4-5-20251101".to_string()),
+            title: None,
+        },
+        messages: vec![
+            message(
+                Role::User,
+                vec![ContentBlock::Text {
+                    text: "why does <script> fail?\nsecond line".to_string(),
+                }],
+                None,
+            ),
+            message(
+                Role::Assistant,
+                vec![
+                    ContentBlock::Thinking {
+                        text: "check the test runner".to_string(),
+                    },
+                    ContentBlock::Text {
+                        text: "Running the tests.".to_string(),
+                    },
+                    ContentBlock::ToolUse {
+                        id: "t1".to_string(),
+                        name: "Bash".to_string(),
+                        input: serde_json::json!({"command": "cargo test"}),
+                    },
+                ],
+                Some(TokenUsage {
+                    input_tokens: 1500,
+                    output_tokens: 200,
+                    cache_read_tokens: 0,
+                    cache_creation_tokens: 0,
+                }),
+            ),
+            message(
+                Role::User,
+                vec![ContentBlock::ToolResult {
+                    tool_use_id: "t1".to_string(),
+                    name: "Bash".to_string(),
+                    success: false,
+                    summary: "1 test failed".to_string(),
+                    output: Some("test it_runs ... FAILED\npanicked at src/lib.rs:3".to_string()),
+                }],
+                None,
+            ),
+        ],
+        stats: SessionStats {
+            user_messages: 1,
+            assistant_messages: 1,
+            tool_calls: 1,
+            tool_errors: 1,
+            duration_seconds: Some(300),
+            ..Default::default()
+        },
+    }
+}
+
+fn render(formatter: &HtmlFormatter, session: Session) -> String {
+    let mut buf = Vec::new();
+    formatter
+        .format(&parsed_from_session(session), &mut buf)
+        .unwrap();
+    String::from_utf8(buf).unwrap()
+}
+
+#[test]
+fn test_html_is_a_single_self_contained_page() {
+    let html = render(&HtmlFormatter::default(), sample_session());
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<style>"));
+    assert!(!html.contains("<script"), "no scripts");
+    assert!(!html.contains("<link"), "no external stylesheets");
+    assert!(!html.contains("src=\""), "no external assets");
+    assert!(html.contains("<dt>Project</dt><dd>/work/faber</dd>"));
+    assert!(html.contains("<dt>Model</dt><dd>opus-4.5</dd>"));
+    assert!(html.contains("<span class=\"badge\">5m</span>"));
+}
+
+#[test]
+fn test_html_escapes_text_and_keeps_newlines() {
+    let html = render(&HtmlFormatter::default(), sample_session());
+    assert!(
+        html.contains("<div class=\"bubble\">why does &lt;script&gt; fail?\nsecond line</div>")
+    );
+}
+
+#[test]
+fn test_html_pairs_tool_calls_with_their_results() {
+    let html = render(&HtmlFormatter::default(), sample_session());
+    assert!(html.contains("<details class=\"tool failed\">"));
+    assert!(html.contains("<code>Bash</code>"));
+    assert!(html.contains("&quot;command&quot;: &quot;cargo test&quot;"));
+    assert!(html.contains("<pre>1 test failed</pre>"));
+    // The user turn holding only the result is folded into the call.
+    assert_eq!(html.matches("<section class=\"turn user\">").count(), 1);
+}
+
+#[test]
+fn test_html_shows_usage_badges_per_turn() {
+    let html = render(&HtmlFormatter::default(), sample_session());
+    assert!(html.contains("<span class=\"badge\">1.5K in \u{b7} 200 out</span>"));
+    // opus-4.5: 1500 in @ $5/MTok + 200 out @ $25/MTok
+    assert!(html.contains("<span class=\"badge\">$0.0125</span>"));
+}
+
+#[test]
+fn test_html_thinking_is_optional() {
+    let html = render(&HtmlFormatter::default(), sample_session());
+    assert!(html.contains("<details class=\"thinking\"><summary>Thinking</summary>"));
+    assert!(html.contains("check the test runner"));
+
+    let html = render(
+        &HtmlFormatter {
+            options: FormatterOptions {
+                show_thinking: Some(false),
+                ..Default::default()
+            },
+        },
+        sample_session(),
+    );
+    assert!(!html.contains("check the test runner"));
+}
+
+#[test]
+fn test_html_options_trim_or_extend_detail() {
+    let html = render(
+        &HtmlFormatter {
+            options: FormatterOptions {
+                tool_input: Some(ToolInput::Summary),
+                failed_output_lines: 1,
+                timestamps: Some(false),
+                turn_usage: Some(false),
+                ..Default::default()
+            },
+        },
+        sample_session(),
+    );
+    assert!(!html.contains("&quot;command&quot;"));
+    assert!(html.contains(
+        "<div class=\"label\">Error</div><pre>test it_runs ... FAILED\n\u{2026} 1 more line</pre>"
+    ));
+    assert!(!html.contains("<time "));
+    assert!(!html.contains("$0.0125"));
+}
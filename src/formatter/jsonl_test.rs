@@ -41,6 +41,7 @@ fn test_jsonl_output_valid_json_lines() {
                 source_record_count: Some(1),
                 source_format: Some("jsonl".to_string()),
                 source_root: None,
+                duplicates: Vec::new(),
             },
             TrainingMetadata {
                 project_path_raw: "/proj".to_string(),
@@ -0,0 +1,461 @@
+//! GitHub-flavoured markdown formatter for reading sessions in a repository or
+//! wiki.
+//!
+//! Each session becomes one document: a YAML frontmatter block with the session
+//! metadata and totals, a `#` title, and one `##` heading per turn. Consecutive
+//! messages from the same speaker share a turn, so an assistant run split over
+//! many API messages reads as one answer. Message text is written verbatim,
+//! since it is already markdown, with any code fence the model left open closed
+//! at the end of the message. Each run of tool calls becomes a single collapsed
+//! `<details>` block that pairs every `ToolUse` input with its `ToolResult`
+//! output; thinking is collapsed the same way. `FormatterOptions` can drop
+//! thinking and timestamps, shorten the tool input, add an output excerpt to
+//! failed calls, and annotate each assistant turn with its tokens and cost.
+//!
+//! Files are written as `<stem>.markdown`, not `.md`: search, summary, compact,
+//! and the other archive readers parse the emoji-text `.md` transcripts, and
+//! keep doing so when both formats are exported side by side.
+//!
+//! # TRADE-OFFS
+//!
+//! - Verbatim text means a message that is not valid markdown renders however
+//!   GitHub interprets it (for example, a line starting with `#` becomes a
+//!   heading). Escaping everything would break the code blocks and lists that
+//!   make this format worth having.
+//! - Tool output is the parser's `summary`, not the raw tool output; the AST
+//!   only keeps the start of a failed call's output, for the excerpt.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write as _;
+use std::io::Write;
+
+use serde::Serialize;
+
+use crate::ast::*;
+use crate::error::CassioError;
+use crate::formatter::emoji_text::{
+    EMOJI_FAILURE, EMOJI_QUEUE, EMOJI_SUCCESS, format_duration, shorten_model_name,
+};
+use crate::formatter::html::escape_html;
+use crate::formatter::{
+    Formatter, FormatterOptions, ToolInput, output_excerpt, tool_input_text, turn_usage_parts,
+};
+use crate::pricing;
+use crate::training::ParsedSession;
+
+/// Formatter that produces one GitHub-flavoured markdown document per session.
+#[derive(Debug, Clone, Default)]
+pub struct MarkdownFormatter {
+    pub options: FormatterOptions,
+}
+
+/// Session-level fields written as YAML frontmatter.
+#[derive(Serialize)]
+struct Frontmatter<'a> {
+    title: &'a str,
+    session_id: &'a str,
+    tool: String,
+    project: &'a str,
+    started: String,
+    session_kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    model: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    version: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    git_branch: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duration: Option<String>,
+    user_messages: u32,
+    assistant_messages: u32,
+    tool_calls: u32,
+    tool_errors: u32,
+    input_tokens: u64,
+    output_tokens: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cost_usd: Option<f64>,
+}
+
+/// One visible piece of a turn, in order.
+enum Item<'a> {
+    Text(&'a str),
+    Thinking(&'a str),
+    Tool {
+        name: &'a str,
+        input: Option<String>,
+        result: Option<Outcome<'a>>,
+    },
+    Event(String),
+}
+
+/// A tool call's outcome: success, the parser summary, and any failed output.
+type Outcome<'a> = (bool, &'a str, Option<&'a str>);
+
+/// Consecutive messages from one speaker.
+struct Turn<'a> {
+    role: Role,
+    message: &'a Message,
+    items: Vec<Item<'a>>,
+    /// Usage summed over the turn's messages, when any carried usage.
+    usage: Option<TokenUsage>,
+}
+
+impl Formatter for MarkdownFormatter {
+    fn format(&self, parsed: &ParsedSession, writer: &mut dyn Write) -> Result<(), CassioError> {
+        let session = &parsed.session;
+        let meta = &session.metadata;
+        let title = meta.title.clone().unwrap_or_else(|| {
+            format!(
+                "{} session {}",
+                meta.tool,
+                meta.started_at.format("%Y-%m-%d %H:%M")
+            )
+        });
+
+        let mut doc = String::new();
+        render_frontmatter(&mut doc, &title, session)?;
+        let _ = writeln!(doc, "# {title}");
+
+        let results = tool_results(&session.messages);
+        let mut turns: Vec<Turn> = Vec::new();
+        for msg in &session.messages {
+            let items = self.items(msg, &results);
+            if items.is_empty() {
+                continue;
+            }
+            match turns.last_mut() {
+                Some(turn) if turn.role == msg.role => {
+                    turn.items.extend(items);
+                    if let Some(usage) = &msg.usage {
+                        add_usage(turn.usage.get_or_insert_default(), usage);
+                    }
+                }
+                _ => turns.push(Turn {
+                    role: msg.role,
+                    message: msg,
+                    items,
+                    usage: msg.usage.clone(),
+                }),
+            }
+        }
+        for turn in &turns {
+            self.render_turn(&mut doc, turn, meta.model.as_deref());
+        }
+
+        writer.write_all(doc.as_bytes())?;
+        Ok(())
+    }
+}
+
+impl MarkdownFormatter {
+    /// The visible items of one message. Tool results already shown with their
+    /// calls are left out, so a user message holding only those is empty.
+    fn items<'a>(
+        &self,
+        msg: &'a Message,
+        results: &HashMap<&'a str, Outcome<'a>>,
+    ) -> Vec<Item<'a>> {
+        let tool_input = self.options.tool_input.unwrap_or(ToolInput::Full);
+        let mut items = Vec::new();
+        for block in &msg.content {
+            match block {
+                ContentBlock::Text { text } => {
+                    if !text.trim().is_empty() {
+                        items.push(Item::Text(text));
+                    }
+                }
+                ContentBlock::Thinking { text } => {
+                    if self.options.show_thinking.unwrap_or(true) && !text.trim().is_empty() {
+                        items.push(Item::Thinking(text));
+                    }
+                }
+                ContentBlock::ToolUse { id, name, input } => items.push(Item::Tool {
+                    name,
+                    input: tool_input_text(input, tool_input),
+                    result: results.get(id.as_str()).copied(),
+                }),
+                ContentBlock::ToolResult {
+                    tool_use_id,
+                    name,
+                    success,
+                    summary,
+                    output,
+                } => {
+                    // Shown with its call when the call is in the session.
+                    let paired = results.contains_key(tool_use_id.as_str());
+                    if !paired {
+                        items.push(Item::Tool {
+                            name,
+                            input: None,
+                            result: Some((*success, summary, output.as_deref())),
+                        });
+                    }
+                }
+                ContentBlock::ModelChange { model } => {
+                    items.push(Item::Event(format!(
+                        "*Model: {}*",
+                        shorten_model_name(model)
+                    )));
+                }
+                ContentBlock::QueueOperation { summary } => {
+                    items.push(Item::Event(format!("> {EMOJI_QUEUE} {summary}")));
+                }
+            }
+        }
+        items
+    }
+}
+
+fn render_frontmatter(doc: &mut String, title: &str, session: &Session) -> Result<(), CassioError> {
+    let meta = &session.metadata;
+    let stats = &session.stats;
+    let tokens = &stats.total_tokens;
+    let cost = stats.cost.or_else(|| {
+        pricing::estimate_cost(
+            meta.model.as_deref(),
+            tokens.input_tokens,
+            tokens.output_tokens,
+            tokens.cache_read_tokens,
+            tokens.cache_creation_tokens,
+            None,
+        )
+    });
+    let frontmatter = Frontmatter {
+        title,
+        session_id: &meta.session_id,
+        tool: meta.tool.to_string(),
+        project: &meta.project_path,
+        started: meta.started_at.to_rfc3339(),
+        session_kind: meta.session_kind.to_string(),
+        model: meta.model.as_deref().map(shorten_model_name),
+        version: meta.version.as_deref(),
+        git_branch: meta.git_branch.as_deref(),
+        duration: stats.duration_seconds.map(format_duration),
+        user_messages: stats.user_messages,
+        assistant_messages: stats.assistant_messages,
+        tool_calls: stats.tool_calls,
+        tool_errors: stats.tool_errors,
+        input_tokens: tokens.input_tokens,
+        output_tokens: tokens.output_tokens,
+        cost_usd: cost.map(|cost| (cost * 10_000.0).round() / 10_000.0),
+    };
+    let yaml = serde_yaml::to_string(&frontmatter)
+        .map_err(|e| CassioError::Other(format!("Failed to serialize frontmatter: {e}")))?;
+    let _ = write!(doc, "---\n{yaml}---\n\n");
+    Ok(())
+}
+
+/// `ToolResult` outcomes keyed by the `ToolUse` id they answer, for results
+/// whose call is in the session.
+fn tool_results(messages: &[Message]) -> HashMap<&str, Outcome<'_>> {
+    let calls: HashSet<&str> = messages
+        .iter()
+        .flat_map(|msg| &msg.content)
+        .filter_map(|block| match block {
+            ContentBlock::ToolUse { id, .. } if !id.is_empty() => Some(id.as_str()),
+            _ => None,
+        })
+        .collect();
+    messages
+        .iter()
+        .flat_map(|msg| &msg.content)
+        .filter_map(|block| match block {
+            ContentBlock::ToolResult {
+                tool_use_id,
+                success,
+                summary,
+                output,
+                ..
+            } if calls.contains(tool_use_id.as_str()) => Some((
+                tool_use_id.as_str(),
+                (*success, summary.as_str(), output.as_deref()),
+            )),
+            _ => None,
+        })
+        .collect()
+}
+
+impl MarkdownFormatter {
+    fn render_turn(&self, doc: &mut String, turn: &Turn, session_model: Option<&str>) {
+        let options = &self.options;
+        let label = match turn.role {
+            Role::User => "User",
+            Role::Assistant => "Assistant",
+            Role::System => "System",
+        };
+        let _ = write!(doc, "\n## {label}");
+        if let Some(ts) = turn
+            .message
+            .timestamp
+            .filter(|_| options.timestamps.unwrap_or(true))
+        {
+            let _ = write!(doc, " \u{b7} {}", ts.format("%H:%M:%S"));
+        }
+        if let Some(model) = turn.message.model.as_deref() {
+            let _ = write!(doc, " \u{b7} {}", shorten_model_name(model));
+        }
+        if let Some(usage) = turn
+            .usage
+            .as_ref()
+            .filter(|_| options.turn_usage.unwrap_or(false))
+        {
+            let model = turn.message.model.as_deref().or(session_model);
+            let _ = write!(
+                doc,
+                " \u{b7} {}",
+                turn_usage_parts(usage, model).join(" \u{b7} ")
+            );
+        }
+        doc.push('\n');
+
+        let mut items = turn.items.iter().peekable();
+        while let Some(item) = items.next() {
+            doc.push('\n');
+            match item {
+                Item::Text(text) => push_text(doc, text),
+                Item::Thinking(text) => {
+                    doc.push_str("<details>\n<summary>Thinking</summary>\n\n");
+                    push_text(doc, text);
+                    doc.push_str("\n</details>\n");
+                }
+                Item::Event(line) => {
+                    let _ = writeln!(doc, "{line}");
+                }
+                Item::Tool { .. } => {
+                    let mut run = vec![item];
+                    while let Some(next) = items.next_if(|next| matches!(next, Item::Tool { .. })) {
+                        run.push(next);
+                    }
+                    render_tool_run(doc, &run, options.failed_output_lines);
+                }
+            }
+        }
+    }
+}
+
+fn add_usage(total: &mut TokenUsage, usage: &TokenUsage) {
+    total.input_tokens += usage.input_tokens;
+    total.output_tokens += usage.output_tokens;
+    total.cache_read_tokens += usage.cache_read_tokens;
+    total.cache_creation_tokens += usage.cache_creation_tokens;
+}
+
+/// One collapsed block for a run of consecutive tool calls, with up to
+/// `excerpt_lines` of each failed call's output.
+fn render_tool_run(doc: &mut String, run: &[&Item], excerpt_lines: usize) {
+    let mut names: Vec<&str> = Vec::new();
+    let mut failed = 0;
+    for item in run {
+        if let Item::Tool { name, result, .. } = item {
+            if !names.contains(name) {
+                names.push(name);
+            }
+            if matches!(result, Some((false, ..))) {
+                failed += 1;
+            }
+        }
+    }
+    let calls = if run.len() == 1 {
+        "1 tool call".to_string()
+    } else {
+        format!("{} tool calls", run.len())
+    };
+    let status = if failed > 0 {
+        format!("{EMOJI_FAILURE} {calls}, {failed} failed")
+    } else {
+        format!("{EMOJI_SUCCESS} {calls}")
+    };
+    let _ = writeln!(
+        doc,
+        "<details>\n<summary>{status}: {}</summary>",
+        escape_html(&names.join(", "))
+    );
+    for item in run {
+        let Item::Tool {
+            name,
+            input,
+            result,
+        } = item
+        else {
+            continue;
+        };
+        let mark = match result {
+            Some((true, ..)) => EMOJI_SUCCESS,
+            Some((false, ..)) => EMOJI_FAILURE,
+            None => "\u{2026}",
+        };
+        let _ = writeln!(doc, "\n{mark} `{name}`");
+        if let Some(input) = input {
+            push_fenced(doc, "json", input);
+        }
+        if let Some((_, summary, _)) = result.filter(|(_, summary, _)| !summary.is_empty()) {
+            push_fenced(doc, "text", summary);
+        }
+        if let Some(excerpt) =
+            result.and_then(|(_, _, output)| output_excerpt(output, excerpt_lines))
+        {
+            push_fenced(doc, "text", &excerpt);
+        }
+    }
+    doc.push_str("\n</details>\n");
+}
+
+/// Message text as written, with a fence left open by the author closed so it
+/// cannot swallow the rest of the document.
+fn push_text(doc: &mut String, text: &str) {
+    let text = text.trim_matches('\n');
+    doc.push_str(text);
+    doc.push('\n');
+    if let Some(fence) = open_fence(text) {
+        let _ = writeln!(doc, "{fence}");
+    }
+}
+
+/// The opening fence of a code block that `text` never closes.
+fn open_fence(text: &str) -> Option<&str> {
+    let mut open: Option<&str> = None;
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        let marker = trimmed.chars().next().filter(|c| *c == '`' || *c == '~');
+        let Some(marker) = marker else {
+            continue;
+        };
+        let len = trimmed.chars().take_while(|c| *c == marker).count();
+        if len < 3 {
+            continue;
+        }
+        let fence = &trimmed[..len];
+        match open {
+            None => open = Some(fence),
+            Some(current)
+                if current.starts_with(marker)
+                    && len >= current.len()
+                    && trimmed[len..].trim().is_empty() =>
+            {
+                open = None;
+            }
+            Some(_) => {}
+        }
+    }
+    open
+}
+
+/// A fenced code block whose fence is longer than any backtick run inside.
+fn push_fenced(doc: &mut String, lang: &str, body: &str) {
+    let mut longest = 0;
+    let mut run = 0;
+    for c in body.chars() {
+        run = if c == '`' { run + 1 } else { 0 };
+        longest = longest.max(run);
+    }
+    let fence = "`".repeat(longest.max(2) + 1);
+    let _ = write!(
+        doc,
+        "\n{fence}{lang}\n{}\n{fence}\n",
+        body.trim_end_matches('\n')
+    );
+}
+
+#[cfg(test)]
+#[path = "markdown_test.rs"]
+mod tests;
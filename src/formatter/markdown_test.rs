@@ -0,0 +1,252 @@
+use super::*;
+use crate::training::{
+    ParsedSession, TrainingMetadata, TrainingSession, TrainingSource, training_stats_from_session,
+};
+use chrono::{TimeZone, Utc};
+
+fn parsed_from_session(session: Session) -> ParsedSession {
+    ParsedSession {
+        training: TrainingSession::new(
+            "test.v1",
+            TrainingSource {
+                tool: session.metadata.tool.to_string(),
+                source_path: "/tmp/source".to_string(),
+                session_id: session.metadata.session_id.clone(),
+                source_hash: "sha256:test".to_string(),
+                source_record_count: Some(1),
+                source_format: Some("jsonl".to_string()),
+                source_root: None,
+                duplicates: Vec::new(),
+            },
+            TrainingMetadata {
+                project_path_raw: session.metadata.project_path.clone(),
+                project_path_sanitized: session.metadata.project_path.clone(),
+                started_at: session.metadata.started_at,
+                ended_at: None,
+                git_branch: session.metadata.git_branch.clone(),
+                title: session.metadata.title.clone(),
+                session_kind: session.metadata.session_kind.to_string(),
+                models_seen: session.metadata.model.clone().into_iter().collect(),
+                version: session.metadata.version.clone(),
+            },
+            training_stats_from_session(&session.stats),
+        ),
+        session,
+    }
+}
+
+fn message(role: Role, content: Vec<ContentBlock>) -> Message {
+    Message {
+        role,
+        timestamp: Some(Utc.with_ymd_and_hms(2026, 4, 1, 9, 0, 5).unwrap()),
+        model: (role == Role::Assistant).then(|| "claude-opus-4-5-20251101".to_string()),
+        content,
+        usage: None,
+    }
+}
+
+fn text(text: &str) -> ContentBlock {
+    ContentBlock::Text {
+        text: text.to_string(),
+    }
+}
+
+fn tool_use(id: &str, name: &str, input: serde_json::Value) -> ContentBlock {
+    ContentBlock::ToolUse {
+        id: id.to_string(),
+        name: name.to_string(),
+        input,
+    }
+}
+
+fn tool_result(id: &str, name: &str, success: bool, summary: &str) -> ContentBlock {
+    ContentBlock::ToolResult {
+        tool_use_id: id.to_string(),
+        name: name.to_string(),
+        success,
+        summary: summary.to_string(),
+        output: (!success).then(|| {
+            "test it_runs ... FAILED\npanicked at src/lib.rs:3\nassertion failed".to_string()
+        }),
+    }
+}
+
+fn sample_session() -> Session {
+    Session {
+        metadata: SessionMetadata {
+            session_id: "s-42".to_string(),
+            tool: Tool::Claude,
+            project_path: "/work/faber".to_string(),
+            started_at: Utc.with_ymd_and_hms(2026, 4, 1, 9, 0, 0).unwrap(),
+            session_kind: SessionKind::Human,
+            version: Some("2.1.0".to_string()),
+            git_branch: Some("main".to_string()),
+            model: Some("claude-opus-4-5-20251101".to_string()),
+            title: None,
+        },
+        messages: vec![
+            message(
+                Role::User,
+                vec![text(
+                    "Why does this fail?\n\n```rust\nfn main() {\n    run();\n}\n```",
+                )],
+            ),
+            message(
+                Role::Assistant,
+                vec![
+                    ContentBlock::Thinking {
+                        text: "check the test runner".to_string(),
+                    },
+                    text("Running the tests."),
+                    tool_use("t1", "Bash", serde_json::json!({"command": "cargo test"})),
+                ],
+            ),
+            message(
+                Role::User,
+                vec![tool_result("t1", "Bash", false, "1 test failed")],
+            ),
+            message(
+                Role::Assistant,
+                vec![tool_use(
+                    "t2",
+                    "Read",
+                    serde_json::json!({"file_path": "src/lib.rs"}),
+                )],
+            ),
+            message(Role::User, vec![tool_result("t2", "Read", true, "")]),
+            message(Role::Assistant, vec![text("- fixed\n- retested")]),
+        ],
+        stats: SessionStats {
+            user_messages: 1,
+            assistant_messages: 3,
+            tool_calls: 2,
+            tool_errors: 1,
+            duration_seconds: Some(300),
+            ..Default::default()
+        },
+    }
+}
+
+fn render(formatter: &MarkdownFormatter, session: Session) -> String {
+    let mut buf = Vec::new();
+    formatter
+        .format(&parsed_from_session(session), &mut buf)
+        .unwrap();
+    String::from_utf8(buf).unwrap()
+}
+
+#[test]
+fn test_markdown_starts_with_yaml_frontmatter() {
+    let md = render(&MarkdownFormatter::default(), sample_session());
+    let yaml = md
+        .strip_prefix("---\n")
+        .and_then(|rest| rest.split_once("---\n"))
+        .map(|(yaml, _)| yaml)
+        .unwrap();
+    let value: serde_yaml::Value = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(value["session_id"].as_str(), Some("s-42"));
+    assert_eq!(value["tool"].as_str(), Some("claude"));
+    assert_eq!(value["project"].as_str(), Some("/work/faber"));
+    assert_eq!(value["model"].as_str(), Some("opus-4.5"));
+    assert_eq!(value["duration"].as_str(), Some("5m"));
+    assert_eq!(value["tool_errors"].as_u64(), Some(1));
+    assert!(md.contains("---\n\n# claude session 2026-04-01 09:00\n"));
+}
+
+#[test]
+fn test_markdown_turns_merge_consecutive_messages() {
+    let md = render(&MarkdownFormatter::default(), sample_session());
+    assert_eq!(md.matches("\n## User \u{b7} 09:00:05\n").count(), 1);
+    // Three assistant messages, with tool-result-only user messages between
+    // them, read as one answer.
+    assert_eq!(
+        md.matches("\n## Assistant \u{b7} 09:00:05 \u{b7} opus-4.5\n")
+            .count(),
+        1
+    );
+    assert!(md.contains("\n- fixed\n- retested\n"));
+}
+
+#[test]
+fn test_markdown_keeps_code_fences_and_newlines() {
+    let md = render(&MarkdownFormatter::default(), sample_session());
+    assert!(md.contains("Why does this fail?\n\n```rust\nfn main() {\n    run();\n}\n```\n"));
+}
+
+#[test]
+fn test_markdown_tool_runs_collapse_into_details() {
+    let md = render(&MarkdownFormatter::default(), sample_session());
+    assert!(
+        md.contains("<details>\n<summary>\u{274c} 2 tool calls, 1 failed: Bash, Read</summary>\n")
+    );
+    assert!(
+        md.contains("\n\u{274c} `Bash`\n\n```json\n{\n  \"command\": \"cargo test\"\n}\n```\n")
+    );
+    assert!(md.contains("\n```text\n1 test failed\n```\n"));
+    assert!(md.contains("\n\u{2705} `Read`\n"));
+    assert_eq!(md.matches("<details>").count(), 2, "thinking + one run");
+}
+
+#[test]
+fn test_markdown_thinking_is_optional() {
+    let md = render(&MarkdownFormatter::default(), sample_session());
+    assert!(md.contains("<details>\n<summary>Thinking</summary>\n\ncheck the test runner\n"));
+
+    let md = render(
+        &MarkdownFormatter {
+            options: FormatterOptions {
+                show_thinking: Some(false),
+                ..Default::default()
+            },
+        },
+        sample_session(),
+    );
+    assert!(!md.contains("check the test runner"));
+}
+
+#[test]
+fn test_markdown_closes_unterminated_fences() {
+    assert_eq!(open_fence("```sh\nls\n```\ntext"), None);
+    assert_eq!(open_fence("````md\n```\ninner\n```\n"), Some("````"));
+    assert_eq!(open_fence("~~~\ncode"), Some("~~~"));
+
+    let mut doc = String::new();
+    push_text(&mut doc, "see:\n```\nunterminated");
+    assert_eq!(doc, "see:\n```\nunterminated\n```\n");
+
+    let mut doc = String::new();
+    push_fenced(&mut doc, "text", "has ``` inside");
+    assert_eq!(doc, "\n````text\nhas ``` inside\n````\n");
+}
+
+#[test]
+fn test_markdown_options_annotate_turns_and_failures() {
+    let mut session = sample_session();
+    session.messages[1].usage = Some(TokenUsage {
+        input_tokens: 1000,
+        output_tokens: 100,
+        ..Default::default()
+    });
+    session.messages[3].usage = session.messages[1].usage.clone();
+    let md = render(
+        &MarkdownFormatter {
+            options: FormatterOptions {
+                tool_input: Some(ToolInput::Inline),
+                failed_output_lines: 2,
+                turn_usage: Some(true),
+                ..Default::default()
+            },
+        },
+        session,
+    );
+    // Usage is summed over the merged assistant messages.
+    assert!(md.contains("\n## Assistant \u{b7} 09:00:05 \u{b7} opus-4.5 \u{b7} 2.0K in \u{b7} 200 out \u{b7} $0.0150\n"));
+    assert!(md.contains("\n```json\n{\"command\":\"cargo test\"}\n```\n"));
+    assert!(md.contains(
+        "\n```text\ntest it_runs ... FAILED\npanicked at src/lib.rs:3\n\u{2026} 1 more line\n```\n"
+    ));
+
+    let md = render(&MarkdownFormatter::default(), sample_session());
+    assert!(!md.contains("panicked at"));
+    assert!(!md.contains(" in \u{b7} "));
+}
@@ -1,18 +1,153 @@
 //! Output formatters that render `ParsedSession` values for humans or downstream tools.
 //!
-//! `OutputFormat` selects among emoji-text (default), JSONL, and training JSON.
+//! `OutputFormat` selects among emoji-text (default), JSONL, training JSON,
+//! self-contained HTML, and GitHub-flavoured markdown.
 //! Each formatter implements the shared `Formatter` trait and writes to any `Write`
 //! target so the CLI can stream to stdout or files without duplicating dispatch.
+//!
+//! `FormatterOptions` carries the verbosity settings (`--show-thinking`,
+//! `--tool-input`, ... and the `[format]` config table) into the formatters
+//! built by `OutputFormat::formatter`. Options left unset keep each format's
+//! own default, so plain emoji-text stays terse while HTML and markdown stay
+//! detailed. JSONL and training JSON are lossless and ignore the options.
 
 pub mod emoji_text;
+pub mod html;
 pub mod jsonl;
+pub mod markdown;
 pub mod training_json;
 
 use std::io::Write;
 
+use serde_json::Value;
+
+use crate::ast::TokenUsage;
 use crate::error::CassioError;
+use crate::pricing;
 use crate::training::ParsedSession;
 
+/// Characters of single-line tool input kept at `ToolInput::Inline`.
+const INLINE_INPUT_CHARS: usize = 200;
+
+/// How much of a tool call's input to show.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolInput {
+    /// Only the parser's one-line summary of the call.
+    Summary,
+    /// The input as single-line JSON, cut at 200 characters.
+    Inline,
+    /// The input as pretty-printed JSON.
+    Full,
+}
+
+impl std::str::FromStr for ToolInput {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "summary" => Ok(ToolInput::Summary),
+            "inline" => Ok(ToolInput::Inline),
+            "full" => Ok(ToolInput::Full),
+            _ => Err(format!(
+                "Unknown tool input level: {s}. Valid: summary, inline, full"
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for ToolInput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ToolInput::Summary => write!(f, "summary"),
+            ToolInput::Inline => write!(f, "inline"),
+            ToolInput::Full => write!(f, "full"),
+        }
+    }
+}
+
+/// Verbosity options for the human-readable formatters.
+///
+/// `None` keeps the format's default, noted per field as emoji-text / HTML /
+/// markdown.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FormatterOptions {
+    /// Show `Thinking` blocks. Default: off / on / on.
+    pub show_thinking: Option<bool>,
+    /// Detail of tool call input. Default: summary / full / full.
+    pub tool_input: Option<ToolInput>,
+    /// Lines of output shown under a failed tool call; 0 shows none.
+    pub failed_output_lines: usize,
+    /// Timestamp on every message. Default: off / on / on.
+    pub timestamps: Option<bool>,
+    /// Token and estimated-cost annotation on each assistant turn. Default:
+    /// off / on / off.
+    pub turn_usage: Option<bool>,
+    /// Start emoji-text transcripts with a YAML frontmatter block (see
+    /// `crate::frontmatter`).
+    pub frontmatter: bool,
+}
+
+/// Tool input rendered at `level`, or `None` when there is nothing to show.
+pub(crate) fn tool_input_text(input: &Value, level: ToolInput) -> Option<String> {
+    if input.is_null() || input.as_object().is_some_and(|map| map.is_empty()) {
+        return None;
+    }
+    match level {
+        ToolInput::Summary => None,
+        ToolInput::Inline => {
+            let line = input.to_string();
+            Some(match line.char_indices().nth(INLINE_INPUT_CHARS) {
+                Some((end, _)) => format!("{}\u{2026}", &line[..end]),
+                None => line,
+            })
+        }
+        ToolInput::Full => serde_json::to_string_pretty(input).ok(),
+    }
+}
+
+/// Token counts and estimated cost of one turn, as separate parts for the
+/// caller to join (`["1.2K in", "340 out", "$0.0123"]`).
+pub(crate) fn turn_usage_parts(usage: &TokenUsage, model: Option<&str>) -> Vec<String> {
+    let mut parts = vec![
+        format!("{} in", emoji_text::format_tokens(usage.input_tokens)),
+        format!("{} out", emoji_text::format_tokens(usage.output_tokens)),
+    ];
+    if usage.cache_read_tokens > 0 {
+        parts.push(format!(
+            "{} cached",
+            emoji_text::format_tokens(usage.cache_read_tokens)
+        ));
+    }
+    if let Some(cost) = pricing::estimate_cost(
+        model,
+        usage.input_tokens,
+        usage.output_tokens,
+        usage.cache_read_tokens,
+        usage.cache_creation_tokens,
+        None,
+    ) {
+        parts.push(format!("${cost:.4}"));
+    }
+    parts
+}
+
+/// The first `lines` lines of a failed call's output, with a count of the
+/// lines left out. `None` when excerpts are off or there is no output.
+pub(crate) fn output_excerpt(output: Option<&str>, lines: usize) -> Option<String> {
+    let output = output.filter(|output| lines > 0 && !output.trim().is_empty())?;
+    let total = output.lines().count();
+    let mut excerpt: Vec<&str> = output.lines().take(lines).collect();
+    let left = total.saturating_sub(lines);
+    let more = format!(
+        "\u{2026} {left} more line{}",
+        if left == 1 { "" } else { "s" }
+    );
+    if total > lines {
+        excerpt.push(&more);
+    }
+    Some(excerpt.join("\n"))
+}
+
 pub trait Formatter {
     fn format(&self, parsed: &ParsedSession, writer: &mut dyn Write) -> Result<(), CassioError>;
 }
@@ -22,14 +157,19 @@ pub enum OutputFormat {
     EmojiText,
     Jsonl,
     TrainingJson,
+    Html,
+    Markdown,
 }
 
 impl OutputFormat {
-    pub fn formatter(&self) -> Box<dyn Formatter> {
+    pub fn formatter(&self, options: &FormatterOptions) -> Box<dyn Formatter> {
+        let options = options.clone();
         match self {
-            OutputFormat::EmojiText => Box::new(emoji_text::EmojiTextFormatter),
+            OutputFormat::EmojiText => Box::new(emoji_text::EmojiTextFormatter { options }),
             OutputFormat::Jsonl => Box::new(jsonl::JsonlFormatter),
             OutputFormat::TrainingJson => Box::new(training_json::TrainingJsonFormatter),
+            OutputFormat::Html => Box::new(html::HtmlFormatter { options }),
+            OutputFormat::Markdown => Box::new(markdown::MarkdownFormatter { options }),
         }
     }
 }
@@ -42,8 +182,10 @@ impl std::str::FromStr for OutputFormat {
             "emoji-text" | "text" => Ok(OutputFormat::EmojiText),
             "jsonl" | "json" => Ok(OutputFormat::Jsonl),
             "training-json" | "training" => Ok(OutputFormat::TrainingJson),
+            "html" => Ok(OutputFormat::Html),
+            "markdown" | "gfm" => Ok(OutputFormat::Markdown),
             _ => Err(format!(
-                "Unknown format: {s}. Valid: emoji-text, jsonl, training-json"
+                "Unknown format: {s}. Valid: emoji-text, jsonl, training-json, html, markdown"
             )),
         }
     }
@@ -55,6 +197,8 @@ impl std::fmt::Display for OutputFormat {
             OutputFormat::EmojiText => write!(f, "emoji-text"),
             OutputFormat::Jsonl => write!(f, "jsonl"),
             OutputFormat::TrainingJson => write!(f, "training-json"),
+            OutputFormat::Html => write!(f, "html"),
+            OutputFormat::Markdown => write!(f, "markdown"),
         }
     }
 }
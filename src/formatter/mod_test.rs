@@ -48,4 +48,54 @@ fn test_display_roundtrip() {
     assert_eq!(fmt.to_string().parse::<OutputFormat>().unwrap(), fmt);
     let fmt = OutputFormat::Jsonl;
     assert_eq!(fmt.to_string().parse::<OutputFormat>().unwrap(), fmt);
+    let fmt = OutputFormat::Html;
+    assert_eq!(fmt.to_string().parse::<OutputFormat>().unwrap(), fmt);
+    let fmt = OutputFormat::Markdown;
+    assert_eq!(fmt.to_string().parse::<OutputFormat>().unwrap(), fmt);
+}
+
+#[test]
+fn test_from_str_markdown_alias() {
+    assert_eq!(
+        "gfm".parse::<OutputFormat>().unwrap(),
+        OutputFormat::Markdown
+    );
+}
+
+#[test]
+fn test_tool_input_levels() {
+    assert_eq!("inline".parse::<ToolInput>().unwrap(), ToolInput::Inline);
+    assert!("verbose".parse::<ToolInput>().is_err());
+    assert_eq!(ToolInput::Full.to_string(), "full");
+
+    let input = serde_json::json!({"command": "ls"});
+    assert_eq!(tool_input_text(&input, ToolInput::Summary), None);
+    assert_eq!(
+        tool_input_text(&input, ToolInput::Inline).as_deref(),
+        Some("{\"command\":\"ls\"}")
+    );
+    assert_eq!(
+        tool_input_text(&serde_json::json!({}), ToolInput::Full),
+        None
+    );
+
+    let long = serde_json::json!({"text": "x".repeat(500)});
+    let inline = tool_input_text(&long, ToolInput::Inline).unwrap();
+    assert_eq!(inline.chars().count(), INLINE_INPUT_CHARS + 1);
+    assert!(inline.ends_with('\u{2026}'));
+}
+
+#[test]
+fn test_output_excerpt() {
+    let output = Some("one\ntwo\nthree\nfour");
+    assert_eq!(output_excerpt(output, 0), None);
+    assert_eq!(output_excerpt(None, 3), None);
+    assert_eq!(
+        output_excerpt(output, 4).as_deref(),
+        Some("one\ntwo\nthree\nfour")
+    );
+    assert_eq!(
+        output_excerpt(output, 2).as_deref(),
+        Some("one\ntwo\n\u{2026} 2 more lines")
+    );
 }
@@ -0,0 +1,240 @@
+//! YAML frontmatter for emoji-text transcripts.
+//!
+//! With `frontmatter = true` (or `--frontmatter`), each emoji-text transcript
+//! starts with a `---`-delimited YAML block carrying the full `SessionMetadata`,
+//! the session stats, token usage and estimated cost per model, and the total
+//! cost. The emoji header follows unchanged, so grep-based workflows and older
+//! cassio versions read the file as before.
+//!
+//! Readers (`summary`, `metrics`, `search`, `compact`) call [`parse`] first and
+//! fall back to scraping `📋 Key: value` lines when a transcript has no block,
+//! or one that no longer parses. Because the keys are plain YAML properties,
+//! Obsidian and Dataview can query the archive directly.
+//!
+//! # TRADE-OFFS
+//!
+//! - Off by default: turning it on changes every transcript, and existing
+//!   archives are only rewritten with `--force`. Readers handle both kinds of
+//!   file side by side.
+//! - Counts are duplicated between the block and the emoji summary. The block is
+//!   the machine-readable copy; the emoji lines stay for people and `grep`.
+//! - Per-model usage comes from per-message usage. Parsers that only record
+//!   session totals produce no `models` map; the total cost is then estimated
+//!   from the session model.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::ast::Session;
+use crate::error::CassioError;
+use crate::pricing;
+
+const DELIMITER: &str = "---";
+
+/// The frontmatter block of one transcript.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TranscriptFrontmatter {
+    pub session_id: String,
+    pub tool: String,
+    pub project_path: String,
+    pub started_at: DateTime<Utc>,
+    pub session_kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_branch: Option<String>,
+    #[serde(default)]
+    pub user_messages: u32,
+    #[serde(default)]
+    pub assistant_messages: u32,
+    #[serde(default)]
+    pub tool_calls: u32,
+    #[serde(default)]
+    pub tool_errors: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_seconds: Option<i64>,
+    #[serde(default)]
+    pub input_tokens: u64,
+    #[serde(default)]
+    pub output_tokens: u64,
+    #[serde(default)]
+    pub cache_read_tokens: u64,
+    #[serde(default)]
+    pub cache_write_tokens: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_tokens_used: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_window_tokens: Option<u64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files_read: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files_written: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files_edited: Vec<String>,
+    /// Recorded cost when the tool reports one, otherwise the estimate.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_usd: Option<f64>,
+    /// Usage per model, keyed by the model id.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub models: BTreeMap<String, ModelUsage>,
+}
+
+/// Token usage and estimated cost for one model within a session.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModelUsage {
+    #[serde(default)]
+    pub messages: u32,
+    #[serde(default)]
+    pub input_tokens: u64,
+    #[serde(default)]
+    pub output_tokens: u64,
+    #[serde(default)]
+    pub cache_read_tokens: u64,
+    #[serde(default)]
+    pub cache_write_tokens: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_usd: Option<f64>,
+}
+
+impl TranscriptFrontmatter {
+    pub fn from_session(session: &Session) -> Self {
+        let meta = &session.metadata;
+        let stats = &session.stats;
+        let tokens = &stats.total_tokens;
+
+        let mut models: BTreeMap<String, ModelUsage> = BTreeMap::new();
+        for msg in &session.messages {
+            let (Some(usage), Some(model)) =
+                (&msg.usage, msg.model.as_ref().or(meta.model.as_ref()))
+            else {
+                continue;
+            };
+            let entry = models.entry(model.clone()).or_default();
+            entry.messages += 1;
+            entry.input_tokens += usage.input_tokens;
+            entry.output_tokens += usage.output_tokens;
+            entry.cache_read_tokens += usage.cache_read_tokens;
+            entry.cache_write_tokens += usage.cache_creation_tokens;
+        }
+        for (model, usage) in models.iter_mut() {
+            usage.cost_usd = pricing::estimate_cost(
+                Some(model),
+                usage.input_tokens,
+                usage.output_tokens,
+                usage.cache_read_tokens,
+                usage.cache_write_tokens,
+                None,
+            )
+            .map(round_cost);
+        }
+
+        let priced: Vec<f64> = models.values().filter_map(|usage| usage.cost_usd).collect();
+        let cost_usd = stats
+            .cost
+            .or_else(|| (!priced.is_empty()).then(|| priced.iter().sum()))
+            .or_else(|| {
+                pricing::estimate_cost(
+                    meta.model.as_deref(),
+                    tokens.input_tokens,
+                    tokens.output_tokens,
+                    tokens.cache_read_tokens,
+                    tokens.cache_creation_tokens,
+                    None,
+                )
+            })
+            .map(round_cost);
+
+        let sorted = |files: &std::collections::HashSet<String>| {
+            let mut files: Vec<String> = files.iter().cloned().collect();
+            files.sort();
+            files
+        };
+        Self {
+            session_id: meta.session_id.clone(),
+            tool: meta.tool.to_string(),
+            project_path: meta.project_path.clone(),
+            started_at: meta.started_at,
+            session_kind: meta.session_kind.to_string(),
+            title: meta.title.clone(),
+            model: meta.model.clone(),
+            version: meta.version.clone(),
+            git_branch: meta.git_branch.clone(),
+            user_messages: stats.user_messages,
+            assistant_messages: stats.assistant_messages,
+            tool_calls: stats.tool_calls,
+            tool_errors: stats.tool_errors,
+            duration_seconds: stats.duration_seconds,
+            input_tokens: tokens.input_tokens,
+            output_tokens: tokens.output_tokens,
+            cache_read_tokens: tokens.cache_read_tokens,
+            cache_write_tokens: tokens.cache_creation_tokens,
+            context_tokens_used: stats.context_tokens_used,
+            context_window_tokens: stats.context_window_tokens,
+            files_read: sorted(&stats.files_read),
+            files_written: sorted(&stats.files_written),
+            files_edited: sorted(&stats.files_edited),
+            cost_usd,
+            models,
+        }
+    }
+
+    /// The block as written at the top of a transcript, delimiters included.
+    pub fn to_block(&self) -> Result<String, CassioError> {
+        let yaml = serde_yaml::to_string(self)
+            .map_err(|e| CassioError::Other(format!("Failed to serialize frontmatter: {e}")))?;
+        Ok(format!("{DELIMITER}\n{yaml}{DELIMITER}\n"))
+    }
+}
+
+fn round_cost(cost: f64) -> f64 {
+    (cost * 1_000_000.0).round() / 1_000_000.0
+}
+
+/// Split a transcript into its frontmatter YAML (without delimiters) and the
+/// rest. Files without a block come back whole.
+pub fn split(content: &str) -> (Option<&str>, &str) {
+    let Some(rest) = content
+        .strip_prefix("---\n")
+        .or_else(|| content.strip_prefix("---\r\n"))
+    else {
+        return (None, content);
+    };
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end_matches(['\r', '\n']) == DELIMITER {
+            return (Some(&rest[..offset]), &rest[offset + line.len()..]);
+        }
+        offset += line.len();
+    }
+    (None, content)
+}
+
+/// The transcript without its frontmatter block.
+pub fn body(content: &str) -> &str {
+    split(content).1
+}
+
+/// Number of lines the frontmatter block occupies, delimiters included; line
+/// scanners skip these so YAML keys are not searched or indexed as text.
+pub fn line_count(content: &str) -> usize {
+    match split(content) {
+        (Some(yaml), _) => yaml.lines().count() + 2,
+        (None, _) => 0,
+    }
+}
+
+/// The parsed frontmatter of a transcript, or `None` when it has no block or
+/// the block does not parse (callers then read the emoji header).
+pub fn parse(content: &str) -> Option<TranscriptFrontmatter> {
+    serde_yaml::from_str(split(content).0?).ok()
+}
+
+#[cfg(test)]
+#[path = "frontmatter_test.rs"]
+mod tests;
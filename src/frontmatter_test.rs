@@ -0,0 +1,118 @@
+use super::*;
+use crate::ast::{
+    ContentBlock, Message, Role, SessionKind, SessionMetadata, SessionStats, TokenUsage, Tool,
+};
+use chrono::TimeZone;
+
+fn usage(input: u64, output: u64) -> Option<TokenUsage> {
+    Some(TokenUsage {
+        input_tokens: input,
+        output_tokens: output,
+        ..Default::default()
+    })
+}
+
+fn assistant(model: Option<&str>, usage: Option<TokenUsage>) -> Message {
+    Message {
+        role: Role::Assistant,
+        timestamp: None,
+        model: model.map(str::to_string),
+        content: vec![ContentBlock::Text {
+            text: "ok".to_string(),
+        }],
+        usage,
+    }
+}
+
+fn sample_session() -> Session {
+    let mut stats = SessionStats {
+        user_messages: 2,
+        assistant_messages: 3,
+        tool_calls: 4,
+        tool_errors: 1,
+        duration_seconds: Some(90),
+        total_tokens: TokenUsage {
+            input_tokens: 3_000,
+            output_tokens: 300,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    stats.files_edited.insert("src/b.rs".to_string());
+    stats.files_edited.insert("src/a.rs".to_string());
+    Session {
+        metadata: SessionMetadata {
+            session_id: "s-1".to_string(),
+            tool: Tool::Claude,
+            project_path: "/work/cassio".to_string(),
+            started_at: Utc.with_ymd_and_hms(2026, 4, 1, 9, 0, 0).unwrap(),
+            session_kind: SessionKind::Human,
+            version: None,
+            git_branch: Some("main".to_string()),
+            model: Some("claude-opus-4-5-20251101".to_string()),
+            title: None,
+        },
+        messages: vec![
+            assistant(None, usage(1_000, 100)),
+            assistant(Some("claude-haiku-4-5"), usage(2_000, 200)),
+            assistant(Some("claude-haiku-4-5"), None),
+        ],
+        stats,
+    }
+}
+
+#[test]
+fn test_block_roundtrips_through_parse() {
+    let fm = TranscriptFrontmatter::from_session(&sample_session());
+    let content = format!("{}📋 Session: s-1\n👤 hi\n", fm.to_block().unwrap());
+
+    assert!(content.starts_with("---\nsession_id: s-1\n"));
+    assert_eq!(parse(&content), Some(fm.clone()));
+    assert_eq!(body(&content), "📋 Session: s-1\n👤 hi\n");
+    assert_eq!(
+        content.lines().nth(line_count(&content)),
+        Some("📋 Session: s-1")
+    );
+    assert_eq!(fm.files_edited, vec!["src/a.rs", "src/b.rs"]);
+    assert_eq!(fm.duration_seconds, Some(90));
+}
+
+#[test]
+fn test_models_split_usage_and_sum_cost() {
+    let fm = TranscriptFrontmatter::from_session(&sample_session());
+    assert_eq!(fm.models.len(), 2);
+    let opus = &fm.models["claude-opus-4-5-20251101"];
+    assert_eq!((opus.messages, opus.input_tokens), (1, 1_000));
+    let haiku = &fm.models["claude-haiku-4-5"];
+    assert_eq!((haiku.messages, haiku.output_tokens), (1, 200));
+
+    let total: f64 = fm.models.values().filter_map(|m| m.cost_usd).sum();
+    assert!(total > 0.0);
+    assert!((fm.cost_usd.unwrap() - total).abs() < 1e-6);
+}
+
+#[test]
+fn test_recorded_cost_wins_over_estimate() {
+    let mut session = sample_session();
+    session.stats.cost = Some(1.25);
+    assert_eq!(
+        TranscriptFrontmatter::from_session(&session).cost_usd,
+        Some(1.25)
+    );
+}
+
+#[test]
+fn test_files_without_a_valid_block_fall_back() {
+    let plain = "📋 Session: s-1\n---\n👤 hi\n";
+    assert_eq!(split(plain), (None, plain));
+    assert_eq!(line_count(plain), 0);
+    assert_eq!(parse(plain), None);
+
+    let unclosed = "---\nsession_id: s-1\n👤 hi\n";
+    assert_eq!(body(unclosed), unclosed);
+
+    let invalid = "---\nnot: [valid\n---\n📋 Session: s-1\n";
+    assert_eq!(parse(invalid), None);
+    assert_eq!(body(invalid), "📋 Session: s-1\n");
+    assert_eq!(line_count(invalid), 3);
+}
@@ -0,0 +1,531 @@
+//! Archive garbage collection: find and remove transcripts no source produces.
+//!
+//! `cassio gc` reconciles the output tree against two views of what *should*
+//! exist:
+//! 1. **Expected stems** — the output names batch mode would derive today from
+//!    every discovered source log (computed by the caller in `main.rs`).
+//! 2. **The manifest** — entries recorded when transcripts were written, whose
+//!    source may live outside the discovered tool directories (batch-mode
+//!    imports, Claude Chat exports).
+//!
+//! Session artifacts covered by neither are orphans: stems renamed by collision
+//! hashes or parser fixes, or transcripts whose source log was deleted. Two
+//! stems recorded from the same source log, or byte-identical transcripts, are
+//! duplicates. Index rows pointing at missing (or about-to-be-removed) files
+//! are stale.
+//!
+//! # Design philosophy
+//!
+//! Planning never touches the archive. `apply` moves findings into
+//! `.cassio/quarantine/<stamp>/` (or deletes them with `purge`), prunes stale
+//! index rows and manifest entries, and writes the markdown report under
+//! `.cassio/gc/` so an archive kept in git records what was removed and why.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use rusqlite::{Connection, OptionalExtension, params};
+use sha2::{Digest, Sha256};
+use walkdir::WalkDir;
+
+use crate::ast::session_tool_suffix;
+use crate::error::CassioError;
+use crate::manifest::{self, ARTIFACT_EXTENSIONS, Manifest, STATE_DIR};
+
+/// Why a file was flagged.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FindingKind {
+    /// No discovered source or manifest entry accounts for the stem.
+    Orphan,
+    /// Another stem already holds the same session.
+    Duplicate,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GcFinding {
+    pub kind: FindingKind,
+    /// Absolute path of the flagged file.
+    pub path: PathBuf,
+    /// Path relative to its archive root, for reports.
+    pub rel_path: String,
+    /// Manifest key (root-relative stem) the file belongs to.
+    pub key: String,
+    pub reason: String,
+}
+
+/// Index rows whose `source_path` no longer resolves to a kept file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StaleIndexRows {
+    pub index_path: PathBuf,
+    pub source_path: String,
+    pub rows: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct GcPlan {
+    pub orphans: Vec<GcFinding>,
+    pub duplicates: Vec<GcFinding>,
+    pub stale_index: Vec<StaleIndexRows>,
+}
+
+impl GcPlan {
+    pub fn is_empty(&self) -> bool {
+        self.orphans.is_empty() && self.duplicates.is_empty() && self.stale_index.is_empty()
+    }
+
+    fn findings(&self) -> impl Iterator<Item = &GcFinding> {
+        self.orphans.iter().chain(self.duplicates.iter())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GcOutcome {
+    pub files_removed: usize,
+    pub index_rows_deleted: usize,
+    pub manifest_entries_removed: usize,
+    /// Quarantine directory, when files were moved rather than deleted.
+    pub quarantine_dir: Option<PathBuf>,
+    pub report_path: PathBuf,
+}
+
+/// A session artifact found on disk during the scan.
+#[derive(Debug, Clone)]
+struct ArchiveFile {
+    path: PathBuf,
+    rel_path: String,
+    key: String,
+    /// True for files under a separate training root.
+    training_root: bool,
+}
+
+/// Compare the archive under `root` (and an optional separate training root)
+/// against the `expected` stem keys and the manifest.
+pub fn plan(
+    root: &Path,
+    training_root: Option<&Path>,
+    expected: &HashSet<String>,
+    manifest: &Manifest,
+) -> Result<GcPlan, CassioError> {
+    let mut files = scan_session_artifacts(root, false);
+    if let Some(training_root) = training_root.filter(|t| *t != root && t.exists()) {
+        files.extend(
+            scan_session_artifacts(training_root, true)
+                .into_iter()
+                .filter(|file| file.rel_path.ends_with(".training.json")),
+        );
+    }
+
+    let mut plan = GcPlan::default();
+    let mut live = Vec::new();
+    for file in files {
+        if expected.contains(&file.key) {
+            live.push(file);
+            continue;
+        }
+        match manifest.get(&file.key) {
+            Some(entry) if source_exists(Path::new(&entry.source_path)) => live.push(file),
+            Some(entry) => plan.orphans.push(finding(
+                FindingKind::Orphan,
+                &file,
+                format!("source missing: {}", entry.source_path),
+            )),
+            None => plan.orphans.push(finding(
+                FindingKind::Orphan,
+                &file,
+                "no discovered source produces this stem".to_string(),
+            )),
+        }
+    }
+
+    plan.duplicates = find_duplicates(&live, expected, manifest)?;
+
+    let removed: HashSet<&str> = plan.findings().map(|f| f.rel_path.as_str()).collect();
+    plan.stale_index = find_stale_index_rows(root, training_root, &removed)?;
+
+    plan.orphans.sort_by(|a, b| a.rel_path.cmp(&b.rel_path));
+    plan.duplicates.sort_by(|a, b| a.rel_path.cmp(&b.rel_path));
+    Ok(plan)
+}
+
+/// Quarantine (or delete, with `purge`) every finding, prune stale index rows
+/// and manifest entries, and write the report under `.cassio/gc/`.
+pub fn apply(
+    root: &Path,
+    training_root: Option<&Path>,
+    plan: &GcPlan,
+    manifest: &mut Manifest,
+    purge: bool,
+) -> Result<GcOutcome, CassioError> {
+    let stamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    let quarantine_dir = root.join(STATE_DIR).join("quarantine").join(&stamp);
+
+    let mut files_removed = 0usize;
+    let mut removed_keys: BTreeMap<&str, ()> = BTreeMap::new();
+    for finding in plan.findings() {
+        if purge {
+            fs::remove_file(&finding.path)?;
+        } else {
+            let in_training =
+                training_root.is_some_and(|t| t != root && finding.path.starts_with(t));
+            let dest = if in_training {
+                quarantine_dir.join("training").join(&finding.rel_path)
+            } else {
+                quarantine_dir.join(&finding.rel_path)
+            };
+            if let Some(parent) = dest.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            move_file(&finding.path, &dest)?;
+        }
+        files_removed += 1;
+        removed_keys.insert(finding.key.as_str(), ());
+    }
+
+    let mut index_rows_deleted = 0usize;
+    for stale in &plan.stale_index {
+        let conn = Connection::open(&stale.index_path)
+            .map_err(|e| CassioError::Other(format!("Failed to open index database: {e}")))?;
+        let Some(table) = source_table(&conn)? else {
+            continue;
+        };
+        let delete = |sql: &str| {
+            conn.execute(sql, params![stale.source_path])
+                .map_err(|e| CassioError::Other(format!("Failed to delete stale index rows: {e}")))
+        };
+        index_rows_deleted += delete(&format!("DELETE FROM {table} WHERE source_path = ?1"))?;
+        if table == "lines" {
+            // The lexical index also stamps each file it has read.
+            delete("DELETE FROM files WHERE source_path = ?1")?;
+        }
+    }
+
+    let mut manifest_entries_removed = 0usize;
+    for key in removed_keys.keys() {
+        let still_present = ARTIFACT_EXTENSIONS
+            .iter()
+            .any(|ext| root.join(format!("{key}{ext}")).exists())
+            || training_root.is_some_and(|t| t.join(format!("{key}.training.json")).exists());
+        if !still_present && manifest.remove(key).is_some() {
+            manifest_entries_removed += 1;
+        }
+    }
+    if manifest_entries_removed > 0 {
+        let sessions = &manifest.sessions;
+        manifest
+            .duplicates
+            .retain(|_, record| sessions.contains_key(&record.kept));
+        manifest.save(root)?;
+    }
+
+    let mode = if purge {
+        "deleted".to_string()
+    } else {
+        format!(
+            "quarantined to {}",
+            quarantine_dir
+                .strip_prefix(root)
+                .unwrap_or(&quarantine_dir)
+                .display()
+        )
+    };
+    let report_path = root.join(STATE_DIR).join("gc").join(format!("{stamp}.md"));
+    if let Some(parent) = report_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&report_path, render_report(root, plan, &mode))?;
+
+    Ok(GcOutcome {
+        files_removed,
+        index_rows_deleted,
+        manifest_entries_removed,
+        quarantine_dir: (!purge && files_removed > 0).then_some(quarantine_dir),
+        report_path,
+    })
+}
+
+/// Render the plan as deterministic markdown (sorted, root-relative paths) so
+/// successive reports diff cleanly in git.
+pub fn render_report(root: &Path, plan: &GcPlan, mode: &str) -> String {
+    let mut out = String::new();
+    out.push_str("# cassio gc report\n\n");
+    out.push_str(&format!("- Root: {}\n", root.display()));
+    out.push_str(&format!("- Mode: {mode}\n\n"));
+
+    out.push_str(&format!(
+        "## Orphaned transcripts ({})\n\n",
+        plan.orphans.len()
+    ));
+    for finding in &plan.orphans {
+        out.push_str(&format!("- `{}` — {}\n", finding.rel_path, finding.reason));
+    }
+    if plan.orphans.is_empty() {
+        out.push_str("None.\n");
+    }
+
+    out.push_str(&format!("\n## Duplicates ({})\n\n", plan.duplicates.len()));
+    for finding in &plan.duplicates {
+        out.push_str(&format!("- `{}` — {}\n", finding.rel_path, finding.reason));
+    }
+    if plan.duplicates.is_empty() {
+        out.push_str("None.\n");
+    }
+
+    let stale_rows: usize = plan.stale_index.iter().map(|s| s.rows).sum();
+    out.push_str(&format!("\n## Stale index rows ({stale_rows})\n\n"));
+    for stale in &plan.stale_index {
+        let index = stale
+            .index_path
+            .strip_prefix(root)
+            .unwrap_or(&stale.index_path);
+        out.push_str(&format!(
+            "- `{}`: `{}` ({} row(s))\n",
+            index.display(),
+            stale.source_path,
+            stale.rows
+        ));
+    }
+    if plan.stale_index.is_empty() {
+        out.push_str("None.\n");
+    }
+    out
+}
+
+fn finding(kind: FindingKind, file: &ArchiveFile, reason: String) -> GcFinding {
+    let rel_path = if file.training_root {
+        format!("training/{}", file.rel_path)
+    } else {
+        file.rel_path.clone()
+    };
+    GcFinding {
+        kind,
+        path: file.path.clone(),
+        rel_path,
+        key: file.key.clone(),
+        reason,
+    }
+}
+
+/// Collect session transcripts, JSONL exports, and training sidecars under `root`.
+fn scan_session_artifacts(root: &Path, training_root: bool) -> Vec<ArchiveFile> {
+    let mut files = Vec::new();
+    for entry in WalkDir::new(root)
+        .into_iter()
+        .filter_entry(|e| !manifest::is_state_dir(e))
+        .filter_map(Result::ok)
+    {
+        let path = entry.path();
+        if !path.is_file() {
+            continue;
+        }
+        let Ok(rel) = path.strip_prefix(root) else {
+            continue;
+        };
+        let rel_path = rel.to_string_lossy().replace('\\', "/");
+        let Some(key) = session_artifact_key(&rel_path) else {
+            continue;
+        };
+        files.push(ArchiveFile {
+            path: path.to_path_buf(),
+            rel_path,
+            key,
+            training_root,
+        });
+    }
+    files.sort_by(|a, b| a.rel_path.cmp(&b.rel_path));
+    files
+}
+
+/// Manifest key for a root-relative session artifact path, or `None` for
+/// summaries and anything else that is not a per-session output.
+pub(crate) fn session_artifact_key(rel_path: &str) -> Option<String> {
+    let stem = ARTIFACT_EXTENSIONS
+        .iter()
+        .find_map(|ext| rel_path.strip_suffix(ext))?;
+    let name = stem.rsplit('/').next().unwrap_or(stem);
+    session_tool_suffix(name)?;
+    Some(stem.to_string())
+}
+
+fn find_duplicates(
+    live: &[ArchiveFile],
+    expected: &HashSet<String>,
+    manifest: &Manifest,
+) -> Result<Vec<GcFinding>, CassioError> {
+    let mut duplicates = Vec::new();
+    let mut flagged: HashSet<String> = HashSet::new();
+
+    // Several stems recorded from one source log: keep the stem batch mode
+    // would write today, otherwise the most recently written one.
+    let live_keys: HashSet<&str> = live.iter().map(|f| f.key.as_str()).collect();
+    let mut by_source: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for (key, entry) in &manifest.sessions {
+        if live_keys.contains(key.as_str()) {
+            by_source
+                .entry(entry.source_path.as_str())
+                .or_default()
+                .push(key.as_str());
+        }
+    }
+    for keys in by_source.values().filter(|keys| keys.len() > 1) {
+        let keep = keys
+            .iter()
+            .copied()
+            .max_by_key(|key| {
+                (
+                    expected.contains(*key),
+                    manifest.get(key).map(|entry| entry.written_at),
+                )
+            })
+            .unwrap_or(keys[0]);
+        for key in keys.iter().filter(|key| **key != keep) {
+            for file in live.iter().filter(|f| f.key == *key) {
+                flagged.insert(file.rel_path.clone());
+                duplicates.push(finding(
+                    FindingKind::Duplicate,
+                    file,
+                    format!("same source as `{keep}`"),
+                ));
+            }
+        }
+    }
+
+    // Byte-identical transcripts under different stems.
+    let mut by_hash: BTreeMap<String, Vec<&ArchiveFile>> = BTreeMap::new();
+    for file in live
+        .iter()
+        .filter(|f| !f.training_root && !flagged.contains(&f.rel_path))
+        .filter(|f| !f.rel_path.ends_with(".training.json"))
+    {
+        by_hash
+            .entry(hash_file(&file.path)?)
+            .or_default()
+            .push(file);
+    }
+    for group in by_hash.values().filter(|group| group.len() > 1) {
+        let keep = group
+            .iter()
+            .copied()
+            .max_by_key(|file| {
+                (
+                    expected.contains(&file.key),
+                    manifest.get(&file.key).is_some(),
+                    std::cmp::Reverse(file.rel_path.clone()),
+                )
+            })
+            .unwrap_or(group[0]);
+        for file in group.iter().filter(|f| f.rel_path != keep.rel_path) {
+            duplicates.push(finding(
+                FindingKind::Duplicate,
+                file,
+                format!("identical to `{}`", keep.rel_path),
+            ));
+        }
+    }
+    Ok(duplicates)
+}
+
+/// Rows in any semantic or lexical index under `root/.cassio/index/` whose
+/// source file is missing or is about to be removed by this plan.
+fn find_stale_index_rows(
+    root: &Path,
+    training_root: Option<&Path>,
+    removed: &HashSet<&str>,
+) -> Result<Vec<StaleIndexRows>, CassioError> {
+    let index_dir = root.join(STATE_DIR).join("index");
+    let Ok(entries) = fs::read_dir(&index_dir) else {
+        return Ok(Vec::new());
+    };
+    let mut index_paths: Vec<PathBuf> = entries
+        .filter_map(Result::ok)
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "sqlite"))
+        .collect();
+    index_paths.sort();
+
+    let mut stale = Vec::new();
+    for index_path in index_paths {
+        let conn = Connection::open(&index_path)
+            .map_err(|e| CassioError::Other(format!("Failed to open index database: {e}")))?;
+        let Some(table) = source_table(&conn)? else {
+            continue;
+        };
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT source_path, COUNT(*) FROM {table} GROUP BY source_path"
+            ))
+            .map_err(|e| CassioError::Other(format!("Failed to query index: {e}")))?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as usize))
+            })
+            .map_err(|e| CassioError::Other(format!("Failed to read index: {e}")))?;
+        for row in rows {
+            let (source_path, count) =
+                row.map_err(|e| CassioError::Other(format!("Failed to read index row: {e}")))?;
+            let exists = root.join(&source_path).is_file()
+                || training_root.is_some_and(|t| t.join(&source_path).is_file());
+            let training_rel = format!("training/{source_path}");
+            let removing =
+                removed.contains(source_path.as_str()) || removed.contains(training_rel.as_str());
+            if !exists || removing {
+                stale.push(StaleIndexRows {
+                    index_path: index_path.clone(),
+                    source_path,
+                    rows: count,
+                });
+            }
+        }
+    }
+    Ok(stale)
+}
+
+/// The table holding per-file rows: `chunks` in a semantic index, `lines` in
+/// the lexical index. `None` for any other database.
+fn source_table(conn: &Connection) -> Result<Option<&'static str>, CassioError> {
+    for table in ["chunks", "lines"] {
+        let found: Option<String> = conn
+            .query_row(
+                "SELECT name FROM sqlite_master WHERE type = 'table' AND name = ?1",
+                params![table],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| CassioError::Other(format!("Failed to inspect index database: {e}")))?;
+        if found.is_some() {
+            return Ok(Some(table));
+        }
+    }
+    Ok(None)
+}
+
+/// Whether a manifest `source_path` still resolves to something on disk.
+///
+/// Virtual child paths (`state.db/<id>`, `conversations.json/<uuid>`) count as
+/// present when their containing file or export still exists.
+fn source_exists(path: &Path) -> bool {
+    if path.exists() || path.parent().is_some_and(Path::is_file) {
+        return true;
+    }
+    crate::parser::claude_chat::export_root_from_virtual(path).is_some_and(|root| root.exists())
+}
+
+fn hash_file(path: &Path) -> Result<String, CassioError> {
+    let bytes = fs::read(path)?;
+    Ok(format!("{:x}", Sha256::digest(&bytes)))
+}
+
+/// Rename when possible; fall back to copy + remove across filesystems.
+fn move_file(from: &Path, to: &Path) -> Result<(), CassioError> {
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    fs::copy(from, to)?;
+    fs::remove_file(from)?;
+    Ok(())
+}
+
+#[cfg(test)]
+#[path = "gc_test.rs"]
+mod tests;
@@ -0,0 +1,323 @@
+use super::*;
+use crate::manifest::ManifestEntry;
+use chrono::TimeZone;
+
+fn temp_root(label: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("cassio_gc_{label}_{}", std::process::id()))
+}
+
+fn write(root: &Path, rel: &str, content: &str) -> PathBuf {
+    let path = root.join(rel);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(&path, content).unwrap();
+    path
+}
+
+fn entry(source: &Path, written_hour: u32) -> ManifestEntry {
+    ManifestEntry {
+        tool: "claude".to_string(),
+        source_path: source.to_string_lossy().into_owned(),
+        session_id: "abc".to_string(),
+        started_at: Utc.with_ymd_and_hms(2025, 11, 12, 21, 52, 16).unwrap(),
+        project_path: "/tmp/project".to_string(),
+        format: "emoji-text".to_string(),
+        training: true,
+        written_at: Utc
+            .with_ymd_and_hms(2025, 11, 13, written_hour, 0, 0)
+            .unwrap(),
+        fingerprint: None,
+    }
+}
+
+fn expected(keys: &[&str]) -> HashSet<String> {
+    keys.iter().map(|k| k.to_string()).collect()
+}
+
+#[test]
+fn session_artifact_key_ignores_summaries() {
+    assert_eq!(
+        session_artifact_key("2025-11/2025-11-12T21-52-16-claude.training.json").as_deref(),
+        Some("2025-11/2025-11-12T21-52-16-claude")
+    );
+    assert_eq!(
+        session_artifact_key("2025-11/2025-11-12T21-52-16-codex.jsonl").as_deref(),
+        Some("2025-11/2025-11-12T21-52-16-codex")
+    );
+    assert_eq!(
+        session_artifact_key("2025-11/2025-11-12.compacted.md"),
+        None
+    );
+    assert_eq!(session_artifact_key("2025-11/2025-11.monthly.md"), None);
+    // Every extension the catalog strips is a session artifact here too.
+    for ext in ARTIFACT_EXTENSIONS {
+        assert_eq!(
+            session_artifact_key(&format!("cassio/2025/11/12T21-52-claude{ext}")).as_deref(),
+            Some("cassio/2025/11/12T21-52-claude")
+        );
+    }
+}
+
+#[test]
+fn unexpected_stems_without_live_source_are_orphans() {
+    let root = temp_root("orphans");
+    let _ = fs::remove_dir_all(&root);
+    write(&root, "2025-11/2025-11-12T21-52-16-claude.md", "live");
+    write(
+        &root,
+        "2025-11/2025-11-12T21-52-16-claude.training.json",
+        "{}",
+    );
+    write(&root, "2025-11/2025-11-12T22-00-00-codex.md", "stale");
+    write(&root, "2025-11/2025-11-12.compacted.md", "daily summary");
+    write(
+        &root,
+        ".cassio/quarantine/x/2025-11/2025-11-01T00-00-00-pi.md",
+        "old",
+    );
+
+    let plan = plan(
+        &root,
+        None,
+        &expected(&["2025-11/2025-11-12T21-52-16-claude"]),
+        &Manifest::default(),
+    )
+    .unwrap();
+
+    let orphans: Vec<_> = plan.orphans.iter().map(|f| f.rel_path.as_str()).collect();
+    assert_eq!(orphans, vec!["2025-11/2025-11-12T22-00-00-codex.md"]);
+    assert!(plan.duplicates.is_empty());
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn manifest_keeps_stems_whose_source_still_exists() {
+    let root = temp_root("manifest_live");
+    let _ = fs::remove_dir_all(&root);
+    let source = write(&root, "sources/a.jsonl", "{}");
+    write(&root, "2025-11/2025-11-12T21-52-16-claude.md", "imported");
+    write(
+        &root,
+        "2025-11/2025-11-12T22-00-00-claude.md",
+        "deleted source",
+    );
+
+    let mut manifest = Manifest::default();
+    manifest.record(
+        "2025-11/2025-11-12T21-52-16-claude".to_string(),
+        entry(&source, 8),
+    );
+    manifest.record(
+        "2025-11/2025-11-12T22-00-00-claude".to_string(),
+        entry(&root.join("sources/gone.jsonl"), 8),
+    );
+
+    let plan = plan(&root, None, &HashSet::new(), &manifest).unwrap();
+    assert_eq!(plan.orphans.len(), 1);
+    assert_eq!(
+        plan.orphans[0].rel_path,
+        "2025-11/2025-11-12T22-00-00-claude.md"
+    );
+    assert!(plan.orphans[0].reason.starts_with("source missing"));
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn renamed_stems_from_one_source_keep_the_expected_name() {
+    let root = temp_root("renamed");
+    let _ = fs::remove_dir_all(&root);
+    let source = write(&root, "sources/a.jsonl", "{}");
+    write(&root, "2025-11/2025-11-12T21-52-16-claude.md", "v1");
+    write(
+        &root,
+        "2025-11/2025-11-12T21-52-16-1a2b3c4d-claude.md",
+        "v2",
+    );
+
+    let mut manifest = Manifest::default();
+    manifest.record(
+        "2025-11/2025-11-12T21-52-16-claude".to_string(),
+        entry(&source, 9),
+    );
+    manifest.record(
+        "2025-11/2025-11-12T21-52-16-1a2b3c4d-claude".to_string(),
+        entry(&source, 8),
+    );
+
+    let plan = plan(
+        &root,
+        None,
+        &expected(&["2025-11/2025-11-12T21-52-16-1a2b3c4d-claude"]),
+        &manifest,
+    )
+    .unwrap();
+    assert!(plan.orphans.is_empty());
+    let dups: Vec<_> = plan
+        .duplicates
+        .iter()
+        .map(|f| f.rel_path.as_str())
+        .collect();
+    assert_eq!(dups, vec!["2025-11/2025-11-12T21-52-16-claude.md"]);
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn identical_transcripts_are_duplicates() {
+    let root = temp_root("identical");
+    let _ = fs::remove_dir_all(&root);
+    write(&root, "2025-11/2025-11-12T21-52-16-claude.md", "same");
+    write(
+        &root,
+        "2025-11/2025-11-12T21-52-16-9f8e7d6c-claude.md",
+        "same",
+    );
+
+    let plan = plan(
+        &root,
+        None,
+        &expected(&[
+            "2025-11/2025-11-12T21-52-16-claude",
+            "2025-11/2025-11-12T21-52-16-9f8e7d6c-claude",
+        ]),
+        &Manifest::default(),
+    )
+    .unwrap();
+    let dups: Vec<_> = plan
+        .duplicates
+        .iter()
+        .map(|f| f.rel_path.as_str())
+        .collect();
+    assert_eq!(dups, vec!["2025-11/2025-11-12T21-52-16-claude.md"]);
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn apply_quarantines_findings_and_prunes_index_rows() {
+    let root = temp_root("apply");
+    let _ = fs::remove_dir_all(&root);
+    write(&root, "2025-11/2025-11-12T21-52-16-claude.md", "live");
+    write(&root, "2025-11/2025-11-12T22-00-00-codex.md", "orphan");
+
+    let index_path = root.join(".cassio/index/builtin-test.sqlite");
+    fs::create_dir_all(index_path.parent().unwrap()).unwrap();
+    let conn = Connection::open(&index_path).unwrap();
+    conn.execute_batch(
+        "CREATE TABLE chunks (id TEXT PRIMARY KEY, source_path TEXT NOT NULL);
+         INSERT INTO chunks VALUES ('1', '2025-11/2025-11-12T21-52-16-claude.md');
+         INSERT INTO chunks VALUES ('2', '2025-11/2025-11-12T22-00-00-codex.md');
+         INSERT INTO chunks VALUES ('3', '2025-10/2025-10-01T00-00-00-pi.md');",
+    )
+    .unwrap();
+    drop(conn);
+
+    let mut manifest = Manifest::default();
+    let plan = plan(
+        &root,
+        None,
+        &expected(&["2025-11/2025-11-12T21-52-16-claude"]),
+        &manifest,
+    )
+    .unwrap();
+    assert_eq!(plan.stale_index.len(), 2);
+
+    let outcome = apply(&root, None, &plan, &mut manifest, false).unwrap();
+    assert_eq!(outcome.files_removed, 1);
+    assert_eq!(outcome.index_rows_deleted, 2);
+    assert!(!root.join("2025-11/2025-11-12T22-00-00-codex.md").exists());
+    let quarantined = outcome
+        .quarantine_dir
+        .unwrap()
+        .join("2025-11/2025-11-12T22-00-00-codex.md");
+    assert_eq!(fs::read_to_string(quarantined).unwrap(), "orphan");
+    let report = fs::read_to_string(&outcome.report_path).unwrap();
+    assert!(report.contains("`2025-11/2025-11-12T22-00-00-codex.md`"));
+
+    let conn = Connection::open(&index_path).unwrap();
+    let remaining: i64 = conn
+        .query_row("SELECT COUNT(*) FROM chunks", [], |row| row.get(0))
+        .unwrap();
+    assert_eq!(remaining, 1);
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn apply_keeps_entries_whose_training_json_lives_in_the_training_root() {
+    let base = temp_root("apply_training");
+    let _ = fs::remove_dir_all(&base);
+    let root = base.join("transcripts");
+    let training = base.join("training");
+    let source = write(&base, "source.jsonl", "{}");
+    write(&root, "2025-11/2025-11-12T21-52-16-claude.md", "same");
+    write(&root, "2025-11/2025-11-12T21-52-17-claude.md", "same");
+    write(
+        &training,
+        "2025-11/2025-11-12T21-52-17-claude.training.json",
+        "{}",
+    );
+
+    let mut manifest = Manifest::default();
+    manifest.record(
+        "2025-11/2025-11-12T21-52-17-claude".to_string(),
+        entry(&source, 1),
+    );
+    let plan = plan(
+        &root,
+        Some(&training),
+        &expected(&["2025-11/2025-11-12T21-52-16-claude"]),
+        &manifest,
+    )
+    .unwrap();
+    let flagged: Vec<&str> = plan
+        .duplicates
+        .iter()
+        .map(|f| f.rel_path.as_str())
+        .collect();
+    assert_eq!(flagged, ["2025-11/2025-11-12T21-52-17-claude.md"]);
+
+    let outcome = apply(&root, Some(&training), &plan, &mut manifest, true).unwrap();
+    assert_eq!(outcome.manifest_entries_removed, 0);
+    assert!(manifest.get("2025-11/2025-11-12T21-52-17-claude").is_some());
+    let _ = fs::remove_dir_all(&base);
+}
+
+#[test]
+fn apply_prunes_lexical_index_lines_of_removed_transcripts() {
+    let root = temp_root("apply_lexical");
+    let _ = fs::remove_dir_all(&root);
+    write(
+        &root,
+        "2025-11/2025-11-12T21-52-16-claude.md",
+        "👤 zepp live\n",
+    );
+    write(
+        &root,
+        "2025-11/2025-11-12T22-00-00-codex.md",
+        "👤 zepp orphan\n",
+    );
+    let catalog = crate::manifest::SessionCatalog::load(&root);
+    let files =
+        crate::index::files_to_index(&root, &crate::index::IndexOptions::default(), &catalog);
+    crate::lexical::update_lexical_index(&root, None, &files, &catalog).unwrap();
+
+    let mut manifest = Manifest::default();
+    let plan = plan(
+        &root,
+        None,
+        &expected(&["2025-11/2025-11-12T21-52-16-claude"]),
+        &manifest,
+    )
+    .unwrap();
+    let stale: Vec<&str> = plan
+        .stale_index
+        .iter()
+        .map(|s| s.source_path.as_str())
+        .collect();
+    assert_eq!(stale, ["2025-11/2025-11-12T22-00-00-codex.md"]);
+
+    let outcome = apply(&root, None, &plan, &mut manifest, true).unwrap();
+    assert_eq!(outcome.index_rows_deleted, 1);
+    let conn = Connection::open(crate::lexical::lexical_index_path(&root)).unwrap();
+    let count = |sql: &str| -> i64 { conn.query_row(sql, [], |row| row.get(0)).unwrap() };
+    assert_eq!(count("SELECT COUNT(*) FROM lines"), 1);
+    assert_eq!(count("SELECT COUNT(*) FROM files"), 1);
+    let _ = fs::remove_dir_all(&root);
+}
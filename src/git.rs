@@ -109,6 +109,35 @@ pub fn auto_commit_and_push(dir: &Path, message: &str, git: &GitConfig) -> Resul
     Ok(())
 }
 
+/// Remote URLs of the repository containing `dir` (fetch and push, deduplicated).
+///
+/// Returns an empty list when `dir` is missing, not in a git repo, or git is not
+/// installed, so callers can treat "no remote" and "cannot tell" alike.
+pub fn remote_urls(dir: &Path) -> Vec<String> {
+    if !dir.is_dir() {
+        return Vec::new();
+    }
+    let Ok(output) = Command::new("git")
+        .args(["remote", "-v"])
+        .current_dir(dir)
+        .stderr(std::process::Stdio::null())
+        .output()
+    else {
+        return Vec::new();
+    };
+    if !output.status.success() {
+        return Vec::new();
+    }
+    let mut urls: Vec<String> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.split_whitespace().nth(1))
+        .map(str::to_string)
+        .collect();
+    urls.sort();
+    urls.dedup();
+    urls
+}
+
 fn is_git_repo(dir: &Path) -> bool {
     let status = Command::new("git")
         .args(["rev-parse", "--is-inside-work-tree"])
@@ -3,7 +3,13 @@
 //! `cassio index` scans monthly, daily, session, and optional training artifacts,
 //! splits them into overlapping text chunks, embeds new or changed chunks through
 //! the configured provider, and writes vectors to `.cassio/index.sqlite` for
-//! `cassio search --semantic`.
+//! `cassio search --semantic`. The same pass refreshes the FTS5 lexical index
+//! (`lexical`) that plain `cassio search` queries, and the IVF clusters (`ann`)
+//! semantic search probes instead of scoring every chunk.
+//!
+//! Session transcripts are chunked on turn boundaries (each `👤` message starts
+//! a new chunk), and every chunk records its speaker mix, project, tool, models,
+//! and date so semantic search applies the same filters as lexical search.
 
 use std::collections::{HashMap, HashSet};
 use std::fs;
@@ -22,8 +28,18 @@ use serde::Deserialize;
 use sha2::{Digest, Sha256};
 use walkdir::WalkDir;
 
+use crate::ann::{ANN_MIN_CHUNKS, AnnReport, prepare_schema, update_ann_index};
+use crate::ast::session_tool_suffix;
 use crate::error::CassioError;
-use crate::search::{SearchArtifact, artifact_for_path, strip_path_noise};
+use crate::formatter::emoji_text::EMOJI_USER;
+use crate::frontmatter;
+use crate::lexical::{LexicalReport, update_lexical_index};
+use crate::manifest::SessionCatalog;
+use crate::related;
+use crate::search::{
+    LineSpeaker, SearchArtifact, block_speakers, cataloged_artifact_for_path, file_date_of,
+    file_stem, header_project, session_models, strip_path_noise,
+};
 
 const DEFAULT_PROVIDER: &str = "builtin";
 const DEFAULT_MODEL: &str = "nomic-embed-text-v1.5.Q4_K_M";
@@ -69,7 +85,13 @@ pub struct IndexReport {
     pub chunks: usize,
     pub embedded: usize,
     pub reused: usize,
+    /// Reused chunks whose metadata columns were rewritten without re-embedding.
+    pub relabeled: usize,
     pub stale_deleted: usize,
+    /// Root-relative session transcripts with newly embedded chunks.
+    pub changed_sessions: Vec<String>,
+    pub lexical: LexicalReport,
+    pub ann: AnnReport,
 }
 
 #[derive(Debug, Clone)]
@@ -82,9 +104,26 @@ struct IndexChunk {
     content_hash: String,
     chunk_text: String,
     embedding_text: String,
+    metadata: ChunkMetadata,
+}
+
+/// Filterable facts about a chunk, stored beside its embedding.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct ChunkMetadata {
+    /// Speakers with at least one line in the chunk, as `user,assistant,tool`.
+    speakers: Option<String>,
+    project: Option<String>,
+    tool: Option<String>,
+    /// Every `📋 Model:` in the session, joined with `, ` (as the lexical index).
+    model: Option<String>,
+    /// `YYYY-MM-DD` of the session or daily summary.
+    date: Option<String>,
 }
 
-pub fn run_index(root: &Path, options: IndexOptions) -> Result<(), CassioError> {
+/// Metadata columns added to `chunks` by `prepare_chunk_metadata`.
+const CHUNK_METADATA_COLUMNS: [&str; 5] = ["speakers", "project", "tool", "model", "date"];
+
+pub fn run_index(root: &Path, options: IndexOptions) -> Result<IndexReport, CassioError> {
     let report = build_index(root, &options)?;
     println!("cassio index: {}", root.display());
     println!("Index: {}", report.index_path.display());
@@ -92,17 +131,46 @@ pub fn run_index(root: &Path, options: IndexOptions) -> Result<(), CassioError>
     println!("Chunks indexed: {}", report.chunks);
     println!("Embedded: {}", report.embedded);
     println!("Reused: {}", report.reused);
+    if report.relabeled > 0 {
+        println!("Metadata refreshed: {}", report.relabeled);
+    }
     if report.stale_deleted > 0 {
         println!("Stale chunks deleted: {}", report.stale_deleted);
     }
-    Ok(())
+    if report.ann.clusters > 0 {
+        println!(
+            "ANN clusters: {}{} ({} chunk(s) assigned)",
+            report.ann.clusters,
+            if report.ann.retrained {
+                ", retrained"
+            } else {
+                ""
+            },
+            report.ann.assigned
+        );
+    }
+    println!("Lexical index: {}", report.lexical.index_path.display());
+    println!("Lexical files updated: {}", report.lexical.updated);
+    if report.lexical.removed > 0 {
+        println!("Lexical files removed: {}", report.lexical.removed);
+    }
+    Ok(report)
 }
 
 pub fn build_index(root: &Path, options: &IndexOptions) -> Result<IndexReport, CassioError> {
-    let target = if let Some(month) = &options.month {
-        root.join(month)
-    } else {
-        root.to_path_buf()
+    let mut catalog = SessionCatalog::load(root);
+    if let Some(training_root) = &options.training_root {
+        catalog = catalog.with_root(training_root);
+    }
+    // A templated archive has no `YYYY-MM` directories: index from the root and
+    // keep only the month's cataloged sessions instead.
+    let month_filter = options
+        .month
+        .as_deref()
+        .filter(|month| !catalog.is_empty() && !root.join(month).is_dir());
+    let target = match &options.month {
+        Some(month) if month_filter.is_none() => root.join(month),
+        _ => root.to_path_buf(),
     };
     if !target.exists() {
         return Err(CassioError::Other(format!(
@@ -117,14 +185,27 @@ pub fn build_index(root: &Path, options: &IndexOptions) -> Result<IndexReport, C
         options.provider, options.model, options.base_url
     );
 
-    let files = files_to_index(&target, options);
+    let mut files = files_to_index(&target, options, &catalog);
+    if let Some(month) = month_filter {
+        files.retain(|path| catalog.lookup(path).is_some_and(|s| s.month() == month));
+    }
     eprintln!("index: found {} file(s) to scan", files.len());
+    // The lexical index needs no embeddings, so refresh it first: a failing
+    // embedding provider must not leave keyword search on a stale index.
+    let lexical = update_lexical_index(root, options.training_root.as_deref(), &files, &catalog)?;
+    eprintln!(
+        "index: lexical index {} ({} file(s) updated, {} removed)",
+        lexical.index_path.display(),
+        lexical.updated,
+        lexical.removed
+    );
     let mut chunks = Vec::new();
     for (index, path) in files.iter().enumerate() {
         let path_root = path_root_for_chunk(root, path, options);
         chunks.extend(chunk_file(
             path_root,
             path,
+            &catalog,
             options.include_paths,
             DEFAULT_CHUNK_CHARS,
         )?);
@@ -147,12 +228,21 @@ pub fn build_index(root: &Path, options: &IndexOptions) -> Result<IndexReport, C
     let index_path = index_path_for(root, &options.provider, &options.model);
     eprintln!("index: database {}", index_path.display());
     let conn = open_index(&index_path, root, options)?;
-    let (to_embed, reused) = changed_chunks(&conn, &chunks)?;
+    let (to_embed, to_relabel, reused) = changed_chunks(&conn, &chunks)?;
+    let relabeled = relabel_chunks(&conn, &to_relabel)?;
     let embedded = embed_and_store(&conn, &to_embed, options)?;
+    let mut changed_sessions: Vec<String> = to_embed
+        .iter()
+        .filter(|chunk| chunk.artifact == SearchArtifact::Session)
+        .map(|chunk| chunk.source_path.clone())
+        .collect();
+    changed_sessions.sort();
+    changed_sessions.dedup();
     let stale_deleted = delete_stale_chunks(&conn, &files, &chunks, root)?;
     if stale_deleted > 0 {
         eprintln!("index: deleted {stale_deleted} stale chunk(s)");
     }
+    let ann = update_ann_index(&conn, ANN_MIN_CHUNKS)?;
 
     Ok(IndexReport {
         index_path,
@@ -160,28 +250,41 @@ pub fn build_index(root: &Path, options: &IndexOptions) -> Result<IndexReport, C
         chunks: chunks.len(),
         embedded,
         reused,
+        relabeled,
         stale_deleted,
+        changed_sessions,
+        lexical,
+        ann,
     })
 }
 
-fn files_to_index(root: &Path, options: &IndexOptions) -> Vec<PathBuf> {
+pub(crate) fn files_to_index(
+    root: &Path,
+    options: &IndexOptions,
+    catalog: &SessionCatalog,
+) -> Vec<PathBuf> {
     let mut paths = Vec::new();
     // Dedicated training root: only index markdown from the transcript tree.
     // Co-located leftovers under output are ignored so they are not double-counted.
     let include_training_in_transcript_tree =
         options.include_training && options.training_root.is_none();
-    collect_index_files(root, include_training_in_transcript_tree, false, &mut paths);
+    collect_index_files(
+        root,
+        include_training_in_transcript_tree,
+        false,
+        catalog,
+        &mut paths,
+    );
 
     if options.include_training
         && let Some(training_root) = &options.training_root
     {
-        let training_target = if let Some(month) = &options.month {
-            training_root.join(month)
-        } else {
-            training_root.clone()
+        let training_target = match &options.month {
+            Some(month) if training_root.join(month).is_dir() => training_root.join(month),
+            _ => training_root.clone(),
         };
         if training_target.exists() {
-            collect_index_files(&training_target, true, true, &mut paths);
+            collect_index_files(&training_target, true, true, catalog, &mut paths);
         }
     }
 
@@ -194,14 +297,19 @@ fn collect_index_files(
     root: &Path,
     include_training: bool,
     training_only: bool,
+    catalog: &SessionCatalog,
     paths: &mut Vec<PathBuf>,
 ) {
-    for entry in WalkDir::new(root).into_iter().filter_map(Result::ok) {
+    for entry in WalkDir::new(root)
+        .into_iter()
+        .filter_entry(|e| !crate::manifest::is_state_dir(e))
+        .filter_map(Result::ok)
+    {
         let path = entry.path();
         if !path.is_file() {
             continue;
         }
-        let Some(artifact) = artifact_for_path(path) else {
+        let Some(artifact) = cataloged_artifact_for_path(path, catalog) else {
             continue;
         };
         if training_only && artifact != SearchArtifact::Training {
@@ -226,10 +334,11 @@ fn path_root_for_chunk<'a>(root: &'a Path, path: &Path, options: &'a IndexOption
 fn chunk_file(
     root: &Path,
     path: &Path,
+    catalog: &SessionCatalog,
     include_paths: bool,
     max_chars: usize,
 ) -> Result<Vec<IndexChunk>, CassioError> {
-    let artifact = artifact_for_path(path).ok_or_else(|| {
+    let artifact = cataloged_artifact_for_path(path, catalog).ok_or_else(|| {
         CassioError::Other(format!("Unsupported index artifact: {}", path.display()))
     })?;
     let source_path = path
@@ -238,13 +347,22 @@ fn chunk_file(
         .to_string_lossy()
         .to_string();
     let content = fs::read_to_string(path)?;
-    Ok(chunk_content(
-        &source_path,
-        artifact,
-        &content,
-        include_paths,
-        max_chars,
-    ))
+    let mut chunks = chunk_content(&source_path, artifact, &content, include_paths, max_chars);
+    let cataloged = catalog.lookup(path);
+    let tool = match cataloged {
+        Some(session) => Some(session.tool.clone()),
+        None => session_tool_suffix(&file_stem(path)).map(str::to_string),
+    }
+    .filter(|_| matches!(artifact, SearchArtifact::Session | SearchArtifact::Training));
+    let date = match cataloged {
+        Some(session) => Some(session.date.clone()),
+        None => file_date_of(path),
+    };
+    for chunk in &mut chunks {
+        chunk.metadata.tool = tool.clone();
+        chunk.metadata.date = date.clone();
+    }
+    Ok(chunks)
 }
 
 fn chunk_content(
@@ -260,14 +378,31 @@ fn chunk_content(
     let mut line_start = 0usize;
     let mut line_end = 0usize;
 
-    for (index, line) in content.lines().enumerate() {
+    let session = artifact == SearchArtifact::Session;
+    let frontmatter_lines = frontmatter::line_count(content);
+
+    for (index, line) in content.lines().enumerate().skip(frontmatter_lines) {
         let line_no = index + 1;
+        // A user message opens a new turn; a chunk never spans two turns.
+        if session && line.starts_with(EMOJI_USER) {
+            push_chunk(
+                &mut chunks,
+                source_path,
+                artifact,
+                &mut original_lines,
+                &mut embedding_lines,
+                line_start,
+                line_end,
+            );
+            line_start = 0;
+            line_end = 0;
+        }
         let mut embedding_line = if include_paths {
             line.trim().to_string()
         } else {
             strip_path_noise(line).trim().to_string()
         };
-        if !include_paths && is_tool_status_line(line) {
+        if (!include_paths && is_tool_status_line(line)) || related::is_footer_line(line) {
             embedding_line.clear();
         }
 
@@ -350,9 +485,38 @@ fn chunk_content(
         line_end,
     );
 
+    if session {
+        label_session_chunks(&mut chunks, content);
+    }
     chunks
 }
 
+/// Fill speaker mix, project, and models from the transcript itself.
+fn label_session_chunks(chunks: &mut [IndexChunk], content: &str) {
+    let lines: Vec<&str> = content.lines().collect();
+    let speakers = block_speakers(&lines);
+    let project = header_project(content);
+    let models = session_models(content);
+    let model = (!models.is_empty()).then(|| models.join(", "));
+    for chunk in chunks {
+        let range = speakers
+            .get(chunk.line_start.saturating_sub(1)..chunk.line_end.min(speakers.len()))
+            .unwrap_or_default();
+        let mix: Vec<&str> = [
+            (LineSpeaker::User, "user"),
+            (LineSpeaker::Assistant, "assistant"),
+            (LineSpeaker::Tool, "tool"),
+        ]
+        .into_iter()
+        .filter(|(speaker, _)| range.contains(speaker))
+        .map(|(_, name)| name)
+        .collect();
+        chunk.metadata.speakers = (!mix.is_empty()).then(|| mix.join(","));
+        chunk.metadata.project = project.clone();
+        chunk.metadata.model = model.clone();
+    }
+}
+
 fn push_chunk(
     chunks: &mut Vec<IndexChunk>,
     source_path: &str,
@@ -404,6 +568,7 @@ fn push_index_chunk(
         content_hash,
         chunk_text,
         embedding_text,
+        metadata: ChunkMetadata::default(),
     });
 }
 
@@ -458,8 +623,10 @@ fn open_index(path: &Path, root: &Path, options: &IndexOptions) -> Result<Connec
         "#,
     )
     .map_err(|e| CassioError::Other(format!("Failed to initialize index database: {e}")))?;
+    prepare_chunk_metadata(&conn)?;
+    prepare_schema(&conn)?;
 
-    set_metadata(&conn, "schema_version", "1")?;
+    set_metadata(&conn, "schema_version", "3")?;
     set_metadata(&conn, "source_root", &root.display().to_string())?;
     set_metadata(&conn, "provider", &options.provider)?;
     set_metadata(&conn, "model", &options.model)?;
@@ -469,6 +636,36 @@ fn open_index(path: &Path, root: &Path, options: &IndexOptions) -> Result<Connec
     Ok(conn)
 }
 
+/// Add the chunk metadata columns to an index created before they existed.
+/// Existing rows keep NULLs until `changed_chunks` relabels them.
+fn prepare_chunk_metadata(conn: &Connection) -> Result<(), CassioError> {
+    for column in CHUNK_METADATA_COLUMNS {
+        if !has_chunk_column(conn, column)? {
+            conn.execute_batch(&format!("ALTER TABLE chunks ADD COLUMN {column} TEXT"))
+                .map_err(|e| {
+                    CassioError::Other(format!("Failed to migrate index database: {e}"))
+                })?;
+        }
+    }
+    Ok(())
+}
+
+/// Whether `chunks` has the metadata columns semantic filters query.
+pub(crate) fn has_chunk_metadata(conn: &Connection) -> Result<bool, CassioError> {
+    for column in CHUNK_METADATA_COLUMNS {
+        if !has_chunk_column(conn, column)? {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+fn has_chunk_column(conn: &Connection, column: &str) -> Result<bool, CassioError> {
+    conn.prepare("SELECT 1 FROM pragma_table_info('chunks') WHERE name = ?1")
+        .and_then(|mut stmt| stmt.exists(params![column]))
+        .map_err(|e| CassioError::Other(format!("Failed to inspect index database: {e}")))
+}
+
 fn set_metadata(conn: &Connection, key: &str, value: &str) -> Result<(), CassioError> {
     conn.execute(
         r#"
@@ -482,28 +679,48 @@ fn set_metadata(conn: &Connection, key: &str, value: &str) -> Result<(), CassioE
     Ok(())
 }
 
+/// Split chunks into those needing an embedding, those whose embedding is
+/// current but whose metadata changed, and a count of reused chunks.
 fn changed_chunks(
     conn: &Connection,
     chunks: &[IndexChunk],
-) -> Result<(Vec<IndexChunk>, usize), CassioError> {
+) -> Result<(Vec<IndexChunk>, Vec<IndexChunk>, usize), CassioError> {
     let mut changed = Vec::new();
+    let mut relabel = Vec::new();
     let mut reused = 0usize;
     eprintln!(
         "index: checking {} chunk(s) against existing index",
         chunks.len()
     );
     let mut stmt = conn
-        .prepare("SELECT content_hash FROM chunks WHERE id = ?1")
+        .prepare(
+            "SELECT content_hash, speakers, project, tool, model, date FROM chunks WHERE id = ?1",
+        )
         .map_err(|e| CassioError::Other(format!("Failed to query index: {e}")))?;
     for (index, chunk) in chunks.iter().enumerate() {
-        let existing: Option<String> = stmt
-            .query_row(params![chunk.id], |row| row.get(0))
+        let existing: Option<(String, ChunkMetadata)> = stmt
+            .query_row(params![chunk.id], |row| {
+                Ok((
+                    row.get(0)?,
+                    ChunkMetadata {
+                        speakers: row.get(1)?,
+                        project: row.get(2)?,
+                        tool: row.get(3)?,
+                        model: row.get(4)?,
+                        date: row.get(5)?,
+                    },
+                ))
+            })
             .optional()
             .map_err(|e| CassioError::Other(format!("Failed to query index chunk: {e}")))?;
-        if existing.as_deref() == Some(&chunk.content_hash) {
-            reused += 1;
-        } else {
-            changed.push(chunk.clone());
+        match existing {
+            Some((hash, metadata)) if hash == chunk.content_hash => {
+                reused += 1;
+                if metadata != chunk.metadata {
+                    relabel.push(chunk.clone());
+                }
+            }
+            _ => changed.push(chunk.clone()),
         }
         let current = index + 1;
         if current == chunks.len() || current % 1_000 == 0 {
@@ -516,7 +733,40 @@ fn changed_chunks(
             );
         }
     }
-    Ok((changed, reused))
+    Ok((changed, relabel, reused))
+}
+
+/// Rewrite metadata columns of chunks whose embedding is still current.
+fn relabel_chunks(conn: &Connection, chunks: &[IndexChunk]) -> Result<usize, CassioError> {
+    if chunks.is_empty() {
+        return Ok(0);
+    }
+    let tx = conn
+        .unchecked_transaction()
+        .map_err(|e| CassioError::Other(format!("Failed to update index metadata: {e}")))?;
+    for chunk in chunks {
+        let metadata = &chunk.metadata;
+        tx.execute(
+            r#"
+            UPDATE chunks
+            SET speakers = ?2, project = ?3, tool = ?4, model = ?5, date = ?6
+            WHERE id = ?1
+            "#,
+            params![
+                chunk.id,
+                metadata.speakers,
+                metadata.project,
+                metadata.tool,
+                metadata.model,
+                metadata.date
+            ],
+        )
+        .map_err(|e| CassioError::Other(format!("Failed to update index metadata: {e}")))?;
+    }
+    tx.commit()
+        .map_err(|e| CassioError::Other(format!("Failed to update index metadata: {e}")))?;
+    eprintln!("index: refreshed metadata on {} chunk(s)", chunks.len());
+    Ok(chunks.len())
 }
 
 fn embed_and_store(
@@ -668,6 +918,83 @@ fn embed_builtin(input: &[&str]) -> Result<Vec<Vec<f32>>, CassioError> {
     Ok(out)
 }
 
+/// Score `(query, document)` pairs with a local GGUF cross-encoder reranker.
+///
+/// Each pair is encoded as `[BOS] query [EOS] [SEP] document [EOS]`, the layout
+/// llama.cpp's rerank pooling expects; the pooled output is one relevance
+/// logit per pair (higher is more relevant). Documents are truncated to fit the
+/// batch rather than rejected.
+pub(crate) fn rerank_local(
+    model_path: &Path,
+    query: &str,
+    documents: &[&str],
+) -> Result<Vec<f32>, CassioError> {
+    if !model_path.is_file() {
+        return Err(CassioError::Other(format!(
+            "Reranker model not found: {}",
+            model_path.display()
+        )));
+    }
+    let backend = builtin_backend()?;
+    let model = LlamaModel::load_from_file(backend, model_path, &LlamaModelParams::default())
+        .map_err(|e| CassioError::Other(format!("Reranker model load failed: {e}")))?;
+    let params = LlamaContextParams::default()
+        .with_embeddings(true)
+        .with_pooling_type(LlamaPoolingType::Rank)
+        .with_attention_type(LlamaAttentionType::NonCausal)
+        .with_n_ctx(NonZeroU32::new(2048))
+        .with_n_batch(2048)
+        .with_n_ubatch(2048)
+        .with_n_seq_max(1);
+    let mut context = model
+        .new_context(backend, params)
+        .map_err(|e| CassioError::Other(format!("Reranker context load failed: {e}")))?;
+    let query_tokens = model
+        .str_to_token(query, AddBos::Always)
+        .map_err(|e| CassioError::Other(format!("Reranker tokenize failed: {e}")))?;
+
+    let mut scores = Vec::with_capacity(documents.len());
+    for document in documents {
+        let mut tokens = query_tokens.clone();
+        tokens.push(model.token_eos());
+        tokens.push(model.token_sep());
+        let document_tokens = model
+            .str_to_token(document, AddBos::Never)
+            .map_err(|e| CassioError::Other(format!("Reranker tokenize failed: {e}")))?;
+        let room = (context.n_batch() as usize).saturating_sub(tokens.len() + 1);
+        if room == 0 {
+            return Err(CassioError::Other(format!(
+                "Reranker query has {} tokens, exceeding batch size {}",
+                query_tokens.len(),
+                context.n_batch()
+            )));
+        }
+        tokens.extend(document_tokens.into_iter().take(room));
+        tokens.push(model.token_eos());
+
+        context.clear_kv_cache();
+        let mut batch = LlamaBatch::new(tokens.len(), 1);
+        for (pos, token) in tokens.iter().enumerate() {
+            batch
+                .add(*token, pos as i32, &[0], true)
+                .map_err(|e| CassioError::Other(format!("Reranker batch failed: {e}")))?;
+        }
+        context
+            .encode(&mut batch)
+            .map_err(|e| CassioError::Other(format!("Reranker encode failed: {e}")))?;
+        let output = context
+            .embeddings_seq_ith(0)
+            .map_err(|e| CassioError::Other(format!("Reranker read failed: {e}")))?;
+        let Some(score) = output.first() else {
+            return Err(CassioError::Other(
+                "Reranker returned no score (is this a reranker model?)".into(),
+            ));
+        };
+        scores.push(*score);
+    }
+    Ok(scores)
+}
+
 fn builtin_backend() -> Result<&'static LlamaBackend, CassioError> {
     static BACKEND: OnceLock<LlamaBackend> = OnceLock::new();
     if let Some(backend) = BACKEND.get() {
@@ -799,15 +1126,22 @@ fn store_chunk(
         r#"
         INSERT INTO chunks(
             id, source_path, artifact, line_start, line_end, content_hash,
-            chunk_text, embedding_text, embedding, updated_at
+            chunk_text, embedding_text, embedding, updated_at,
+            speakers, project, tool, model, date
         )
-        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)
         ON CONFLICT(id) DO UPDATE SET
             content_hash = excluded.content_hash,
             chunk_text = excluded.chunk_text,
             embedding_text = excluded.embedding_text,
             embedding = excluded.embedding,
-            updated_at = excluded.updated_at
+            cluster = NULL,
+            updated_at = excluded.updated_at,
+            speakers = excluded.speakers,
+            project = excluded.project,
+            tool = excluded.tool,
+            model = excluded.model,
+            date = excluded.date
         "#,
         params![
             chunk.id,
@@ -819,7 +1153,12 @@ fn store_chunk(
             chunk.chunk_text,
             chunk.embedding_text,
             embedding_blob,
-            now
+            now,
+            chunk.metadata.speakers,
+            chunk.metadata.project,
+            chunk.metadata.tool,
+            chunk.metadata.model,
+            chunk.metadata.date
         ],
     )
     .map_err(|e| CassioError::Other(format!("Failed to store index chunk: {e}")))?;
@@ -870,8 +1209,8 @@ fn delete_stale_chunks(
     Ok(deleted)
 }
 
-pub(crate) fn index_path_for(root: &Path, provider: &str, model: &str) -> PathBuf {
-    root.join(".cassio")
+pub fn index_path_for(root: &Path, provider: &str, model: &str) -> PathBuf {
+    root.join(crate::manifest::STATE_DIR)
         .join("index")
         .join(format!("{}.sqlite", slug(&format!("{provider}-{model}"))))
 }
@@ -891,7 +1230,7 @@ fn slug(value: &str) -> String {
         .to_string()
 }
 
-fn artifact_name(artifact: SearchArtifact) -> &'static str {
+pub(crate) fn artifact_name(artifact: SearchArtifact) -> &'static str {
     match artifact {
         SearchArtifact::Monthly => "monthly",
         SearchArtifact::Daily => "daily",
@@ -927,13 +1266,13 @@ fn chunk_id(
     }
 }
 
-fn hash_text(text: &str) -> String {
+pub(crate) fn hash_text(text: &str) -> String {
     let mut hasher = Sha256::new();
     hasher.update(text.as_bytes());
     format!("{:x}", hasher.finalize())
 }
 
-fn encode_embedding(embedding: &[f32]) -> Vec<u8> {
+pub(crate) fn encode_embedding(embedding: &[f32]) -> Vec<u8> {
     let mut out = Vec::with_capacity(std::mem::size_of_val(embedding));
     for value in embedding {
         out.extend_from_slice(&value.to_le_bytes());
@@ -95,14 +95,14 @@ fn default_file_selection_excludes_training_metadata() {
         include_training: false,
         ..IndexOptions::default()
     };
-    let files = files_to_index(&root, &without_training);
+    let files = files_to_index(&root, &without_training, &SessionCatalog::default());
     assert_eq!(files.len(), 3);
 
     let with_training = IndexOptions {
         include_training: true,
         ..IndexOptions::default()
     };
-    let files = files_to_index(&root, &with_training);
+    let files = files_to_index(&root, &with_training, &SessionCatalog::default());
     assert_eq!(files.len(), 4);
 
     fs::remove_dir_all(&root).ok();
@@ -134,7 +134,7 @@ fn file_selection_uses_separate_training_root() {
         training_root: Some(training.clone()),
         ..IndexOptions::default()
     };
-    let files = files_to_index(&root, &options);
+    let files = files_to_index(&root, &options, &SessionCatalog::default());
     assert_eq!(files.len(), 2);
     assert!(
         files
@@ -200,3 +200,99 @@ fn embedding_decoding_round_trips_f32_blob() {
 fn embedding_decoding_rejects_invalid_blob_length() {
     assert!(decode_embedding(&[1, 2, 3]).is_err());
 }
+
+#[test]
+fn session_chunks_split_on_turns_and_carry_metadata() {
+    let content = "📋 Session: abc\n📋 Project: /work/faber\n📋 Model: gpt-5\n\
+                   👤 plan the deploy\n🤖 here is the plan\n✅ Bash: cargo test\n\
+                   👤 ship it\n🤖 shipped\n📋 Model: opus-4.5\n";
+    let chunks = chunk_content(
+        "2026-04/2026-04-30T10-00-00-codex.md",
+        SearchArtifact::Session,
+        content,
+        false,
+        1_800,
+    );
+    let ranges: Vec<(usize, usize)> = chunks
+        .iter()
+        .map(|chunk| (chunk.line_start, chunk.line_end))
+        .collect();
+    // Header, then one chunk per turn; the tool status line is not embedded and
+    // closes the first turn's chunk early.
+    assert_eq!(ranges, vec![(1, 3), (4, 5), (7, 9)]);
+    assert_eq!(chunks[0].metadata.speakers, None);
+    assert_eq!(
+        chunks[1].metadata.speakers.as_deref(),
+        Some("user,assistant")
+    );
+    assert!(
+        chunks
+            .iter()
+            .all(|chunk| chunk.metadata.project.as_deref() == Some("/work/faber"))
+    );
+    assert_eq!(chunks[2].metadata.model.as_deref(), Some("gpt-5, opus-4.5"));
+
+    let daily = chunk_content(
+        "2026-04/2026-04-30.daily.md",
+        SearchArtifact::Daily,
+        "👤 quoted prompt\n👤 another\n",
+        false,
+        1_800,
+    );
+    assert_eq!(daily.len(), 1, "summaries keep size-based chunks");
+    assert_eq!(daily[0].metadata, ChunkMetadata::default());
+}
+
+#[test]
+fn old_index_gains_metadata_columns_and_relabels_reused_chunks() {
+    let root = std::env::temp_dir().join(format!("cassio_index_migrate_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    let path = root.join("index.sqlite");
+    fs::create_dir_all(&root).unwrap();
+    let legacy = Connection::open(&path).unwrap();
+    legacy
+        .execute_batch(
+            "CREATE TABLE chunks (
+                id TEXT PRIMARY KEY, source_path TEXT NOT NULL, artifact TEXT NOT NULL,
+                line_start INTEGER NOT NULL, line_end INTEGER NOT NULL,
+                content_hash TEXT NOT NULL, chunk_text TEXT NOT NULL,
+                embedding_text TEXT NOT NULL, embedding BLOB NOT NULL, updated_at TEXT NOT NULL
+            );",
+        )
+        .unwrap();
+    drop(legacy);
+
+    let mut chunks = chunk_content(
+        "2026-04/2026-04-30T10-00-00-codex.md",
+        SearchArtifact::Session,
+        "📋 Project: /work/faber\n👤 plan the deploy\n",
+        false,
+        1_800,
+    );
+    let conn = open_index(&path, &root, &IndexOptions::default()).unwrap();
+    assert!(has_chunk_metadata(&conn).unwrap());
+    for chunk in &chunks {
+        store_chunk(&conn, chunk, &[1.0, 0.0]).unwrap();
+    }
+    conn.execute("UPDATE chunks SET speakers = NULL, project = NULL", [])
+        .unwrap();
+
+    let (embed, relabel, reused) = changed_chunks(&conn, &chunks).unwrap();
+    assert!(embed.is_empty());
+    assert_eq!((relabel.len(), reused), (chunks.len(), chunks.len()));
+    assert_eq!(relabel_chunks(&conn, &relabel).unwrap(), chunks.len());
+    let project: Option<String> = conn
+        .query_row(
+            "SELECT project FROM chunks WHERE id = ?1",
+            params![chunks[1].id],
+            |row| row.get(0),
+        )
+        .unwrap();
+    assert_eq!(project.as_deref(), Some("/work/faber"));
+
+    chunks[1].metadata.tool = Some("codex".to_string());
+    let (_, relabel, _) = changed_chunks(&conn, &chunks).unwrap();
+    assert_eq!(relabel.len(), 1);
+
+    let _ = fs::remove_dir_all(&root);
+}
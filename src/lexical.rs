@@ -0,0 +1,433 @@
+//! Lexical full-text index: SQLite FTS5 over transcript lines.
+//!
+//! `cassio index` (and auto-index) keeps `.cassio/index/lexical.sqlite` in step
+//! with the archive: one FTS row per non-empty line, stored with the line's
+//! speaker and its file's tool, project, models, artifact kind, and date.
+//! `cassio search` turns `--speaker`, `--tool`, `--project`, `model:`, and date
+//! bounds into SQL filters and ranks matches with BM25 instead of opening every
+//! file per query.
+//!
+//! # TRADE-OFFS
+//!
+//! - Rows are lines rather than embedding chunks, so indexed hits keep the line
+//!   numbers and speaker attribution of the line scan. The index costs roughly
+//!   the archive's text size again on disk.
+//! - FTS5 matches whole tokens and token prefixes; the scan matches arbitrary
+//!   substrings. Every candidate is re-checked with the scan's matcher, so
+//!   indexed results are a subset of scan results: `zepp` finds `zeppelin`, but
+//!   `pelin` only matches with `--scan`.
+//! - Search only trusts the index when every in-scope file is indexed at its
+//!   current size and modification time. Otherwise it falls back to the scan
+//!   until the next `cassio index`.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use chrono::Utc;
+use rusqlite::{Connection, params};
+
+use crate::ast::session_tool_suffix;
+use crate::error::CassioError;
+use crate::frontmatter;
+use crate::index::{artifact_name, hash_text};
+use crate::manifest::SessionCatalog;
+use crate::related;
+use crate::search::{
+    LineSpeaker, SearchArtifact, artifact_from_index_name, block_speakers,
+    cataloged_artifact_for_path, file_date_of, file_stem, header_project, month_dir_of,
+    session_models,
+};
+
+const LEXICAL_INDEX_FILE: &str = "lexical.sqlite";
+/// Bump when the `lines` columns change; older indexes are dropped and rebuilt
+/// by the next `cassio index`.
+const LEXICAL_SCHEMA_VERSION: &str = "2";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LexicalReport {
+    pub index_path: PathBuf,
+    /// Files re-read because their size, mtime, or content changed.
+    pub updated: usize,
+    /// Indexed files that no longer exist on disk.
+    pub removed: usize,
+}
+
+/// Where the lexical index for `root` lives, beside the semantic indexes.
+pub fn lexical_index_path(root: &Path) -> PathBuf {
+    root.join(crate::manifest::STATE_DIR)
+        .join("index")
+        .join(LEXICAL_INDEX_FILE)
+}
+
+/// Bring the lexical index up to date for `files` and drop rows for files that
+/// have been deleted. Files outside `files` (other months) are left alone.
+pub(crate) fn update_lexical_index(
+    root: &Path,
+    training_root: Option<&Path>,
+    files: &[PathBuf],
+    catalog: &SessionCatalog,
+) -> Result<LexicalReport, CassioError> {
+    let index_path = lexical_index_path(root);
+    let mut conn = open_lexical_index(&index_path)?;
+    let known = indexed_files(&conn)?;
+    let tx = conn.transaction().map_err(sql_error)?;
+
+    let mut updated = 0usize;
+    for path in files {
+        let Some(artifact) = cataloged_artifact_for_path(path, catalog) else {
+            continue;
+        };
+        let source_path = source_path_for(root, training_root, path);
+        let stamp = FileStamp::read(path)?;
+        let previous = known.get(&source_path);
+        if previous.is_some_and(|prev| prev.stamp == stamp) {
+            continue;
+        }
+        let content = fs::read_to_string(path)?;
+        let content_hash = hash_text(&content);
+        if previous.is_none_or(|prev| prev.content_hash != content_hash) {
+            tx.execute(
+                "DELETE FROM lines WHERE source_path = ?1",
+                params![source_path],
+            )
+            .map_err(sql_error)?;
+            insert_lines(
+                &tx,
+                root,
+                training_root,
+                path,
+                &source_path,
+                artifact,
+                &content,
+                catalog,
+            )?;
+            updated += 1;
+        }
+        tx.execute(
+            r#"
+            INSERT INTO files(source_path, artifact, size, modified_ms, content_hash)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            ON CONFLICT(source_path) DO UPDATE SET
+                artifact = excluded.artifact,
+                size = excluded.size,
+                modified_ms = excluded.modified_ms,
+                content_hash = excluded.content_hash
+            "#,
+            params![
+                source_path,
+                artifact_name(artifact),
+                stamp.size,
+                stamp.modified_ms,
+                content_hash
+            ],
+        )
+        .map_err(sql_error)?;
+    }
+
+    let mut removed = 0usize;
+    for (source_path, file) in &known {
+        if !resolve_source_path(root, training_root, file.artifact, source_path).exists() {
+            tx.execute(
+                "DELETE FROM lines WHERE source_path = ?1",
+                params![source_path],
+            )
+            .map_err(sql_error)?;
+            tx.execute(
+                "DELETE FROM files WHERE source_path = ?1",
+                params![source_path],
+            )
+            .map_err(sql_error)?;
+            removed += 1;
+        }
+    }
+    tx.execute(
+        "INSERT OR REPLACE INTO metadata(key, value) VALUES ('updated_at', ?1)",
+        params![Utc::now().to_rfc3339()],
+    )
+    .map_err(sql_error)?;
+    tx.commit().map_err(sql_error)?;
+
+    Ok(LexicalReport {
+        index_path,
+        updated,
+        removed,
+    })
+}
+
+/// Open the lexical index for querying, or `None` when it is missing or out of
+/// date for `files` (the in-scope artifacts the search would otherwise scan).
+pub(crate) fn open_fresh_lexical_index(
+    root: &Path,
+    training_root: Option<&Path>,
+    files: &[PathBuf],
+    include_training: bool,
+) -> Result<Option<Connection>, CassioError> {
+    let index_path = lexical_index_path(root);
+    if !index_path.is_file() {
+        return Ok(None);
+    }
+    let conn = open_lexical_index(&index_path)?;
+    let known = indexed_files(&conn)?;
+
+    let mut listed = HashSet::new();
+    for path in files {
+        let source_path = source_path_for(root, training_root, path);
+        let current = FileStamp::read(path)?;
+        if known
+            .get(&source_path)
+            .is_none_or(|file| file.stamp != current)
+        {
+            return Ok(None);
+        }
+        listed.insert(source_path);
+    }
+    let vanished = known.iter().any(|(source_path, file)| {
+        (include_training || file.artifact != SearchArtifact::Training)
+            && !listed.contains(source_path)
+    });
+    Ok((!vanished).then_some(conn))
+}
+
+/// Root-relative key for `path`: training artifacts under a separate training
+/// root are keyed relative to that root, like semantic index chunks.
+pub(crate) fn source_path_for(root: &Path, training_root: Option<&Path>, path: &Path) -> String {
+    let base = match training_root {
+        Some(training_root) if path.starts_with(training_root) => training_root,
+        _ => root,
+    };
+    path.strip_prefix(base)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Inverse of [`source_path_for`].
+pub(crate) fn resolve_source_path(
+    root: &Path,
+    training_root: Option<&Path>,
+    artifact: SearchArtifact,
+    source_path: &str,
+) -> PathBuf {
+    match training_root {
+        Some(training_root) if artifact == SearchArtifact::Training => {
+            let path = training_root.join(source_path);
+            if path.exists() {
+                path
+            } else {
+                root.join(source_path)
+            }
+        }
+        _ => root.join(source_path),
+    }
+}
+
+fn open_lexical_index(path: &Path) -> Result<Connection, CassioError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let conn = Connection::open(path)
+        .map_err(|e| CassioError::Other(format!("Failed to open lexical index: {e}")))?;
+    let version: Option<String> = conn
+        .query_row(
+            "SELECT value FROM metadata WHERE key = 'schema_version'",
+            params![],
+            |row| row.get(0),
+        )
+        .ok();
+    if version.is_some_and(|version| version != LEXICAL_SCHEMA_VERSION) {
+        conn.execute_batch("DROP TABLE IF EXISTS lines; DROP TABLE IF EXISTS files; DROP TABLE IF EXISTS metadata;")
+            .map_err(sql_error)?;
+    }
+    conn.execute_batch(
+        r#"
+        CREATE TABLE IF NOT EXISTS metadata (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS files (
+            source_path TEXT PRIMARY KEY,
+            artifact TEXT NOT NULL,
+            size INTEGER NOT NULL,
+            modified_ms INTEGER NOT NULL,
+            content_hash TEXT NOT NULL
+        );
+        CREATE VIRTUAL TABLE IF NOT EXISTS lines USING fts5(
+            text,
+            source_path UNINDEXED,
+            artifact UNINDEXED,
+            line UNINDEXED,
+            speaker UNINDEXED,
+            tool UNINDEXED,
+            project UNINDEXED,
+            model UNINDEXED,
+            month UNINDEXED,
+            date UNINDEXED,
+            tokenize = 'unicode61'
+        );
+        "#,
+    )
+    .map_err(|e| CassioError::Other(format!("Failed to initialize lexical index: {e}")))?;
+    conn.execute(
+        "INSERT OR IGNORE INTO metadata(key, value) VALUES ('schema_version', ?1)",
+        params![LEXICAL_SCHEMA_VERSION],
+    )
+    .map_err(sql_error)?;
+    Ok(conn)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileStamp {
+    size: i64,
+    modified_ms: i64,
+}
+
+impl FileStamp {
+    fn read(path: &Path) -> Result<Self, CassioError> {
+        let metadata = fs::metadata(path)?;
+        let modified_ms = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map(|elapsed| elapsed.as_millis() as i64)
+            .unwrap_or_default();
+        Ok(Self {
+            size: metadata.len() as i64,
+            modified_ms,
+        })
+    }
+}
+
+struct IndexedFile {
+    artifact: SearchArtifact,
+    stamp: FileStamp,
+    content_hash: String,
+}
+
+fn indexed_files(conn: &Connection) -> Result<HashMap<String, IndexedFile>, CassioError> {
+    let mut stmt = conn
+        .prepare("SELECT source_path, artifact, size, modified_ms, content_hash FROM files")
+        .map_err(sql_error)?;
+    let rows = stmt
+        .query_map(params![], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                FileStamp {
+                    size: row.get(2)?,
+                    modified_ms: row.get(3)?,
+                },
+                row.get::<_, String>(4)?,
+            ))
+        })
+        .map_err(sql_error)?;
+    let mut files = HashMap::new();
+    for row in rows {
+        let (source_path, artifact, stamp, content_hash) = row.map_err(sql_error)?;
+        let Some(artifact) = artifact_from_index_name(&artifact) else {
+            continue;
+        };
+        files.insert(
+            source_path,
+            IndexedFile {
+                artifact,
+                stamp,
+                content_hash,
+            },
+        );
+    }
+    Ok(files)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn insert_lines(
+    conn: &Connection,
+    root: &Path,
+    training_root: Option<&Path>,
+    path: &Path,
+    source_path: &str,
+    artifact: SearchArtifact,
+    content: &str,
+    catalog: &SessionCatalog,
+) -> Result<(), CassioError> {
+    let cataloged = catalog.lookup(path);
+    let base = match training_root {
+        Some(training_root) if path.starts_with(training_root) => training_root,
+        _ => root,
+    };
+    let (month, date) = match cataloged {
+        Some(session) => (
+            Some(session.month().to_string()),
+            Some(session.date.clone()),
+        ),
+        None => (month_dir_of(path, base), file_date_of(path)),
+    };
+    // Summaries aggregate every tool and project, so only per-session
+    // artifacts carry them; training JSON has no project header.
+    let tool = match artifact {
+        SearchArtifact::Session | SearchArtifact::Training => match cataloged {
+            Some(session) => Some(session.tool.clone()),
+            None => session_tool_suffix(&file_stem(path)).map(str::to_string),
+        },
+        _ => None,
+    };
+    let project = (artifact == SearchArtifact::Session)
+        .then(|| header_project(content))
+        .flatten();
+    let models = (artifact == SearchArtifact::Session)
+        .then(|| session_models(content).join(", "))
+        .filter(|models| !models.is_empty());
+
+    let lines: Vec<&str> = content.lines().collect();
+    let speakers = (artifact == SearchArtifact::Session).then(|| block_speakers(&lines));
+    let mut stmt = conn
+        .prepare(
+            r#"
+            INSERT INTO lines(
+                text, source_path, artifact, line, speaker, tool, project, model, month, date
+            )
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+            "#,
+        )
+        .map_err(sql_error)?;
+    for (index, line) in lines
+        .iter()
+        .enumerate()
+        .skip(frontmatter::line_count(content))
+    {
+        if line.trim().is_empty() || related::is_footer_line(line) {
+            continue;
+        }
+        let speaker = speakers
+            .as_ref()
+            .and_then(|speakers| match speakers[index] {
+                LineSpeaker::User => Some("user"),
+                LineSpeaker::Assistant => Some("assistant"),
+                LineSpeaker::Tool => Some("tool"),
+                LineSpeaker::Other => None,
+            });
+        stmt.execute(params![
+            line,
+            source_path,
+            artifact_name(artifact),
+            (index + 1) as i64,
+            speaker,
+            tool,
+            project,
+            models,
+            month,
+            date
+        ])
+        .map_err(sql_error)?;
+    }
+    Ok(())
+}
+
+fn sql_error(e: rusqlite::Error) -> CassioError {
+    CassioError::Other(format!("Lexical index error: {e}"))
+}
+
+#[cfg(test)]
+#[path = "lexical_test.rs"]
+mod tests;
@@ -0,0 +1,133 @@
+use super::*;
+
+fn temp_root(label: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("cassio_lexical_{label}_{}", std::process::id()))
+}
+
+fn write(root: &Path, rel: &str, content: &str) -> PathBuf {
+    let path = root.join(rel);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(&path, content).unwrap();
+    path
+}
+
+/// `(source_path, line, speaker, tool, date)` per indexed line.
+type Row = (String, i64, Option<String>, Option<String>, Option<String>);
+
+fn rows(root: &Path) -> Vec<Row> {
+    let conn = Connection::open(lexical_index_path(root)).unwrap();
+    let mut stmt = conn
+        .prepare(
+            "SELECT source_path, line, speaker, tool, date FROM lines ORDER BY source_path, line",
+        )
+        .unwrap();
+    stmt.query_map(params![], |row| {
+        Ok((
+            row.get(0)?,
+            row.get(1)?,
+            row.get(2)?,
+            row.get(3)?,
+            row.get(4)?,
+        ))
+    })
+    .unwrap()
+    .map(Result::unwrap)
+    .collect()
+}
+
+#[test]
+fn lines_carry_speaker_tool_and_date() {
+    let root = temp_root("columns");
+    let session = write(
+        &root,
+        "2026-04/2026-04-30T10-00-00-codex.md",
+        "📋 Project: /work/faber\n\n👤 question\ncontinued\n🤖 answer\n",
+    );
+    let daily = write(&root, "2026-04/2026-04-30.daily.md", "# Daily\n");
+    let catalog = SessionCatalog::default();
+    let report = update_lexical_index(&root, None, &[daily, session], &catalog).unwrap();
+    assert_eq!(report.updated, 2);
+
+    let rows = rows(&root);
+    let session_rows: Vec<_> = rows
+        .iter()
+        .filter(|row| row.0.ends_with("codex.md"))
+        .collect();
+    assert_eq!(session_rows.len(), 4, "blank lines are skipped");
+    assert_eq!(session_rows[0].2, None);
+    assert_eq!(session_rows[2].1, 4);
+    assert_eq!(session_rows[2].2.as_deref(), Some("user"));
+    assert_eq!(session_rows[3].2.as_deref(), Some("assistant"));
+    assert!(
+        session_rows
+            .iter()
+            .all(|row| row.3.as_deref() == Some("codex"))
+    );
+    assert!(
+        session_rows
+            .iter()
+            .all(|row| row.4.as_deref() == Some("2026-04-30"))
+    );
+
+    let daily_row = rows.iter().find(|row| row.0.ends_with("daily.md")).unwrap();
+    assert_eq!(daily_row.3, None, "summaries carry no tool");
+
+    fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn updates_are_incremental_and_drop_deleted_files() {
+    let root = temp_root("incremental");
+    let first = write(&root, "2026-04/2026-04-30T10-00-00-codex.md", "👤 one\n");
+    let second = write(&root, "2026-04/2026-04-30T11-00-00-pi.md", "👤 two\n");
+    let catalog = SessionCatalog::default();
+    let files = vec![first.clone(), second.clone()];
+    assert_eq!(
+        update_lexical_index(&root, None, &files, &catalog)
+            .unwrap()
+            .updated,
+        2
+    );
+    assert_eq!(
+        update_lexical_index(&root, None, &files, &catalog)
+            .unwrap()
+            .updated,
+        0
+    );
+    assert!(
+        open_fresh_lexical_index(&root, None, &files, false)
+            .unwrap()
+            .is_some()
+    );
+
+    fs::write(&first, "👤 one, edited\n").unwrap();
+    assert!(
+        open_fresh_lexical_index(&root, None, &files, false)
+            .unwrap()
+            .is_none()
+    );
+    assert_eq!(
+        update_lexical_index(&root, None, &files, &catalog)
+            .unwrap()
+            .updated,
+        1
+    );
+
+    fs::remove_file(&second).unwrap();
+    let files = vec![first];
+    assert!(
+        open_fresh_lexical_index(&root, None, &files, false)
+            .unwrap()
+            .is_none()
+    );
+    let report = update_lexical_index(&root, None, &files, &catalog).unwrap();
+    assert_eq!(report.removed, 1);
+    assert_eq!(rows(&root).len(), 1);
+    assert!(
+        open_fresh_lexical_index(&root, None, &files, false)
+            .unwrap()
+            .is_some()
+    );
+
+    fs::remove_dir_all(&root).ok();
+}
@@ -1,16 +1,38 @@
+pub mod ann;
+pub mod archive;
+pub mod ask;
 pub mod ast;
 pub mod compact;
 pub mod config;
 pub mod discover;
 pub mod error;
 pub mod evidence;
+pub mod fingerprint;
 pub mod formatter;
+pub mod frontmatter;
+pub mod gc;
 pub mod git;
 pub mod index;
+pub mod lexical;
+pub mod manifest;
+pub mod mcp;
 pub mod metrics;
+pub mod obsidian;
 pub mod parser;
 pub mod pricing;
+pub mod query;
 pub mod redact;
+pub mod related;
+pub mod rerank;
+pub mod roots;
+pub mod route;
 pub mod search;
+pub mod serve;
+pub mod session_search;
+pub mod site;
 pub mod summary;
+pub mod template;
+pub mod topics;
 pub mod training;
+pub mod tui;
+pub mod watchlist;
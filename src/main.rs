@@ -29,7 +29,7 @@
 //! prints them to stderr before exiting with code 1. This keeps error reporting
 //! consistent regardless of which path through `run()` failed.
 
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fs;
 use std::io::{self, BufRead};
 use std::path::{Path, PathBuf};
@@ -41,8 +41,11 @@ use cassio::ast::Tool;
 use cassio::config::{self, Config};
 use cassio::discover;
 use cassio::error::CassioError;
-use cassio::formatter::{Formatter, OutputFormat};
+use cassio::fingerprint::Fingerprint;
+use cassio::formatter::{Formatter, FormatterOptions, OutputFormat, ToolInput};
 use cassio::parser::Parser;
+use cassio::route::Router;
+use cassio::template::OutputTemplate;
 use cassio::training::ParsedSession;
 
 #[derive(ClapParser)]
@@ -54,8 +57,12 @@ struct Cli {
     /// Input file or directory (omit for stdin)
     path: Option<PathBuf>,
 
-    /// Output directory for batch mode
-    #[arg(short, long, global = true)]
+    /// Output directory for batch mode (repeat to search several archives)
+    #[arg(short = 'o', long = "output", value_name = "OUTPUT", global = true)]
+    outputs: Vec<PathBuf>,
+
+    /// The first `--output`; only `cassio search` takes more than one.
+    #[arg(skip)]
     output: Option<PathBuf>,
 
     /// Directory for *.training.json exports (defaults to --output / config output)
@@ -66,6 +73,9 @@ struct Cli {
     #[arg(short, long, default_value = "emoji-text", global = true)]
     format: String,
 
+    #[command(flatten)]
+    formatting: FormatFlags,
+
     /// Discover and process all tools' default paths
     #[arg(long, global = true)]
     all: bool,
@@ -92,6 +102,35 @@ struct Cli {
     claude_chat: Option<PathBuf>,
 }
 
+/// Formatter verbosity flags (see `FormatterOptions`); each overrides the
+/// matching `[format]` config key.
+#[derive(clap::Args)]
+struct FormatFlags {
+    /// Start emoji-text transcripts with a YAML frontmatter block
+    #[arg(long, global = true)]
+    frontmatter: bool,
+
+    /// Show thinking blocks (`--show-thinking=false` hides them in html/markdown)
+    #[arg(long, global = true, num_args = 0..=1, default_missing_value = "true", value_name = "BOOL")]
+    show_thinking: Option<bool>,
+
+    /// Tool call input detail: summary, inline, or full
+    #[arg(long, global = true, value_name = "LEVEL")]
+    tool_input: Option<String>,
+
+    /// Lines of output shown under each failed tool call
+    #[arg(long, global = true, value_name = "N")]
+    failed_output_lines: Option<usize>,
+
+    /// Timestamp every message (`--timestamps=false` drops them in html/markdown)
+    #[arg(long, global = true, num_args = 0..=1, default_missing_value = "true", value_name = "BOOL")]
+    timestamps: Option<bool>,
+
+    /// Annotate each assistant turn with its tokens and estimated cost
+    #[arg(long, global = true, num_args = 0..=1, default_missing_value = "true", value_name = "BOOL")]
+    turn_usage: Option<bool>,
+}
+
 #[derive(Subcommand)]
 enum Command {
     /// Create a default config file
@@ -126,7 +165,7 @@ enum Command {
     },
     /// Search transcript outputs with summary-first ranking
     Search {
-        /// Search query. Literal terms are ANDed by default.
+        /// Search query: terms are ANDed; supports "phrases", -exclusions, OR, and field:value filters
         query: String,
         /// Restrict search to one YYYY-MM month directory (sugar for --from X --to X)
         #[arg(short, long)]
@@ -170,6 +209,15 @@ enum Command {
         /// Use the semantic embedding index instead of line-level lexical matching
         #[arg(long)]
         semantic: bool,
+        /// Run lexical and semantic search and merge them with reciprocal rank fusion
+        #[arg(long, conflicts_with = "semantic")]
+        hybrid: bool,
+        /// Score every indexed chunk instead of probing the approximate (IVF) index
+        #[arg(long)]
+        exact: bool,
+        /// Rescore the top semantic or hybrid hits with the embedding.rerank_model cross-encoder
+        #[arg(long)]
+        rerank: bool,
         /// Semantic embedding provider: builtin, ollama, openai, or lmstudio
         #[arg(long)]
         provider: Option<String>,
@@ -191,9 +239,15 @@ enum Command {
         /// Print a per-file match count instead of matches
         #[arg(long)]
         count: bool,
-        /// Search oldest files first (default is newest first)
+        /// Search oldest files first (default is newest first, or BM25 rank with a lexical index)
         #[arg(long)]
         oldest_first: bool,
+        /// Scan files line by line even when a fresh lexical index exists
+        #[arg(long)]
+        scan: bool,
+        /// Rank whole sessions by hit density instead of listing matching lines
+        #[arg(long, conflicts_with_all = ["semantic", "hybrid", "count", "files_with_matches", "summaries_only"])]
+        sessions: bool,
         /// Emit JSON instead of text
         #[arg(long)]
         json: bool,
@@ -225,6 +279,132 @@ enum Command {
         #[arg(long)]
         timeout: Option<u64>,
     },
+    /// List sessions similar to a transcript, from the semantic index
+    Related {
+        /// Transcript path or session id (a unique prefix is enough)
+        target: String,
+        /// Maximum related sessions to print
+        #[arg(short, long, default_value_t = 10)]
+        limit: usize,
+        /// Embedding provider whose index to read
+        #[arg(long)]
+        provider: Option<String>,
+        /// Embedding model whose index to read
+        #[arg(long)]
+        model: Option<String>,
+        /// Emit JSON instead of text
+        #[arg(long)]
+        json: bool,
+    },
+    /// Cluster indexed sessions into topics, labelled by terms and user quotes
+    Topics {
+        /// Restrict to one YYYY-MM month (sugar for --from X --to X)
+        #[arg(short, long)]
+        month: Option<String>,
+        /// Earliest session date (YYYY-MM or YYYY-MM-DD), inclusive
+        #[arg(long)]
+        from: Option<String>,
+        /// Latest session date (YYYY-MM or YYYY-MM-DD), inclusive
+        #[arg(long)]
+        to: Option<String>,
+        /// Number of topics (default: sqrt(sessions / 2), at most 12)
+        #[arg(long)]
+        clusters: Option<usize>,
+        /// Embedding provider whose index to read
+        #[arg(long)]
+        provider: Option<String>,
+        /// Embedding model whose index to read
+        #[arg(long)]
+        model: Option<String>,
+        /// Emit JSON instead of markdown
+        #[arg(long)]
+        json: bool,
+    },
+    /// Answer a question from the archive, citing transcript passages
+    Ask {
+        /// The question
+        question: String,
+        /// Passages to retrieve
+        #[arg(short = 'n', long, default_value_t = cassio::ask::DEFAULT_PASSAGES)]
+        limit: usize,
+        /// Fuse lexical and semantic retrieval
+        #[arg(long)]
+        hybrid: bool,
+        /// Drop passages whose retrieval score is below this
+        #[arg(long)]
+        min_score: Option<f32>,
+        /// Restrict to one YYYY-MM month (sugar for --from X --to X)
+        #[arg(short, long)]
+        month: Option<String>,
+        /// Earliest date (YYYY-MM or YYYY-MM-DD), inclusive
+        #[arg(long)]
+        from: Option<String>,
+        /// Latest date (YYYY-MM or YYYY-MM-DD), inclusive
+        #[arg(long)]
+        to: Option<String>,
+        /// Only sessions from this tool
+        #[arg(long)]
+        tool: Option<String>,
+        /// Only sessions whose project path contains this
+        #[arg(long)]
+        project: Option<String>,
+        /// LLM provider for the answer (default: compaction provider)
+        #[arg(long)]
+        provider: Option<String>,
+        /// LLM model for the answer (default: compaction model)
+        #[arg(long)]
+        model: Option<String>,
+        /// Base URL for the openai provider
+        #[arg(long)]
+        base_url: Option<String>,
+        /// Emit JSON instead of text
+        #[arg(long)]
+        json: bool,
+    },
+    /// Run saved searches ([[saved_search]]) and report new hits
+    Watchlist {
+        #[command(subcommand)]
+        action: WatchlistAction,
+    },
+    /// Serve the archive to MCP clients over stdio (JSON-RPC)
+    Mcp {
+        /// Embedding provider for semantic and hybrid search
+        #[arg(long)]
+        provider: Option<String>,
+        /// Embedding model for semantic and hybrid search
+        #[arg(long)]
+        model: Option<String>,
+        /// Embedding server base URL
+        #[arg(long)]
+        base_url: Option<String>,
+    },
+    /// Browse and search the archive in a local web UI and JSON API
+    Serve {
+        /// Port on 127.0.0.1 (0 picks a free port)
+        #[arg(long, default_value_t = cassio::serve::DEFAULT_PORT)]
+        port: u16,
+        /// Embedding provider for semantic and hybrid search
+        #[arg(long)]
+        provider: Option<String>,
+        /// Embedding model for semantic and hybrid search
+        #[arg(long)]
+        model: Option<String>,
+        /// Embedding server base URL
+        #[arg(long)]
+        base_url: Option<String>,
+    },
+    /// Browse, filter, and search transcripts in a full-screen terminal UI
+    Tui,
+    /// Generate a static website of the archive (indexes, summaries, search)
+    Site {
+        /// Directory to write the site into (empty, or a previous site)
+        dir: PathBuf,
+    },
+    /// Export the archive into another tool's format
+    Export {
+        #[command(subcommand)]
+        action: ExportAction,
+    },
     /// Compact transcripts into daily/weekly/monthly analysis
     Compact {
         #[command(subcommand)]
@@ -240,6 +420,25 @@ enum Command {
         #[command(subcommand)]
         action: AuditAction,
     },
+    /// Find orphaned transcripts, duplicates, and stale index rows in the archive
+    Gc {
+        /// Quarantine findings under .cassio/quarantine/ without prompting
+        #[arg(long)]
+        apply: bool,
+        /// Delete findings instead of quarantining them (implies --apply)
+        #[arg(long)]
+        purge: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum WatchlistAction {
+    /// Run every saved search and report hits that are new since the last run
+    Run {
+        /// Emit JSON instead of text
+        #[arg(long)]
+        json: bool,
+    },
 }
 
 #[derive(Subcommand)]
@@ -262,6 +461,21 @@ enum MetricsAction {
     },
 }
 
+#[derive(Subcommand)]
+enum ExportAction {
+    /// Write linked notes into an Obsidian vault (re-run to update them)
+    Obsidian {
+        /// Vault directory
+        vault: PathBuf,
+        /// Folder inside the vault for session, project, and summary notes
+        #[arg(long, default_value = cassio::obsidian::DEFAULT_FOLDER)]
+        folder: String,
+        /// Folder inside the vault holding daily notes (default: the vault root)
+        #[arg(long, default_value = "")]
+        daily_folder: String,
+    },
+}
+
 #[derive(Subcommand)]
 enum AuditAction {
     /// Compare CaseStudyEvidence preservation between two markdown files
@@ -370,6 +584,7 @@ fn main() {
         eprintln!("Error: {e}");
         let exit_code = match e {
             CassioError::PartialRun { .. } => 2,
+            CassioError::CriticalMatch(_) => 3,
             _ => 1,
         };
         std::process::exit(exit_code);
@@ -377,6 +592,13 @@ fn main() {
 }
 
 fn run(mut cli: Cli) -> Result<(), CassioError> {
+    if cli.outputs.len() > 1 && !matches!(cli.command, Some(Command::Search { .. })) {
+        return Err(CassioError::Other(
+            "--output can only be repeated for `cassio search`".into(),
+        ));
+    }
+    cli.output = cli.outputs.first().cloned();
+
     // Handle config subcommands
     match cli.command {
         Some(Command::Init) => {
@@ -431,6 +653,9 @@ fn run(mut cli: Cli) -> Result<(), CassioError> {
             regex,
             case_sensitive,
             semantic,
+            hybrid,
+            exact,
+            rerank,
             provider,
             model,
             base_url,
@@ -439,6 +664,8 @@ fn run(mut cli: Cli) -> Result<(), CassioError> {
             files_with_matches,
             count,
             oldest_first,
+            scan,
+            sessions,
             json,
         }) => {
             let config = if cli.detached {
@@ -446,16 +673,21 @@ fn run(mut cli: Cli) -> Result<(), CassioError> {
             } else {
                 Config::load()
             };
-            let dir = cli
-                .output
-                .clone()
-                .or_else(|| config.output_path())
-                .ok_or_else(|| {
-                    CassioError::Other(
-                        "--output is required (or set via `cassio set output <path>`)".into(),
-                    )
-                })?;
-            let semantic_options = if semantic {
+            // Repeated -o, else `search.roots`, else the configured output.
+            let mut roots = cli.outputs.clone();
+            if roots.is_empty() {
+                roots = config.search_roots();
+            }
+            if roots.is_empty() {
+                roots.extend(config.output_path());
+            }
+            if roots.is_empty() {
+                return Err(CassioError::Other(
+                    "--output is required (or set via `cassio set output <path>`)".into(),
+                ));
+            }
+            let roots = cassio::roots::SearchRoot::labelled(roots);
+            let semantic_options = if semantic || hybrid {
                 let embedding = config.embedding.as_ref();
                 let index_options = index_options_from_config(
                     embedding, None, false, false, None, timeout, provider, model, base_url,
@@ -465,10 +697,21 @@ fn run(mut cli: Cli) -> Result<(), CassioError> {
                     model: index_options.model,
                     base_url: index_options.base_url,
                     timeout_secs: index_options.timeout_secs,
+                    exact,
                 })
             } else {
                 None
             };
+            let rerank_model = if rerank {
+                Some(config.rerank_model_path().ok_or_else(|| {
+                    CassioError::Other(
+                        "--rerank needs a reranker model (`cassio set embedding.rerank_model <path>`)"
+                            .into(),
+                    )
+                })?)
+            } else {
+                None
+            };
             let training_root = cli
                 .training_output
                 .clone()
@@ -489,6 +732,7 @@ fn run(mut cli: Cli) -> Result<(), CassioError> {
                 to,
                 tool,
                 project,
+                session_model: None,
                 speaker,
                 limit,
                 summaries_only,
@@ -503,8 +747,12 @@ fn run(mut cli: Cli) -> Result<(), CassioError> {
                 oldest_first,
                 semantic: semantic_options,
                 training_root,
+                scan,
+                hybrid,
+                rerank_model,
+                sessions,
             };
-            return cassio::search::run_search(&dir, &query, options);
+            return cassio::search::run_search(&roots, &query, options);
         }
         Some(Command::Index {
             month,
@@ -552,7 +800,92 @@ fn run(mut cli: Cli) -> Result<(), CassioError> {
                 println!("Output: {}", dir.display());
                 return Ok(());
             }
-            return cassio::index::run_index(&dir, options);
+            cassio::index::run_index(&dir, options)?;
+            return Ok(());
+        }
+        Some(Command::Related {
+            target,
+            limit,
+            provider,
+            model,
+            json,
+        }) => {
+            let config = if cli.detached {
+                Config::default()
+            } else {
+                Config::load()
+            };
+            let dir = cli
+                .output
+                .clone()
+                .or_else(|| config.output_path())
+                .ok_or_else(|| {
+                    CassioError::Other(
+                        "--output is required (or set via `cassio set output <path>`)".into(),
+                    )
+                })?;
+            let options = index_options_from_config(
+                config.embedding.as_ref(),
+                None,
+                false,
+                false,
+                None,
+                None,
+                provider,
+                model,
+                None,
+            );
+            let index_path = cassio::index::index_path_for(&dir, &options.provider, &options.model);
+            return cassio::related::run_related(&dir, &target, &index_path, limit, json);
+        }
+        Some(Command::Topics {
+            month,
+            from,
+            to,
+            clusters,
+            provider,
+            model,
+            json,
+        }) => {
+            let config = if cli.detached {
+                Config::default()
+            } else {
+                Config::load()
+            };
+            let dir = cli
+                .output
+                .clone()
+                .or_else(|| config.output_path())
+                .ok_or_else(|| {
+                    CassioError::Other(
+                        "--output is required (or set via `cassio set output <path>`)".into(),
+                    )
+                })?;
+            let options = index_options_from_config(
+                config.embedding.as_ref(),
+                None,
+                false,
+                false,
+                None,
+                None,
+                provider,
+                model,
+                None,
+            );
+            let index_path = cassio::index::index_path_for(&dir, &options.provider, &options.model);
+            // --month X is sugar for --from X --to X and wins over explicit bounds.
+            let (from, to) = match month {
+                Some(m) => (Some(m.clone()), Some(m)),
+                None => (from, to),
+            };
+            return cassio::topics::run_topics(
+                &dir,
+                &index_path,
+                from.as_deref(),
+                to.as_deref(),
+                clusters,
+                json,
+            );
         }
         Some(Command::Compact { action }) => {
             let config = if cli.detached {
@@ -613,9 +946,11 @@ fn run(mut cli: Cli) -> Result<(), CassioError> {
                             )
                         })?;
 
+                    let router = Router::from_config(&config)?;
                     if !cli.dry_run {
                         cassio::git::sync_before_writing(&output_dir, &config.git)?;
                     }
+                    sync_routes(&router, cli.dry_run)?;
 
                     // Step 1: sessions → transcripts
                     eprintln!("=== Step 1: Processing sessions ===\n");
@@ -623,10 +958,12 @@ fn run(mut cli: Cli) -> Result<(), CassioError> {
                         .format
                         .parse()
                         .map_err(|e: String| CassioError::Other(e))?;
+                    let options = formatter_options(&cli.formatting, &config)?;
                     let training_output = cli
                         .training_output
                         .clone()
                         .or_else(|| config.training_output_path());
+                    let template = output_template(&config)?;
                     let sources = discover::discover_all_sources_with_config(&config.sources);
                     if sources.is_empty() {
                         eprintln!("No session sources found, skipping.");
@@ -648,7 +985,10 @@ fn run(mut cli: Cli) -> Result<(), CassioError> {
                                 training_output.as_deref(),
                                 cli.force,
                                 format,
+                                &options,
                                 cli.filter_dir.as_deref(),
+                                template.as_ref(),
+                                &router,
                                 cli.dry_run,
                             )?;
                         }
@@ -685,17 +1025,16 @@ fn run(mut cli: Cli) -> Result<(), CassioError> {
                         &compact_options,
                     )?;
 
-                    maybe_auto_index(&output_dir, &config, cli.dry_run)?;
+                    let mut critical = maybe_auto_index(&output_dir, &config, cli.dry_run)?;
 
+                    let message =
+                        format!("cassio compact all ({})", Local::now().format("%Y-%m-%d"));
                     if !cli.dry_run {
-                        cassio::git::auto_commit_and_push(
-                            &output_dir,
-                            &format!("cassio compact all ({})", Local::now().format("%Y-%m-%d")),
-                            &config.git,
-                        )?;
+                        cassio::git::auto_commit_and_push(&output_dir, &message, &config.git)?;
                     }
+                    critical.extend(finish_routes(&router, &config, &message, cli.dry_run)?);
 
-                    return Ok(());
+                    return critical_hits(critical);
                 }
                 CompactAction::Dailies {
                     input,
@@ -759,7 +1098,7 @@ fn run(mut cli: Cli) -> Result<(), CassioError> {
                             details: daily_report.failed_details.join("; "),
                         });
                     }
-                    maybe_auto_index(&output_dir, &config, cli.dry_run)?;
+                    let critical = maybe_auto_index(&output_dir, &config, cli.dry_run)?;
                     if !cli.dry_run {
                         cassio::git::auto_commit_and_push(
                             &output_dir,
@@ -770,7 +1109,7 @@ fn run(mut cli: Cli) -> Result<(), CassioError> {
                             &config.git,
                         )?;
                     }
-                    return Ok(());
+                    return critical_hits(critical);
                 }
                 CompactAction::Monthly {
                     input,
@@ -820,7 +1159,7 @@ fn run(mut cli: Cli) -> Result<(), CassioError> {
                         &compact_options,
                         monthly_source,
                     )?;
-                    maybe_auto_index(&dir, &config, cli.dry_run)?;
+                    let critical = maybe_auto_index(&dir, &config, cli.dry_run)?;
                     if !cli.dry_run {
                         cassio::git::auto_commit_and_push(
                             &dir,
@@ -828,7 +1167,7 @@ fn run(mut cli: Cli) -> Result<(), CassioError> {
                             &config.git,
                         )?;
                     }
-                    return Ok(());
+                    return critical_hits(critical);
                 }
                 CompactAction::Weeklies {
                     input,
@@ -892,7 +1231,7 @@ fn run(mut cli: Cli) -> Result<(), CassioError> {
                             details: report.failed_details.join("; "),
                         });
                     }
-                    maybe_auto_index(&output_dir, &config, cli.dry_run)?;
+                    let critical = maybe_auto_index(&output_dir, &config, cli.dry_run)?;
                     if !cli.dry_run {
                         cassio::git::auto_commit_and_push(
                             &output_dir,
@@ -903,7 +1242,7 @@ fn run(mut cli: Cli) -> Result<(), CassioError> {
                             &config.git,
                         )?;
                     }
-                    return Ok(());
+                    return critical_hits(critical);
                 }
             }
         }
@@ -1005,67 +1344,315 @@ fn run(mut cli: Cli) -> Result<(), CassioError> {
             }
             return Ok(());
         }
-        None => {}
-    }
-
-    // Process mode — load config and merge
-    let config = if cli.detached {
-        Config::default()
-    } else {
-        Config::load()
-    };
-
-    // Merge output: CLI arg → config value
-    if cli.output.is_none() {
-        cli.output = config.output_path();
-    }
-    if cli.training_output.is_none() {
-        cli.training_output = config.training_output_path();
-    }
-
-    // Merge format: CLI arg (if not default) → config value → "emoji-text"
-    if cli.format == "emoji-text"
-        && let Some(ref fmt) = config.format
-    {
-        cli.format = fmt.clone();
-    }
-
-    let format: OutputFormat = cli
-        .format
-        .parse()
-        .map_err(|e: String| CassioError::Other(e))?;
-
-    // Claude Chat privacy export is an explicit opt-in path (not auto-discovered).
-    if let Some(ref export) = cli.claude_chat {
-        return run_claude_chat_mode(export, &cli, &config, format);
-    }
-
-    if cli.all {
-        return run_all_mode(&cli, &config, format);
-    }
-
-    match cli.path {
-        Some(ref path) if path.is_dir() => run_batch_mode(path, &cli, &config, format),
-        Some(ref path) if path.is_file() => {
-            run_single_file(path, format, cli.filter_dir.as_deref())
+        Some(Command::Ask {
+            question,
+            limit,
+            hybrid,
+            min_score,
+            month,
+            from,
+            to,
+            tool,
+            project,
+            provider,
+            model,
+            base_url,
+            json,
+        }) => {
+            let config = if cli.detached {
+                Config::default()
+            } else {
+                Config::load()
+            };
+            let dir = cli
+                .output
+                .clone()
+                .or_else(|| config.output_path())
+                .ok_or_else(|| {
+                    CassioError::Other(
+                        "--output is required (or set via `cassio set output <path>`)".into(),
+                    )
+                })?;
+            let index_options = index_options_from_config(
+                config.embedding.as_ref(),
+                None,
+                false,
+                false,
+                None,
+                None,
+                None,
+                None,
+                None,
+            );
+            // --month X is sugar for --from X --to X and wins over explicit bounds.
+            let (from, to) = match month {
+                Some(m) => (Some(m.clone()), Some(m)),
+                None => (from, to),
+            };
+            let options = cassio::ask::AskOptions {
+                semantic: cassio::search::SemanticSearchOptions {
+                    provider: index_options.provider,
+                    model: index_options.model,
+                    base_url: index_options.base_url,
+                    timeout_secs: index_options.timeout_secs,
+                    exact: false,
+                },
+                hybrid,
+                passages: limit,
+                min_score,
+                from,
+                to,
+                tool,
+                project,
+                model: model
+                    .or_else(|| config.model.clone())
+                    .unwrap_or_else(|| "llama3.1".to_string()),
+                provider: provider
+                    .or_else(|| config.provider.clone())
+                    .unwrap_or_else(|| "ollama".to_string()),
+                base_url: base_url.or_else(|| config.base_url.clone()),
+                compact: cassio::compact::CompactOptions::new(
+                    config.chunk_timeout_secs.unwrap_or(300),
+                    config.max_retries.unwrap_or(3),
+                    config
+                        .max_input_bytes
+                        .unwrap_or(cassio::compact::CompactOptions::default().max_input_bytes),
+                ),
+            };
+            return cassio::ask::run_ask(&dir, &question, &options, cli.dry_run, json);
         }
-        Some(ref path) => Err(CassioError::Other(format!(
-            "Path not found: {}",
-            path.display()
-        ))),
-        None => run_stdin(format, cli.filter_dir.as_deref()),
-    }
-}
-
-/// `YYYY-MM-DD` for `days` days before today (0 = today), used by `--days`.
-fn days_ago(days: u64) -> Result<String, CassioError> {
-    let today = chrono::Utc::now().date_naive();
-    let date = today
-        .checked_sub_days(chrono::Days::new(days))
-        .ok_or_else(|| CassioError::Other("--days is out of range".into()))?;
-    Ok(date.format("%Y-%m-%d").to_string())
-}
-
+        Some(Command::Watchlist {
+            action: WatchlistAction::Run { json },
+        }) => {
+            let config = if cli.detached {
+                Config::default()
+            } else {
+                Config::load()
+            };
+            return run_watchlist_command(&cli, &config, json);
+        }
+        Some(Command::Mcp {
+            provider,
+            model,
+            base_url,
+        }) => {
+            let (dir, semantic) = archive_server_settings(
+                cli.output.clone(),
+                cli.detached,
+                provider,
+                model,
+                base_url,
+            )?;
+            return cassio::mcp::run_mcp(&dir, semantic);
+        }
+        Some(Command::Serve {
+            port,
+            provider,
+            model,
+            base_url,
+        }) => {
+            let (dir, semantic) = archive_server_settings(
+                cli.output.clone(),
+                cli.detached,
+                provider,
+                model,
+                base_url,
+            )?;
+            return cassio::serve::run_serve(&dir, port, semantic);
+        }
+        Some(Command::Tui) => {
+            let config = if cli.detached {
+                Config::default()
+            } else {
+                Config::load()
+            };
+            let dir = cli
+                .output
+                .clone()
+                .or_else(|| config.output_path())
+                .ok_or_else(|| {
+                    CassioError::Other(
+                        "--output is required (or set via `cassio set output <path>`)".into(),
+                    )
+                })?;
+            return cassio::tui::run_tui(&dir);
+        }
+        Some(Command::Site { dir: site_dir }) => {
+            let config = if cli.detached {
+                Config::default()
+            } else {
+                Config::load()
+            };
+            let dir = cli
+                .output
+                .clone()
+                .or_else(|| config.output_path())
+                .ok_or_else(|| {
+                    CassioError::Other(
+                        "--output is required (or set via `cassio set output <path>`)".into(),
+                    )
+                })?;
+            return cassio::site::run_site(&dir, &site_dir);
+        }
+        Some(Command::Export {
+            action:
+                ExportAction::Obsidian {
+                    vault,
+                    folder,
+                    daily_folder,
+                },
+        }) => {
+            let config = if cli.detached {
+                Config::default()
+            } else {
+                Config::load()
+            };
+            let dir = cli
+                .output
+                .clone()
+                .or_else(|| config.output_path())
+                .ok_or_else(|| {
+                    CassioError::Other(
+                        "--output is required (or set via `cassio set output <path>`)".into(),
+                    )
+                })?;
+            return cassio::obsidian::run_export(&dir, &vault, &folder, &daily_folder);
+        }
+        Some(Command::Gc { apply, purge }) => {
+            let config = if cli.detached {
+                Config::default()
+            } else {
+                Config::load()
+            };
+            return run_gc(&cli, &config, apply || purge, purge);
+        }
+        None => {}
+    }
+
+    // Process mode — load config and merge
+    let config = if cli.detached {
+        Config::default()
+    } else {
+        Config::load()
+    };
+
+    // Merge output: CLI arg → config value
+    if cli.output.is_none() {
+        cli.output = config.output_path();
+    }
+    if cli.training_output.is_none() {
+        cli.training_output = config.training_output_path();
+    }
+
+    // Merge format: CLI arg (if not default) → config value → "emoji-text"
+    if cli.format == "emoji-text"
+        && let Some(fmt) = config.format_name()
+    {
+        cli.format = fmt.to_string();
+    }
+
+    let format: OutputFormat = cli
+        .format
+        .parse()
+        .map_err(|e: String| CassioError::Other(e))?;
+    let options = formatter_options(&cli.formatting, &config)?;
+
+    // Claude Chat privacy export is an explicit opt-in path (not auto-discovered).
+    if let Some(ref export) = cli.claude_chat {
+        return run_claude_chat_mode(export, &cli, &config, format, &options);
+    }
+
+    if cli.all {
+        return run_all_mode(&cli, &config, format, &options);
+    }
+
+    match cli.path {
+        Some(ref path) if path.is_dir() => run_batch_mode(path, &cli, &config, format, &options),
+        Some(ref path) if path.is_file() => {
+            run_single_file(path, format, &options, cli.filter_dir.as_deref())
+        }
+        Some(ref path) => Err(CassioError::Other(format!(
+            "Path not found: {}",
+            path.display()
+        ))),
+        None => run_stdin(format, &options, cli.filter_dir.as_deref()),
+    }
+}
+
+/// Formatter options: CLI flags over the `[format]` config table, over each
+/// format's defaults.
+fn formatter_options(
+    flags: &FormatFlags,
+    config: &Config,
+) -> Result<FormatterOptions, CassioError> {
+    let table = config.format_options();
+    let tool_input = flags
+        .tool_input
+        .clone()
+        .or(table.tool_input)
+        .map(|level| level.parse::<ToolInput>())
+        .transpose()
+        .map_err(CassioError::Other)?;
+    Ok(FormatterOptions {
+        show_thinking: flags.show_thinking.or(table.show_thinking),
+        tool_input,
+        failed_output_lines: flags
+            .failed_output_lines
+            .or(table.failed_output_lines)
+            .unwrap_or(0),
+        timestamps: flags.timestamps.or(table.timestamps),
+        turn_usage: flags.turn_usage.or(table.turn_usage),
+        frontmatter: flags.frontmatter || table.frontmatter.unwrap_or(false),
+    })
+}
+
+/// `YYYY-MM-DD` for `days` days before today (0 = today), used by `--days`.
+fn days_ago(days: u64) -> Result<String, CassioError> {
+    let today = chrono::Utc::now().date_naive();
+    let date = today
+        .checked_sub_days(chrono::Days::new(days))
+        .ok_or_else(|| CassioError::Other("--days is out of range".into()))?;
+    Ok(date.format("%Y-%m-%d").to_string())
+}
+
+/// Archive root and semantic search settings for `cassio mcp` and
+/// `cassio serve`.
+fn archive_server_settings(
+    output: Option<PathBuf>,
+    detached: bool,
+    provider: Option<String>,
+    model: Option<String>,
+    base_url: Option<String>,
+) -> Result<(PathBuf, cassio::search::SemanticSearchOptions), CassioError> {
+    let config = if detached {
+        Config::default()
+    } else {
+        Config::load()
+    };
+    let dir = output.or_else(|| config.output_path()).ok_or_else(|| {
+        CassioError::Other("--output is required (or set via `cassio set output <path>`)".into())
+    })?;
+    let options = index_options_from_config(
+        config.embedding.as_ref(),
+        None,
+        false,
+        false,
+        None,
+        None,
+        provider,
+        model,
+        base_url,
+    );
+    let semantic = cassio::search::SemanticSearchOptions {
+        provider: options.provider,
+        model: options.model,
+        base_url: options.base_url,
+        timeout_secs: options.timeout_secs,
+        exact: false,
+    };
+    Ok((dir, semantic))
+}
+
 #[allow(clippy::too_many_arguments)]
 fn index_options_from_config(
     embedding: Option<&cassio::config::EmbeddingConfig>,
@@ -1108,18 +1695,38 @@ fn index_options_from_config(
     }
 }
 
-fn maybe_auto_index(output_dir: &Path, config: &Config, dry_run: bool) -> Result<(), CassioError> {
-    let Some(embedding) = config.embedding.as_ref() else {
-        return Ok(());
-    };
-    if !embedding.auto_index {
-        return Ok(());
-    }
-    if dry_run {
-        eprintln!("index: dry run, skipping automatic semantic index update");
-        return Ok(());
+fn maybe_auto_index(
+    output_dir: &Path,
+    config: &Config,
+    dry_run: bool,
+) -> Result<Vec<String>, CassioError> {
+    maybe_auto_index_archive(output_dir, config.training_output_path(), config, dry_run)
+}
+
+/// `maybe_auto_index` for an archive with its own training root (a route).
+/// Checks the saved searches afterwards, whether or not indexing is enabled,
+/// and returns the critical ones with new hits.
+fn maybe_auto_index_archive(
+    output_dir: &Path,
+    training_root: Option<PathBuf>,
+    config: &Config,
+    dry_run: bool,
+) -> Result<Vec<String>, CassioError> {
+    if let Some(embedding) = config.embedding.as_ref().filter(|cfg| cfg.auto_index) {
+        if dry_run {
+            eprintln!("index: dry run, skipping automatic semantic index update");
+        } else {
+            auto_index_archive(output_dir, training_root, embedding)?;
+        }
     }
+    maybe_run_watchlist(output_dir, config, dry_run)
+}
 
+fn auto_index_archive(
+    output_dir: &Path,
+    training_root: Option<PathBuf>,
+    embedding: &config::EmbeddingConfig,
+) -> Result<(), CassioError> {
     eprintln!("\n=== Updating semantic index ===\n");
     let mut options = index_options_from_config(
         Some(embedding),
@@ -1132,14 +1739,193 @@ fn maybe_auto_index(output_dir: &Path, config: &Config, dry_run: bool) -> Result
         None,
         None,
     );
-    options.training_root = config.training_output_path();
-    cassio::index::run_index(output_dir, options)
+    options.training_root = training_root.clone();
+    let report = cassio::index::run_index(output_dir, options)?;
+    if embedding.related_footer {
+        let updated = cassio::related::update_footers(
+            output_dir,
+            training_root.as_deref(),
+            &report.index_path,
+            &report.changed_sessions,
+        )?;
+        if updated > 0 {
+            eprintln!("index: wrote related-session footers to {updated} transcript(s)");
+        }
+    }
+    Ok(())
+}
+
+/// Report new saved-search hits after a batch, returning the names of critical
+/// searches with new hits. Callers turn them into an error with
+/// `critical_hits` once the batch (and its git commit) has finished.
+fn maybe_run_watchlist(
+    output_dir: &Path,
+    config: &Config,
+    dry_run: bool,
+) -> Result<Vec<String>, CassioError> {
+    if config.saved_searches.is_empty() || dry_run {
+        return Ok(Vec::new());
+    }
+    eprintln!("\n=== Checking saved searches ===\n");
+    let report = cassio::watchlist::run_watchlist(output_dir, &config.saved_searches, false)?;
+    eprint!("{}", cassio::watchlist::render_report(&report));
+    Ok(report
+        .critical_matches()
+        .into_iter()
+        .map(str::to_string)
+        .collect())
+}
+
+/// `CriticalMatch` (exit code 3) when critical saved searches had new hits.
+/// A search with hits in several archives is named once.
+fn critical_hits(mut critical: Vec<String>) -> Result<(), CassioError> {
+    if critical.is_empty() {
+        return Ok(());
+    }
+    critical.sort();
+    critical.dedup();
+    Err(CassioError::CriticalMatch(critical.join(", ")))
+}
+
+/// `cassio watchlist run`: check the saved searches against the output archive
+/// and every routed archive.
+fn run_watchlist_command(cli: &Cli, config: &Config, json: bool) -> Result<(), CassioError> {
+    if config.saved_searches.is_empty() {
+        return Err(CassioError::Other(
+            "No saved searches: add [[saved_search]] entries to the config".into(),
+        ));
+    }
+    let output_dir = cli
+        .output
+        .clone()
+        .or_else(|| config.output_path())
+        .ok_or_else(|| {
+            CassioError::Other(
+                "--output is required (or set via `cassio set output <path>`)".into(),
+            )
+        })?;
+    let router = Router::from_config(config)?;
+    let mut archives = vec![output_dir];
+    for route in router.routes() {
+        let output = &route.destination.output;
+        if output.is_dir() && !archives.contains(output) {
+            archives.push(output.clone());
+        }
+    }
+
+    let mut critical = Vec::new();
+    let mut reports = Vec::new();
+    for archive in &archives {
+        let report =
+            cassio::watchlist::run_watchlist(archive, &config.saved_searches, cli.dry_run)?;
+        critical.extend(report.critical_matches().into_iter().map(str::to_string));
+        if !json {
+            if archives.len() > 1 {
+                println!("{}:", archive.display());
+            }
+            print!("{}", cassio::watchlist::render_report(&report));
+        }
+        reports.push(serde_json::json!({ "archive": archive, "searches": report.searches }));
+    }
+    if json {
+        serde_json::to_writer_pretty(io::stdout(), &reports)?;
+        println!();
+    }
+    critical_hits(critical)
+}
+
+/// Manifest keys batch mode would write today for every session under `sources`.
+///
+/// With the default layout the name follows from the source path. With an
+/// `output_template` or `[[routes]]` it is only known after parsing, so each
+/// source is expected where batch mode last wrote it (the key its up-to-date
+/// check uses); keys it wrote to a route's archive are not expected here.
+fn expected_keys(
+    config: &Config,
+    sources: &[(Tool, PathBuf)],
+    manifest: &cassio::manifest::Manifest,
+) -> Result<HashSet<String>, CassioError> {
+    let templated = output_template(config)?.is_some();
+    let router = Router::from_config(config)?;
+    let route_manifests = router
+        .routes()
+        .iter()
+        .map(|route| cassio::manifest::Manifest::load(&route.destination.output))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut manifests = vec![manifest];
+    manifests.extend(route_manifests.iter());
+    let previous = last_written_keys(&manifests);
+
+    let mut expected = HashSet::new();
+    for (tool, path) in sources {
+        let files = discover::find_session_files(path, Some(*tool));
+        if templated || !router.is_empty() {
+            for (_, file) in &files {
+                if let Some((0, key)) = previous.get(file.to_string_lossy().as_ref()) {
+                    expected.insert(key.to_string());
+                }
+            }
+            continue;
+        }
+        for (folder, stem) in unique_output_stems(&files)? {
+            expected.insert(cassio::manifest::entry_key(&folder, &stem));
+        }
+    }
+    Ok(expected)
+}
+
+/// Pull every routed archive before a batch writes into it.
+fn sync_routes(router: &Router, dry_run: bool) -> Result<(), CassioError> {
+    if dry_run {
+        return Ok(());
+    }
+    for route in router.routes() {
+        let destination = &route.destination;
+        if destination.output.is_dir() {
+            cassio::git::sync_before_writing(&destination.output, &destination.git)?;
+        }
+    }
+    Ok(())
+}
+
+/// Update the index of, then commit and push, every routed archive after a
+/// batch. Returns the critical saved searches with new hits in any of them.
+fn finish_routes(
+    router: &Router,
+    config: &Config,
+    message: &str,
+    dry_run: bool,
+) -> Result<Vec<String>, CassioError> {
+    let mut critical = Vec::new();
+    if dry_run {
+        return Ok(critical);
+    }
+    for route in router.routes() {
+        let destination = &route.destination;
+        if !destination.output.is_dir() {
+            continue;
+        }
+        eprintln!(
+            "\nRoute {}: {}",
+            destination.name,
+            destination.output.display()
+        );
+        critical.extend(maybe_auto_index_archive(
+            &destination.output,
+            destination.training_output.clone(),
+            config,
+            dry_run,
+        )?);
+        cassio::git::auto_commit_and_push(&destination.output, message, &destination.git)?;
+    }
+    Ok(critical)
 }
 
 /// Parse and format a single session file, writing output to stdout.
 fn run_single_file(
     path: &Path,
     format: OutputFormat,
+    options: &FormatterOptions,
     filter_dir: Option<&Path>,
 ) -> Result<(), CassioError> {
     let parser = cassio::parser::detect_parser(path)?;
@@ -1164,7 +1950,7 @@ fn run_single_file(
     let stdout = io::stdout();
     let mut writer = stdout.lock();
     let parsed = cassio::redact::redact_export(&parsed);
-    format.formatter().format(&parsed, &mut writer)?;
+    format.formatter(options).format(&parsed, &mut writer)?;
     Ok(())
 }
 
@@ -1174,7 +1960,11 @@ fn run_single_file(
 /// requires peeking at the first line, but the parser needs all lines. An
 /// alternative would be a two-pass approach, but that would require the input
 /// to be seekable (stdin is not).
-fn run_stdin(format: OutputFormat, filter_dir: Option<&Path>) -> Result<(), CassioError> {
+fn run_stdin(
+    format: OutputFormat,
+    options: &FormatterOptions,
+    filter_dir: Option<&Path>,
+) -> Result<(), CassioError> {
     let stdin = io::stdin();
     let reader = stdin.lock();
     let lines: Vec<String> = reader.lines().collect::<Result<_, _>>()?;
@@ -1230,6 +2020,7 @@ fn run_stdin(format: OutputFormat, filter_dir: Option<&Path>) -> Result<(), Cass
                 source_record_count: None,
                 source_format: None,
                 source_root: None,
+                duplicates: Vec::new(),
             },
             cassio::training::TrainingMetadata {
                 project_path_raw: session.metadata.project_path.clone(),
@@ -1247,7 +2038,7 @@ fn run_stdin(format: OutputFormat, filter_dir: Option<&Path>) -> Result<(), Cass
         session,
     };
     let parsed = cassio::redact::redact_export(&parsed);
-    format.formatter().format(&parsed, &mut writer)?;
+    format.formatter(options).format(&parsed, &mut writer)?;
     Ok(())
 }
 
@@ -1261,12 +2052,15 @@ fn run_batch_mode(
     cli: &Cli,
     config: &Config,
     format: OutputFormat,
+    options: &FormatterOptions,
 ) -> Result<(), CassioError> {
     let output_dir = cli
         .output
         .as_ref()
         .ok_or_else(|| CassioError::Other("--output is required for batch mode".into()))?;
 
+    let template = output_template(config)?;
+    let router = Router::from_config(config)?;
     let files = discover::find_session_files(dir, None);
     let total = files.len();
     eprintln!("Found {total} session files");
@@ -1274,6 +2068,7 @@ fn run_batch_mode(
     if !cli.dry_run {
         cassio::git::sync_before_writing(output_dir, &config.git)?;
     }
+    sync_routes(&router, cli.dry_run)?;
 
     process_file_list(
         &files,
@@ -1281,26 +2076,29 @@ fn run_batch_mode(
         cli.training_output.as_deref(),
         cli.force,
         format,
+        options,
         cli.filter_dir.as_deref(),
+        template.as_ref(),
+        &router,
         cli.dry_run,
     )?;
 
+    let message = format!("cassio batch ({})", Local::now().format("%Y-%m-%d"));
+    let mut critical = Vec::new();
     if !cli.dry_run {
-        maybe_auto_index(output_dir, config, cli.dry_run)?;
-        cassio::git::auto_commit_and_push(
-            output_dir,
-            &format!("cassio batch ({})", Local::now().format("%Y-%m-%d")),
-            &config.git,
-        )?;
+        critical = maybe_auto_index(output_dir, config, cli.dry_run)?;
+        cassio::git::auto_commit_and_push(output_dir, &message, &config.git)?;
     }
+    critical.extend(finish_routes(&router, config, &message, cli.dry_run)?);
 
-    Ok(())
+    critical_hits(critical)
 }
 
 /// Import every conversation from a Claude Chat privacy export into `--output`.
 ///
 /// Accepts a `.zip`, a directory containing `conversations.json`, or the JSON file.
-/// Each conversation becomes `YYYY-MM/YYYY-MM-DDTHH-MM-SS-claude-chat.md`.
+/// Each conversation becomes `YYYY-MM/YYYY-MM-DDTHH-MM-SS-claude-chat.md`, or
+/// the configured `output_template`.
 /// Without `--force`, conversations whose output is newer than the export are skipped.
 ///
 /// Loads the export once (important for large zip archives), then writes each session.
@@ -1309,6 +2107,7 @@ fn run_claude_chat_mode(
     cli: &Cli,
     config: &Config,
     format: OutputFormat,
+    options: &FormatterOptions,
 ) -> Result<(), CassioError> {
     let output_dir = cli.output.as_ref().ok_or_else(|| {
         CassioError::Other(
@@ -1337,11 +2136,24 @@ fn run_claude_chat_mode(
     if !cli.dry_run {
         cassio::git::sync_before_writing(output_dir, &config.git)?;
     }
+    let router = Router::from_config(config)?;
+    sync_routes(&router, cli.dry_run)?;
 
-    let training_dir = cli.training_output.as_deref().unwrap_or(output_dir);
+    let template = output_template(config)?;
     let mut processed = 0u32;
     let mut skipped = 0u32;
     let mut up_to_date = 0u32;
+    // Archive 0 is the top-level output; archive `n + 1` is route `n`.
+    let mut archives = vec![Archive::load(
+        output_dir,
+        cli.training_output.as_deref().unwrap_or(output_dir),
+    )?];
+    for route in router.routes() {
+        archives.push(Archive::load(
+            &route.destination.output,
+            route.destination.training_dir(),
+        )?);
+    }
 
     for (i, parsed) in sessions.into_iter().enumerate() {
         if (i + 1) == 1 || (i + 1) % 100 == 0 {
@@ -1366,29 +2178,31 @@ fn run_claude_chat_mode(
             }
         }
 
-        let dt = parsed.session.metadata.started_at;
-        let folder = format!("{:04}-{:02}", dt.year(), dt.month());
-        let ts = format!(
-            "{:04}-{:02}-{:02}T{:02}-{:02}-{:02}",
-            dt.year(),
-            dt.month(),
-            dt.day(),
-            dt.hour(),
-            dt.minute(),
-            dt.second()
-        );
-        let stem = format!("{ts}-claude-chat");
-
-        let primary_root = match format {
-            OutputFormat::TrainingJson => training_dir,
-            _ => output_dir,
+        let key = match &template {
+            Some(template) => {
+                let (folder, stem) = template.render(&parsed.session);
+                cassio::manifest::entry_key(&folder, &stem)
+            }
+            None => {
+                let dt = parsed.session.metadata.started_at;
+                let folder = format!("{:04}-{:02}", dt.year(), dt.month());
+                let ts = format!(
+                    "{:04}-{:02}-{:02}T{:02}-{:02}-{:02}",
+                    dt.year(),
+                    dt.month(),
+                    dt.day(),
+                    dt.hour(),
+                    dt.minute(),
+                    dt.second()
+                );
+                cassio::manifest::entry_key(&folder, &format!("{ts}-claude-chat"))
+            }
         };
-        let out_path = primary_root
-            .join(&folder)
-            .join(output_filename(stem.as_str(), format));
-        let training_path = training_dir
-            .join(&folder)
-            .join(format!("{stem}.training.json"));
+
+        let archive = &mut archives[router
+            .route_for(&parsed.session)
+            .map_or(0, |route| route + 1)];
+        let (out_path, training_path) = archive.output_paths(&key, format);
 
         if !cli.force {
             let primary_ok = is_up_to_date(export, &out_path);
@@ -1415,8 +2229,14 @@ fn run_claude_chat_mode(
         if let Some(parent) = out_path.parent() {
             fs::create_dir_all(parent)?;
         }
+        let entry = cassio::manifest::ManifestEntry::for_session(
+            &parsed.session,
+            Path::new(&parsed.training.source.source_path),
+            &format.to_string(),
+            matches!(format, OutputFormat::EmojiText | OutputFormat::TrainingJson),
+        );
         let parsed = cassio::redact::redact_export(&parsed);
-        let formatter = format.formatter();
+        let formatter = format.formatter(options);
         let mut file = fs::File::create(&out_path)?;
         formatter.format(&parsed, &mut file)?;
         if format == OutputFormat::EmojiText {
@@ -1427,22 +2247,26 @@ fn run_claude_chat_mode(
             cassio::formatter::training_json::TrainingJsonFormatter
                 .format(&parsed, &mut training_file)?;
         }
+        archive.manifest.record(key, entry);
+        archive.dirty = true;
         processed += 1;
     }
 
     eprintln!("\r  Done: {processed} processed, {skipped} skipped, {up_to_date} up-to-date     ");
 
+    let message = format!("cassio --claude-chat ({})", Local::now().format("%Y-%m-%d"));
+    let mut critical = Vec::new();
     if !cli.dry_run {
-        maybe_auto_index(output_dir, config, cli.dry_run)?;
-        cassio::git::auto_commit_and_push(
-            output_dir,
-            &format!("cassio --claude-chat ({})", Local::now().format("%Y-%m-%d")),
-            &config.git,
-        )?;
+        for archive in archives.iter().filter(|archive| archive.dirty) {
+            archive.manifest.save(archive.output)?;
+        }
+        critical = maybe_auto_index(output_dir, config, cli.dry_run)?;
+        cassio::git::auto_commit_and_push(output_dir, &message, &config.git)?;
     }
+    critical.extend(finish_routes(&router, config, &message, cli.dry_run)?);
 
     eprintln!("\nClaude Chat import done.");
-    Ok(())
+    critical_hits(critical)
 }
 
 /// Discover all installed tool sources and batch-process them into `--output`.
@@ -1450,7 +2274,12 @@ fn run_claude_chat_mode(
 /// Uses `discover::discover_all_sources_with_config` to find source directories,
 /// then calls `process_file_list` for each tool. Sources that don't exist on this
 /// machine are silently skipped. Errors if no sources are found at all.
-fn run_all_mode(cli: &Cli, config: &Config, format: OutputFormat) -> Result<(), CassioError> {
+fn run_all_mode(
+    cli: &Cli,
+    config: &Config,
+    format: OutputFormat,
+    options: &FormatterOptions,
+) -> Result<(), CassioError> {
     let output_dir = cli
         .output
         .as_ref()
@@ -1480,7 +2309,10 @@ fn run_all_mode(cli: &Cli, config: &Config, format: OutputFormat) -> Result<(),
     if !cli.dry_run {
         cassio::git::sync_before_writing(output_dir, &config.git)?;
     }
+    let router = Router::from_config(config)?;
+    sync_routes(&router, cli.dry_run)?;
 
+    let template = output_template(config)?;
     for (tool, path) in &sources {
         eprintln!("\nProcessing {} ({})...", tool, path.display());
 
@@ -1494,96 +2326,204 @@ fn run_all_mode(cli: &Cli, config: &Config, format: OutputFormat) -> Result<(),
             cli.training_output.as_deref(),
             cli.force,
             format,
+            options,
             cli.filter_dir.as_deref(),
+            template.as_ref(),
+            &router,
             cli.dry_run,
         )?;
     }
 
+    let message = format!("cassio --all ({})", Local::now().format("%Y-%m-%d"));
+    let mut critical = Vec::new();
     if !cli.dry_run {
-        maybe_auto_index(output_dir, config, cli.dry_run)?;
-        cassio::git::auto_commit_and_push(
-            output_dir,
-            &format!("cassio --all ({})", Local::now().format("%Y-%m-%d")),
-            &config.git,
-        )?;
+        critical = maybe_auto_index(output_dir, config, cli.dry_run)?;
+        cassio::git::auto_commit_and_push(output_dir, &message, &config.git)?;
     }
+    critical.extend(finish_routes(&router, config, &message, cli.dry_run)?);
 
     eprintln!("\nAll done.");
-    Ok(())
+    critical_hits(critical)
 }
 
-/// Process a list of `(Tool, path)` pairs and write formatted transcripts to `output_dir`.
+/// Reconcile the archive against discovered sources and the manifest.
+///
+/// Prints the gc report, then quarantines (or with `purge`, deletes) the
+/// findings when `apply` is set or the user confirms at an interactive prompt.
+/// Non-interactive runs without `--apply` only report.
+fn run_gc(cli: &Cli, config: &Config, apply: bool, purge: bool) -> Result<(), CassioError> {
+    let output_dir = cli
+        .output
+        .clone()
+        .or_else(|| config.output_path())
+        .ok_or_else(|| {
+            CassioError::Other(
+                "--output is required (or set via `cassio set output <path>`)".into(),
+            )
+        })?;
+    let training_root = cli
+        .training_output
+        .clone()
+        .or_else(|| config.training_output_path());
+
+    let mut manifest = cassio::manifest::Manifest::load(&output_dir)?;
+    let sources = discover::discover_all_sources_with_config(&config.sources);
+    let expected = expected_keys(config, &sources, &manifest)?;
+    let plan = cassio::gc::plan(&output_dir, training_root.as_deref(), &expected, &manifest)?;
+    if plan.is_empty() {
+        println!("cassio gc: archive is clean");
+        return Ok(());
+    }
+
+    let mode = if purge { "delete" } else { "quarantine" };
+    print!("{}", cassio::gc::render_report(&output_dir, &plan, mode));
+    if cli.dry_run {
+        println!("\ncassio gc: dry run, nothing changed");
+        return Ok(());
+    }
+    if !apply {
+        use std::io::{IsTerminal, Write};
+        if !io::stdin().is_terminal() {
+            println!("\nRe-run with --apply to {mode} these findings.");
+            return Ok(());
+        }
+        print!("\nProceed ({mode})? [y/N] ");
+        io::stdout().flush()?;
+        let mut answer = String::new();
+        io::stdin().read_line(&mut answer)?;
+        if !matches!(answer.trim(), "y" | "Y" | "yes") {
+            println!("Aborted.");
+            return Ok(());
+        }
+    }
+
+    cassio::git::sync_before_writing(&output_dir, &config.git)?;
+    let outcome = cassio::gc::apply(
+        &output_dir,
+        training_root.as_deref(),
+        &plan,
+        &mut manifest,
+        purge,
+    )?;
+    println!(
+        "\ncassio gc: {} file(s) removed, {} index row(s) deleted, {} manifest entr(ies) pruned",
+        outcome.files_removed, outcome.index_rows_deleted, outcome.manifest_entries_removed
+    );
+    if let Some(dir) = &outcome.quarantine_dir {
+        println!("Quarantine: {}", dir.display());
+    }
+    println!("Report: {}", outcome.report_path.display());
+    cassio::git::auto_commit_and_push(
+        &output_dir,
+        &format!("cassio gc ({})", Local::now().format("%Y-%m-%d")),
+        &config.git,
+    )?;
+    Ok(())
+}
+
+/// The archive (index into `manifests`) and key each source was last written
+/// to. Templated names and routed archives are only known after parsing, so
+/// batch mode's up-to-date check and `cassio gc` treat the latest write as the
+/// source's current output.
+fn last_written_keys<'a>(
+    manifests: &[&'a cassio::manifest::Manifest],
+) -> HashMap<&'a str, (usize, &'a str)> {
+    let mut latest: HashMap<&str, (usize, &str, chrono::DateTime<Utc>)> = HashMap::new();
+    for (archive_index, manifest) in manifests.iter().enumerate() {
+        for (key, entry) in &manifest.sessions {
+            let newer = latest
+                .get(entry.source_path.as_str())
+                .is_none_or(|(_, _, written_at)| entry.written_at > *written_at);
+            if newer {
+                latest.insert(&entry.source_path, (archive_index, key, entry.written_at));
+            }
+        }
+    }
+    latest
+        .into_iter()
+        .map(|(source, (archive, key, _))| (source, (archive, key)))
+        .collect()
+}
+
+/// Process a list of `(Tool, path)` pairs and write formatted transcripts to `output_dir`.
 ///
 /// PHASE 1: PRE-FLIGHT CHECKS
 /// Skip empty files (zero bytes) and files whose output is already up-to-date,
-/// unless `force` is true.
+/// unless `force` is true. Sources the manifest records as duplicates of a
+/// kept transcript are skipped while unchanged.
 ///
 /// PHASE 2: OUTPUT PATH DERIVATION
 /// Compute the `YYYY-MM/filename.md` path within `output_dir` using
 /// `derive_output_path_for`. Create parent directories as needed.
 /// `*.training.json` files go to `training_output` when set, otherwise
-/// beside the transcript under `output_dir`.
+/// beside the transcript under `output_dir`. With an output `template`, the
+/// path is rendered from the parsed session instead. Sessions matching a
+/// `[[routes]]` rule go to that route's archive. In either case the up-to-date
+/// check uses the archive and path the manifests recorded for the source on an
+/// earlier run.
 ///
-/// PHASE 3: PARSING AND WRITING
-/// Parse the session with the appropriate tool parser and write formatted output.
-/// Sessions with no user or assistant messages are skipped (they contain only
-/// system events and produce empty transcripts).
+/// PHASE 3: PARSING AND FINGERPRINTING
+/// Parse each pending session with the appropriate tool parser and fingerprint
+/// its normalized message stream. Sessions with no user or assistant messages
+/// are skipped (they contain only system events and produce empty transcripts).
 /// Parse failures are logged as warnings but do not abort the batch.
+/// Templated paths that collide are disambiguated here (see
+/// `disambiguate_template_keys`).
+///
+/// PHASE 4: DUPLICATE RESOLUTION
+/// Per archive, compare fingerprints against each other and against previously
+/// written transcripts that open the same way (see `resolve_duplicates`). Exact and
+/// prefix copies are not written; the most complete copy lists them under
+/// `source.duplicates` in its training JSON.
+///
+/// PHASE 5: WRITING
+/// Re-parse and write each kept session. Parsing twice keeps memory bounded by
+/// one session instead of holding the whole batch. Under a template, outputs a
+/// source wrote under a different path earlier are removed, as are outputs in
+/// another archive when its route changed.
 ///
 /// Progress is reported to stderr with a rolling counter every 100 files.
+#[allow(clippy::too_many_arguments)]
 fn process_file_list(
     files: &[(Tool, PathBuf)],
     output_dir: &Path,
     training_output: Option<&Path>,
     force: bool,
     format: OutputFormat,
+    options: &FormatterOptions,
     filter_dir: Option<&Path>,
+    template: Option<&OutputTemplate>,
+    router: &Router,
     dry_run: bool,
 ) -> Result<(), CassioError> {
     let total = files.len();
-    let mut processed = 0u32;
-    let mut skipped = 0u32;
-    let mut up_to_date = 0u32;
-    let training_dir = training_output.unwrap_or(output_dir);
-
-    // Disambiguate same-name output paths before writing. Several tools derive
-    // `YYYY-MM-DDTHH-MM-SS-{tool}.md` stems at second precision, so sessions
-    // that start in the same second (e.g. Grok spawning many sub-agents) would
-    // otherwise map to one file and silently overwrite each other. Within a
-    // (folder, stem) group, exactly one session keeps the canonical stem: the
-    // earliest one, determined by a stable sort of the source paths (Grok
-    // session directories are UUIDv7, so path order is chronological). The
-    // remaining sessions get a stable `-<hash8>` suffix derived from their own
-    // source paths. Keeping the first session's name stable means a --force
-    // regeneration never renames the original transcript in Git — it only adds
-    // new files for the extra sessions.
-    let mut stems: Vec<(String, String)> = Vec::with_capacity(files.len());
-    for (tool, path) in files {
-        stems.push(derive_output_stem_for(*tool, path)?);
-    }
-    let mut groups: HashMap<(&str, &str), Vec<usize>> = HashMap::new();
-    for (index, (folder, stem)) in stems.iter().enumerate() {
-        groups
-            .entry((folder.as_str(), stem.as_str()))
-            .or_default()
-            .push(index);
-    }
-    let mut unique_stems: Vec<(String, String)> = Vec::with_capacity(files.len());
-    for (index, (folder, stem)) in stems.iter().enumerate() {
-        let group = &groups[&(folder.as_str(), stem.as_str())];
-        let first = group
-            .iter()
-            .copied()
-            .min_by(|&a, &b| files[a].1.cmp(&files[b].1))
-            .unwrap_or(index);
-        if group.len() > 1 && index != first {
-            let hash = short_source_hash(&files[index].1);
-            unique_stems.push((folder.clone(), disambiguated_stem(stem, &hash)));
-        } else {
-            unique_stems.push((folder.clone(), stem.clone()));
-        }
+    let mut counts = BatchCounts::default();
+
+    // Archive 0 is the top-level output; archive `n + 1` is route `n`.
+    let mut archives = vec![Archive::load(
+        output_dir,
+        training_output.unwrap_or(output_dir),
+    )?];
+    for route in router.routes() {
+        archives.push(Archive::load(
+            &route.destination.output,
+            route.destination.training_dir(),
+        )?);
     }
 
+    let unique_stems = match template {
+        Some(_) => Vec::new(),
+        None => unique_output_stems(files)?,
+    };
+    let previous = if template.is_some() || !router.is_empty() {
+        let manifests: Vec<&cassio::manifest::Manifest> =
+            archives.iter().map(|archive| &archive.manifest).collect();
+        last_written_keys(&manifests)
+    } else {
+        HashMap::new()
+    };
+
+    let mut pending: Vec<PendingSession> = Vec::new();
     for (i, (tool, path)) in files.iter().enumerate() {
         if (i + 1) == 1 || (i + 1) % 100 == 0 {
             eprint!("\r  Processing {}/{}...", i + 1, total);
@@ -1594,53 +2534,50 @@ fn process_file_list(
             && let Ok(meta) = fs::metadata(path)
             && meta.len() == 0
         {
-            skipped += 1;
+            counts.skipped += 1;
             continue;
         }
 
-        let (folder, stem) = &unique_stems[i];
-        let primary_root = match format {
-            OutputFormat::TrainingJson => training_dir,
-            _ => output_dir,
+        let source = path.to_string_lossy();
+        let last_written = if template.is_some() || !router.is_empty() {
+            previous
+                .get(source.as_ref())
+                .map(|(archive, key)| (*archive, key.to_string()))
+        } else {
+            let (folder, stem) = &unique_stems[i];
+            Some((0, cassio::manifest::entry_key(folder, stem)))
         };
-        let out_path = primary_root
-            .join(folder)
-            .join(output_filename(stem.as_str(), format));
-        let training_path = training_dir
-            .join(folder)
-            .join(format!("{stem}.training.json"));
 
         if !force {
-            let primary_ok = is_up_to_date(path, &out_path);
-            let training_ok = if format == OutputFormat::EmojiText {
-                is_up_to_date(path, &training_path)
-            } else {
-                true
-            };
-            if primary_ok && training_ok {
-                up_to_date += 1;
+            if let Some((archive, key)) = &last_written {
+                let (out_path, training_path) = archives[*archive].output_paths(key, format);
+                let primary_ok = is_up_to_date(path, &out_path);
+                let training_ok = if format == OutputFormat::EmojiText {
+                    is_up_to_date(path, &training_path)
+                } else {
+                    true
+                };
+                if primary_ok && training_ok {
+                    counts.up_to_date += 1;
+                    continue;
+                }
+            }
+            if let Some(record) = archives
+                .iter()
+                .find_map(|archive| archive.manifest.duplicate_of(&source))
+                && is_unchanged_since(path, record.recorded_at)
+            {
+                counts.duplicates += 1;
                 continue;
             }
         }
 
-        let parser: Box<dyn Parser> = match tool {
-            Tool::Claude | Tool::ClaudeDesktop => Box::new(cassio::parser::claude::ClaudeParser),
-            Tool::ClaudeChat => Box::new(cassio::parser::claude_chat::ClaudeChatParser),
-            Tool::Codex => Box::new(cassio::parser::codex::CodexParser),
-            Tool::Hermes => Box::new(cassio::parser::hermes::HermesParser),
-            Tool::OpenCode => Box::new(cassio::parser::opencode::OpenCodeParser),
-            Tool::Pi => Box::new(cassio::parser::pi::PiParser),
-            Tool::Grok => Box::new(cassio::parser::grok::GrokParser),
-            Tool::Cursor => Box::new(cassio::parser::cursor::CursorParser),
-            Tool::Kimi => Box::new(cassio::parser::kimi::KimiCodeParser),
-        };
-
-        match parser.parse_export(path) {
+        match parser_for(*tool).parse_export(path) {
             Ok(parsed) => {
                 if parsed.session.stats.user_messages == 0
                     && parsed.session.stats.assistant_messages == 0
                 {
-                    skipped += 1;
+                    counts.skipped += 1;
                     continue;
                 }
 
@@ -1652,47 +2589,575 @@ fn process_file_list(
                         .project_path
                         .starts_with(filter_str.as_ref())
                     {
-                        skipped += 1;
+                        counts.skipped += 1;
                         continue;
                     }
                 }
 
-                if dry_run {
-                    eprintln!("  would write: {}", out_path.display());
-                    if format == OutputFormat::EmojiText {
-                        eprintln!("  would write: {}", training_path.display());
+                let key = match template {
+                    Some(template) => {
+                        let (folder, stem) = template.render(&parsed.session);
+                        cassio::manifest::entry_key(&folder, &stem)
                     }
-                    processed += 1;
-                } else {
-                    if let Some(parent) = out_path.parent() {
-                        fs::create_dir_all(parent)?;
-                    }
-                    let parsed = cassio::redact::redact_export(&parsed);
-                    let formatter = format.formatter();
-                    let mut file = fs::File::create(&out_path)?;
-                    formatter.format(&parsed, &mut file)?;
-                    if format == OutputFormat::EmojiText {
-                        if let Some(parent) = training_path.parent() {
-                            fs::create_dir_all(parent)?;
-                        }
-                        let mut training_file = fs::File::create(&training_path)?;
-                        cassio::formatter::training_json::TrainingJsonFormatter
-                            .format(&parsed, &mut training_file)?;
+                    None => {
+                        let (folder, stem) = &unique_stems[i];
+                        cassio::manifest::entry_key(folder, stem)
                     }
-                    processed += 1;
+                };
+                pending.push(PendingSession {
+                    index: i,
+                    archive: router
+                        .route_for(&parsed.session)
+                        .map_or(0, |route| route + 1),
+                    key,
+                    tool: parsed.session.metadata.tool.to_string(),
+                    session_id: parsed.session.metadata.session_id.clone(),
+                    project_path: parsed.session.metadata.project_path.clone(),
+                    fingerprint: Fingerprint::of(&parsed.session),
+                });
+            }
+            Err(e) => {
+                eprintln!("\r  warning: skipping {}: {e}", path.display());
+                counts.skipped += 1;
+            }
+        }
+    }
+
+    // Each archive resolves names and duplicates against its own manifest.
+    let mut written: Vec<(usize, String)> = Vec::new();
+    for (archive_index, archive) in archives.iter_mut().enumerate() {
+        let (mut group, rest): (Vec<_>, Vec<_>) = pending
+            .into_iter()
+            .partition(|session| session.archive == archive_index);
+        pending = rest;
+        if group.is_empty() {
+            continue;
+        }
+        if template.is_some() {
+            disambiguate_template_keys(files, &mut group, &archive.manifest);
+        }
+        for source in write_archive(
+            files,
+            archive,
+            &group,
+            format,
+            options,
+            template,
+            dry_run,
+            &mut counts,
+        )? {
+            written.push((archive_index, source));
+        }
+    }
+
+    // A source routed elsewhere than last time moves: drop its old transcript.
+    for (archive_index, source) in &written {
+        for (other_index, other) in archives.iter_mut().enumerate() {
+            if other_index == *archive_index {
+                continue;
+            }
+            let stale: Vec<String> = other
+                .manifest
+                .sessions
+                .iter()
+                .filter(|(_, entry)| entry.source_path == *source)
+                .map(|(key, _)| key.clone())
+                .collect();
+            for key in stale {
+                if dry_run {
+                    eprintln!(
+                        "  would remove moved: {}",
+                        other.output.join(&key).display()
+                    );
+                    continue;
                 }
+                remove_outputs(other.output, other.training, &key)?;
+                other.manifest.remove(&key);
+                other.dirty = true;
+            }
+        }
+    }
+
+    if !dry_run {
+        for archive in archives.iter().filter(|archive| archive.dirty) {
+            archive.manifest.save(archive.output)?;
+        }
+    }
+
+    let BatchCounts {
+        processed,
+        skipped,
+        up_to_date,
+        duplicates,
+    } = counts;
+    eprintln!(
+        "\r  Done: {processed} processed, {skipped} skipped, {up_to_date} up-to-date, {duplicates} duplicate(s)     "
+    );
+    Ok(())
+}
+
+/// Resolve duplicates for one archive's pending sessions, then write the kept
+/// ones (PHASES 4 and 5 of `process_file_list`). Returns the source paths
+/// written.
+#[allow(clippy::too_many_arguments)]
+fn write_archive(
+    files: &[(Tool, PathBuf)],
+    archive: &mut Archive,
+    pending: &[PendingSession],
+    format: OutputFormat,
+    options: &FormatterOptions,
+    template: Option<&OutputTemplate>,
+    dry_run: bool,
+    counts: &mut BatchCounts,
+) -> Result<Vec<String>, CassioError> {
+    let (removed, kept_with_new_duplicates) =
+        resolve_duplicates(files, pending, &mut archive.manifest, dry_run)?;
+    for key in &removed {
+        if !dry_run {
+            remove_outputs(archive.output, archive.training, key)?;
+        }
+    }
+
+    let mut written = Vec::new();
+    let mut duplicates = 0u32;
+    for session in pending {
+        let (tool, path) = &files[session.index];
+        let source = path.to_string_lossy();
+        if let Some(record) = archive.manifest.duplicates.get(source.as_ref()) {
+            if dry_run {
+                eprintln!(
+                    "  duplicate: {} ({} copy of {})",
+                    path.display(),
+                    record.kind,
+                    record.kept
+                );
             }
+            duplicates += 1;
+            continue;
+        }
+
+        let (out_path, training_path) = archive.output_paths(&session.key, format);
+        if dry_run {
+            eprintln!("  would write: {}", out_path.display());
+            if format == OutputFormat::EmojiText {
+                eprintln!("  would write: {}", training_path.display());
+            }
+            counts.processed += 1;
+            written.push(source.into_owned());
+            continue;
+        }
+
+        let mut parsed = match parser_for(*tool).parse_export(path) {
+            Ok(parsed) => parsed,
             Err(e) => {
                 eprintln!("\r  warning: skipping {}: {e}", path.display());
-                skipped += 1;
+                counts.skipped += 1;
+                continue;
+            }
+        };
+        let entry = cassio::manifest::ManifestEntry::for_session(
+            &parsed.session,
+            path,
+            &format.to_string(),
+            matches!(format, OutputFormat::EmojiText | OutputFormat::TrainingJson),
+        );
+        let manifest = &mut archive.manifest;
+        if template.is_some() {
+            // The template (or the session's metadata) changed since this
+            // source was last written: move it rather than leave a copy behind.
+            let moved: Vec<String> = manifest
+                .sessions
+                .iter()
+                .filter(|(key, entry)| entry.source_path == source && **key != session.key)
+                .map(|(key, _)| key.clone())
+                .collect();
+            for key in moved {
+                remove_outputs(archive.output, archive.training, &key)?;
+                manifest.remove(&key);
+                for record in manifest.duplicates.values_mut() {
+                    if record.kept == key {
+                        record.kept = session.key.clone();
+                    }
+                }
             }
         }
+        manifest.record(session.key.clone(), entry);
+        parsed.training.source.duplicates = manifest.duplicates_kept_by(&session.key);
+
+        if let Some(parent) = out_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let parsed = cassio::redact::redact_export(&parsed);
+        let formatter = format.formatter(options);
+        let mut file = fs::File::create(&out_path)?;
+        formatter.format(&parsed, &mut file)?;
+        if format == OutputFormat::EmojiText {
+            if let Some(parent) = training_path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let mut training_file = fs::File::create(&training_path)?;
+            cassio::formatter::training_json::TrainingJsonFormatter
+                .format(&parsed, &mut training_file)?;
+        }
+        counts.processed += 1;
+        written.push(source.into_owned());
     }
+    counts.duplicates += duplicates;
 
-    eprintln!("\r  Done: {processed} processed, {skipped} skipped, {up_to_date} up-to-date     ");
+    if !dry_run {
+        // Transcripts kept from earlier runs that gained duplicates this run.
+        let written_keys: HashSet<&str> = pending.iter().map(|s| s.key.as_str()).collect();
+        for key in kept_with_new_duplicates
+            .iter()
+            .filter(|key| !written_keys.contains(key.as_str()))
+        {
+            let training_path = archive.training.join(format!("{key}.training.json"));
+            if training_path.is_file() {
+                cassio::training::append_duplicates(
+                    &training_path,
+                    &archive.manifest.duplicates_kept_by(key),
+                )?;
+            }
+        }
+        if !written.is_empty() || duplicates > 0 || !removed.is_empty() {
+            archive.dirty = true;
+        }
+    }
+    Ok(written)
+}
+
+/// Session counts reported at the end of a batch.
+#[derive(Default)]
+struct BatchCounts {
+    processed: u32,
+    skipped: u32,
+    up_to_date: u32,
+    duplicates: u32,
+}
+
+/// An archive a batch writes into: transcript and training roots plus the
+/// manifest kept under the transcript root.
+struct Archive<'a> {
+    output: &'a Path,
+    training: &'a Path,
+    manifest: cassio::manifest::Manifest,
+    /// The manifest changed and must be saved.
+    dirty: bool,
+}
+
+impl<'a> Archive<'a> {
+    fn load(output: &'a Path, training: &'a Path) -> Result<Self, CassioError> {
+        Ok(Self {
+            output,
+            training,
+            manifest: cassio::manifest::Manifest::load(output)?,
+            dirty: false,
+        })
+    }
+
+    /// Primary artifact and `*.training.json` paths for manifest `key`.
+    fn output_paths(&self, key: &str, format: OutputFormat) -> (PathBuf, PathBuf) {
+        let primary_root = match format {
+            OutputFormat::TrainingJson => self.training,
+            _ => self.output,
+        };
+        (
+            primary_root.join(output_filename(key, format)),
+            self.training.join(format!("{key}.training.json")),
+        )
+    }
+}
+
+/// A parsed, not-yet-written session awaiting duplicate resolution.
+struct PendingSession {
+    /// Position in the `files` slice.
+    index: usize,
+    /// Archive it is routed to: 0 for the top-level output, `n + 1` for route `n`.
+    archive: usize,
+    /// Manifest key of the transcript it would be written to.
+    key: String,
+    tool: String,
+    session_id: String,
+    project_path: String,
+    fingerprint: Fingerprint,
+}
+
+/// Resolve duplicates among `pending` sessions and previously written
+/// transcripts, recording every duplicate source in `manifest.duplicates`.
+///
+/// Earlier transcripts take part only when their fingerprint opens with the
+/// same message as a pending session; their sources are re-parsed to recover
+/// the full fingerprint. Returns the manifest keys of earlier transcripts that
+/// a more complete copy supersedes (their entries are already removed from
+/// `manifest`; the caller deletes their files), and the keys of transcripts
+/// that gained duplicates.
+fn resolve_duplicates(
+    files: &[(Tool, PathBuf)],
+    pending: &[PendingSession],
+    manifest: &mut cassio::manifest::Manifest,
+    dry_run: bool,
+) -> Result<(Vec<String>, BTreeSet<String>), CassioError> {
+    use cassio::fingerprint::{Candidate, resolve};
+
+    let pending_sources: HashSet<String> = pending
+        .iter()
+        .map(|s| files[s.index].1.to_string_lossy().into_owned())
+        .collect();
+    let heads: HashSet<u64> = pending
+        .iter()
+        .filter_map(|s| s.fingerprint.head())
+        .collect();
+
+    // (key, source_path, project_path, fingerprint) of earlier transcripts
+    // worth comparing.
+    let mut earlier: Vec<(String, String, String, Fingerprint)> = Vec::new();
+    for (key, entry) in &manifest.sessions {
+        if pending_sources.contains(&entry.source_path)
+            || !entry
+                .fingerprint
+                .as_ref()
+                .and_then(|f| f.head())
+                .is_some_and(|head| heads.contains(&head))
+        {
+            continue;
+        }
+        let Some(tool) = tool_from_name(&entry.tool) else {
+            continue;
+        };
+        if let Ok(parsed) = parser_for(tool).parse_export(Path::new(&entry.source_path)) {
+            earlier.push((
+                key.clone(),
+                entry.source_path.clone(),
+                parsed.session.metadata.project_path.clone(),
+                Fingerprint::of(&parsed.session),
+            ));
+        }
+    }
+
+    let mut candidates: Vec<Candidate> = pending
+        .iter()
+        .map(|s| Candidate {
+            fingerprint: s.fingerprint.clone(),
+            existing: manifest.get(&s.key).is_some(),
+            source_path: files[s.index].1.to_string_lossy().into_owned(),
+            project_path: s.project_path.clone(),
+        })
+        .collect();
+    candidates.extend(
+        earlier
+            .iter()
+            .map(|(_, source_path, project_path, fingerprint)| Candidate {
+                fingerprint: fingerprint.clone(),
+                existing: true,
+                source_path: source_path.clone(),
+                project_path: project_path.clone(),
+            }),
+    );
+
+    let key_of = |index: usize| -> &str {
+        if index < pending.len() {
+            &pending[index].key
+        } else {
+            &earlier[index - pending.len()].0
+        }
+    };
+
+    let now = Utc::now();
+    let mut removed = Vec::new();
+    let mut kept_with_new_duplicates = BTreeSet::new();
+    for (index, resolution) in resolve(&candidates).into_iter().enumerate() {
+        let source_path = &candidates[index].source_path;
+        let Some((keeper, kind)) = resolution else {
+            manifest.duplicates.remove(source_path);
+            continue;
+        };
+        let kept = key_of(keeper).to_string();
+        let (tool, session_id) = if index < pending.len() {
+            (
+                pending[index].tool.clone(),
+                pending[index].session_id.clone(),
+            )
+        } else {
+            let entry = manifest.get(key_of(index));
+            (
+                entry.map(|e| e.tool.clone()).unwrap_or_default(),
+                entry.map(|e| e.session_id.clone()).unwrap_or_default(),
+            )
+        };
+
+        // Earlier transcripts of this source (its previous output, or the
+        // earlier copy itself) are superseded by the kept one.
+        let superseded: Vec<String> = manifest
+            .sessions
+            .iter()
+            .filter(|(key, entry)| entry.source_path == *source_path && **key != kept)
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in superseded {
+            if dry_run {
+                eprintln!("  would remove superseded: {key}");
+            } else {
+                manifest.remove(&key);
+            }
+            for record in manifest.duplicates.values_mut() {
+                if record.kept == key {
+                    record.kept = kept.clone();
+                }
+            }
+            removed.push(key);
+        }
+
+        kept_with_new_duplicates.insert(kept.clone());
+        manifest.duplicates.insert(
+            source_path.clone(),
+            cassio::manifest::DuplicateRecord {
+                kept,
+                tool,
+                session_id,
+                kind,
+                messages: candidates[index].fingerprint.len(),
+                recorded_at: now,
+            },
+        );
+    }
+    Ok((removed, kept_with_new_duplicates))
+}
+
+/// Give every templated key a single owner, mirroring `unique_output_stems`.
+///
+/// A key the manifest already records stays with its source so reruns never
+/// rename a transcript in Git; otherwise the lowest source path keeps it. Every
+/// other session rendering the same key gets the `-<hash8>` disambiguator.
+fn disambiguate_template_keys(
+    files: &[(Tool, PathBuf)],
+    pending: &mut [PendingSession],
+    manifest: &cassio::manifest::Manifest,
+) {
+    let mut owners: HashMap<String, PathBuf> = HashMap::new();
+    for session in pending.iter() {
+        let source = &files[session.index].1;
+        let owner = owners.entry(session.key.clone()).or_insert_with(|| {
+            manifest
+                .get(&session.key)
+                .map(|entry| PathBuf::from(&entry.source_path))
+                .unwrap_or_else(|| source.clone())
+        });
+        if manifest.get(&session.key).is_none() && source < owner {
+            *owner = source.clone();
+        }
+    }
+    for session in pending.iter_mut() {
+        let source = &files[session.index].1;
+        if owners.get(&session.key) == Some(source) {
+            continue;
+        }
+        let hash = short_source_hash(source);
+        session.key = match session.key.rsplit_once('/') {
+            Some((folder, stem)) => {
+                cassio::manifest::entry_key(folder, &disambiguated_stem(stem, &hash))
+            }
+            None => disambiguated_stem(&session.key, &hash),
+        };
+    }
+}
+
+/// Delete every artifact written for manifest `key` (any of
+/// `manifest::ARTIFACT_EXTENSIONS`, plus training JSON under `training_dir`).
+fn remove_outputs(output_dir: &Path, training_dir: &Path, key: &str) -> Result<(), CassioError> {
+    let candidates = cassio::manifest::ARTIFACT_EXTENSIONS
+        .iter()
+        .map(|ext| output_dir.join(format!("{key}{ext}")))
+        .chain([training_dir.join(format!("{key}.training.json"))]);
+    for path in candidates {
+        if path.is_file() {
+            fs::remove_file(path)?;
+        }
+    }
     Ok(())
 }
 
+/// True when the source (or its virtual export root) has not been modified since `at`.
+fn is_unchanged_since(input: &Path, at: chrono::DateTime<Utc>) -> bool {
+    let mtime_input = cassio::parser::claude_chat::export_root_from_virtual(input)
+        .unwrap_or_else(|| input.to_path_buf());
+    fs::metadata(&mtime_input)
+        .and_then(|meta| meta.modified())
+        .is_ok_and(|modified| chrono::DateTime::<Utc>::from(modified) <= at)
+}
+
+fn parser_for(tool: Tool) -> Box<dyn Parser> {
+    match tool {
+        Tool::Claude | Tool::ClaudeDesktop => Box::new(cassio::parser::claude::ClaudeParser),
+        Tool::ClaudeChat => Box::new(cassio::parser::claude_chat::ClaudeChatParser),
+        Tool::Codex => Box::new(cassio::parser::codex::CodexParser),
+        Tool::Hermes => Box::new(cassio::parser::hermes::HermesParser),
+        Tool::OpenCode => Box::new(cassio::parser::opencode::OpenCodeParser),
+        Tool::Pi => Box::new(cassio::parser::pi::PiParser),
+        Tool::Grok => Box::new(cassio::parser::grok::GrokParser),
+        Tool::Cursor => Box::new(cassio::parser::cursor::CursorParser),
+        Tool::Kimi => Box::new(cassio::parser::kimi::KimiCodeParser),
+    }
+}
+
+/// Inverse of `Tool`'s `Display` (manifest entries store the display name).
+fn tool_from_name(name: &str) -> Option<Tool> {
+    Some(match name {
+        "claude" => Tool::Claude,
+        "claude-chat" => Tool::ClaudeChat,
+        "codex" => Tool::Codex,
+        "hermes" => Tool::Hermes,
+        "opencode" => Tool::OpenCode,
+        "pi" => Tool::Pi,
+        "grok" => Tool::Grok,
+        "cursor" => Tool::Cursor,
+        "kimi" => Tool::Kimi,
+        _ => return None,
+    })
+}
+
+/// Derive the `(folder, stem)` output name for every file in `files`.
+///
+/// Same-name output paths are disambiguated before writing. Several tools derive
+/// `YYYY-MM-DDTHH-MM-SS-{tool}.md` stems at second precision, so sessions
+/// that start in the same second (e.g. Grok spawning many sub-agents) would
+/// otherwise map to one file and silently overwrite each other. Within a
+/// (folder, stem) group, exactly one session keeps the canonical stem: the
+/// earliest one, determined by a stable sort of the source paths (Grok
+/// session directories are UUIDv7, so path order is chronological). The
+/// remaining sessions get a stable `-<hash8>` suffix derived from their own
+/// source paths. Keeping the first session's name stable means a --force
+/// regeneration never renames the original transcript in Git — it only adds
+/// new files for the extra sessions.
+fn unique_output_stems(files: &[(Tool, PathBuf)]) -> Result<Vec<(String, String)>, CassioError> {
+    let mut stems: Vec<(String, String)> = Vec::with_capacity(files.len());
+    for (tool, path) in files {
+        stems.push(derive_output_stem_for(*tool, path)?);
+    }
+    let mut groups: HashMap<(&str, &str), Vec<usize>> = HashMap::new();
+    for (index, (folder, stem)) in stems.iter().enumerate() {
+        groups
+            .entry((folder.as_str(), stem.as_str()))
+            .or_default()
+            .push(index);
+    }
+    let mut unique_stems: Vec<(String, String)> = Vec::with_capacity(files.len());
+    for (index, (folder, stem)) in stems.iter().enumerate() {
+        let group = &groups[&(folder.as_str(), stem.as_str())];
+        let first = group
+            .iter()
+            .copied()
+            .min_by(|&a, &b| files[a].1.cmp(&files[b].1))
+            .unwrap_or(index);
+        if group.len() > 1 && index != first {
+            let hash = short_source_hash(&files[index].1);
+            unique_stems.push((folder.clone(), disambiguated_stem(stem, &hash)));
+        } else {
+            unique_stems.push((folder.clone(), stem.clone()));
+        }
+    }
+    Ok(unique_stems)
+}
+
 /// Compute the `(year-month-folder, filename)` output path for a session file.
 ///
 /// OpenCode requires reading the session JSON to get a timestamp (since its session
@@ -1775,11 +3240,23 @@ fn derive_output_stem_for(tool: Tool, path: &Path) -> Result<(String, String), C
     }
 }
 
+/// The configured `output_template`, validated up front so a typo fails the run
+/// before anything is written.
+fn output_template(config: &Config) -> Result<Option<OutputTemplate>, CassioError> {
+    config
+        .output_template
+        .as_deref()
+        .map(OutputTemplate::parse)
+        .transpose()
+}
+
 fn output_filename(stem: &str, format: OutputFormat) -> String {
     match format {
         OutputFormat::EmojiText => format!("{stem}.md"),
         OutputFormat::Jsonl => format!("{stem}.jsonl"),
         OutputFormat::TrainingJson => format!("{stem}.training.json"),
+        OutputFormat::Html => format!("{stem}.html"),
+        OutputFormat::Markdown => format!("{stem}.markdown"),
     }
 }
 
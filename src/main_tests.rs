@@ -22,6 +22,8 @@ fn index_options_from_config_uses_embedding_defaults() {
         include_paths: true,
         batch_size: Some(4),
         timeout_secs: Some(45),
+        rerank_model: None,
+        related_footer: false,
     };
     let options = index_options_from_config(
         Some(&embedding),
@@ -130,7 +132,10 @@ fn test_process_file_list_disambiguates_same_second_collision() {
         None,
         false,
         OutputFormat::EmojiText,
+        &FormatterOptions::default(),
         None,
+        None,
+        &Router::default(),
         false,
     )
     .unwrap();
@@ -193,7 +198,10 @@ fn test_process_file_list_disambiguates_same_second_collision() {
         None,
         true,
         OutputFormat::EmojiText,
+        &FormatterOptions::default(),
+        None,
         None,
+        &Router::default(),
         false,
     )
     .unwrap();
@@ -201,3 +209,330 @@ fn test_process_file_list_disambiguates_same_second_collision() {
 
     fs::remove_dir_all(dir).ok();
 }
+
+fn write_grok_chat(dir: &PathBuf, id: &str, created_at: &str, turns: &[&str]) {
+    fs::create_dir_all(dir).unwrap();
+    fs::write(
+        dir.join("summary.json"),
+        json!({
+            "info": { "id": format!("019fb8eb-0000-7000-8000-00000000000{id}"), "cwd": "/tmp/proj" },
+            "created_at": created_at
+        })
+        .to_string(),
+    )
+    .unwrap();
+    let chat: String = turns
+        .iter()
+        .map(|text| {
+            format!(
+                "{}\n{}\n",
+                json!({"type": "user", "content": [{"type": "text", "text": format!("ask {text}")}]}),
+                json!({"type": "assistant", "content": format!("answer {text}"), "model_id": "deepseek-v4-flash"})
+            )
+        })
+        .collect();
+    fs::write(dir.join("chat_history.jsonl"), chat).unwrap();
+}
+
+#[test]
+fn test_process_file_list_keeps_most_complete_duplicate() {
+    let dir = temp_dir("duplicates");
+    let out = dir.join("out");
+    // A forked sub-agent replaying its parent's turns, and an exact mirror.
+    write_grok_chat(
+        &dir.join("sess-a"),
+        "a",
+        "2026-07-31T16:05:00Z",
+        &["one", "two"],
+    );
+    write_grok_chat(
+        &dir.join("sess-b"),
+        "b",
+        "2026-07-31T16:06:00Z",
+        &["one", "two", "three"],
+    );
+    write_grok_chat(
+        &dir.join("sess-c"),
+        "c",
+        "2026-07-31T16:07:00Z",
+        &["one", "two", "three"],
+    );
+
+    let files = vec![
+        (Tool::Grok, dir.join("sess-a/chat_history.jsonl")),
+        (Tool::Grok, dir.join("sess-b/chat_history.jsonl")),
+        (Tool::Grok, dir.join("sess-c/chat_history.jsonl")),
+    ];
+    process_file_list(
+        &files,
+        &out,
+        None,
+        false,
+        OutputFormat::EmojiText,
+        &FormatterOptions::default(),
+        None,
+        None,
+        &Router::default(),
+        false,
+    )
+    .unwrap();
+
+    let month = out.join("2026-07");
+    assert!(month.join("2026-07-31T16-06-00-grok.md").is_file());
+    assert!(!month.join("2026-07-31T16-05-00-grok.md").exists());
+    assert!(!month.join("2026-07-31T16-07-00-grok.md").exists());
+
+    let training: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(month.join("2026-07-31T16-06-00-grok.training.json")).unwrap(),
+    )
+    .unwrap();
+    let kinds: Vec<&str> = training["source"]["duplicates"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|d| d["kind"].as_str().unwrap())
+        .collect();
+    assert_eq!(kinds, vec!["prefix", "exact"]);
+
+    let manifest = cassio::manifest::Manifest::load(&out).unwrap();
+    assert_eq!(manifest.duplicates.len(), 2);
+
+    // A later run grows the short copy past the kept one: it takes over and
+    // the superseded transcript is removed.
+    write_grok_chat(
+        &dir.join("sess-a"),
+        "a",
+        "2026-07-31T16:05:00Z",
+        &["one", "two", "three", "four"],
+    );
+    // Date the rewrite past the first run's output, whatever the mtime
+    // granularity, so the up-to-date check cannot skip it.
+    fs::File::options()
+        .write(true)
+        .open(dir.join("sess-a/chat_history.jsonl"))
+        .unwrap()
+        .set_modified(SystemTime::now() + std::time::Duration::from_secs(60))
+        .unwrap();
+    process_file_list(
+        &files,
+        &out,
+        None,
+        false,
+        OutputFormat::EmojiText,
+        &FormatterOptions::default(),
+        None,
+        None,
+        &Router::default(),
+        false,
+    )
+    .unwrap();
+    assert!(month.join("2026-07-31T16-05-00-grok.md").is_file());
+    assert!(!month.join("2026-07-31T16-06-00-grok.md").exists());
+    let manifest = cassio::manifest::Manifest::load(&out).unwrap();
+    assert_eq!(
+        manifest
+            .duplicates_kept_by("2026-07/2026-07-31T16-05-00-grok")
+            .len(),
+        2
+    );
+
+    fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn test_process_file_list_renders_output_template() {
+    let dir = temp_dir("template");
+    let out = dir.join("out");
+    write_grok_chat(&dir.join("sess-a"), "a", "2026-07-31T16:05:00Z", &["one"]);
+    write_grok_chat(&dir.join("sess-b"), "b", "2026-07-31T18:00:00Z", &["two"]);
+    let files = vec![
+        (Tool::Grok, dir.join("sess-a/chat_history.jsonl")),
+        (Tool::Grok, dir.join("sess-b/chat_history.jsonl")),
+    ];
+    let run = |template: &str, force: bool| {
+        let template = OutputTemplate::parse(template).unwrap();
+        process_file_list(
+            &files,
+            &out,
+            None,
+            force,
+            OutputFormat::EmojiText,
+            &FormatterOptions::default(),
+            None,
+            Some(&template),
+            &Router::default(),
+            false,
+        )
+        .unwrap();
+    };
+
+    // Both sessions render the same day-level name: the lower source path
+    // keeps it, the other gets the hash disambiguator.
+    run("{project}/{yyyy}/{dd}-{tool}", false);
+    let folder = out.join("proj/2026");
+    assert!(folder.join("31-grok.md").is_file());
+    assert!(folder.join("31-grok.training.json").is_file());
+    let suffixed = format!(
+        "31-{}-grok.md",
+        short_source_hash(&dir.join("sess-b/chat_history.jsonl"))
+    );
+    assert!(folder.join(&suffixed).is_file());
+
+    let catalog = cassio::manifest::SessionCatalog::load(&folder);
+    let session = catalog.lookup(&folder.join(&suffixed)).unwrap();
+    assert_eq!(session.date, "2026-07-31");
+    assert_eq!(session.tool, "grok");
+
+    // Changing the template moves existing transcripts instead of copying them.
+    run("{tool}/{yyyy}-{mm}-{dd}T{HH}", true);
+    assert!(out.join("grok/2026-07-31T16.md").is_file());
+    assert!(out.join("grok/2026-07-31T18.md").is_file());
+    assert!(!folder.join("31-grok.md").exists());
+    assert!(!folder.join(&suffixed).exists());
+    let manifest = cassio::manifest::Manifest::load(&out).unwrap();
+    assert_eq!(manifest.sessions.len(), 2);
+
+    fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn test_gc_expects_the_templated_output_batch_last_wrote() {
+    let dir = temp_dir("gc_template");
+    let out = dir.join("out");
+    let sources = dir.join("grok");
+    write_grok_chat(
+        &sources.join("sess-a"),
+        "a",
+        "2026-07-31T16:05:00Z",
+        &["one"],
+    );
+    let files = vec![(Tool::Grok, sources.join("sess-a/chat_history.jsonl"))];
+    let config: Config =
+        toml::from_str("output_template = \"{tool}/{yyyy}-{mm}-{dd}-{tool}\"\n").unwrap();
+    let template = output_template(&config).unwrap();
+    process_file_list(
+        &files,
+        &out,
+        None,
+        false,
+        OutputFormat::EmojiText,
+        &FormatterOptions::default(),
+        None,
+        template.as_ref(),
+        &Router::default(),
+        false,
+    )
+    .unwrap();
+
+    // A leftover from before the template, recorded earlier for the same source.
+    let templated = "grok/2026-07-31-grok";
+    let leftover = "2026-07/2026-07-31T16-05-00-grok";
+    let mut manifest = cassio::manifest::Manifest::load(&out).unwrap();
+    let mut entry = manifest.get(templated).unwrap().clone();
+    entry.written_at -= chrono::Duration::days(1);
+    manifest.sessions.insert(leftover.to_string(), entry);
+    fs::create_dir_all(out.join("2026-07")).unwrap();
+    fs::copy(
+        out.join(format!("{templated}.md")),
+        out.join(format!("{leftover}.md")),
+    )
+    .unwrap();
+
+    let expected = expected_keys(&config, &[(Tool::Grok, sources)], &manifest).unwrap();
+    assert_eq!(expected, HashSet::from([templated.to_string()]));
+    let plan = cassio::gc::plan(&out, None, &expected, &manifest).unwrap();
+    assert!(plan.orphans.is_empty());
+    let flagged: Vec<&str> = plan.duplicates.iter().map(|f| f.key.as_str()).collect();
+    assert_eq!(flagged, [leftover]);
+
+    fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn test_process_file_list_routes_sessions_to_separate_archives() {
+    let dir = temp_dir("routes");
+    let out = dir.join("out");
+    let acme = dir.join("acme");
+    write_grok_chat(&dir.join("sess-a"), "a", "2026-07-31T16:05:00Z", &["one"]);
+    write_grok_chat(&dir.join("sess-b"), "b", "2026-07-31T18:00:00Z", &["two"]);
+    fs::write(
+        dir.join("sess-b/summary.json"),
+        json!({
+            "info": { "id": "019fb8eb-0000-7000-8000-00000000000b", "cwd": "/work/acme/api" },
+            "created_at": "2026-07-31T18:00:00Z"
+        })
+        .to_string(),
+    )
+    .unwrap();
+    let files = vec![
+        (Tool::Grok, dir.join("sess-a/chat_history.jsonl")),
+        (Tool::Grok, dir.join("sess-b/chat_history.jsonl")),
+    ];
+    let config: Config = toml::from_str(&format!(
+        "[[routes]]\nproject = \"/work/acme\"\noutput = \"{}\"\n",
+        acme.display()
+    ))
+    .unwrap();
+    let run = |router: &Router, force: bool| {
+        process_file_list(
+            &files,
+            &out,
+            None,
+            force,
+            OutputFormat::EmojiText,
+            &FormatterOptions::default(),
+            None,
+            None,
+            router,
+            false,
+        )
+        .unwrap();
+    };
+
+    run(&Router::from_config(&config).unwrap(), false);
+    assert!(out.join("2026-07/2026-07-31T16-05-00-grok.md").is_file());
+    assert!(!out.join("2026-07/2026-07-31T18-00-00-grok.md").exists());
+    assert!(acme.join("2026-07/2026-07-31T18-00-00-grok.md").is_file());
+    assert!(
+        acme.join("2026-07/2026-07-31T18-00-00-grok.training.json")
+            .is_file()
+    );
+    let routed = cassio::manifest::Manifest::load(&acme).unwrap();
+    assert!(routed.get("2026-07/2026-07-31T18-00-00-grok").is_some());
+
+    // Unchanged sources are up to date in whichever archive holds them.
+    run(&Router::from_config(&config).unwrap(), false);
+    assert_eq!(
+        cassio::manifest::Manifest::load(&out)
+            .unwrap()
+            .sessions
+            .len(),
+        1
+    );
+
+    // When the route stops matching, the session moves back to the top-level
+    // output and leaves the routed archive.
+    let narrowed: Config = toml::from_str(&format!(
+        "[[routes]]\nproject = \"/work/acme/web\"\noutput = \"{}\"\n",
+        acme.display()
+    ))
+    .unwrap();
+    run(&Router::from_config(&narrowed).unwrap(), true);
+    assert!(out.join("2026-07/2026-07-31T18-00-00-grok.md").is_file());
+    assert!(!acme.join("2026-07/2026-07-31T18-00-00-grok.md").exists());
+    assert!(
+        cassio::manifest::Manifest::load(&acme)
+            .unwrap()
+            .sessions
+            .is_empty()
+    );
+
+    fs::remove_dir_all(dir).ok();
+}
+
+#[test]
+fn test_critical_hits_become_an_exit_code_error() {
+    assert!(critical_hits(Vec::new()).is_ok());
+    let err = critical_hits(vec!["leaks".into(), "deploys".into(), "leaks".into()]).unwrap_err();
+    assert!(matches!(err, CassioError::CriticalMatch(ref names) if names == "deploys, leaks"));
+}
@@ -0,0 +1,315 @@
+//! Archive manifest: which source session produced each written transcript.
+//!
+//! Batch runs record one entry per output stem in `.cassio/manifest.json` under
+//! the transcript output root. The manifest is the archive's memory of where a
+//! transcript came from, so maintenance commands (`cassio gc`) can tell a live
+//! transcript from an orphan left behind by a renamed stem or a deleted log
+//! without re-deriving every output name from filenames.
+//!
+//! # TRADE-OFFS
+//!
+//! - Entries are keyed by the extension-less output stem relative to the root
+//!   (`2025-11/2025-11-12T21-52-16-claude`), so one entry covers the transcript
+//!   and its `*.training.json` sidecar regardless of output format.
+//! - The manifest is plain pretty-printed JSON with sorted keys so it diffs
+//!   cleanly when the archive is a git repository.
+//! - With a custom `output_template`, filenames no longer carry the session date
+//!   and tool, so archive readers resolve them through [`SessionCatalog`]. A
+//!   templated archive whose manifest is lost has to be regenerated with
+//!   `--force` before summary, search, compaction, and the index see it again.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::ast::Session;
+use crate::error::CassioError;
+use crate::fingerprint::{DuplicateKind, Fingerprint, FingerprintSummary};
+use crate::training::TrainingDuplicate;
+
+/// Directory under an archive root holding cassio's own state (index, manifest).
+pub const STATE_DIR: &str = ".cassio";
+
+const MANIFEST_FILE: &str = "manifest.json";
+const MANIFEST_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Manifest {
+    pub schema_version: u32,
+    /// Entries keyed by root-relative output stem (no extension).
+    #[serde(default)]
+    pub sessions: BTreeMap<String, ManifestEntry>,
+    /// Source logs skipped as duplicates, keyed by source path.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub duplicates: BTreeMap<String, DuplicateRecord>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub tool: String,
+    pub source_path: String,
+    pub session_id: String,
+    pub started_at: DateTime<Utc>,
+    pub project_path: String,
+    /// Output format of the primary artifact (`emoji-text`, `jsonl`, ...).
+    pub format: String,
+    /// Whether a `*.training.json` sidecar was written for this stem.
+    #[serde(default)]
+    pub training: bool,
+    pub written_at: DateTime<Utc>,
+    /// Content fingerprint of the written session, for duplicate detection.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<FingerprintSummary>,
+}
+
+/// A source log whose conversation is already held by another transcript.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DuplicateRecord {
+    /// Manifest key of the transcript that was kept.
+    pub kept: String,
+    pub tool: String,
+    pub session_id: String,
+    pub kind: DuplicateKind,
+    /// Fingerprinted messages in the duplicate copy.
+    pub messages: usize,
+    pub recorded_at: DateTime<Utc>,
+}
+
+impl DuplicateRecord {
+    /// The `source.duplicates` item for this record's `source_path`.
+    pub fn training_duplicate(&self, source_path: &str) -> TrainingDuplicate {
+        TrainingDuplicate {
+            tool: self.tool.clone(),
+            source_path: source_path.to_string(),
+            session_id: self.session_id.clone(),
+            kind: self.kind.to_string(),
+            messages: self.messages,
+        }
+    }
+}
+
+impl ManifestEntry {
+    /// Entry for a session from `source_path` just written in `format`.
+    pub fn for_session(
+        session: &Session,
+        source_path: &Path,
+        format: &str,
+        training: bool,
+    ) -> Self {
+        let meta = &session.metadata;
+        Self {
+            tool: meta.tool.to_string(),
+            source_path: source_path.to_string_lossy().into_owned(),
+            session_id: meta.session_id.clone(),
+            started_at: meta.started_at,
+            project_path: meta.project_path.clone(),
+            format: format.to_string(),
+            training,
+            written_at: Utc::now(),
+            fingerprint: Fingerprint::of(session).summary(),
+        }
+    }
+}
+
+impl Default for Manifest {
+    fn default() -> Self {
+        Self {
+            schema_version: MANIFEST_SCHEMA_VERSION,
+            sessions: BTreeMap::new(),
+            duplicates: BTreeMap::new(),
+        }
+    }
+}
+
+impl Manifest {
+    /// Load the manifest for `root`, or an empty one when none has been written.
+    pub fn load(root: &Path) -> Result<Self, CassioError> {
+        let path = manifest_path(root);
+        match fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).map_err(|source| CassioError::Json {
+                path: path.clone(),
+                source,
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(CassioError::Io(e)),
+        }
+    }
+
+    /// Write the manifest atomically (temp file + rename) under `root/.cassio/`.
+    pub fn save(&self, root: &Path) -> Result<(), CassioError> {
+        let path = manifest_path(root);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut json = serde_json::to_string_pretty(self)?;
+        json.push('\n');
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, json)?;
+        fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+
+    pub fn record(&mut self, key: String, entry: ManifestEntry) {
+        self.sessions.insert(key, entry);
+    }
+
+    pub fn get(&self, key: &str) -> Option<&ManifestEntry> {
+        self.sessions.get(key)
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<ManifestEntry> {
+        self.sessions.remove(key)
+    }
+
+    /// Duplicate record for `source_path` whose kept transcript is still listed.
+    pub fn duplicate_of(&self, source_path: &str) -> Option<&DuplicateRecord> {
+        self.duplicates
+            .get(source_path)
+            .filter(|record| self.sessions.contains_key(&record.kept))
+    }
+
+    /// Training `source.duplicates` items for the transcript stored under `key`.
+    pub fn duplicates_kept_by(&self, key: &str) -> Vec<TrainingDuplicate> {
+        self.duplicates
+            .iter()
+            .filter(|(_, record)| record.kept == key)
+            .map(|(source_path, record)| record.training_duplicate(source_path))
+            .collect()
+    }
+}
+
+pub fn manifest_path(root: &Path) -> PathBuf {
+    root.join(STATE_DIR).join(MANIFEST_FILE)
+}
+
+/// Manifest key for an output `folder` and `stem` pair.
+pub fn entry_key(folder: &str, stem: &str) -> String {
+    if folder.is_empty() {
+        stem.to_string()
+    } else {
+        format!("{folder}/{stem}")
+    }
+}
+
+/// Artifact extensions stripped to recover a manifest key, longest first.
+pub const ARTIFACT_EXTENSIONS: &[&str] = &[
+    ".training.json",
+    ".md",
+    ".txt",
+    ".jsonl",
+    ".json",
+    ".html",
+    ".markdown",
+];
+
+/// Date and tool of a written session, as recorded in the manifest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CatalogSession {
+    /// Session start date, `YYYY-MM-DD`.
+    pub date: String,
+    pub tool: String,
+}
+
+impl CatalogSession {
+    /// `YYYY-MM` month of the session.
+    pub fn month(&self) -> &str {
+        self.date.get(..7).unwrap_or(&self.date)
+    }
+}
+
+/// Read-only view of a manifest for archive readers that cannot rely on
+/// filenames (summary, search, compaction, index).
+#[derive(Debug, Clone, Default)]
+pub struct SessionCatalog {
+    roots: Vec<PathBuf>,
+    sessions: HashMap<String, CatalogSession>,
+}
+
+impl SessionCatalog {
+    /// Catalog from the nearest manifest at or above `dir`, so readers pointed at
+    /// a month or project subfolder still find the archive root. Missing or
+    /// unreadable manifests give an empty catalog: readers then fall back to
+    /// filename parsing alone.
+    pub fn load(dir: &Path) -> Self {
+        for root in dir.ancestors() {
+            if !manifest_path(root).is_file() {
+                continue;
+            }
+            let Ok(manifest) = Manifest::load(root) else {
+                break;
+            };
+            let sessions = manifest
+                .sessions
+                .into_iter()
+                .map(|(key, entry)| {
+                    let session = CatalogSession {
+                        date: entry.started_at.format("%Y-%m-%d").to_string(),
+                        tool: entry.tool,
+                    };
+                    (key, session)
+                })
+                .collect();
+            return Self {
+                roots: vec![root.to_path_buf()],
+                sessions,
+            };
+        }
+        Self::default()
+    }
+
+    /// Also resolve paths under `root`, e.g. a dedicated training directory that
+    /// mirrors the transcript layout.
+    pub fn with_root(mut self, root: &Path) -> Self {
+        if !self.sessions.is_empty() && !self.roots.iter().any(|r| r == root) {
+            self.roots.push(root.to_path_buf());
+        }
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sessions.is_empty()
+    }
+
+    /// Session recorded for an artifact path under one of the catalog roots.
+    pub fn lookup(&self, path: &Path) -> Option<&CatalogSession> {
+        self.roots.iter().find_map(|root| {
+            let rel = path.strip_prefix(root).ok()?;
+            self.lookup_relative(&rel.to_string_lossy())
+        })
+    }
+
+    /// Date and tool of an emoji-text transcript (`.md` or `.txt`) written
+    /// under a custom `output_template`, for readers that cannot parse them
+    /// from the filename.
+    pub fn lookup_transcript(&self, path: &Path) -> Option<(String, String)> {
+        let name = path.file_name()?.to_str()?;
+        if !(name.ends_with(".md") || name.ends_with(".txt")) {
+            return None;
+        }
+        let session = self.lookup(path)?;
+        Some((session.date.clone(), session.tool.clone()))
+    }
+
+    /// Session recorded for a root-relative artifact path such as
+    /// `cassio/2025/11/12T21-52-claude.md`.
+    pub fn lookup_relative(&self, rel: &str) -> Option<&CatalogSession> {
+        let rel = rel.replace('\\', "/");
+        let key = ARTIFACT_EXTENSIONS
+            .iter()
+            .find_map(|ext| rel.strip_suffix(ext))?;
+        self.sessions.get(key)
+    }
+}
+
+/// True for the `.cassio` state directory, so archive walks never descend into
+/// the index, manifest, or gc quarantine.
+pub fn is_state_dir(entry: &walkdir::DirEntry) -> bool {
+    entry.file_type().is_dir() && entry.file_name() == STATE_DIR
+}
+
+#[cfg(test)]
+#[path = "manifest_test.rs"]
+mod tests;
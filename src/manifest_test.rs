@@ -0,0 +1,98 @@
+use super::*;
+use chrono::TimeZone;
+
+fn temp_root(label: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("cassio_manifest_{label}_{}", std::process::id()))
+}
+
+fn entry(source: &str) -> ManifestEntry {
+    ManifestEntry {
+        tool: "claude".to_string(),
+        source_path: source.to_string(),
+        session_id: "abc".to_string(),
+        started_at: Utc.with_ymd_and_hms(2025, 11, 12, 21, 52, 16).unwrap(),
+        project_path: "/tmp/project".to_string(),
+        format: "emoji-text".to_string(),
+        training: true,
+        written_at: Utc.with_ymd_and_hms(2025, 11, 13, 8, 0, 0).unwrap(),
+        fingerprint: None,
+    }
+}
+
+#[test]
+fn missing_manifest_loads_empty() {
+    let root = temp_root("missing");
+    let manifest = Manifest::load(&root).unwrap();
+    assert_eq!(manifest.schema_version, MANIFEST_SCHEMA_VERSION);
+    assert!(manifest.sessions.is_empty());
+}
+
+#[test]
+fn manifest_round_trips_through_state_dir() {
+    let root = temp_root("roundtrip");
+    let _ = fs::remove_dir_all(&root);
+    let mut manifest = Manifest::default();
+    let key = entry_key("2025-11", "2025-11-12T21-52-16-claude");
+    manifest.record(key.clone(), entry("/logs/a.jsonl"));
+    manifest.save(&root).unwrap();
+
+    assert!(root.join(".cassio").join("manifest.json").is_file());
+    let loaded = Manifest::load(&root).unwrap();
+    assert_eq!(loaded.get(&key), Some(&entry("/logs/a.jsonl")));
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn malformed_manifest_is_an_error() {
+    let root = temp_root("malformed");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join(STATE_DIR)).unwrap();
+    fs::write(manifest_path(&root), "{not json").unwrap();
+    assert!(matches!(
+        Manifest::load(&root),
+        Err(CassioError::Json { .. })
+    ));
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn catalog_resolves_templated_artifacts_from_subfolders_and_extra_roots() {
+    let root = temp_root("catalog");
+    let training = temp_root("catalog_training");
+    let _ = fs::remove_dir_all(&root);
+    let mut manifest = Manifest::default();
+    manifest.record(
+        entry_key("cassio/2025/11", "12T21-52-claude"),
+        entry("/logs/a.jsonl"),
+    );
+    manifest.record(entry_key("", "flat-claude"), entry("/logs/b.jsonl"));
+    manifest.save(&root).unwrap();
+    let subfolder = root.join("cassio/2025");
+    fs::create_dir_all(&subfolder).unwrap();
+
+    let catalog = SessionCatalog::load(&subfolder).with_root(&training);
+    let found = catalog
+        .lookup(&root.join("cassio/2025/11/12T21-52-claude.md"))
+        .unwrap();
+    assert_eq!(found.date, "2025-11-12");
+    assert_eq!(found.month(), "2025-11");
+    assert_eq!(found.tool, "claude");
+    assert!(
+        catalog
+            .lookup(&training.join("cassio/2025/11/12T21-52-claude.training.json"))
+            .is_some()
+    );
+    assert!(catalog.lookup_relative("flat-claude.md").is_some());
+    assert!(catalog.lookup(&root.join("cassio/other.md")).is_none());
+    assert_eq!(
+        catalog.lookup_transcript(&root.join("cassio/2025/11/12T21-52-claude.md")),
+        Some(("2025-11-12".to_string(), "claude".to_string()))
+    );
+    assert!(
+        catalog
+            .lookup_transcript(&root.join("cassio/2025/11/12T21-52-claude.jsonl"))
+            .is_none()
+    );
+    assert!(SessionCatalog::load(&training).is_empty());
+    let _ = fs::remove_dir_all(&root);
+}
@@ -0,0 +1,269 @@
+//! Model Context Protocol server over stdio (`cassio mcp`).
+//!
+//! An MCP client (a desktop assistant, an editor, another agent) launches
+//! `cassio mcp` and speaks JSON-RPC 2.0 over its stdin and stdout, one message
+//! per line. The server exposes the archive as read-only tools:
+//!
+//! - `search_transcripts`: `cassio search`, lexical, semantic, or hybrid
+//! - `get_session`: one transcript, by path or session id
+//! - `list_sessions`: manifest sessions, newest first
+//! - `get_daily` / `get_monthly`: compaction summaries
+//! - `summary_stats`: the `cassio summary` month × tool table as data
+//!
+//! The tools are `archive::Archive` queries, so every transcript, summary, and
+//! search hit is redacted before it is returned; a secret pasted into a session
+//! is not handed to the client's model.
+//!
+//! # TRADE-OFFS
+//!
+//! - Requests are answered one at a time, in order. A semantic search blocks
+//!   the loop while the query is embedded; clients wait on each call anyway.
+//! - Tool failures (an unknown session, a day that was never compacted) are
+//!   tool results with `isError`, so the model sees the message and can try
+//!   something else. Only malformed requests and unknown tools or methods are
+//!   JSON-RPC errors.
+//! - `get_session` returns the whole transcript. Long sessions cost the
+//!   client's context; `search_transcripts` is the way in for narrow questions.
+
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use serde_json::{Value, json};
+
+use crate::archive::{Archive, required_arg};
+use crate::error::CassioError;
+use crate::search::SemanticSearchOptions;
+
+/// Newest protocol revision spoken; a client asking for an older supported
+/// revision gets that one back.
+const PROTOCOL_VERSION: &str = "2025-06-18";
+const SUPPORTED_VERSIONS: &[&str] = &["2024-11-05", "2025-03-26", PROTOCOL_VERSION];
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// JSON-RPC error code and message.
+type RpcError = (i64, String);
+
+/// Serve MCP requests from stdin until it closes.
+pub fn run_mcp(root: &Path, semantic: SemanticSearchOptions) -> Result<(), CassioError> {
+    if !root.is_dir() {
+        return Err(CassioError::Other(format!(
+            "Output directory not found: {}",
+            root.display()
+        )));
+    }
+    let server = McpServer::new(root.to_path_buf(), semantic);
+    // stdout carries protocol messages only.
+    eprintln!("cassio mcp: serving {} on stdio", root.display());
+    let mut stdout = std::io::stdout().lock();
+    for line in std::io::stdin().lock().lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(response) = server.handle_line(&line) {
+            writeln!(stdout, "{response}")?;
+            stdout.flush()?;
+        }
+    }
+    Ok(())
+}
+
+/// The archive behind an MCP session.
+pub struct McpServer {
+    archive: Archive,
+}
+
+impl McpServer {
+    pub fn new(root: PathBuf, semantic: SemanticSearchOptions) -> Self {
+        Self {
+            archive: Archive::new(root, semantic),
+        }
+    }
+
+    /// Response line for one JSON-RPC message; `None` for notifications and
+    /// stray responses, which get no reply.
+    pub fn handle_line(&self, line: &str) -> Option<String> {
+        let response = match serde_json::from_str::<Value>(line) {
+            Ok(message) => self.handle_message(&message)?,
+            Err(e) => error_response(Value::Null, PARSE_ERROR, &format!("Parse error: {e}")),
+        };
+        Some(response.to_string())
+    }
+
+    fn handle_message(&self, message: &Value) -> Option<Value> {
+        let id = message.get("id").cloned();
+        let Some(method) = message.get("method").and_then(Value::as_str) else {
+            // A response to a request this server never sends.
+            if message.get("result").is_some() || message.get("error").is_some() {
+                return None;
+            }
+            return Some(error_response(
+                id.unwrap_or(Value::Null),
+                INVALID_REQUEST,
+                "Expected a JSON-RPC request object",
+            ));
+        };
+        let id = id?;
+        let params = message.get("params").cloned().unwrap_or_else(|| json!({}));
+        Some(match self.dispatch(method, &params) {
+            Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+            Err((code, text)) => error_response(id, code, &text),
+        })
+    }
+
+    fn dispatch(&self, method: &str, params: &Value) -> Result<Value, RpcError> {
+        match method {
+            "initialize" => Ok(initialize_result(params)),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({"tools": tool_definitions()})),
+            "tools/call" => self.call_tool(params),
+            _ => Err((METHOD_NOT_FOUND, format!("Method not found: {method}"))),
+        }
+    }
+
+    fn call_tool(&self, params: &Value) -> Result<Value, RpcError> {
+        let Some(name) = params.get("name").and_then(Value::as_str) else {
+            return Err((INVALID_PARAMS, "tools/call needs a tool name".to_string()));
+        };
+        let empty = json!({});
+        let args = params.get("arguments").unwrap_or(&empty);
+        let archive = &self.archive;
+        let pretty = |value: Value| format!("{value:#}");
+        let outcome = match name {
+            "search_transcripts" => archive.search(args).map(pretty),
+            "get_session" => required_arg(args, "session")
+                .and_then(|target| archive.session(target))
+                .map(|(_, text)| text),
+            "list_sessions" => archive.list_sessions(args).map(pretty),
+            "get_daily" => required_arg(args, "date").and_then(|date| archive.daily(date)),
+            "get_monthly" => required_arg(args, "month").and_then(|month| archive.monthly(month)),
+            "summary_stats" => archive.summary_stats(args).map(pretty),
+            _ => return Err((INVALID_PARAMS, format!("Unknown tool: {name}"))),
+        };
+        let (text, is_error) = match outcome {
+            Ok(text) => (text, false),
+            Err(e) => (e.to_string(), true),
+        };
+        Ok(json!({
+            "content": [{"type": "text", "text": text}],
+            "isError": is_error,
+        }))
+    }
+}
+
+fn initialize_result(params: &Value) -> Value {
+    let version = params
+        .get("protocolVersion")
+        .and_then(Value::as_str)
+        .filter(|requested| SUPPORTED_VERSIONS.contains(requested))
+        .unwrap_or(PROTOCOL_VERSION);
+    json!({
+        "protocolVersion": version,
+        "capabilities": {"tools": {"listChanged": false}},
+        "serverInfo": {"name": "cassio", "version": env!("CARGO_PKG_VERSION")},
+        "instructions": "Read-only access to an archive of AI coding session transcripts. \
+            Search first, then fetch whole sessions or daily/monthly summaries. \
+            Paths are relative to the archive root.",
+    })
+}
+
+fn tool_definitions() -> Value {
+    let date_range = json!({
+        "from": {"type": "string", "description": "Earliest date (YYYY-MM or YYYY-MM-DD), inclusive"},
+        "to": {"type": "string", "description": "Latest date (YYYY-MM or YYYY-MM-DD), inclusive"},
+    });
+    let with_dates = |mut properties: Value| {
+        if let (Some(target), Some(dates)) = (properties.as_object_mut(), date_range.as_object()) {
+            target.extend(dates.clone());
+        }
+        properties
+    };
+    json!([
+        {
+            "name": "search_transcripts",
+            "description": "Search session transcripts and daily/monthly summaries. Returns matching lines with their transcript paths.",
+            "inputSchema": {
+                "type": "object",
+                "properties": with_dates(json!({
+                    "query": {"type": "string", "description": "Search terms, or a regex when `regex` is true"},
+                    "mode": {"type": "string", "enum": ["lexical", "semantic", "hybrid"], "description": "Retriever (default lexical); semantic and hybrid need an embedding index"},
+                    "limit": {"type": "integer", "minimum": 1, "description": "Maximum hits (default 20)"},
+                    "tool": {"type": "string", "description": "Only sessions from this tool (claude, codex, ...)"},
+                    "project": {"type": "string", "description": "Only sessions whose project path contains this"},
+                    "speaker": {"type": "string", "enum": ["user", "assistant", "tool"], "description": "Only lines from this speaker"},
+                    "regex": {"type": "boolean", "description": "Treat the query as a regular expression"},
+                })),
+                "required": ["query"],
+            },
+        },
+        {
+            "name": "get_session",
+            "description": "Full transcript of one session, by archive-relative path or session id (prefixes work).",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "session": {"type": "string", "description": "Transcript path or session id"},
+                },
+                "required": ["session"],
+            },
+        },
+        {
+            "name": "list_sessions",
+            "description": "Archived sessions, newest first, with path, session id, tool, project, and start time.",
+            "inputSchema": {
+                "type": "object",
+                "properties": with_dates(json!({
+                    "tool": {"type": "string", "description": "Only sessions from this tool"},
+                    "project": {"type": "string", "description": "Only sessions whose project path contains this"},
+                    "limit": {"type": "integer", "minimum": 1, "description": "Maximum sessions (default 50)"},
+                })),
+            },
+        },
+        {
+            "name": "get_daily",
+            "description": "Daily summary written by `cassio compact dailies`.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "date": {"type": "string", "description": "Day, YYYY-MM-DD"},
+                },
+                "required": ["date"],
+            },
+        },
+        {
+            "name": "get_monthly",
+            "description": "Monthly summary written by `cassio compact monthly`.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "month": {"type": "string", "description": "Month, YYYY-MM"},
+                },
+                "required": ["month"],
+            },
+        },
+        {
+            "name": "summary_stats",
+            "description": "Sessions, messages, tokens, duration, and estimated cost per month and tool, with totals.",
+            "inputSchema": {
+                "type": "object",
+                "properties": with_dates(json!({})),
+            },
+        },
+    ])
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": {"code": code, "message": message},
+    })
+}
+
+#[cfg(test)]
+#[path = "mcp_test.rs"]
+mod tests;
@@ -0,0 +1,199 @@
+use super::*;
+use crate::manifest::{Manifest, ManifestEntry};
+use chrono::{TimeZone, Utc};
+use std::fs;
+
+fn temp_root(label: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("cassio_mcp_{label}_{}", std::process::id()))
+}
+
+fn server(root: &Path) -> McpServer {
+    McpServer::new(
+        root.to_path_buf(),
+        SemanticSearchOptions {
+            provider: "ollama".to_string(),
+            model: "cassio-embedding".to_string(),
+            base_url: "http://127.0.0.1:1".to_string(),
+            timeout_secs: 1,
+            exact: false,
+        },
+    )
+}
+
+/// `tools/call` of `name` with `arguments`: (tool output text, isError).
+fn call(server: &McpServer, name: &str, arguments: Value) -> (String, bool) {
+    let request = json!({
+        "jsonrpc": "2.0",
+        "id": 7,
+        "method": "tools/call",
+        "params": {"name": name, "arguments": arguments},
+    });
+    let response: Value =
+        serde_json::from_str(&server.handle_line(&request.to_string()).unwrap()).unwrap();
+    let result = &response["result"];
+    (
+        result["content"][0]["text"].as_str().unwrap().to_string(),
+        result["isError"].as_bool().unwrap(),
+    )
+}
+
+fn entry(tool: &str, session_id: &str, day: u32, project: &str) -> ManifestEntry {
+    let started_at = Utc.with_ymd_and_hms(2026, 4, day, 10, 0, 0).unwrap();
+    ManifestEntry {
+        tool: tool.to_string(),
+        source_path: format!("/logs/{session_id}.jsonl"),
+        session_id: session_id.to_string(),
+        started_at,
+        project_path: project.to_string(),
+        format: "emoji-text".to_string(),
+        training: false,
+        written_at: started_at,
+        fingerprint: None,
+    }
+}
+
+#[test]
+fn protocol_messages_follow_json_rpc() {
+    let server = server(&temp_root("protocol"));
+
+    let init: Value = serde_json::from_str(
+        &server
+            .handle_line(
+                r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2024-11-05"}}"#,
+            )
+            .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(init["id"], 1);
+    assert_eq!(init["result"]["protocolVersion"], "2024-11-05");
+    assert_eq!(init["result"]["serverInfo"]["name"], "cassio");
+
+    assert!(
+        server
+            .handle_line(r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#)
+            .is_none()
+    );
+    let code = |line: &str| -> i64 {
+        let response: Value = serde_json::from_str(&server.handle_line(line).unwrap()).unwrap();
+        response["error"]["code"].as_i64().unwrap()
+    };
+    assert_eq!(code("{not json"), PARSE_ERROR);
+    assert_eq!(code(r#"{"jsonrpc":"2.0","id":2}"#), INVALID_REQUEST);
+    assert_eq!(
+        code(r#"{"jsonrpc":"2.0","id":3,"method":"resources/list"}"#),
+        METHOD_NOT_FOUND
+    );
+    assert_eq!(
+        code(r#"{"jsonrpc":"2.0","id":4,"method":"tools/call","params":{"name":"rm_rf"}}"#),
+        INVALID_PARAMS
+    );
+
+    let tools = tool_definitions();
+    let names: Vec<&str> = tools
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|tool| tool["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            "search_transcripts",
+            "get_session",
+            "list_sessions",
+            "get_daily",
+            "get_monthly",
+            "summary_stats"
+        ]
+    );
+}
+
+#[test]
+fn sessions_are_listed_newest_first_and_fetched_redacted() {
+    let root = temp_root("sessions");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("2026-04")).unwrap();
+    fs::write(
+        root.join("2026-04/2026-04-02T10-00-00-codex.md"),
+        "👤 set OPENAI_API_KEY=sk-abcdefghijklmnopqrstuvwxyz123456\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("2026-04/2026-04-20T10-00-00-claude.md"),
+        "👤 hello\n",
+    )
+    .unwrap();
+    let mut manifest = Manifest::default();
+    manifest.record(
+        "2026-04/2026-04-02T10-00-00-codex".to_string(),
+        entry("codex", "aaaa-1111", 2, "/home/u/acme"),
+    );
+    manifest.record(
+        "2026-04/2026-04-20T10-00-00-claude".to_string(),
+        entry("claude", "bbbb-2222", 20, "/home/u/cassio"),
+    );
+    // Recorded, but the transcript was removed.
+    manifest.record(
+        "2026-04/2026-04-25T10-00-00-claude".to_string(),
+        entry("claude", "cccc-3333", 25, "/home/u/cassio"),
+    );
+    manifest.save(&root).unwrap();
+    let server = server(&root);
+
+    let (text, is_error) = call(&server, "list_sessions", json!({}));
+    assert!(!is_error);
+    let listed: Value = serde_json::from_str(&text).unwrap();
+    assert_eq!(listed["total"], 2);
+    assert_eq!(
+        listed["sessions"][0]["path"],
+        "2026-04/2026-04-20T10-00-00-claude.md"
+    );
+    let (text, _) = call(
+        &server,
+        "list_sessions",
+        json!({"project": "ACME", "to": "2026-04-10"}),
+    );
+    let listed: Value = serde_json::from_str(&text).unwrap();
+    assert_eq!(listed["sessions"][0]["session_id"], "aaaa-1111");
+    assert_eq!(listed["total"], 1);
+
+    let (text, is_error) = call(&server, "get_session", json!({"session": "aaaa"}));
+    assert!(!is_error);
+    assert!(text.starts_with("👤 set OPENAI_API_KEY="));
+    assert!(!text.contains("sk-abcdefghijklmnopqrstuvwxyz123456"));
+    let (text, is_error) = call(&server, "get_session", json!({"session": "zzzz"}));
+    assert!(is_error);
+    assert!(text.contains("zzzz"));
+
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn summaries_are_fetched_by_date_and_validated() {
+    let root = temp_root("summaries");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("2026-04")).unwrap();
+    fs::write(
+        root.join("2026-04/2026-04-10.compaction.md"),
+        "legacy day\n",
+    )
+    .unwrap();
+    fs::write(root.join("2026-04/2026-04.monthly.md"), "the month\n").unwrap();
+    let server = server(&root);
+
+    assert_eq!(
+        call(&server, "get_daily", json!({"date": "2026-04-10"})),
+        ("legacy day\n".to_string(), false)
+    );
+    assert_eq!(
+        call(&server, "get_monthly", json!({"month": "2026-04"})),
+        ("the month\n".to_string(), false)
+    );
+    assert!(call(&server, "get_daily", json!({"date": "2026-04-11"})).1);
+    let (text, is_error) = call(&server, "get_daily", json!({"date": "../../etc"}));
+    assert!(is_error);
+    assert!(text.contains("expected YYYY-MM-DD"));
+    assert!(call(&server, "get_monthly", json!({})).1);
+
+    let _ = fs::remove_dir_all(&root);
+}
@@ -11,6 +11,8 @@ use serde::Serialize;
 use walkdir::WalkDir;
 
 use crate::error::CassioError;
+use crate::frontmatter;
+use crate::manifest::SessionCatalog;
 use crate::pricing;
 
 #[derive(Debug, Clone, Serialize)]
@@ -237,7 +239,12 @@ fn bump_bucket(b: &mut AgentBucket, tin: u64, tout: u64, cost: f64) {
 
 fn scan_sessions(input_dir: &Path) -> Result<Vec<SessionRow>, CassioError> {
     let mut rows = Vec::new();
-    for entry in WalkDir::new(input_dir).into_iter().filter_map(|e| e.ok()) {
+    let catalog = SessionCatalog::load(input_dir);
+    for entry in WalkDir::new(input_dir)
+        .into_iter()
+        .filter_entry(|e| !crate::manifest::is_state_dir(e))
+        .filter_map(|e| e.ok())
+    {
         let path = entry.path();
         if !path.is_file() {
             continue;
@@ -245,7 +252,9 @@ fn scan_sessions(input_dir: &Path) -> Result<Vec<SessionRow>, CassioError> {
         let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
             continue;
         };
-        let Some((date, tool)) = parse_session_filename(name) else {
+        let Some((date, tool)) =
+            parse_session_filename(name).or_else(|| catalog.lookup_transcript(path))
+        else {
             continue;
         };
         match parse_session(path, &date, &tool, name) {
@@ -280,6 +289,33 @@ fn parse_session(
     file_name: &str,
 ) -> Result<SessionRow, CassioError> {
     let content = std::fs::read_to_string(path)?;
+    if let Some(fm) = frontmatter::parse(&content) {
+        let cost_usd = fm.cost_usd.or_else(|| {
+            pricing::estimate_cost(
+                fm.model.as_deref(),
+                fm.input_tokens,
+                fm.output_tokens,
+                0,
+                0,
+                None,
+            )
+        });
+        return Ok(SessionRow {
+            date: date.to_string(),
+            tool: tool.to_string(),
+            project: fm.project_path,
+            model: fm.model,
+            user_msgs: fm.user_messages,
+            asst_msgs: fm.assistant_messages,
+            tool_ok: fm.tool_calls.saturating_sub(fm.tool_errors),
+            tool_fail: fm.tool_errors,
+            input_tokens: fm.input_tokens,
+            output_tokens: fm.output_tokens,
+            cost_usd: cost_usd.unwrap_or(0.0),
+            file_name: file_name.to_string(),
+        });
+    }
+
     let mut project = String::new();
     let mut model = None;
     let mut user_msgs = 0u32;
@@ -369,12 +405,11 @@ fn parse_session(
         }
     }
 
-    if cost_usd == 0.0 {
-        if let Some(price) =
+    if cost_usd == 0.0
+        && let Some(price) =
             pricing::estimate_cost(model.as_deref(), input_tokens, output_tokens, 0, 0, None)
-        {
-            cost_usd = price;
-        }
+    {
+        cost_usd = price;
     }
 
     Ok(SessionRow {
@@ -0,0 +1,505 @@
+//! Obsidian vault export (`cassio export obsidian <VAULT>`).
+//!
+//! Writes the archive into a vault as linked notes, all under one folder of the
+//! vault (`cassio/` by default):
+//!
+//! - `sessions/YYYY-MM/<stem>.md`: one note per transcript. Its properties carry
+//!   the date, tool, model, session kind, project, and cost, and it is tagged
+//!   `tool/<tool>`, `model/<model>`, and `kind/<session kind>`. The note links
+//!   to its project note and to the daily note for its date.
+//! - `projects/<slug>.md`: one note per project, listing its sessions.
+//! - `daily/`, `weekly/`, `monthly/`: the compaction summaries, each listing the
+//!   sessions it covers.
+//!
+//! Every date with sessions or a daily summary also gets a block in the vault's
+//! daily note for that date (`<daily-folder>/YYYY-MM-DD.md`), linking to the
+//! day's summary and sessions. The block sits between `<!-- cassio:start -->`
+//! and `<!-- cassio:end -->`. A missing daily note is created, and the rest of
+//! an existing one is left alone.
+//!
+//! Each note's path comes from the archive, so a re-run rewrites notes in place
+//! and replaces the daily-note block instead of adding copies. Notes whose
+//! content has not changed are not written again, which keeps sync clients and
+//! Obsidian's file watcher quiet. Transcript and summary text passes through
+//! `redact::redact_text`, as in `cassio site`.
+//!
+//! # TRADE-OFFS
+//!
+//! - The export owns its folder, and edits to notes there are lost on the next
+//!   run. Personal notes belong in the daily notes (outside the block) or
+//!   elsewhere in the vault.
+//! - Nothing is deleted. Sessions removed from the archive keep their notes.
+//! - Daily notes are matched by Obsidian's default `YYYY-MM-DD` name. Vaults
+//!   using another date format get new notes rather than their existing ones.
+//! - Transcripts are copied as emoji-text rather than re-rendered as markdown.
+//!   Obsidian shows each line on its own unless "strict line breaks" is on.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::error::CassioError;
+use crate::formatter::emoji_text::shorten_model_name;
+use crate::frontmatter;
+use crate::redact::redact_text;
+use crate::session_search::header_value;
+use crate::site::{SiteSummary, SummaryKind, find_summaries, session_title, slug};
+use crate::summary::{SessionRow, session_rows, shorten_project};
+
+/// Folder inside the vault that holds the exported notes.
+pub const DEFAULT_FOLDER: &str = "cassio";
+
+const BLOCK_START: &str = "<!-- cassio:start -->";
+const BLOCK_END: &str = "<!-- cassio:end -->";
+
+/// Counts reported once the export is written.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ObsidianReport {
+    pub sessions: usize,
+    pub projects: usize,
+    pub summaries: usize,
+    pub daily_notes: usize,
+    /// Notes created or changed by this run.
+    pub written: usize,
+    /// Notes already up to date.
+    pub unchanged: usize,
+}
+
+/// Export the archive at `root` into `vault` and print what was written.
+pub fn run_export(
+    root: &Path,
+    vault: &Path,
+    folder: &str,
+    daily_folder: &str,
+) -> Result<(), CassioError> {
+    let report = export_vault(root, vault, folder, daily_folder)?;
+    eprintln!(
+        "Exported {} sessions, {} projects, {} summaries and {} daily notes to {} \
+         ({} written, {} unchanged)",
+        report.sessions,
+        report.projects,
+        report.summaries,
+        report.daily_notes,
+        vault.display(),
+        report.written,
+        report.unchanged
+    );
+    Ok(())
+}
+
+/// Write or update the notes for the archive at `root` inside `vault`.
+/// `folder` and `daily_folder` are relative to the vault; empty means its root.
+pub fn export_vault(
+    root: &Path,
+    vault: &Path,
+    folder: &str,
+    daily_folder: &str,
+) -> Result<ObsidianReport, CassioError> {
+    if !root.is_dir() {
+        return Err(CassioError::Other(format!(
+            "archive directory not found: {}",
+            root.display()
+        )));
+    }
+    if !vault.is_dir() {
+        return Err(CassioError::Other(format!(
+            "vault directory not found: {}",
+            vault.display()
+        )));
+    }
+    let vault_notes = fs::canonicalize(vault)?.join(folder);
+    if vault_notes.starts_with(fs::canonicalize(root)?) {
+        return Err(CassioError::Other(format!(
+            "refusing to export into the archive itself: {}",
+            vault_notes.display()
+        )));
+    }
+
+    let mut export = Export {
+        vault: vault.to_path_buf(),
+        folder: folder.trim_matches('/').to_string(),
+        daily_folder: daily_folder.trim_matches('/').to_string(),
+        report: ObsidianReport::default(),
+    };
+    let summaries = find_summaries(root);
+    let dailies: BTreeSet<&str> = summaries
+        .iter()
+        .filter(|s| s.kind == SummaryKind::Daily)
+        .map(|s| s.period.as_str())
+        .collect();
+
+    let mut sessions = Vec::new();
+    for row in session_rows(root)? {
+        let session = export.write_session(root, row, &dailies)?;
+        sessions.push(session);
+    }
+    // Lists read oldest first, the way a day unfolds.
+    sessions.reverse();
+
+    for summary in &summaries {
+        export.write_summary(summary, &sessions)?;
+    }
+    export.write_projects(&sessions)?;
+    export.write_daily_notes(&sessions, &dailies)?;
+
+    export.report.sessions = sessions.len();
+    export.report.summaries = summaries.len();
+    Ok(export.report)
+}
+
+struct Export {
+    vault: PathBuf,
+    folder: String,
+    daily_folder: String,
+    report: ObsidianReport,
+}
+
+/// One exported transcript.
+struct VaultSession {
+    row: SessionRow,
+    /// Vault-relative link target, e.g. `cassio/sessions/2026-04/<stem>`.
+    target: String,
+    title: String,
+}
+
+impl VaultSession {
+    /// `09:00 · codex · fix the launchd plist`
+    fn label(&self) -> String {
+        let stem = self
+            .target
+            .rsplit('/')
+            .next()
+            .unwrap_or(self.target.as_str());
+        match stem_time(stem) {
+            Some(time) => format!("{time} · {} · {}", self.row.tool, self.title),
+            None => format!("{} · {}", self.row.tool, self.title),
+        }
+    }
+}
+
+/// Properties frontmatter. Obsidian shows these in the note's properties panel
+/// and Dataview can query them.
+#[derive(Debug, Default, Serialize)]
+struct Properties {
+    #[serde(rename = "type")]
+    note_type: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    period: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    model: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    session_kind: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    activity: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    session_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    project: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sessions: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cost_usd: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source: Option<String>,
+    tags: Vec<String>,
+}
+
+impl Properties {
+    fn to_block(&self) -> Result<String, CassioError> {
+        let yaml = serde_yaml::to_string(self)
+            .map_err(|e| CassioError::Other(format!("Failed to serialize properties: {e}")))?;
+        Ok(format!("---\n{yaml}---\n"))
+    }
+}
+
+impl Export {
+    /// Vault-relative link target for a note inside the export folder.
+    fn target(&self, rel: &str) -> String {
+        if self.folder.is_empty() {
+            rel.to_string()
+        } else {
+            format!("{}/{rel}", self.folder)
+        }
+    }
+
+    fn daily_target(&self, date: &str) -> String {
+        if self.daily_folder.is_empty() {
+            date.to_string()
+        } else {
+            format!("{}/{date}", self.daily_folder)
+        }
+    }
+
+    fn project_target(&self, project: &str) -> String {
+        self.target(&format!("projects/{}", slug(project)))
+    }
+
+    fn summary_target(&self, kind: SummaryKind, period: &str) -> String {
+        self.target(&format!("{}/{period}", kind.dir()))
+    }
+
+    /// Write the note at vault-relative `target` unless it already holds
+    /// `content`.
+    fn write_note(&mut self, target: &str, content: &str) -> Result<(), CassioError> {
+        let path = self.vault.join(format!("{target}.md"));
+        if fs::read_to_string(&path).is_ok_and(|old| old == content) {
+            self.report.unchanged += 1;
+            return Ok(());
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, content)?;
+        self.report.written += 1;
+        Ok(())
+    }
+
+    fn write_session(
+        &mut self,
+        root: &Path,
+        row: SessionRow,
+        dailies: &BTreeSet<&str>,
+    ) -> Result<VaultSession, CassioError> {
+        let rel = row
+            .path
+            .strip_prefix(root)
+            .unwrap_or(&row.path)
+            .with_extension("");
+        let key = rel.to_string_lossy().replace('\\', "/");
+        let target = self.target(&format!("sessions/{key}"));
+        let text = redact_text(&fs::read_to_string(&row.path)?);
+        let title = session_title(&text).unwrap_or_else(|| key.clone());
+
+        let model = row.model.as_deref().map(shorten_model_name);
+        let session_kind = header_value(&text, "Session Kind:").map(str::to_string);
+        let mut tags = vec!["cassio/session".to_string(), tag("tool", &row.tool)];
+        tags.extend(model.as_deref().map(|model| tag("model", model)));
+        tags.extend(session_kind.as_deref().map(|kind| tag("kind", kind)));
+
+        let project = (!row.project.is_empty()).then(|| {
+            wikilink(
+                &self.project_target(&row.project),
+                &project_name(&row.project),
+            )
+        });
+        let properties = Properties {
+            note_type: "session",
+            date: Some(row.date.clone()),
+            tool: Some(row.tool.clone()),
+            model,
+            session_kind,
+            activity: Some(row.kind),
+            session_id: header_value(&text, "Session:").map(str::to_string),
+            project: project.clone(),
+            cost_usd: Some(round_cost(row.estimated_cost_usd)),
+            source: Some(row.path.display().to_string()),
+            tags,
+            ..Default::default()
+        };
+
+        let mut context = vec![wikilink(&self.daily_target(&row.date), &row.date)];
+        if dailies.contains(row.date.as_str()) {
+            context.push(wikilink(
+                &self.summary_target(SummaryKind::Daily, &row.date),
+                "daily summary",
+            ));
+        }
+        context.extend(project);
+        let note = format!(
+            "{}\n# {}\n\n{}\n\n{}",
+            properties.to_block()?,
+            link_label(&title),
+            context.join(" · "),
+            frontmatter::body(&text)
+        );
+        self.write_note(&target, &note)?;
+        Ok(VaultSession { row, target, title })
+    }
+
+    fn write_summary(
+        &mut self,
+        summary: &SiteSummary,
+        sessions: &[VaultSession],
+    ) -> Result<(), CassioError> {
+        let text = redact_text(&fs::read_to_string(&summary.path)?);
+        let covered: Vec<&VaultSession> = sessions
+            .iter()
+            .filter(|s| summary.covers(&s.row.date))
+            .collect();
+        let properties = Properties {
+            note_type: summary.kind.dir(),
+            period: Some(summary.period.clone()),
+            sessions: Some(covered.len()),
+            source: Some(summary.path.display().to_string()),
+            tags: vec![format!("cassio/{}", summary.kind.dir())],
+            ..Default::default()
+        };
+        let mut note = format!(
+            "{}\n# {} {}\n\n",
+            properties.to_block()?,
+            summary.kind.label(),
+            summary.period
+        );
+        if summary.kind == SummaryKind::Daily {
+            note.push_str(&format!(
+                "{}\n\n",
+                wikilink(&self.daily_target(&summary.period), &summary.period)
+            ));
+        }
+        note.push_str(text.trim_end());
+        note.push_str("\n\n## Sessions\n\n");
+        note.push_str(&session_list(&covered));
+        self.write_note(&self.summary_target(summary.kind, &summary.period), &note)
+    }
+
+    fn write_projects(&mut self, sessions: &[VaultSession]) -> Result<(), CassioError> {
+        let mut by_project: BTreeMap<&str, Vec<&VaultSession>> = BTreeMap::new();
+        for session in sessions.iter().filter(|s| !s.row.project.is_empty()) {
+            by_project
+                .entry(session.row.project.as_str())
+                .or_default()
+                .push(session);
+        }
+        for (project, sessions) in &by_project {
+            let properties = Properties {
+                note_type: "project",
+                path: Some(project.to_string()),
+                sessions: Some(sessions.len()),
+                cost_usd: Some(round_cost(
+                    sessions.iter().map(|s| s.row.estimated_cost_usd).sum(),
+                )),
+                tags: vec!["cassio/project".to_string()],
+                ..Default::default()
+            };
+            let items: String = sessions
+                .iter()
+                .map(|s| {
+                    format!(
+                        "- {} · {}\n",
+                        wikilink(&self.daily_target(&s.row.date), &s.row.date),
+                        wikilink(&s.target, &s.label())
+                    )
+                })
+                .collect();
+            let note = format!(
+                "{}\n# {}\n\n`{project}`\n\n## Sessions\n\n{items}",
+                properties.to_block()?,
+                project_name(project),
+            );
+            self.write_note(&self.project_target(project), &note)?;
+        }
+        self.report.projects = by_project.len();
+        Ok(())
+    }
+
+    fn write_daily_notes(
+        &mut self,
+        sessions: &[VaultSession],
+        dailies: &BTreeSet<&str>,
+    ) -> Result<(), CassioError> {
+        let mut by_date: BTreeMap<&str, Vec<&VaultSession>> =
+            dailies.iter().map(|date| (*date, Vec::new())).collect();
+        for session in sessions {
+            by_date
+                .entry(session.row.date.as_str())
+                .or_default()
+                .push(session);
+        }
+        for (date, sessions) in &by_date {
+            let mut block = format!("{BLOCK_START}\n## Agent sessions\n\n");
+            if dailies.contains(date) {
+                block.push_str(&format!(
+                    "- {}\n",
+                    wikilink(
+                        &self.summary_target(SummaryKind::Daily, date),
+                        "Daily summary"
+                    )
+                ));
+            }
+            block.push_str(&session_list(sessions));
+            block.push_str(BLOCK_END);
+
+            let target = self.daily_target(date);
+            let existing =
+                fs::read_to_string(self.vault.join(format!("{target}.md"))).unwrap_or_default();
+            self.write_note(&target, &upsert_block(&existing, &block))?;
+        }
+        self.report.daily_notes = by_date.len();
+        Ok(())
+    }
+}
+
+fn session_list(sessions: &[&VaultSession]) -> String {
+    if sessions.is_empty() {
+        return "No sessions.\n".to_string();
+    }
+    sessions
+        .iter()
+        .map(|s| format!("- {}\n", wikilink(&s.target, &s.label())))
+        .collect()
+}
+
+/// Replace the cassio block in a daily note, or append one when it has none.
+fn upsert_block(note: &str, block: &str) -> String {
+    if let Some(start) = note.find(BLOCK_START)
+        && let Some(end) = note[start..].find(BLOCK_END)
+    {
+        let end = start + end + BLOCK_END.len();
+        return format!("{}{block}{}", &note[..start], &note[end..]);
+    }
+    let note = note.trim_end();
+    if note.is_empty() {
+        format!("{block}\n")
+    } else {
+        format!("{note}\n\n{block}\n")
+    }
+}
+
+fn wikilink(target: &str, label: &str) -> String {
+    format!("[[{target}|{}]]", link_label(label))
+}
+
+/// Text safe inside a wikilink alias or a heading: no brackets or pipes.
+fn link_label(text: &str) -> String {
+    text.replace(['[', ']', '|'], "")
+}
+
+/// A nested tag such as `model/opus-4-5`. Tags allow letters, digits, `_`, `-`
+/// and `/`, so anything else becomes `-`.
+fn tag(prefix: &str, value: &str) -> String {
+    format!("{prefix}/{}", slug(value))
+}
+
+/// The last component of a project path, for note titles and link aliases.
+fn project_name(project: &str) -> String {
+    let short = shorten_project(project);
+    short
+        .rsplit('/')
+        .find(|part| !part.is_empty())
+        .unwrap_or(short.as_str())
+        .to_string()
+}
+
+/// `HH:MM` from a default transcript stem (`2026-04-01T09-00-00-codex`).
+fn stem_time(stem: &str) -> Option<String> {
+    let time = stem.get(10..16)?.strip_prefix('T')?;
+    let (hour, minute) = time.split_once('-')?;
+    let digits = |part: &str| part.len() == 2 && part.bytes().all(|b| b.is_ascii_digit());
+    (digits(hour) && digits(minute)).then(|| format!("{hour}:{minute}"))
+}
+
+fn round_cost(cost: f64) -> f64 {
+    (cost * 10_000.0).round() / 10_000.0
+}
+
+#[cfg(test)]
+#[path = "obsidian_test.rs"]
+mod tests;
@@ -0,0 +1,178 @@
+use super::*;
+
+fn temp_root(label: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("cassio_obsidian_{label}_{}", std::process::id()))
+}
+
+fn sample_archive(root: &Path) {
+    let _ = fs::remove_dir_all(root);
+    fs::create_dir_all(root.join("2026-04")).unwrap();
+    fs::write(
+        root.join("2026-04/2026-04-01T09-00-00-codex.md"),
+        "📋 Session: s-1\n📋 Project: /work/faber\n📋 Session Kind: human\n\
+         📋 Model: gpt-5.1\n👤 fix the [launchd] plist\nOPENAI_API_KEY=sk-secret\n\
+         🤖 rewrote the plist loader\n📋 Messages: 5 user, 6 assistant\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("2026-04/2026-04-03T10-00-00-claude.md"),
+        "📋 Project: /work/faber\n👤 hello\n📋 Messages: 1 user, 9 assistant\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("2026-04/2026-04-01.daily.md"),
+        "# Daily 2026-04-01\nmigrated sync\n",
+    )
+    .unwrap();
+    fs::write(root.join("2026-04/2026-04.monthly.md"), "# April\n").unwrap();
+}
+
+fn read(vault: &Path, rel: &str) -> String {
+    fs::read_to_string(vault.join(rel)).unwrap()
+}
+
+#[test]
+fn test_export_writes_linked_and_tagged_notes() {
+    let root = temp_root("links_archive");
+    let vault = temp_root("links_vault");
+    sample_archive(&root);
+    let _ = fs::remove_dir_all(&vault);
+    fs::create_dir_all(&vault).unwrap();
+
+    let report = export_vault(&root, &vault, DEFAULT_FOLDER, "").unwrap();
+    assert_eq!(
+        (report.sessions, report.projects, report.summaries),
+        (2, 1, 2)
+    );
+    assert_eq!(report.daily_notes, 2);
+
+    let session = read(
+        &vault,
+        "cassio/sessions/2026-04/2026-04-01T09-00-00-codex.md",
+    );
+    let yaml = session
+        .strip_prefix("---\n")
+        .and_then(|rest| rest.split_once("---\n"))
+        .map(|(yaml, _)| yaml)
+        .unwrap();
+    let props: serde_yaml::Value = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(props["type"].as_str(), Some("session"));
+    assert_eq!(props["date"].as_str(), Some("2026-04-01"));
+    assert_eq!(props["session_id"].as_str(), Some("s-1"));
+    assert_eq!(
+        props["project"].as_str(),
+        Some("[[cassio/projects/work-faber|faber]]")
+    );
+    let tags: Vec<&str> = props["tags"]
+        .as_sequence()
+        .unwrap()
+        .iter()
+        .filter_map(|t| t.as_str())
+        .collect();
+    assert_eq!(
+        tags,
+        [
+            "cassio/session",
+            "tool/codex",
+            "model/gpt-5-1",
+            "kind/human"
+        ]
+    );
+    assert!(session.contains("\n# fix the launchd plist\n"));
+    assert!(session.contains(
+        "[[2026-04-01|2026-04-01]] · [[cassio/daily/2026-04-01|daily summary]] · \
+         [[cassio/projects/work-faber|faber]]"
+    ));
+    assert!(!session.contains("sk-secret"));
+
+    let project = read(&vault, "cassio/projects/work-faber.md");
+    assert!(project.contains(
+        "- [[2026-04-01|2026-04-01]] · \
+         [[cassio/sessions/2026-04/2026-04-01T09-00-00-codex|09:00 · codex · fix the launchd plist]]\n"
+    ));
+    assert!(project.contains("09:00 · codex") && project.contains("10:00 · claude · hello"));
+
+    let daily = read(&vault, "cassio/daily/2026-04-01.md");
+    assert!(daily.contains("migrated sync"));
+    assert!(daily.contains("[[cassio/sessions/2026-04/2026-04-01T09-00-00-codex|"));
+    let monthly = read(&vault, "cassio/monthly/2026-04.md");
+    assert_eq!(monthly.matches("[[cassio/sessions/").count(), 2);
+
+    let note = read(&vault, "2026-04-01.md");
+    assert!(note.starts_with("<!-- cassio:start -->\n## Agent sessions\n\n"));
+    assert!(note.contains("- [[cassio/daily/2026-04-01|Daily summary]]\n"));
+    assert!(read(&vault, "2026-04-03.md").contains("10:00 · claude · hello"));
+}
+
+#[test]
+fn test_rerun_updates_notes_without_duplicating() {
+    let root = temp_root("rerun_archive");
+    let vault = temp_root("rerun_vault");
+    sample_archive(&root);
+    let _ = fs::remove_dir_all(&vault);
+    fs::create_dir_all(vault.join("Daily")).unwrap();
+    fs::write(
+        vault.join("Daily/2026-04-01.md"),
+        "# Wednesday\n\nmy own notes\n",
+    )
+    .unwrap();
+
+    let first = export_vault(&root, &vault, "ai", "Daily").unwrap();
+    assert_eq!(first.unchanged, 0);
+    let second = export_vault(&root, &vault, "ai", "Daily").unwrap();
+    assert_eq!(second.written, 0);
+    assert_eq!(second.unchanged, first.written);
+
+    fs::write(
+        root.join("2026-04/2026-04-01T15-30-00-claude.md"),
+        "📋 Project: /work/cassio\n👤 afternoon\n",
+    )
+    .unwrap();
+    export_vault(&root, &vault, "ai", "Daily").unwrap();
+
+    let note = read(&vault, "Daily/2026-04-01.md");
+    assert!(note.starts_with("# Wednesday\n\nmy own notes\n\n<!-- cassio:start -->"));
+    assert_eq!(note.matches("<!-- cassio:start -->").count(), 1);
+    assert_eq!(note.matches("09:00 · codex").count(), 1);
+    assert!(
+        note.contains(
+            "[[ai/sessions/2026-04/2026-04-01T15-30-00-claude|15:30 · claude · afternoon]]"
+        )
+    );
+    assert!(read(&vault, "ai/projects/work-cassio.md").contains("afternoon"));
+    assert!(
+        read(&vault, "ai/sessions/2026-04/2026-04-01T15-30-00-claude.md")
+            .contains("[[Daily/2026-04-01|2026-04-01]]")
+    );
+}
+
+#[test]
+fn test_upsert_block_keeps_surrounding_text() {
+    let block = "<!-- cassio:start -->\nnew\n<!-- cassio:end -->";
+    assert_eq!(upsert_block("", block), format!("{block}\n"));
+    assert_eq!(
+        upsert_block(
+            "top\n\n<!-- cassio:start -->\nold\n<!-- cassio:end -->\nbottom\n",
+            block
+        ),
+        format!("top\n\n{block}\nbottom\n")
+    );
+    // An unterminated block is left alone and a fresh one appended.
+    assert_eq!(
+        upsert_block("top\n<!-- cassio:start -->\n", block),
+        format!("top\n<!-- cassio:start -->\n\n{block}\n")
+    );
+}
+
+#[test]
+fn test_refuses_missing_vault_or_export_inside_archive() {
+    let root = temp_root("guard_archive");
+    sample_archive(&root);
+    assert!(export_vault(&root, &temp_root("guard_missing"), DEFAULT_FOLDER, "").is_err());
+    assert!(export_vault(&root, &root, DEFAULT_FOLDER, "").is_err());
+    assert_eq!(
+        stem_time("2026-04-01T09-05-00-codex").as_deref(),
+        Some("09:05")
+    );
+    assert_eq!(stem_time("session-abc"), None);
+}
@@ -324,6 +324,7 @@ fn parse_lines<I: Iterator<Item = String>>(
         source_record_count: Some(line_count),
         source_format: Some("jsonl".to_string()),
         source_root,
+        duplicates: Vec::new(),
     };
     let mut training = TrainingSession::new(
         "claude.v1",
@@ -665,11 +666,16 @@ fn parse_user_record(
                         }
 
                         let summary = format_tool_input(&name, &input);
+                        let output = super::failed_output(
+                            !is_error,
+                            &super::tool_output_text(block.get("content")),
+                        );
                         blocks.push(ContentBlock::ToolResult {
                             tool_use_id,
                             name,
                             success: !is_error,
                             summary,
+                            output,
                         });
                     }
                 }
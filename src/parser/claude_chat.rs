@@ -516,6 +516,7 @@ fn normalize(
         source_record_count: Some(raw.chat_messages.len() as u64),
         source_format: Some("claude-chat-export".to_string()),
         source_root,
+        duplicates: Vec::new(),
     };
 
     let mut training = TrainingSession::new(
@@ -689,6 +690,10 @@ fn append_content_blocks(
                             name: name.clone(),
                             success: !is_error,
                             summary,
+                            output: crate::parser::failed_output(
+                                !is_error,
+                                &crate::parser::tool_output_text(block.get("content")),
+                            ),
                         });
 
                         *sequence += 1;
@@ -155,12 +155,22 @@ fn test_parse_tool_error() {
                 "type": "tool_result",
                 "tool_use_id": "tool1",
                 "is_error": true,
+                "content": [{"type": "text", "text": "ls: cannot access 'x'\n"}],
             })]),
         ),
     ];
     let session = ClaudeParser::parse_from_lines(lines.into_iter()).unwrap();
     assert_eq!(session.stats.tool_calls, 1);
     assert_eq!(session.stats.tool_errors, 1);
+    let output = session
+        .messages
+        .iter()
+        .flat_map(|m| &m.content)
+        .find_map(|b| match b {
+            ContentBlock::ToolResult { output, .. } => output.clone(),
+            _ => None,
+        });
+    assert_eq!(output.as_deref(), Some("ls: cannot access 'x'"));
 }
 
 #[test]
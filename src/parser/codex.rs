@@ -397,6 +397,10 @@ fn parse_lines<I: Iterator<Item = String>>(
                                     name: name.clone(),
                                     success: !is_error,
                                     summary,
+                                    output: super::failed_output(
+                                        !is_error,
+                                        &codex_output_text(output),
+                                    ),
                                 }],
                                 usage: None,
                             });
@@ -620,6 +624,7 @@ fn parse_lines<I: Iterator<Item = String>>(
         source_record_count: Some(line_count),
         source_format: Some("jsonl".to_string()),
         source_root,
+        duplicates: Vec::new(),
     };
     let mut training = TrainingSession::new(
         "codex.v1",
@@ -638,6 +643,15 @@ fn parse_lines<I: Iterator<Item = String>>(
 ///
 /// Mirrors `format_tool_input` in the Claude parser but uses Codex's function
 /// naming conventions (`shell`, `read_file`, `write_file`, `update_plan`).
+/// Text of a `function_call_output`: the `output` field when the payload is a
+/// JSON envelope (`{"output": ..., "exit_code": ...}`), else the payload itself.
+fn codex_output_text(output: &str) -> String {
+    serde_json::from_str::<Value>(output)
+        .ok()
+        .and_then(|v| v.get("output")?.as_str().map(str::to_string))
+        .unwrap_or_else(|| output.to_string())
+}
+
 pub(crate) fn format_codex_function(name: &str, args_json: &str) -> String {
     let args: Value = serde_json::from_str(args_json).unwrap_or(Value::Object(Default::default()));
 
@@ -214,6 +214,7 @@ fn parse_lines<I: Iterator<Item = String>>(
         source_record_count: Some(line_count),
         source_format: Some("jsonl".to_string()),
         source_root,
+        duplicates: Vec::new(),
     };
     let mut training = TrainingSession::new(
         "cursor.v1",
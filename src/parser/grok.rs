@@ -225,6 +225,7 @@ fn parse_lines<I: Iterator<Item = String>>(
                         name: name.clone(),
                         success,
                         summary: format_grok_tool_input(&name, &input),
+                        output: super::failed_output(success, content),
                     }],
                     usage: None,
                 });
@@ -301,6 +302,7 @@ fn parse_lines<I: Iterator<Item = String>>(
         source_record_count: Some(line_count),
         source_format: Some("jsonl".to_string()),
         source_root,
+        duplicates: Vec::new(),
     };
     let mut training = TrainingSession::new(
         "grok.v1",
@@ -460,6 +460,7 @@ fn normalize(raw: RawHermesSession) -> Result<ParsedSession, CassioError> {
                 name: name.clone(),
                 success,
                 summary: truncate(&output, 500).to_string(),
+                output: super::failed_output(success, &output),
             });
             training_events.push(tool_result_event(
                 &mut sequence,
@@ -532,6 +533,7 @@ fn normalize(raw: RawHermesSession) -> Result<ParsedSession, CassioError> {
             source_record_count: raw.source_record_count,
             source_format: Some(raw.source_format),
             source_root: raw.source_root,
+            duplicates: Vec::new(),
         },
         TrainingMetadata {
             project_path_raw: session.metadata.project_path.clone(),
@@ -299,6 +299,7 @@ fn parse_lines<I: Iterator<Item = String>>(
                                     name: name.clone(),
                                     success: !is_error,
                                     summary,
+                                    output: super::failed_output(!is_error, output),
                                 }],
                                 usage: None,
                             });
@@ -437,6 +438,7 @@ fn parse_lines<I: Iterator<Item = String>>(
         source_record_count: Some(line_count),
         source_format: Some("jsonl".to_string()),
         source_root,
+        duplicates: Vec::new(),
     };
     let mut training = TrainingSession::new(
         "kimi.v1",
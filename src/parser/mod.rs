@@ -155,6 +155,30 @@ fn parser_for_jsonl_content(first_line: &str) -> Box<dyn Parser> {
 /// truncate summaries at fixed byte limits to keep the AST and formatted output
 /// readable. Naive byte slicing would corrupt multibyte characters, so this helper
 /// walks backwards from the limit to find a safe boundary.
+/// Bytes of a failed call's output kept on `ContentBlock::ToolResult`.
+const FAILED_OUTPUT_BYTES: usize = 4000;
+
+/// The output a `ToolResult` keeps: a bounded prefix for failed calls and
+/// nothing for successful ones, whose output can be arbitrarily large.
+pub(crate) fn failed_output(success: bool, output: &str) -> Option<String> {
+    let output = output.trim();
+    (!success && !output.is_empty()).then(|| truncate(output, FAILED_OUTPUT_BYTES).to_string())
+}
+
+/// Plain text of a tool result `content` value: a string, or the `text` of
+/// each item in an array of content blocks.
+pub(crate) fn tool_output_text(content: Option<&serde_json::Value>) -> String {
+    match content {
+        Some(serde_json::Value::String(text)) => text.clone(),
+        Some(serde_json::Value::Array(items)) => items
+            .iter()
+            .filter_map(|item| item.get("text").and_then(|t| t.as_str()).or(item.as_str()))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        _ => String::new(),
+    }
+}
+
 pub(crate) fn truncate(s: &str, max: usize) -> &str {
     if s.len() <= max {
         return s;
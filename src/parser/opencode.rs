@@ -579,6 +579,7 @@ fn parse_session(
                                 name: tool_name.to_string(),
                                 success: !is_error,
                                 summary: truncated,
+                                output: None,
                             });
                             sequence += 1;
                             let raw_output = serde_json::to_value(serde_json::json!({
@@ -673,6 +674,7 @@ fn parse_session(
         ),
         source_format: Some("opencode.fragmented-json".to_string()),
         source_root: Some(storage_dir.to_string_lossy().to_string()),
+        duplicates: Vec::new(),
     };
     let metadata = TrainingMetadata {
         project_path_raw: session.metadata.project_path.clone(),
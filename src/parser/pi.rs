@@ -399,6 +399,10 @@ fn parse_lines<I: Iterator<Item = String>>(
                                 name: name.clone(),
                                 success: !is_error,
                                 summary,
+                                output: super::failed_output(
+                                    !is_error,
+                                    &super::tool_output_text(message.get("content")),
+                                ),
                             }],
                             usage: None,
                         });
@@ -462,6 +466,7 @@ fn parse_lines<I: Iterator<Item = String>>(
         source_record_count: Some(line_count),
         source_format: Some("jsonl".to_string()),
         source_root,
+        duplicates: Vec::new(),
     };
     let mut training = TrainingSession::new(
         "pi.v1",
@@ -0,0 +1,314 @@
+//! Inline query syntax for `cassio search`.
+//!
+//! Filters and boolean structure can live in the query string itself, so the
+//! same string works on the command line, in saved searches, and over any API:
+//!
+//! ```text
+//! tool:codex project:faber speaker:user after:2026-06 model:opus "exact phrase" -excluded OR alt
+//! ```
+//!
+//! | Syntax                      | Meaning                                          |
+//! |-----------------------------|--------------------------------------------------|
+//! | `word`                      | Line contains `word` (terms in a group are ANDed) |
+//! | `"exact phrase"`            | Line contains the phrase verbatim                |
+//! | `-word`, `-"phrase"`        | Line must not contain it                         |
+//! | `\-word`                    | Line contains `-word` (escapes the exclusion)    |
+//! | `A OR B`                    | Either group matches                             |
+//! | `tool:` `project:` `model:` | Same as `--tool` / `--project` / model header    |
+//! | `speaker:`                  | Same as `--speaker`                              |
+//! | `from:`/`after:`, `to:`/`before:` | Same as `--from` / `--to` (inclusive)      |
+//! | `month:`                    | Same as `--month`                                |
+//!
+//! # TRADE-OFFS
+//!
+//! - No parentheses: AND binds tighter than `OR`, so a query is an OR of AND
+//!   groups, and an exclusion belongs to the group it appears in. Only
+//!   upper-case `OR` is an operator; `or` is an ordinary term.
+//! - Only the field names above are filters. Other `word:word` tokens (URLs,
+//!   `file.rs:42`, `E0502:`) stay literal terms; quote a filter
+//!   (`"tool:codex"`) to search for it as text.
+//! - Exclusions need something to narrow: a group with no positive term (and no
+//!   filter in the query) reads its `-word`s as text, so `--force` alone finds
+//!   `--force` rather than every line without it. Only `-` before a plain
+//!   word or a quoted phrase negates; flag-like tokens such as `--force` or
+//!   `-la.` stay text. A short flag such as `-rf` next to other terms still
+//!   reads as an exclusion, so search for it as `\-rf`.
+
+use crate::error::CassioError;
+use crate::search::{SearchOptions, Speaker};
+
+/// A parsed query: boolean term groups plus the filters it carried.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParsedQuery {
+    /// Alternatives joined by `OR`; a line matches when any group matches.
+    pub groups: Vec<TermGroup>,
+    pub filters: QueryFilters,
+}
+
+/// Terms that must all appear (`include`) and must not appear (`exclude`).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TermGroup {
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct QueryFilters {
+    pub tool: Option<String>,
+    pub project: Option<String>,
+    pub model: Option<String>,
+    pub speaker: Option<Speaker>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+#[derive(Debug)]
+struct Token {
+    negated: bool,
+    field: Option<String>,
+    value: String,
+    quoted: bool,
+}
+
+const FIELDS: &[&str] = &[
+    "tool", "project", "model", "speaker", "from", "after", "to", "before", "month",
+];
+
+/// Parse `query` into term groups and filters.
+pub fn parse(query: &str) -> Result<ParsedQuery, CassioError> {
+    let mut parsed = ParsedQuery::default();
+    let mut group = TermGroup::default();
+    let mut group_has_terms = false;
+    let mut pending_or = false;
+
+    let tokens = tokenize(query)?;
+    let filtered = tokens.iter().any(|token| token.field.is_some());
+    for token in tokens {
+        if !token.quoted && !token.negated && token.field.is_none() && token.value == "OR" {
+            if !group_has_terms {
+                return Err(invalid(query, "OR needs terms on both sides"));
+            }
+            parsed
+                .groups
+                .push(narrowing(std::mem::take(&mut group), filtered));
+            group_has_terms = false;
+            pending_or = true;
+            continue;
+        }
+        if let Some(field) = token.field {
+            if token.negated {
+                return Err(invalid(query, &format!("cannot negate '{field}:'")));
+            }
+            parsed.filters.set(&field, token.value, query)?;
+            continue;
+        }
+        if token.value.is_empty() {
+            continue;
+        }
+        if token.negated {
+            group.exclude.push(token.value);
+        } else {
+            group.include.push(token.value);
+        }
+        group_has_terms = true;
+        pending_or = false;
+    }
+    if pending_or {
+        return Err(invalid(query, "OR needs terms on both sides"));
+    }
+    if group_has_terms {
+        parsed.groups.push(narrowing(group, filtered));
+    }
+    Ok(parsed)
+}
+
+/// `group` with its exclusions read as text when nothing else in it (and no
+/// filter) narrows the search, so `-x` alone does not match every other line.
+fn narrowing(mut group: TermGroup, filtered: bool) -> TermGroup {
+    if group.include.is_empty() && !filtered {
+        group.include = group
+            .exclude
+            .drain(..)
+            .map(|term| format!("-{term}"))
+            .collect();
+    }
+    group
+}
+
+impl ParsedQuery {
+    /// The positive terms as plain text, for embedding a semantic query.
+    pub fn text(&self) -> String {
+        self.groups
+            .iter()
+            .flat_map(|group| group.include.iter())
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Fold inline filters into `options`. An inline filter that contradicts
+    /// the matching flag is an error rather than a silent override.
+    pub fn apply_filters(&self, options: &mut SearchOptions) -> Result<(), CassioError> {
+        let filters = &self.filters;
+        merge("tool", "--tool", &mut options.tool, &filters.tool)?;
+        merge(
+            "project",
+            "--project",
+            &mut options.project,
+            &filters.project,
+        )?;
+        merge(
+            "model",
+            "session_model",
+            &mut options.session_model,
+            &filters.model,
+        )?;
+        merge("from", "--from", &mut options.from, &filters.from)?;
+        merge("to", "--to", &mut options.to, &filters.to)?;
+        match (options.speaker, filters.speaker) {
+            (Some(flag), Some(inline)) if flag != inline => {
+                return Err(CassioError::Other(format!(
+                    "speaker:{} conflicts with --speaker {}",
+                    inline.name(),
+                    flag.name()
+                )));
+            }
+            (None, Some(inline)) => options.speaker = Some(inline),
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+impl QueryFilters {
+    fn set(&mut self, field: &str, value: String, query: &str) -> Result<(), CassioError> {
+        if value.is_empty() {
+            return Err(invalid(query, &format!("'{field}:' needs a value")));
+        }
+        let slot = match field {
+            "tool" => &mut self.tool,
+            "project" => &mut self.project,
+            "model" => &mut self.model,
+            "from" | "after" => &mut self.from,
+            "to" | "before" => &mut self.to,
+            "month" => {
+                self.from = Some(value.clone());
+                self.to = Some(value);
+                return Ok(());
+            }
+            "speaker" => {
+                self.speaker = Some(value.parse().map_err(|e: String| invalid(query, &e))?);
+                return Ok(());
+            }
+            _ => return Err(invalid(query, &format!("unknown field '{field}:'"))),
+        };
+        *slot = Some(value);
+        Ok(())
+    }
+}
+
+fn merge(
+    name: &str,
+    flag_name: &str,
+    flag: &mut Option<String>,
+    inline: &Option<String>,
+) -> Result<(), CassioError> {
+    match (flag.as_deref(), inline) {
+        (Some(current), Some(value)) if !current.eq_ignore_ascii_case(value) => {
+            Err(CassioError::Other(format!(
+                "{name}:{value} conflicts with {flag_name} {current}"
+            )))
+        }
+        (None, Some(value)) => {
+            *flag = Some(value.clone());
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Split on whitespace, keeping `"quoted phrases"` (optionally after `-` or
+/// `field:`) together.
+fn tokenize(query: &str) -> Result<Vec<Token>, CassioError> {
+    let mut tokens = Vec::new();
+    let mut chars = query.chars().peekable();
+    loop {
+        while chars.next_if(|ch| ch.is_whitespace()).is_some() {}
+        if chars.peek().is_none() {
+            break;
+        }
+        let mut raw = String::new();
+        let mut quoted = false;
+        while let Some(&ch) = chars.peek() {
+            if ch.is_whitespace() && !quoted {
+                break;
+            }
+            chars.next();
+            if ch == '"' {
+                quoted = !quoted;
+                if !quoted {
+                    break;
+                }
+                raw.push('"');
+                continue;
+            }
+            raw.push(ch);
+        }
+        if quoted {
+            return Err(invalid(query, "unclosed '\"'"));
+        }
+        tokens.push(token(&raw));
+    }
+    Ok(tokens)
+}
+
+/// Classify one raw token. A `"` marks where a quoted phrase began.
+fn token(raw: &str) -> Token {
+    if let Some(escaped) = raw.strip_prefix('\\').filter(|rest| rest.starts_with('-')) {
+        return Token {
+            negated: false,
+            field: None,
+            value: escaped.to_string(),
+            quoted: false,
+        };
+    }
+    let (negated, rest) = match raw.strip_prefix('-') {
+        Some(rest) if !rest.is_empty() => (true, rest),
+        _ => (false, raw),
+    };
+    if let Some(phrase) = rest.strip_prefix('"') {
+        return Token {
+            negated,
+            field: None,
+            value: phrase.to_string(),
+            quoted: true,
+        };
+    }
+    if let Some((name, value)) = rest.split_once(':') {
+        let name = name.to_ascii_lowercase();
+        if FIELDS.contains(&name.as_str()) {
+            return Token {
+                negated,
+                field: Some(name),
+                value: value.trim_start_matches('"').to_string(),
+                quoted: false,
+            };
+        }
+    }
+    // Flag-like tokens (`--force`, `-la.`) are text, not exclusions.
+    let word = rest.chars().all(|ch| ch.is_alphanumeric() || ch == '_');
+    Token {
+        negated: negated && word,
+        field: None,
+        value: if negated && !word { raw } else { rest }.to_string(),
+        quoted: false,
+    }
+}
+
+fn invalid(query: &str, reason: &str) -> CassioError {
+    CassioError::Other(format!("Invalid search query '{query}': {reason}"))
+}
+
+#[cfg(test)]
+#[path = "query_test.rs"]
+mod tests;
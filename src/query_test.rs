@@ -0,0 +1,146 @@
+use super::*;
+
+fn group(include: &[&str], exclude: &[&str]) -> TermGroup {
+    TermGroup {
+        include: include.iter().map(|s| s.to_string()).collect(),
+        exclude: exclude.iter().map(|s| s.to_string()).collect(),
+    }
+}
+
+#[test]
+fn parses_filters_phrases_exclusions_and_or() {
+    let parsed = parse(
+        r#"tool:codex project:faber speaker:user after:2026-06 model:opus "exact phrase" -excluded OR alt"#,
+    )
+    .unwrap();
+    assert_eq!(
+        parsed.groups,
+        vec![
+            group(&["exact phrase"], &["excluded"]),
+            group(&["alt"], &[])
+        ]
+    );
+    assert_eq!(
+        parsed.filters,
+        QueryFilters {
+            tool: Some("codex".to_string()),
+            project: Some("faber".to_string()),
+            model: Some("opus".to_string()),
+            speaker: Some(Speaker::User),
+            from: Some("2026-06".to_string()),
+            to: None,
+        }
+    );
+    assert_eq!(parsed.text(), "exact phrase alt");
+}
+
+#[test]
+fn plain_queries_and_unknown_fields_stay_literal() {
+    let parsed = parse("launchd rsync https://example.com src/main.rs:42 or").unwrap();
+    assert_eq!(
+        parsed.groups,
+        vec![group(
+            &[
+                "launchd",
+                "rsync",
+                "https://example.com",
+                "src/main.rs:42",
+                "or"
+            ],
+            &[]
+        )]
+    );
+    assert_eq!(parsed.filters, QueryFilters::default());
+
+    let parsed = parse(r#""tool:codex" project:"My Project" month:2026-04 -"not this""#).unwrap();
+    assert_eq!(parsed.groups, vec![group(&["tool:codex"], &["not this"])]);
+    assert_eq!(parsed.filters.project.as_deref(), Some("My Project"));
+    assert_eq!(parsed.filters.from.as_deref(), Some("2026-04"));
+    assert_eq!(parsed.filters.to.as_deref(), Some("2026-04"));
+}
+
+#[test]
+fn flag_like_terms_stay_literal() {
+    let parsed = parse(r"rm \-rf --force -la. -v").unwrap();
+    assert_eq!(
+        parsed.groups,
+        vec![group(&["rm", "-rf", "--force", "-la."], &["v"])]
+    );
+    // A bare `\` or `-` is text too.
+    assert_eq!(
+        parse(r"\ -").unwrap().groups,
+        vec![group(&[r"\", "-"], &[])]
+    );
+}
+
+#[test]
+fn exclusions_need_a_positive_term_or_filter() {
+    assert_eq!(parse("-rf").unwrap().groups, vec![group(&["-rf"], &[])]);
+    assert_eq!(
+        parse(r#"-"dry run" -force"#).unwrap().groups,
+        vec![group(&["-dry run", "-force"], &[])]
+    );
+    assert_eq!(
+        parse("deploy OR -rollback").unwrap().groups,
+        vec![group(&["deploy"], &[]), group(&["-rollback"], &[])]
+    );
+    // A filter narrows the search, so the exclusion keeps its meaning.
+    let parsed = parse("tool:codex -test").unwrap();
+    assert_eq!(parsed.groups, vec![group(&[], &["test"])]);
+}
+
+#[test]
+fn rejects_malformed_queries() {
+    for bad in [
+        "OR foo",
+        "foo OR",
+        "foo OR OR bar",
+        "\"unclosed",
+        "-tool:codex foo",
+        "speaker:robot foo",
+        "tool: foo",
+    ] {
+        assert!(parse(bad).is_err(), "accepted {bad:?}");
+    }
+}
+
+#[test]
+fn inline_filters_merge_with_flags() {
+    let mut options = crate::search::SearchOptions {
+        from: None,
+        to: None,
+        tool: Some("codex".to_string()),
+        project: None,
+        session_model: None,
+        speaker: None,
+        limit: 50,
+        summaries_only: false,
+        include_training: false,
+        include_paths: false,
+        json: false,
+        regex: false,
+        case_sensitive: false,
+        context: 0,
+        files_with_matches: false,
+        count: false,
+        oldest_first: false,
+        semantic: None,
+        training_root: None,
+        scan: false,
+        hybrid: false,
+        rerank_model: None,
+        sessions: false,
+    };
+    parse("tool:Codex speaker:user before:2026-07 x")
+        .unwrap()
+        .apply_filters(&mut options)
+        .unwrap();
+    assert_eq!(options.speaker, Some(Speaker::User));
+    assert_eq!(options.to.as_deref(), Some("2026-07"));
+
+    let err = parse("tool:grok x")
+        .unwrap()
+        .apply_filters(&mut options)
+        .unwrap_err();
+    assert!(err.to_string().contains("conflicts with --tool codex"));
+}
@@ -226,11 +226,15 @@ fn redact_block(block: &ContentBlock, audit: &mut RedactionAudit) -> ContentBloc
             name,
             success,
             summary,
+            output,
         } => ContentBlock::ToolResult {
             tool_use_id: redact_text_with_audit(tool_use_id, audit),
             name: redact_text_with_audit(name, audit),
             success: *success,
             summary: redact_text_with_audit(summary, audit),
+            output: output
+                .as_deref()
+                .map(|output| redact_text_with_audit(output, audit)),
         },
         ContentBlock::ModelChange { model } => ContentBlock::ModelChange {
             model: redact_text_with_audit(model, audit),
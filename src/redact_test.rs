@@ -58,9 +58,10 @@ fn redacts_session_content_before_output() {
                 ContentBlock::ToolResult {
                     tool_use_id: "t1".to_string(),
                     name: "Bash".to_string(),
-                    success: true,
+                    success: false,
                     summary: "export CLAUDE_CODE_OAUTH_TOKEN=sk-ant-REDACTED"
                         .to_string(),
+                    output: Some("bad token sk-ant-REDACTED".to_string()),
                 },
             ],
             usage: Some(TokenUsage::default()),
@@ -73,12 +74,15 @@ fn redacts_session_content_before_output() {
         ContentBlock::Text { text } => text.clone(),
         _ => String::new(),
     };
-    let summary = match &redacted.messages[0].content[1] {
-        ContentBlock::ToolResult { summary, .. } => summary.clone(),
-        _ => String::new(),
+    let (summary, output) = match &redacted.messages[0].content[1] {
+        ContentBlock::ToolResult {
+            summary, output, ..
+        } => (summary.clone(), output.clone().unwrap_or_default()),
+        _ => (String::new(), String::new()),
     };
 
     assert!(!rendered.contains("sk-ant-"));
     assert!(!summary.contains("sk-ant-"));
     assert!(summary.contains("CLAUDE_CODE_OAUTH_TOKEN=[REDACTED]"));
+    assert!(output.starts_with("bad token ") && !output.contains("sk-ant-"));
 }
@@ -0,0 +1,346 @@
+//! Related sessions (`cassio related`) and the optional transcript footer.
+//!
+//! A session's embedding is the normalized mean of its chunk vectors in the
+//! semantic index, so no model call is needed: `cassio related` pools every
+//! session in the index once and ranks the others by cosine similarity to the
+//! target. With `embedding.related_footer`, automatic indexing after a batch
+//! rewrites a `📋 --- Related ---` footer at the end of each emoji-text
+//! transcript it embedded.
+//!
+//! # TRADE-OFFS
+//!
+//! - Mean pooling weights a session by how much it says about each topic; a
+//!   long session that drifts between problems pools toward the middle and
+//!   relates loosely to everything. Cheap and stable beats a second model.
+//! - Footers are only rewritten for sessions embedded in that run, so an older
+//!   transcript does not learn about newer related sessions. Rewriting every
+//!   footer would touch the whole archive (and its git history) on each batch.
+//! - Footer lines are skipped by chunking and the lexical index, so writing a
+//!   footer never invalidates the transcript's own embeddings or search hits.
+//!   The rewrite does change the file's size and mtime, so the rewritten
+//!   transcripts are re-stamped in the lexical index; otherwise search would
+//!   treat the index as stale and fall back to scanning after every batch.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rusqlite::{Connection, params};
+use serde::Serialize;
+
+use crate::ann::{dot, normalized};
+use crate::error::CassioError;
+use crate::formatter::emoji_text::EMOJI_META;
+use crate::frontmatter;
+use crate::index;
+use crate::lexical;
+use crate::manifest::{ARTIFACT_EXTENSIONS, Manifest, SessionCatalog};
+
+/// Related sessions listed in a transcript footer.
+pub const FOOTER_LIMIT: usize = 5;
+const FOOTER_HEADER: &str = "📋 --- Related ---";
+const FOOTER_PREFIX: &str = "📋 Related:";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RelatedSession {
+    /// Root-relative transcript path.
+    pub path: String,
+    pub score: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+}
+
+/// Pooled session vectors from one semantic index.
+pub struct SessionVectors {
+    sessions: Vec<PooledSession>,
+}
+
+/// One session's normalized mean chunk embedding.
+pub(crate) struct PooledSession {
+    pub(crate) source_path: String,
+    pub(crate) vector: Vec<f32>,
+    pub(crate) date: Option<String>,
+    pub(crate) tool: Option<String>,
+    pub(crate) project: Option<String>,
+}
+
+impl SessionVectors {
+    /// Pool every session transcript's chunk embeddings from `index_path`.
+    pub fn load(index_path: &Path) -> Result<Self, CassioError> {
+        if !index_path.exists() {
+            return Err(CassioError::Other(format!(
+                "Semantic index not found: {} (run `cassio index` first)",
+                index_path.display()
+            )));
+        }
+        let conn = Connection::open(index_path)
+            .map_err(|e| CassioError::Other(format!("Failed to open semantic index: {e}")))?;
+        let columns = if index::has_chunk_metadata(&conn)? {
+            "date, tool, project"
+        } else {
+            "NULL, NULL, NULL"
+        };
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT source_path, embedding, {columns} FROM chunks \
+                 WHERE artifact = 'session' ORDER BY source_path"
+            ))
+            .map_err(sql_error)?;
+        let rows = stmt
+            .query_map(params![], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, Vec<u8>>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                    row.get::<_, Option<String>>(3)?,
+                    row.get::<_, Option<String>>(4)?,
+                ))
+            })
+            .map_err(sql_error)?;
+
+        let mut sessions: Vec<PooledSession> = Vec::new();
+        let mut positions: HashMap<String, usize> = HashMap::new();
+        for row in rows {
+            let (source_path, blob, date, tool, project) = row.map_err(sql_error)?;
+            let embedding = normalized(&index::decode_embedding(&blob)?);
+            let slot = *positions.entry(source_path.clone()).or_insert_with(|| {
+                sessions.push(PooledSession {
+                    source_path,
+                    vector: vec![0.0; embedding.len()],
+                    date,
+                    tool,
+                    project,
+                });
+                sessions.len() - 1
+            });
+            let pooled = &mut sessions[slot].vector;
+            if pooled.len() == embedding.len() {
+                for (sum, value) in pooled.iter_mut().zip(&embedding) {
+                    *sum += value;
+                }
+            }
+        }
+        for session in &mut sessions {
+            session.vector = normalized(&session.vector);
+        }
+        Ok(Self { sessions })
+    }
+
+    /// Every pooled session, in source path order.
+    pub(crate) fn sessions(&self) -> &[PooledSession] {
+        &self.sessions
+    }
+
+    pub fn contains(&self, source_path: &str) -> bool {
+        self.sessions.iter().any(|s| s.source_path == source_path)
+    }
+
+    /// The `limit` sessions nearest to `source_path`, best first.
+    pub fn nearest(&self, source_path: &str, limit: usize) -> Vec<RelatedSession> {
+        let Some(target) = self.sessions.iter().find(|s| s.source_path == source_path) else {
+            return Vec::new();
+        };
+        let mut related: Vec<RelatedSession> = self
+            .sessions
+            .iter()
+            .filter(|session| session.source_path != source_path)
+            .filter(|session| session.vector.len() == target.vector.len())
+            .map(|session| RelatedSession {
+                path: session.source_path.clone(),
+                score: dot(&target.vector, &session.vector),
+                date: session.date.clone(),
+                tool: session.tool.clone(),
+                project: session.project.clone(),
+            })
+            .collect();
+        related.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.path.cmp(&b.path)));
+        related.truncate(limit);
+        related
+    }
+}
+
+/// Root-relative source path of `target`: a transcript path (absolute, or
+/// relative to the working directory or `root`) or a session id.
+pub fn resolve_target(root: &Path, target: &str) -> Result<String, CassioError> {
+    let given = PathBuf::from(target);
+    for candidate in [given.clone(), root.join(&given)] {
+        if candidate.is_file() {
+            let absolute = candidate.canonicalize()?;
+            let root = root.canonicalize()?;
+            return absolute
+                .strip_prefix(&root)
+                .map(|rel| rel.to_string_lossy().replace('\\', "/"))
+                .map_err(|_| {
+                    CassioError::Other(format!(
+                        "{} is not under the archive {}",
+                        candidate.display(),
+                        root.display()
+                    ))
+                });
+        }
+    }
+
+    let manifest = Manifest::load(root)?;
+    // A full id wins even when it is also a prefix of a longer id.
+    let exact: Vec<&String> = manifest
+        .sessions
+        .iter()
+        .filter(|(_, entry)| entry.session_id == target)
+        .map(|(key, _)| key)
+        .collect();
+    let candidates = if exact.is_empty() {
+        manifest
+            .sessions
+            .iter()
+            .filter(|(_, entry)| entry.session_id.starts_with(target))
+            .map(|(key, _)| key)
+            .collect()
+    } else {
+        exact
+    };
+    let mut matches = candidates.into_iter();
+    let Some(key) = matches.next() else {
+        return Err(CassioError::Other(format!(
+            "No transcript or session id matches '{target}'"
+        )));
+    };
+    if matches.next().is_some() {
+        return Err(CassioError::Other(format!(
+            "Session id prefix '{target}' is ambiguous"
+        )));
+    }
+    transcript_for_key(root, key)
+        .ok_or_else(|| CassioError::Other(format!("Transcript for session '{target}' is missing")))
+}
+
+/// Root-relative transcript for a manifest key: the transcript, not its
+/// training sidecar. `None` when no artifact was written or it was removed.
+pub(crate) fn transcript_for_key(root: &Path, key: &str) -> Option<String> {
+    ARTIFACT_EXTENSIONS
+        .iter()
+        .filter(|ext| **ext != ".training.json")
+        .map(|ext| format!("{key}{ext}"))
+        .find(|rel| root.join(rel).is_file())
+}
+
+pub fn run_related(
+    root: &Path,
+    target: &str,
+    index_path: &Path,
+    limit: usize,
+    json: bool,
+) -> Result<(), CassioError> {
+    let source_path = resolve_target(root, target)?;
+    let vectors = SessionVectors::load(index_path)?;
+    if !vectors.contains(&source_path) {
+        return Err(CassioError::Other(format!(
+            "{source_path} is not in the semantic index (run `cassio index`)"
+        )));
+    }
+    let related = vectors.nearest(&source_path, limit);
+    if json {
+        serde_json::to_writer_pretty(std::io::stdout(), &related)?;
+        println!();
+        return Ok(());
+    }
+    println!("cassio related: {source_path}");
+    if related.is_empty() {
+        println!("No related sessions.");
+    }
+    for session in &related {
+        println!("{}", describe(session));
+    }
+    Ok(())
+}
+
+/// Rewrite the Related footer of each emoji-text transcript in `source_paths`.
+/// Returns the number of transcripts whose footer changed.
+pub fn update_footers(
+    root: &Path,
+    training_root: Option<&Path>,
+    index_path: &Path,
+    source_paths: &[String],
+) -> Result<usize, CassioError> {
+    if source_paths.is_empty() {
+        return Ok(0);
+    }
+    let vectors = SessionVectors::load(index_path)?;
+    let mut rewritten_paths = Vec::new();
+    for source_path in source_paths {
+        if !source_path.ends_with(".md") {
+            continue;
+        }
+        let path = root.join(source_path);
+        let Ok(content) = fs::read_to_string(&path) else {
+            continue;
+        };
+        if !frontmatter::body(&content).starts_with(EMOJI_META) {
+            continue;
+        }
+        let related = vectors.nearest(source_path, FOOTER_LIMIT);
+        let rewritten = with_footer(&content, &related);
+        if rewritten != content {
+            fs::write(&path, rewritten)?;
+            rewritten_paths.push(path);
+        }
+    }
+    if !rewritten_paths.is_empty() && lexical::lexical_index_path(root).is_file() {
+        let mut catalog = SessionCatalog::load(root);
+        if let Some(training_root) = training_root {
+            catalog = catalog.with_root(training_root);
+        }
+        lexical::update_lexical_index(root, training_root, &rewritten_paths, &catalog)?;
+    }
+    Ok(rewritten_paths.len())
+}
+
+/// True for lines of the Related footer, which indexing skips.
+pub(crate) fn is_footer_line(line: &str) -> bool {
+    line.starts_with(FOOTER_HEADER) || line.starts_with(FOOTER_PREFIX)
+}
+
+/// `content` with any existing footer replaced by one listing `related`.
+fn with_footer(content: &str, related: &[RelatedSession]) -> String {
+    let body = match content.find(&format!("\n{FOOTER_HEADER}")) {
+        Some(index) => &content[..index],
+        None => content,
+    };
+    let mut out = body.trim_end_matches('\n').to_string();
+    out.push('\n');
+    if !related.is_empty() {
+        out.push('\n');
+        out.push_str(FOOTER_HEADER);
+        out.push('\n');
+        for session in related {
+            out.push_str(&format!("{FOOTER_PREFIX} {}\n", describe(session)));
+        }
+    }
+    out
+}
+
+fn describe(session: &RelatedSession) -> String {
+    let mut parts = vec![format!("{:.3}", session.score)];
+    if let Some(date) = &session.date {
+        parts.push(date.clone());
+    }
+    if let Some(tool) = &session.tool {
+        parts.push(tool.clone());
+    }
+    parts.push(session.path.clone());
+    if let Some(project) = &session.project {
+        parts.push(format!("({project})"));
+    }
+    parts.join(" ")
+}
+
+fn sql_error(e: rusqlite::Error) -> CassioError {
+    CassioError::Other(format!("Related sessions error: {e}"))
+}
+
+#[cfg(test)]
+#[path = "related_test.rs"]
+mod tests;
@@ -0,0 +1,223 @@
+use super::*;
+use crate::index::encode_embedding;
+use crate::manifest::ManifestEntry;
+
+fn temp_root(label: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("cassio_related_{label}_{}", std::process::id()))
+}
+
+/// An index with session chunks `(source_path, embedding, project)`.
+fn write_index(path: &Path, chunks: &[(&str, [f32; 2], Option<&str>)]) {
+    let conn = Connection::open(path).unwrap();
+    conn.execute_batch(
+        "CREATE TABLE chunks (
+            source_path TEXT, artifact TEXT, embedding BLOB,
+            speakers TEXT, project TEXT, tool TEXT, model TEXT, date TEXT
+        );",
+    )
+    .unwrap();
+    for (source_path, embedding, project) in chunks {
+        conn.execute(
+            "INSERT INTO chunks VALUES (?1, 'session', ?2, NULL, ?3, 'codex', NULL, '2026-04-10')",
+            params![source_path, encode_embedding(embedding), project],
+        )
+        .unwrap();
+    }
+    conn.execute(
+        "INSERT INTO chunks VALUES ('2026-04/2026-04.monthly.md', 'monthly', ?1, NULL, NULL, NULL, NULL, NULL)",
+        params![encode_embedding(&[1.0, 0.0])],
+    )
+    .unwrap();
+}
+
+#[test]
+fn nearest_sessions_pool_chunk_vectors() {
+    let root = temp_root("pool");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+    let index_path = root.join("index.sqlite");
+    write_index(
+        &index_path,
+        &[
+            ("a.md", [1.0, 0.0], Some("/work/faber")),
+            ("a.md", [0.8, 0.6], Some("/work/faber")),
+            // Pools to the same direction as `a.md`.
+            ("b.md", [0.9, 0.3], None),
+            ("c.md", [0.0, 1.0], None),
+        ],
+    );
+
+    let vectors = SessionVectors::load(&index_path).unwrap();
+    assert!(vectors.contains("a.md"));
+    assert!(
+        !vectors.contains("2026-04/2026-04.monthly.md"),
+        "only sessions"
+    );
+    let related = vectors.nearest("a.md", 10);
+    let paths: Vec<&str> = related.iter().map(|r| r.path.as_str()).collect();
+    assert_eq!(paths, vec!["b.md", "c.md"]);
+    assert!(related[0].score > 0.99);
+    assert_eq!(related[0].tool.as_deref(), Some("codex"));
+    assert_eq!(related[0].date.as_deref(), Some("2026-04-10"));
+    assert_eq!(vectors.nearest("b.md", 1)[0].path, "a.md");
+    assert_eq!(
+        vectors.nearest("b.md", 1)[0].project.as_deref(),
+        Some("/work/faber")
+    );
+    assert!(vectors.nearest("missing.md", 10).is_empty());
+
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn footer_is_replaced_in_place_and_skipped_by_indexing() {
+    let related = vec![RelatedSession {
+        path: "2026-03/2026-03-01T09-00-00-codex.md".to_string(),
+        score: 0.91234,
+        date: Some("2026-03-01".to_string()),
+        tool: Some("codex".to_string()),
+        project: Some("/work/faber".to_string()),
+    }];
+    let body = "📋 Session: abc\n👤 hello\n";
+    let once = with_footer(body, &related);
+    assert_eq!(
+        once,
+        "📋 Session: abc\n👤 hello\n\n📋 --- Related ---\n\
+         📋 Related: 0.912 2026-03-01 codex 2026-03/2026-03-01T09-00-00-codex.md (/work/faber)\n"
+    );
+    assert_eq!(with_footer(&once, &related), once, "idempotent");
+    assert_eq!(
+        with_footer(&once, &[]),
+        body,
+        "removed when nothing relates"
+    );
+    assert_eq!(once.lines().filter(|line| is_footer_line(line)).count(), 2);
+    assert!(!is_footer_line("📋 Session: abc"));
+}
+
+#[test]
+fn update_footers_rewrites_emoji_text_transcripts_only() {
+    let root = temp_root("footers");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("2026-04")).unwrap();
+    let index_path = root.join("index.sqlite");
+    write_index(
+        &index_path,
+        &[
+            ("2026-04/a.md", [1.0, 0.0], None),
+            ("2026-04/b.md", [0.9, 0.1], None),
+            ("2026-04/c.jsonl", [0.9, 0.1], None),
+        ],
+    );
+    fs::write(root.join("2026-04/a.md"), "📋 Session: a\n👤 hello\n").unwrap();
+    fs::write(root.join("2026-04/c.jsonl"), "{}\n").unwrap();
+
+    let changed = vec![
+        "2026-04/a.md".to_string(),
+        "2026-04/c.jsonl".to_string(),
+        "2026-04/gone.md".to_string(),
+    ];
+    assert_eq!(
+        update_footers(&root, None, &index_path, &changed).unwrap(),
+        1
+    );
+    let content = fs::read_to_string(root.join("2026-04/a.md")).unwrap();
+    assert!(content.contains("📋 Related: 0.994 2026-04-10 codex 2026-04/b.md"));
+    assert_eq!(
+        fs::read_to_string(root.join("2026-04/c.jsonl")).unwrap(),
+        "{}\n"
+    );
+    assert_eq!(
+        update_footers(&root, None, &index_path, &changed).unwrap(),
+        0
+    );
+
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn targets_resolve_by_path_or_session_id() {
+    let root = temp_root("targets");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("2026-04")).unwrap();
+    let transcript = root.join("2026-04/2026-04-10T10-00-00-codex.md");
+    fs::write(&transcript, "📋 Session: abc-123\n").unwrap();
+    fs::write(
+        root.join("2026-04/2026-04-10T10-00-00-codex.training.json"),
+        "{}",
+    )
+    .unwrap();
+    let mut manifest = Manifest::default();
+    manifest.record(
+        "2026-04/2026-04-10T10-00-00-codex".to_string(),
+        ManifestEntry {
+            tool: "codex".to_string(),
+            source_path: "/logs/abc.jsonl".to_string(),
+            session_id: "abc-123".to_string(),
+            started_at: chrono::Utc::now(),
+            project_path: "/work/faber".to_string(),
+            format: "emoji-text".to_string(),
+            training: true,
+            written_at: chrono::Utc::now(),
+            fingerprint: None,
+        },
+    );
+    let longer = root.join("2026-04/2026-04-11T10-00-00-codex.md");
+    fs::write(&longer, "📋 Session: abc-1234\n").unwrap();
+    manifest.record(
+        "2026-04/2026-04-11T10-00-00-codex".to_string(),
+        ManifestEntry {
+            session_id: "abc-1234".to_string(),
+            training: false,
+            ..manifest.sessions["2026-04/2026-04-10T10-00-00-codex"].clone()
+        },
+    );
+    manifest.save(&root).unwrap();
+
+    let expected = "2026-04/2026-04-10T10-00-00-codex.md";
+    assert_eq!(
+        resolve_target(&root, transcript.to_str().unwrap()).unwrap(),
+        expected
+    );
+    assert_eq!(resolve_target(&root, expected).unwrap(), expected);
+    // "abc-123" is also a prefix of "abc-1234", but matches one id exactly.
+    assert_eq!(resolve_target(&root, "abc-123").unwrap(), expected);
+    assert_eq!(
+        resolve_target(&root, "abc-1234").unwrap(),
+        "2026-04/2026-04-11T10-00-00-codex.md"
+    );
+    assert!(resolve_target(&root, "abc").is_err(), "ambiguous prefix");
+    assert!(resolve_target(&root, "zzz").is_err());
+
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn footers_keep_the_lexical_index_fresh() {
+    let root = temp_root("footers_lexical");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("2026-04")).unwrap();
+    let a = "2026-04/2026-04-10T10-00-00-codex.md";
+    let b = "2026-04/2026-04-11T10-00-00-codex.md";
+    fs::write(root.join(a), "📋 Session: a\n👤 zeppelin route\n").unwrap();
+    fs::write(root.join(b), "📋 Session: b\n👤 zeppelin hangar\n").unwrap();
+    let files = vec![root.join(a), root.join(b)];
+    let catalog = SessionCatalog::load(&root);
+    lexical::update_lexical_index(&root, None, &files, &catalog).unwrap();
+
+    let index_path = root.join("index.sqlite");
+    write_index(&index_path, &[(a, [1.0, 0.0], None), (b, [0.9, 0.1], None)]);
+    let changed = vec![a.to_string()];
+    assert_eq!(
+        update_footers(&root, None, &index_path, &changed).unwrap(),
+        1
+    );
+
+    assert!(
+        lexical::open_fresh_lexical_index(&root, None, &files, false)
+            .unwrap()
+            .is_some(),
+        "a footer rewrite must not leave search on the scan fallback"
+    );
+    let _ = fs::remove_dir_all(&root);
+}
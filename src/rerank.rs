@@ -0,0 +1,103 @@
+//! Cross-encoder reranking for semantic and hybrid search (`--rerank`).
+//!
+//! Bi-encoder retrieval embeds the query and each chunk separately, so a broad
+//! summary that shares vocabulary with the query can outscore the session that
+//! actually answers it. A cross-encoder reads the query and a candidate
+//! together and scores their relevance directly. Reranking rescores the top
+//! `RERANK_DEPTH` candidates with a local GGUF reranker (for example
+//! `bge-reranker-v2-m3`) through the llama.cpp runtime in `index`, and reorders
+//! them by that score.
+//!
+//! # TRADE-OFFS
+//!
+//! - Only the top candidates are rescored: a cross-encoder costs one model pass
+//!   per candidate, so depth is bounded and anything the retriever ranked
+//!   lower stays where it was, behind the reranked hits.
+//! - The reranker reads the hit's full line range from disk, not the truncated
+//!   display text, so long chunks are judged on their content.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::CassioError;
+use crate::index;
+use crate::search::SearchHit;
+
+/// Candidates rescored by the reranker (at least `--limit`).
+pub(crate) const RERANK_DEPTH: usize = 30;
+/// Characters of a candidate passed to the reranker.
+const DOCUMENT_CHARS: usize = 4_000;
+
+/// Rescore the first `depth` hits with the reranker at `model_path`, put them
+/// in reranker order, and keep at most `limit` hits.
+pub(crate) fn rerank_hits(
+    model_path: &Path,
+    query: &str,
+    mut hits: Vec<SearchHit>,
+    depth: usize,
+    limit: usize,
+) -> Result<Vec<SearchHit>, CassioError> {
+    let depth = depth.min(hits.len());
+    if depth == 0 {
+        return Ok(hits);
+    }
+    let mut files = HashMap::new();
+    let documents: Vec<String> = hits[..depth]
+        .iter()
+        .map(|hit| document_text(hit, &mut files))
+        .collect();
+    let inputs: Vec<&str> = documents.iter().map(String::as_str).collect();
+    let scores = index::rerank_local(model_path, query, &inputs)?;
+    if scores.len() != depth {
+        return Err(CassioError::Other(format!(
+            "Reranker returned {} scores for {} candidates",
+            scores.len(),
+            depth
+        )));
+    }
+    apply_scores(&mut hits, &scores);
+    hits.truncate(limit);
+    Ok(hits)
+}
+
+/// Attach `scores` to the leading hits and sort those hits by score, keeping
+/// retriever order among ties and for the hits past the scored prefix.
+fn apply_scores(hits: &mut [SearchHit], scores: &[f32]) {
+    let scored = &mut hits[..scores.len()];
+    for (hit, score) in scored.iter_mut().zip(scores) {
+        hit.rerank_score = Some(*score);
+    }
+    scored.sort_by(|a, b| {
+        b.rerank_score
+            .unwrap_or(f32::NEG_INFINITY)
+            .total_cmp(&a.rerank_score.unwrap_or(f32::NEG_INFINITY))
+    });
+}
+
+/// The hit's full line range, or its display text when the file is unreadable.
+fn document_text(hit: &SearchHit, files: &mut HashMap<PathBuf, Option<String>>) -> String {
+    let content = files
+        .entry(hit.path.clone())
+        .or_insert_with(|| fs::read_to_string(&hit.path).ok());
+    let Some(content) = content.as_deref() else {
+        return hit.text.clone();
+    };
+    let start = hit.line.saturating_sub(1);
+    let end = hit.line_end.unwrap_or(hit.line).max(hit.line);
+    let text = content
+        .lines()
+        .skip(start)
+        .take(end - start)
+        .map(str::trim)
+        .collect::<Vec<_>>()
+        .join("\n");
+    if text.is_empty() {
+        return hit.text.clone();
+    }
+    text.chars().take(DOCUMENT_CHARS).collect()
+}
+
+#[cfg(test)]
+#[path = "rerank_test.rs"]
+mod tests;
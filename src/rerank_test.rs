@@ -0,0 +1,74 @@
+use super::*;
+use crate::search::SearchArtifact;
+
+fn hit(path: &Path, line: usize, line_end: Option<usize>, text: &str) -> SearchHit {
+    SearchHit {
+        artifact: SearchArtifact::Session,
+        path: path.to_path_buf(),
+        line,
+        line_end,
+        text: text.to_string(),
+        score: Some(0.5),
+        context: None,
+        retrievers: None,
+        rerank_score: None,
+        archive: None,
+    }
+}
+
+#[test]
+fn scores_reorder_only_the_reranked_prefix() {
+    let path = Path::new("a.md");
+    let mut hits = vec![
+        hit(path, 1, None, "summary"),
+        hit(path, 2, None, "exact session"),
+        hit(path, 3, None, "tie"),
+        hit(path, 4, None, "unscored"),
+    ];
+    apply_scores(&mut hits, &[0.1, 4.5, 0.1]);
+    let order: Vec<&str> = hits.iter().map(|hit| hit.text.as_str()).collect();
+    assert_eq!(order, vec!["exact session", "summary", "tie", "unscored"]);
+    assert_eq!(hits[0].rerank_score, Some(4.5));
+    assert_eq!(hits[0].score, Some(0.5), "retriever score is kept");
+    assert_eq!(hits[3].rerank_score, None);
+}
+
+#[test]
+fn documents_use_the_full_chunk_range() {
+    let root = std::env::temp_dir().join(format!("cassio_rerank_docs_{}", std::process::id()));
+    fs::create_dir_all(&root).unwrap();
+    let path = root.join("2026-04-10T10-00-00-codex.md");
+    fs::write(&path, "👤 question\n  🤖 answer\nmore answer\n").unwrap();
+    let mut files = HashMap::new();
+
+    let chunk = hit(&path, 2, Some(3), "🤖 answer / more…");
+    assert_eq!(document_text(&chunk, &mut files), "🤖 answer\nmore answer");
+    assert_eq!(
+        document_text(&hit(&path, 1, None, "x"), &mut files),
+        "👤 question"
+    );
+    let missing = hit(&root.join("gone.md"), 1, None, "display text");
+    assert_eq!(document_text(&missing, &mut files), "display text");
+
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn missing_model_is_reported_before_loading() {
+    let path = Path::new("a.md");
+    let err = rerank_hits(
+        Path::new("/nonexistent/reranker.gguf"),
+        "query",
+        vec![hit(path, 1, None, "text")],
+        RERANK_DEPTH,
+        10,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("Reranker model not found"));
+    // Nothing to rescore needs no model at all.
+    assert!(
+        rerank_hits(Path::new("/nonexistent"), "q", Vec::new(), 30, 10)
+            .unwrap()
+            .is_empty()
+    );
+}
@@ -0,0 +1,231 @@
+//! Searching several archive roots at once (repeatable `-o`, `[search].roots`).
+//!
+//! Personal and client work often live in separate archives, each with its own
+//! manifest, lexical index, and semantic index. A multi-root search runs the
+//! same search, with the same date, tool, project, and speaker filters, in each
+//! root, labels every hit with the archive it came from, and merges the results
+//! into one list.
+//!
+//! Ranked results (BM25, semantic, hybrid, reranked) merge by score. Unranked
+//! line scans keep the single-root order: artifact kind first (summaries before
+//! sessions), then file date. When some roots answer from an index and others
+//! fall back to a scan, hits interleave by their rank within each root, so a
+//! root with a stale index is not pushed past `--limit`.
+//!
+//! # TRADE-OFFS
+//!
+//! - BM25 scores from separate lexical indexes are only roughly comparable:
+//!   each root weighs terms by its own document frequencies. Cosine, fusion,
+//!   and reranker scores compare directly.
+//! - Semantic search embeds the query once per root. Embedding a short query is
+//!   cheap next to scoring each root's chunks, and it keeps a root whose index
+//!   uses a different model dimension from failing the others.
+//! - `--training-output` belongs to the first root; other roots read training
+//!   JSON co-located under their own directories.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::error::CassioError;
+use crate::search::{SearchHit, SearchOptions, artifact_order, file_date_of, search_root};
+use crate::session_search::{SessionResult, search_sessions_in};
+
+/// One archive root searched by `cassio search`.
+#[derive(Debug, Clone)]
+pub struct SearchRoot {
+    pub path: PathBuf,
+    /// Archive label on merged hits; `None` when only one root is searched.
+    pub label: Option<String>,
+}
+
+impl SearchRoot {
+    /// Search roots for `paths`, deduplicated. Several roots are labelled with
+    /// their directory names, or their full paths when two names collide.
+    pub fn labelled(paths: Vec<PathBuf>) -> Vec<SearchRoot> {
+        let mut unique: Vec<PathBuf> = Vec::new();
+        for path in paths {
+            let key = path.canonicalize().unwrap_or_else(|_| path.clone());
+            if !unique
+                .iter()
+                .any(|seen| seen.canonicalize().unwrap_or_else(|_| seen.clone()) == key)
+            {
+                unique.push(path);
+            }
+        }
+        if unique.len() < 2 {
+            return unique
+                .into_iter()
+                .map(|path| SearchRoot { path, label: None })
+                .collect();
+        }
+        let name_of = |path: &Path| {
+            path.file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| path.display().to_string())
+        };
+        let mut name_counts: HashMap<String, usize> = HashMap::new();
+        for path in &unique {
+            *name_counts.entry(name_of(path)).or_default() += 1;
+        }
+        unique
+            .into_iter()
+            .map(|path| {
+                let name = name_of(&path);
+                let label = if name_counts[&name] > 1 {
+                    path.display().to_string()
+                } else {
+                    name
+                };
+                SearchRoot {
+                    path,
+                    label: Some(label),
+                }
+            })
+            .collect()
+    }
+}
+
+/// Line search across `roots`, merged and labelled.
+pub(crate) fn search_roots(
+    roots: &[SearchRoot],
+    query: &str,
+    options: &SearchOptions,
+) -> Result<Vec<SearchHit>, CassioError> {
+    if let [root] = roots {
+        return search_root(&root.path, query, options, false);
+    }
+    let mut per_root = Vec::new();
+    for (index, root) in roots.iter().enumerate() {
+        let options = root_options(options, index);
+        // A root without the requested month simply has no hits there.
+        let mut hits = search_root(&root.path, query, &options, true)?;
+        for hit in &mut hits {
+            hit.archive = root.label.clone();
+        }
+        per_root.push(hits);
+    }
+    Ok(merge_hits(per_root, options))
+}
+
+/// Session-level search across `roots`, merged by score.
+pub(crate) fn search_root_sessions(
+    roots: &[SearchRoot],
+    query: &str,
+    options: &SearchOptions,
+) -> Result<Vec<SessionResult>, CassioError> {
+    let mut sessions = Vec::new();
+    for (index, root) in roots.iter().enumerate() {
+        let options = root_options(options, index);
+        for mut session in search_sessions_in(&root.path, query, &options, roots.len() > 1)? {
+            session.archive = root.label.clone();
+            sessions.push(session);
+        }
+    }
+    if roots.len() > 1 {
+        sessions.sort_by(|a, b| b.score.total_cmp(&a.score).then(b.path.cmp(&a.path)));
+        sessions.truncate(options.limit);
+    }
+    Ok(sessions)
+}
+
+/// `path` for display: relative to its root, prefixed with the archive label
+/// when several roots were searched.
+pub(crate) fn display_path(roots: &[SearchRoot], archive: Option<&str>, path: &Path) -> String {
+    let root = roots
+        .iter()
+        .find(|root| root.label.as_deref() == archive && path.starts_with(&root.path))
+        .or_else(|| roots.iter().find(|root| path.starts_with(&root.path)));
+    let relative = root
+        .and_then(|root| path.strip_prefix(&root.path).ok())
+        .unwrap_or(path)
+        .display()
+        .to_string();
+    match archive {
+        Some(label) => format!("[{label}] {relative}"),
+        None => relative,
+    }
+}
+
+/// The roots' paths, for headers.
+pub(crate) fn describe_roots(roots: &[SearchRoot]) -> String {
+    roots
+        .iter()
+        .map(|root| root.path.display().to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// `options` for the root at `index`: the training root belongs to the first.
+fn root_options(options: &SearchOptions, index: usize) -> SearchOptions {
+    let mut options = options.clone();
+    if index > 0 {
+        options.training_root = None;
+    }
+    options
+}
+
+/// Merge each root's hits, given in that root's own order. Count and file-list
+/// modes keep every hit; otherwise the result is cut to `--limit`.
+fn merge_hits(per_root: Vec<Vec<SearchHit>>, options: &SearchOptions) -> Vec<SearchHit> {
+    if options.count || options.files_with_matches {
+        return per_root.into_iter().flatten().collect();
+    }
+    let scored = |hit: &SearchHit| hit.score.is_some() || hit.rerank_score.is_some();
+    let all_hits = || per_root.iter().flatten();
+    let ranked = all_hits().any(scored);
+    if ranked && !all_hits().all(scored) {
+        // Scores and unscored scan hits do not compare: take each root's best
+        // hit in turn.
+        let mut ranked: Vec<(usize, SearchHit)> = per_root
+            .into_iter()
+            .flat_map(|hits| hits.into_iter().enumerate())
+            .collect();
+        ranked.sort_by_key(|(rank, _)| *rank);
+        return ranked
+            .into_iter()
+            .map(|(_, hit)| hit)
+            .take(options.limit)
+            .collect();
+    }
+    let mut hits: Vec<SearchHit> = per_root.into_iter().flatten().collect();
+    if ranked {
+        // Reranked hits first (they were rescored), then by retriever score;
+        // hits without a score sort last.
+        let key = |hit: &SearchHit| {
+            (
+                hit.rerank_score.unwrap_or(f32::NEG_INFINITY),
+                hit.score.unwrap_or(f32::NEG_INFINITY),
+            )
+        };
+        hits.sort_by(|a, b| {
+            let (a_rerank, a_score) = key(a);
+            let (b_rerank, b_score) = key(b);
+            b_rerank
+                .total_cmp(&a_rerank)
+                .then(b_score.total_cmp(&a_score))
+        });
+    } else {
+        let order = artifact_order(options);
+        let position = |hit: &SearchHit| {
+            order
+                .iter()
+                .position(|artifact| *artifact == hit.artifact)
+                .unwrap_or(order.len())
+        };
+        hits.sort_by(|a, b| {
+            let dates = (file_date_of(&a.path), file_date_of(&b.path));
+            let by_date = if options.oldest_first {
+                dates.0.cmp(&dates.1)
+            } else {
+                dates.1.cmp(&dates.0)
+            };
+            position(a).cmp(&position(b)).then(by_date)
+        });
+    }
+    hits.truncate(options.limit);
+    hits
+}
+
+#[cfg(test)]
+#[path = "roots_test.rs"]
+mod tests;
@@ -0,0 +1,186 @@
+use super::*;
+use std::collections::BTreeSet;
+use std::fs;
+
+fn test_options() -> SearchOptions {
+    SearchOptions {
+        from: None,
+        to: None,
+        tool: None,
+        project: None,
+        session_model: None,
+        speaker: None,
+        limit: 50,
+        summaries_only: false,
+        include_training: false,
+        include_paths: false,
+        json: false,
+        regex: false,
+        case_sensitive: false,
+        context: 0,
+        files_with_matches: false,
+        count: false,
+        oldest_first: false,
+        semantic: None,
+        training_root: None,
+        scan: false,
+        hybrid: false,
+        rerank_model: None,
+        sessions: false,
+    }
+}
+
+fn temp_root(label: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("cassio_roots_{label}_{}", std::process::id()))
+}
+
+fn write_file(root: &Path, rel: &str, content: &str) {
+    let path = root.join(rel);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
+
+/// `personal/` and `work/` archives under one temp directory.
+fn archives(label: &str) -> (PathBuf, Vec<SearchRoot>) {
+    let base = temp_root(label);
+    let _ = fs::remove_dir_all(&base);
+    let personal = base.join("personal");
+    let work = base.join("work");
+    write_file(
+        &personal,
+        "2026-04/2026-04-02T09-00-00-codex.md",
+        "👤 zepp at home\n",
+    );
+    write_file(
+        &personal,
+        "2026-04/2026-04-20T09-00-00-claude.md",
+        "👤 zepp again\n🤖 zepp reply\n",
+    );
+    write_file(
+        &work,
+        "2026-05/2026-05-01T09-00-00-codex.md",
+        "👤 zepp at work\n",
+    );
+    write_file(&work, "2026-05/2026-05.monthly.md", "zepp all month\n");
+    (base, SearchRoot::labelled(vec![personal, work]))
+}
+
+#[test]
+fn roots_are_labelled_by_directory_name_unless_names_collide() {
+    let single = SearchRoot::labelled(vec![PathBuf::from("/a/transcripts")]);
+    assert_eq!(single[0].label, None);
+
+    let roots = SearchRoot::labelled(vec![
+        PathBuf::from("/a/personal"),
+        PathBuf::from("/b/work"),
+        PathBuf::from("/a/personal"),
+    ]);
+    let labels: Vec<_> = roots.iter().map(|r| r.label.as_deref()).collect();
+    assert_eq!(labels, vec![Some("personal"), Some("work")], "deduplicated");
+
+    let roots = SearchRoot::labelled(vec![
+        PathBuf::from("/a/transcripts"),
+        PathBuf::from("/b/transcripts"),
+    ]);
+    assert_eq!(roots[1].label.as_deref(), Some("/b/transcripts"));
+    assert_eq!(
+        display_path(
+            &roots,
+            Some("/b/transcripts"),
+            Path::new("/b/transcripts/2026-04/x.md")
+        ),
+        "[/b/transcripts] 2026-04/x.md"
+    );
+}
+
+#[test]
+fn merges_labelled_hits_with_filters_applied_to_every_root() {
+    let (base, roots) = archives("merge");
+
+    let hits = search_roots(&roots, "zepp", &test_options()).unwrap();
+    let found: Vec<(Option<&str>, String)> = hits
+        .iter()
+        .map(|hit| {
+            (
+                hit.archive.as_deref(),
+                hit.path.file_name().unwrap().to_string_lossy().into_owned(),
+            )
+        })
+        .collect();
+    // Summaries first, then sessions newest first, across both archives.
+    assert_eq!(
+        found,
+        vec![
+            (Some("work"), "2026-05.monthly.md".to_string()),
+            (Some("work"), "2026-05-01T09-00-00-codex.md".to_string()),
+            (
+                Some("personal"),
+                "2026-04-20T09-00-00-claude.md".to_string()
+            ),
+            (
+                Some("personal"),
+                "2026-04-20T09-00-00-claude.md".to_string()
+            ),
+            (Some("personal"), "2026-04-02T09-00-00-codex.md".to_string()),
+        ]
+    );
+
+    let mut options = test_options();
+    options.tool = Some("codex".to_string());
+    options.limit = 1;
+    let hits = search_roots(&roots, "zepp", &options).unwrap();
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].archive.as_deref(), Some("work"));
+
+    // Only `personal` has April; `work` contributes nothing instead of failing.
+    let mut options = test_options();
+    options.from = Some("2026-04".to_string());
+    options.to = Some("2026-04".to_string());
+    let hits = search_roots(&roots, "zepp", &options).unwrap();
+    assert_eq!(hits.len(), 3);
+    assert!(
+        hits.iter()
+            .all(|hit| hit.archive.as_deref() == Some("personal"))
+    );
+    assert!(search_roots(&roots[1..], "zepp", &options).is_err());
+
+    let _ = fs::remove_dir_all(&base);
+}
+
+#[test]
+fn session_results_merge_across_roots() {
+    let (base, roots) = archives("sessions");
+    let mut options = test_options();
+    options.sessions = true;
+
+    let sessions = search_root_sessions(&roots, "zepp", &options).unwrap();
+    assert_eq!(sessions.len(), 3);
+    assert!(sessions.windows(2).all(|w| w[0].score >= w[1].score));
+    let archives: BTreeSet<_> = sessions
+        .iter()
+        .filter_map(|s| s.archive.as_deref())
+        .collect();
+    assert_eq!(archives, BTreeSet::from(["personal", "work"]));
+
+    let _ = fs::remove_dir_all(&base);
+}
+
+#[test]
+fn indexed_and_scanned_roots_interleave_by_rank() {
+    let (base, roots) = archives("mixed");
+    // Only `personal` has a fresh lexical index; `work` falls back to a scan.
+    let personal = &roots[0].path;
+    let catalog = crate::manifest::SessionCatalog::load(personal);
+    let files =
+        crate::index::files_to_index(personal, &crate::index::IndexOptions::default(), &catalog);
+    crate::lexical::update_lexical_index(personal, None, &files, &catalog).unwrap();
+
+    let mut options = test_options();
+    options.limit = 2;
+    let hits = search_roots(&roots, "zepp", &options).unwrap();
+    let archives: Vec<_> = hits.iter().map(|hit| hit.archive.as_deref()).collect();
+    assert_eq!(archives, vec![Some("personal"), Some("work")]);
+    assert!(hits[0].score.is_some() && hits[1].score.is_none());
+
+    let _ = fs::remove_dir_all(&base);
+}
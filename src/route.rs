@@ -0,0 +1,151 @@
+//! Per-project routing: which archive a session is written to.
+//!
+//! `[[routes]]` rules in config send sessions to separate output roots, each
+//! with its own training root, manifest, semantic index, and git settings.
+//! Rules match on project path prefix, tool, and git remote URL; every
+//! criterion a rule sets must match, the first matching rule wins, and
+//! unmatched sessions stay in the top-level `output`.
+//!
+//! # TRADE-OFFS
+//!
+//! - Routing needs the parsed session (project path, tool), so a new source is
+//!   always parsed before its destination is known. Up-to-date checks for known
+//!   sources use the manifest of whichever archive wrote them last.
+//! - Remote URLs come from `git remote -v` in the session's project directory
+//!   and are cached per project. Sessions whose project no longer exists on this
+//!   machine never match a `remote` rule.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::ast::Session;
+use crate::config::{Config, GitConfig, RouteConfig, expand_tilde};
+use crate::error::CassioError;
+
+/// An archive root sessions can be written to.
+#[derive(Debug, Clone)]
+pub struct Destination {
+    pub name: String,
+    pub output: PathBuf,
+    pub training_output: Option<PathBuf>,
+    pub git: GitConfig,
+}
+
+impl Destination {
+    /// Where `*.training.json` files go: the training root, or beside transcripts.
+    pub fn training_dir(&self) -> &Path {
+        self.training_output.as_deref().unwrap_or(&self.output)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Route {
+    pub project: Option<PathBuf>,
+    pub tool: Option<String>,
+    pub remote: Option<String>,
+    pub destination: Destination,
+}
+
+impl Route {
+    fn from_config(route: &RouteConfig, git: &GitConfig) -> Result<Self, CassioError> {
+        let label = route.name.as_deref().unwrap_or(&route.output);
+        if route.output.trim().is_empty() {
+            return Err(CassioError::Other(format!(
+                "Route '{label}' needs an output directory"
+            )));
+        }
+        if route.project.is_none() && route.tool.is_none() && route.remote.is_none() {
+            return Err(CassioError::Other(format!(
+                "Route '{label}' needs at least one of project, tool, or remote"
+            )));
+        }
+        Ok(Self {
+            project: route.project.as_deref().map(expand_tilde),
+            tool: route.tool.clone(),
+            remote: route.remote.clone(),
+            destination: Destination {
+                name: label.to_string(),
+                output: expand_tilde(&route.output),
+                training_output: route.training_output.as_deref().map(expand_tilde),
+                git: route.git.clone().unwrap_or_else(|| git.clone()),
+            },
+        })
+    }
+
+    /// True when every criterion the route sets matches. `remotes` is only
+    /// called when the route has a `remote` criterion.
+    pub fn matches(
+        &self,
+        project_path: &str,
+        tool: &str,
+        remotes: impl FnOnce() -> Vec<String>,
+    ) -> bool {
+        if let Some(prefix) = &self.project
+            && (project_path.is_empty() || !Path::new(project_path).starts_with(prefix))
+        {
+            return false;
+        }
+        if let Some(wanted) = &self.tool
+            && !wanted.eq_ignore_ascii_case(tool)
+        {
+            return false;
+        }
+        if let Some(needle) = &self.remote {
+            let needle = needle.to_lowercase();
+            return remotes()
+                .iter()
+                .any(|url| url.to_lowercase().contains(&needle));
+        }
+        true
+    }
+}
+
+/// The configured routes, with a per-project cache of git remotes.
+#[derive(Debug, Default)]
+pub struct Router {
+    routes: Vec<Route>,
+    remotes: RefCell<HashMap<String, Vec<String>>>,
+}
+
+impl Router {
+    pub fn from_config(config: &Config) -> Result<Self, CassioError> {
+        let routes = config
+            .routes
+            .iter()
+            .map(|route| Route::from_config(route, &config.git))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self {
+            routes,
+            remotes: RefCell::default(),
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
+
+    pub fn routes(&self) -> &[Route] {
+        &self.routes
+    }
+
+    /// Index of the first route matching `session`, or `None` for the default
+    /// output.
+    pub fn route_for(&self, session: &Session) -> Option<usize> {
+        let meta = &session.metadata;
+        let tool = meta.tool.to_string();
+        self.routes.iter().position(|route| {
+            route.matches(&meta.project_path, &tool, || {
+                self.remotes
+                    .borrow_mut()
+                    .entry(meta.project_path.clone())
+                    .or_insert_with(|| crate::git::remote_urls(Path::new(&meta.project_path)))
+                    .clone()
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+#[path = "route_test.rs"]
+mod tests;
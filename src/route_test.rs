@@ -0,0 +1,69 @@
+use super::*;
+
+fn route(project: Option<&str>, tool: Option<&str>, remote: Option<&str>) -> RouteConfig {
+    RouteConfig {
+        name: Some("acme".to_string()),
+        project: project.map(str::to_string),
+        tool: tool.map(str::to_string),
+        remote: remote.map(str::to_string),
+        output: "/archives/acme".to_string(),
+        ..Default::default()
+    }
+}
+
+fn no_remotes() -> Vec<String> {
+    panic!("remotes must not be read without a remote criterion")
+}
+
+#[test]
+fn project_prefix_matches_whole_components() {
+    let route = Route::from_config(
+        &route(Some("/work/acme"), None, None),
+        &GitConfig::default(),
+    )
+    .unwrap();
+    assert!(route.matches("/work/acme", "claude", no_remotes));
+    assert!(route.matches("/work/acme/api", "codex", no_remotes));
+    assert!(!route.matches("/work/acme-old", "claude", no_remotes));
+    assert!(!route.matches("", "claude", no_remotes));
+}
+
+#[test]
+fn every_set_criterion_must_match() {
+    let route = Route::from_config(
+        &route(Some("/work"), Some("Codex"), Some("github.com/acme/")),
+        &GitConfig::default(),
+    )
+    .unwrap();
+    let acme = || vec!["git@GitHub.com:acme/api.git".to_string()];
+    assert!(!route.matches("/work/api", "codex", acme));
+    let acme = || vec!["https://github.com/acme/api".to_string()];
+    assert!(route.matches("/work/api", "codex", acme));
+    assert!(!route.matches("/work/api", "claude", acme));
+    assert!(!route.matches("/work/api", "codex", Vec::new));
+}
+
+#[test]
+fn routes_inherit_top_level_git_and_need_a_criterion() {
+    let git = GitConfig {
+        commit: true,
+        push: true,
+    };
+    let inherited = Route::from_config(&route(None, Some("pi"), None), &git).unwrap();
+    assert!(inherited.destination.git.push);
+    assert_eq!(inherited.destination.name, "acme");
+    assert_eq!(
+        inherited.destination.training_dir(),
+        Path::new("/archives/acme")
+    );
+
+    let mut own = route(None, Some("pi"), None);
+    own.git = Some(GitConfig {
+        commit: true,
+        push: false,
+    });
+    let own = Route::from_config(&own, &git).unwrap();
+    assert!(!own.destination.git.push);
+
+    assert!(Route::from_config(&route(None, None, None), &git).is_err());
+}
@@ -3,6 +3,9 @@
 //! Text search walks monthly summaries, daily compactions, session transcripts,
 //! and optional training JSON. Results are newest-first by default so unconstrained
 //! searches surface recent material instead of the oldest months in the archive.
+//! When `cassio index` has built a fresh FTS5 lexical index (`lexical`), literal
+//! queries run against it instead and come back BM25-ranked. Hybrid search runs
+//! both retrievers and merges their rankings with reciprocal rank fusion.
 //! Filters narrow the walk by date range (`--from`/`--to`), agent (`--tool`),
 //! project header (`--project`), and speaker role (`--speaker`). Semantic search
 //! reuses the SQLite index built by `cassio index` and ranks chunks by cosine
@@ -13,7 +16,7 @@ use std::io::Read;
 use std::path::{Path, PathBuf};
 
 use regex::RegexBuilder;
-use rusqlite::{Connection, params};
+use rusqlite::Connection;
 use serde::Serialize;
 use walkdir::WalkDir;
 
@@ -22,7 +25,15 @@ use crate::error::CassioError;
 use crate::formatter::emoji_text::{
     EMOJI_ASSISTANT, EMOJI_FAILURE, EMOJI_META, EMOJI_QUEUE, EMOJI_SUCCESS, EMOJI_USER,
 };
-use crate::index;
+use crate::frontmatter;
+use crate::index::{self, IndexOptions};
+use crate::lexical;
+use crate::manifest::SessionCatalog;
+use crate::query::{self, ParsedQuery, TermGroup};
+use crate::related;
+use crate::rerank;
+use crate::roots::{self, SearchRoot};
+use crate::session_search;
 
 #[derive(Debug, Clone)]
 pub struct SearchOptions {
@@ -34,6 +45,9 @@ pub struct SearchOptions {
     pub tool: Option<String>,
     /// Restrict to sessions whose `📋 Project:` header contains this substring.
     pub project: Option<String>,
+    /// Restrict to sessions with a `📋 Model:` line containing this substring
+    /// (set through the `model:` query filter).
+    pub session_model: Option<String>,
     /// Match only lines spoken by this role in session transcripts.
     pub speaker: Option<Speaker>,
     pub limit: usize,
@@ -52,6 +66,17 @@ pub struct SearchOptions {
     pub semantic: Option<SemanticSearchOptions>,
     /// Separate root for `*.training.json` when not co-located under `root`.
     pub training_root: Option<PathBuf>,
+    /// Line-scan files even when a fresh lexical index exists.
+    pub scan: bool,
+    /// Fuse lexical and semantic results (`semantic` supplies the embedding
+    /// settings).
+    pub hybrid: bool,
+    /// Reranker GGUF that rescores the top semantic or hybrid hits
+    /// (`--rerank`; see `rerank`).
+    pub rerank_model: Option<PathBuf>,
+    /// Group hits per session transcript and rank the sessions
+    /// (see `session_search`).
+    pub sessions: bool,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
@@ -78,7 +103,7 @@ impl std::str::FromStr for Speaker {
 }
 
 impl Speaker {
-    fn name(self) -> &'static str {
+    pub(crate) fn name(self) -> &'static str {
         match self {
             Self::User => "user",
             Self::Assistant => "assistant",
@@ -101,6 +126,8 @@ pub struct SemanticSearchOptions {
     pub model: String,
     pub base_url: String,
     pub timeout_secs: u64,
+    /// Score every indexed chunk instead of probing the nearest IVF clusters.
+    pub exact: bool,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
@@ -124,6 +151,42 @@ pub struct SearchHit {
     pub score: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub context: Option<Vec<SearchContextLine>>,
+    /// Hybrid search only: which retrievers ranked this hit, and where.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retrievers: Option<Vec<RetrieverRank>>,
+    /// Cross-encoder relevance when `--rerank` rescored this hit; `score`
+    /// keeps the retriever's own value.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rerank_score: Option<f32>,
+    /// Archive label when several roots were searched (see `roots`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub archive: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Retriever {
+    Lexical,
+    Semantic,
+}
+
+impl Retriever {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Lexical => "lexical",
+            Self::Semantic => "semantic",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RetrieverRank {
+    pub retriever: Retriever,
+    /// 1-based position in that retriever's own result list.
+    pub rank: usize,
+    /// The retriever's native score (BM25 or cosine), when it has one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<f32>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -139,7 +202,7 @@ pub struct SearchContextLine {
 /// (sessions, dailies, training). `YYYY-MM` and `YYYY-MM-DD` compare correctly
 /// as plain strings.
 #[derive(Debug, Clone, Default)]
-struct DateBounds {
+pub(crate) struct DateBounds {
     from: Option<Bound>,
     to: Option<Bound>,
 }
@@ -173,7 +236,7 @@ impl Bound {
 }
 
 impl DateBounds {
-    fn parse(from: Option<&str>, to: Option<&str>) -> Result<Self, CassioError> {
+    pub(crate) fn parse(from: Option<&str>, to: Option<&str>) -> Result<Self, CassioError> {
         Ok(Self {
             from: from.map(Bound::parse).transpose()?,
             to: to.map(Bound::parse).transpose()?,
@@ -195,7 +258,7 @@ impl DateBounds {
         true
     }
 
-    fn contains(&self, month_dir: &str, date: Option<&str>) -> bool {
+    pub(crate) fn contains(&self, month_dir: &str, date: Option<&str>) -> bool {
         if !self.month_in_range(month_dir) {
             return false;
         }
@@ -227,8 +290,9 @@ fn is_day(s: &str) -> bool {
 
 #[derive(Debug)]
 enum Matcher {
+    /// Inline query syntax: a line matches when any group matches.
     Terms {
-        terms: Vec<String>,
+        groups: Vec<TermGroup>,
         case_sensitive: bool,
     },
     Regex(regex::Regex),
@@ -244,17 +308,20 @@ impl Matcher {
                 .map_err(|e| CassioError::Other(format!("Invalid search regex: {e}")));
         }
 
-        let terms: Vec<_> = if case_sensitive {
-            query.split_whitespace().map(str::to_string).collect()
-        } else {
-            query.split_whitespace().map(normalize_term).collect()
-        };
-        if terms.is_empty() {
+        let mut groups = query::parse(query)?.groups;
+        if groups.is_empty() {
             return Err(CassioError::Other("Search query cannot be empty".into()));
         }
+        if !case_sensitive {
+            for group in &mut groups {
+                for term in group.include.iter_mut().chain(group.exclude.iter_mut()) {
+                    *term = normalize_term(term);
+                }
+            }
+        }
 
         Ok(Self::Terms {
-            terms,
+            groups,
             case_sensitive,
         })
     }
@@ -262,7 +329,7 @@ impl Matcher {
     fn is_match(&self, line: &str) -> bool {
         match self {
             Self::Terms {
-                terms,
+                groups,
                 case_sensitive,
             } => {
                 let haystack = if *case_sensitive {
@@ -270,33 +337,51 @@ impl Matcher {
                 } else {
                     line.to_lowercase()
                 };
-                terms.iter().all(|term| haystack.contains(term))
+                groups.iter().any(|group| {
+                    group.include.iter().all(|term| haystack.contains(term))
+                        && !group.exclude.iter().any(|term| haystack.contains(term))
+                })
             }
             Self::Regex(regex) => regex.is_match(line),
         }
     }
 }
 
-pub fn run_search(root: &Path, query: &str, options: SearchOptions) -> Result<(), CassioError> {
-    let hits = search(root, query, &options)?;
+pub fn run_search(
+    roots: &[SearchRoot],
+    query: &str,
+    options: SearchOptions,
+) -> Result<(), CassioError> {
+    if options.sessions {
+        let sessions = roots::search_root_sessions(roots, query, &options)?;
+        if options.json {
+            serde_json::to_writer_pretty(std::io::stdout(), &sessions)?;
+            println!();
+        } else {
+            print_search_header(roots, query, &options);
+            session_search::print_sessions(roots, &sessions);
+        }
+        return Ok(());
+    }
+    let hits = roots::search_roots(roots, query, &options)?;
 
     if options.json {
         if options.count {
             serde_json::to_writer_pretty(
                 std::io::stdout(),
-                &count_entries(root, &hits, options.limit),
+                &count_entries(roots, &hits, options.limit),
             )?;
         } else if options.files_with_matches {
             serde_json::to_writer_pretty(
                 std::io::stdout(),
-                &matching_file_paths(root, &hits, options.limit),
+                &matching_file_paths(roots, &hits, options.limit),
             )?;
         } else {
             serde_json::to_writer_pretty(std::io::stdout(), &hits)?;
         }
         println!();
     } else {
-        print_hits(root, query, &options, &hits);
+        print_hits(roots, query, &options, &hits);
     }
 
     Ok(())
@@ -306,30 +391,75 @@ pub fn search(
     root: &Path,
     query: &str,
     options: &SearchOptions,
+) -> Result<Vec<SearchHit>, CassioError> {
+    search_root(root, query, options, false)
+}
+
+/// `search`, where `allow_missing_month` turns a missing `--from X --to X`
+/// month directory into no hits instead of an error (one of several roots).
+pub(crate) fn search_root(
+    root: &Path,
+    query: &str,
+    options: &SearchOptions,
+    allow_missing_month: bool,
 ) -> Result<Vec<SearchHit>, CassioError> {
     if options.limit == 0 {
         return Ok(Vec::new());
     }
 
+    // Inline filters (`tool:codex after:2026-06`) become ordinary options; a
+    // regex query is taken verbatim.
+    let parsed = if options.regex {
+        None
+    } else {
+        Some(query::parse(query)?)
+    };
+    let mut resolved = options.clone();
+    if let Some(parsed) = &parsed {
+        parsed.apply_filters(&mut resolved)?;
+    }
+    let options = &resolved;
+
     validate_options(options)?;
     let bounds = DateBounds::parse(options.from.as_deref(), options.to.as_deref())?;
 
     if options.semantic.is_some() {
-        return semantic_search(root, query, options, &bounds);
+        // Reranking rescores a deeper candidate list than it returns.
+        let mut candidates = options.clone();
+        if options.rerank_model.is_some() {
+            candidates.limit = options.limit.max(rerank::RERANK_DEPTH);
+        }
+        let text = parsed.as_ref().map_or(query.to_string(), ParsedQuery::text);
+        let hits = if options.hybrid {
+            hybrid_search(root, query, &candidates, &bounds, allow_missing_month)?
+        } else {
+            semantic_search(root, &text, &candidates, &bounds)?
+        };
+        return match &options.rerank_model {
+            Some(model) => rerank::rerank_hits(model, &text, hits, candidates.limit, options.limit),
+            None => Ok(hits),
+        };
     }
 
     // `--from X --to X` with a month-only X resolves to that month directory so a
     // missing month still produces the friendly "Search target does not exist"
     // error instead of an empty walk of the root.
+    let catalog = session_catalog(root, options);
+    // A templated archive has no `YYYY-MM` directories to narrow into, so only
+    // take the shortcut when the month directory exists or nothing is cataloged.
     let single_month = options
         .from
         .as_deref()
-        .filter(|f| options.to.as_deref() == Some(f) && is_month(f));
+        .filter(|f| options.to.as_deref() == Some(f) && is_month(f))
+        .filter(|month| catalog.is_empty() || root.join(month).is_dir());
     let target = match single_month {
         Some(month) => root.join(month),
         None => root.to_path_buf(),
     };
     if !target.exists() {
+        if allow_missing_month && single_month.is_some() && root.is_dir() {
+            return Ok(Vec::new());
+        }
         return Err(CassioError::Other(format!(
             "Search target does not exist: {}",
             target.display()
@@ -339,18 +469,27 @@ pub fn search(
     let walk_bounds = if single_month.is_some() {
         DateBounds::default()
     } else {
-        bounds
+        bounds.clone()
     };
 
     let matcher = Matcher::new(query, options.regex, options.case_sensitive)?;
+    if let Some(hits) = indexed_search(root, query, options, &bounds, &matcher, &catalog)? {
+        return Ok(hits);
+    }
     let mut hits = Vec::new();
     // --count and --files-with-matches report on every file, so the per-file
     // limit early-exit must not apply; the file list is capped at output time.
     let scan_all = options.count || options.files_with_matches;
 
     for artifact in artifact_order(options) {
-        for path in files_for_artifact_with_options(root, &target, artifact, options, &walk_bounds)
-        {
+        for path in files_for_artifact_with_options(
+            &target,
+            artifact,
+            options,
+            single_month,
+            &walk_bounds,
+            &catalog,
+        ) {
             search_file(&path, artifact, &matcher, options, &mut hits)?;
             if !scan_all && hits.len() >= options.limit {
                 return Ok(hits);
@@ -367,11 +506,35 @@ fn validate_options(options: &SearchOptions) -> Result<(), CassioError> {
             "--count and --files-with-matches cannot be combined".into(),
         ));
     }
-    if options.regex && options.semantic.is_some() {
+    if options.hybrid {
+        if options.semantic.is_none() {
+            return Err(CassioError::Other(
+                "--hybrid needs semantic embedding settings".into(),
+            ));
+        }
+        if options.count || options.files_with_matches {
+            return Err(CassioError::Other(
+                "--hybrid cannot be combined with --count or --files-with-matches".into(),
+            ));
+        }
+    }
+    if options.rerank_model.is_some() && options.semantic.is_none() {
+        return Err(CassioError::Other(
+            "--rerank needs --semantic or --hybrid".into(),
+        ));
+    }
+    // Hybrid runs the lexical retriever with --regex/--scan.
+    let semantic_only = options.semantic.is_some() && !options.hybrid;
+    if options.regex && semantic_only {
         return Err(CassioError::Other(
             "--semantic cannot be combined with --regex".into(),
         ));
     }
+    if options.scan && semantic_only {
+        return Err(CassioError::Other(
+            "--scan cannot be combined with --semantic".into(),
+        ));
+    }
     if let Some(tool) = options.tool.as_deref()
         && !SESSION_TOOL_SUFFIXES
             .iter()
@@ -400,12 +563,19 @@ fn validate_options(options: &SearchOptions) -> Result<(), CassioError> {
                 "--project cannot be combined with --include-training".into(),
             ));
         }
-        if options.semantic.is_some() {
+    }
+    if options.session_model.is_some() {
+        if options.summaries_only {
             return Err(CassioError::Other(
-                "--project cannot be combined with --semantic (index chunks carry no project)"
+                "model: cannot be combined with --summaries-only (summaries aggregate models)"
                     .into(),
             ));
         }
+        if options.include_training {
+            return Err(CassioError::Other(
+                "model: cannot be combined with --include-training".into(),
+            ));
+        }
     }
     if options.speaker.is_some() {
         if options.summaries_only {
@@ -420,17 +590,15 @@ fn validate_options(options: &SearchOptions) -> Result<(), CassioError> {
                     .into(),
             ));
         }
-        if options.semantic.is_some() {
-            return Err(CassioError::Other(
-                "--speaker cannot be combined with --semantic (index chunks carry no speaker)"
-                    .into(),
-            ));
-        }
     }
     Ok(())
 }
 
-fn artifact_order(options: &SearchOptions) -> Vec<SearchArtifact> {
+pub(crate) fn artifact_order(options: &SearchOptions) -> Vec<SearchArtifact> {
+    // Session mode ranks transcripts, so summaries are never candidates.
+    if options.sessions {
+        return vec![SearchArtifact::Session];
+    }
     let mut order = vec![SearchArtifact::Monthly, SearchArtifact::Daily];
     if !options.summaries_only {
         order.push(SearchArtifact::Session);
@@ -441,18 +609,24 @@ fn artifact_order(options: &SearchOptions) -> Vec<SearchArtifact> {
     order
 }
 
+/// Manifest-backed date/tool lookup for artifacts under `root` and the
+/// training root, for archives written with a custom `output_template`.
+pub(crate) fn session_catalog(root: &Path, options: &SearchOptions) -> SessionCatalog {
+    let catalog = SessionCatalog::load(root);
+    match &options.training_root {
+        Some(training_root) => catalog.with_root(training_root),
+        None => catalog,
+    }
+}
+
 fn files_for_artifact_with_options(
-    root: &Path,
     target: &Path,
     artifact: SearchArtifact,
     options: &SearchOptions,
+    single_month: Option<&str>,
     bounds: &DateBounds,
+    catalog: &SessionCatalog,
 ) -> Vec<PathBuf> {
-    let single_month = options
-        .from
-        .as_deref()
-        .filter(|f| options.to.as_deref() == Some(f) && is_month(f));
-
     let base = if artifact == SearchArtifact::Training {
         if let Some(training_root) = &options.training_root {
             let training_target = match single_month {
@@ -472,12 +646,11 @@ fn files_for_artifact_with_options(
     } else {
         // Keep non-training walks on the transcript tree only so a separate
         // training_root is never scanned for markdown.
-        let _ = root;
         target.to_path_buf()
     };
 
-    let mut paths = files_for_artifact(&base, artifact, bounds);
-    paths.retain(|path| artifact_in_walk_scope(path, &base, artifact, options, bounds));
+    let mut paths = files_for_artifact(&base, artifact, bounds, catalog);
+    paths.retain(|path| artifact_in_walk_scope(path, &base, artifact, options, bounds, catalog));
     if options.oldest_first {
         paths.sort();
     } else {
@@ -488,11 +661,19 @@ fn files_for_artifact_with_options(
     paths
 }
 
-fn files_for_artifact(root: &Path, artifact: SearchArtifact, bounds: &DateBounds) -> Vec<PathBuf> {
+fn files_for_artifact(
+    root: &Path,
+    artifact: SearchArtifact,
+    bounds: &DateBounds,
+    catalog: &SessionCatalog,
+) -> Vec<PathBuf> {
     let mut paths = Vec::new();
     // Prune out-of-range `YYYY-MM` directories during the walk so a range
     // search over a multi-year archive never descends into every month.
     let walker = WalkDir::new(root).into_iter().filter_entry(|entry| {
+        if crate::manifest::is_state_dir(entry) {
+            return false;
+        }
         if entry.file_type().is_dir()
             && let Some(name) = entry.file_name().to_str()
             && is_month(name)
@@ -506,7 +687,7 @@ fn files_for_artifact(root: &Path, artifact: SearchArtifact, bounds: &DateBounds
         if !path.is_file() {
             continue;
         }
-        if artifact_for_path(path) == Some(artifact) {
+        if cataloged_artifact_for_path(path, catalog) == Some(artifact) {
             paths.push(path.to_path_buf());
         }
     }
@@ -520,18 +701,19 @@ fn artifact_in_walk_scope(
     artifact: SearchArtifact,
     options: &SearchOptions,
     bounds: &DateBounds,
+    catalog: &SessionCatalog,
 ) -> bool {
-    // Monthly/daily summaries aggregate every tool, project, and speaker, so
-    // where/what filters cannot apply to them. Training JSON has no speaker
-    // lines and project filtering on it is not implemented.
+    // Monthly/daily summaries aggregate every tool, project, model, and
+    // speaker, so where/what filters cannot apply to them. Training JSON has no
+    // speaker lines and project/model filtering on it is not implemented.
+    let session_only =
+        options.project.is_some() || options.session_model.is_some() || options.speaker.is_some();
     if matches!(artifact, SearchArtifact::Monthly | SearchArtifact::Daily)
-        && (options.tool.is_some() || options.project.is_some() || options.speaker.is_some())
+        && (options.tool.is_some() || session_only)
     {
         return false;
     }
-    if artifact == SearchArtifact::Training
-        && (options.project.is_some() || options.speaker.is_some())
-    {
+    if artifact == SearchArtifact::Training && session_only {
         return false;
     }
 
@@ -540,9 +722,14 @@ fn artifact_in_walk_scope(
     {
         return false;
     }
+    let cataloged = catalog.lookup(path);
     if let Some(tool) = options.tool.as_deref() {
         let stem = file_stem(path);
-        if !session_tool_suffix(&stem).is_some_and(|suffix| suffix.eq_ignore_ascii_case(tool)) {
+        let file_tool = match cataloged {
+            Some(session) => Some(session.tool.as_str()),
+            None => session_tool_suffix(&stem),
+        };
+        if !file_tool.is_some_and(|file_tool| file_tool.eq_ignore_ascii_case(tool)) {
             return false;
         }
     }
@@ -552,10 +739,22 @@ fn artifact_in_walk_scope(
     {
         return false;
     }
+    if let Some(model) = options.session_model.as_deref()
+        && !fs::read_to_string(path).is_ok_and(|content| models_match(&content, model))
+    {
+        return false;
+    }
 
-    match month_dir_of(path, base) {
+    let (month, date) = match cataloged {
+        Some(session) => (
+            Some(session.month().to_string()),
+            Some(session.date.clone()),
+        ),
+        None => (month_dir_of(path, base), file_date_of(path)),
+    };
+    match month {
         Some(month) => {
-            if !bounds.contains(&month, file_date_of(path).as_deref()) {
+            if !bounds.contains(&month, date.as_deref()) {
                 return false;
             }
         }
@@ -570,7 +769,7 @@ fn artifact_in_walk_scope(
     true
 }
 
-fn file_stem(path: &Path) -> String {
+pub(crate) fn file_stem(path: &Path) -> String {
     path.file_name()
         .and_then(|n| n.to_str())
         .map(|n| {
@@ -584,14 +783,14 @@ fn file_stem(path: &Path) -> String {
 }
 
 /// `YYYY-MM` directory a file lives under, relative to its walk base.
-fn month_dir_of(path: &Path, base: &Path) -> Option<String> {
+pub(crate) fn month_dir_of(path: &Path, base: &Path) -> Option<String> {
     let rel = path.strip_prefix(base).ok()?;
     let first = rel.components().next()?.as_os_str().to_str()?;
     (is_month(first)).then(|| first.to_string())
 }
 
 /// `YYYY-MM-DD` prefix of a session/daily/training filename, if any.
-fn file_date_of(path: &Path) -> Option<String> {
+pub(crate) fn file_date_of(path: &Path) -> Option<String> {
     let stem = file_stem(path);
     let date = stem.get(..10)?;
     (is_day(date)).then(|| date.to_string())
@@ -600,7 +799,9 @@ fn file_date_of(path: &Path) -> Option<String> {
 /// Substring (case-insensitive) match against the session's `📋 Project:` header.
 ///
 /// The metadata block sits at the top of a transcript, so a bounded read keeps
-/// project filtering cheap without loading whole sessions.
+/// project filtering cheap without loading whole sessions. The frontmatter
+/// `project_path` is preferred; a block longer than the bounded read is read to
+/// its end.
 fn project_header_matches(path: &Path, needle: &str) -> bool {
     let Ok(file) = fs::File::open(path) else {
         return false;
@@ -610,7 +811,13 @@ fn project_header_matches(path: &Path, needle: &str) -> bool {
     let _ = std::io::Read::by_ref(&mut reader)
         .take(8192)
         .read_to_string(&mut header);
+    if header.starts_with("---") && frontmatter::split(&header).0.is_none() {
+        let _ = std::io::Read::read_to_string(&mut reader, &mut header);
+    }
     let needle = needle.to_lowercase();
+    if let Some(meta) = frontmatter::parse(&header) {
+        return meta.project_path.to_lowercase().contains(&needle);
+    }
     header.lines().any(|line| {
         line.starts_with(EMOJI_META)
             && line.contains("Project:")
@@ -618,6 +825,47 @@ fn project_header_matches(path: &Path, needle: &str) -> bool {
     })
 }
 
+/// The `📋 Project:` header value of a session transcript.
+pub(crate) fn header_project(content: &str) -> Option<String> {
+    if let Some(meta) = frontmatter::parse(content) {
+        return Some(meta.project_path).filter(|project| !project.is_empty());
+    }
+    frontmatter::body(content)
+        .lines()
+        .take_while(|line| !line.starts_with(EMOJI_USER) && !line.starts_with(EMOJI_ASSISTANT))
+        .filter(|line| line.starts_with(EMOJI_META))
+        .find_map(|line| line.split_once("Project:"))
+        .map(|(_, project)| project.trim().to_string())
+        .filter(|project| !project.is_empty())
+}
+
+/// Distinct `📋 Model:` values in a transcript, in order of appearance.
+///
+/// Model changes are recorded inline as the session goes, and Codex records
+/// its model in the summary block, so the whole transcript is read.
+pub(crate) fn session_models(content: &str) -> Vec<String> {
+    let mut models: Vec<String> = Vec::new();
+    for line in content.lines() {
+        if let Some(rest) = line.strip_prefix(EMOJI_META)
+            && let Some(model) = rest.trim_start().strip_prefix("Model:")
+        {
+            let model = model.trim();
+            if !model.is_empty() && !models.iter().any(|seen| seen == model) {
+                models.push(model.to_string());
+            }
+        }
+    }
+    models
+}
+
+/// Case-insensitive substring match of `needle` against any session model.
+fn models_match(content: &str, needle: &str) -> bool {
+    let needle = needle.to_lowercase();
+    session_models(content)
+        .iter()
+        .any(|model| model.to_lowercase().contains(&needle))
+}
+
 pub(crate) fn artifact_for_path(path: &Path) -> Option<SearchArtifact> {
     let name = path.file_name()?.to_str()?;
     if name.ends_with(".monthly.md") {
@@ -633,6 +881,19 @@ pub(crate) fn artifact_for_path(path: &Path) -> Option<SearchArtifact> {
     }
 }
 
+/// [`artifact_for_path`], also treating transcripts the manifest records under a
+/// custom `output_template` as sessions.
+pub(crate) fn cataloged_artifact_for_path(
+    path: &Path,
+    catalog: &SessionCatalog,
+) -> Option<SearchArtifact> {
+    artifact_for_path(path).or_else(|| {
+        let name = path.file_name()?.to_str()?;
+        let transcript = name.ends_with(".md") || name.ends_with(".txt");
+        (transcript && catalog.lookup(path).is_some()).then_some(SearchArtifact::Session)
+    })
+}
+
 fn is_session_markdown_name(name: &str) -> bool {
     let stem = name
         .strip_suffix(".md")
@@ -656,7 +917,7 @@ fn is_session_markdown_name(name: &str) -> bool {
 /// Only the first line of a message carries its prefix; continuation lines have
 /// none and inherit the enclosing block's speaker.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum LineSpeaker {
+pub(crate) enum LineSpeaker {
     User,
     Assistant,
     Tool,
@@ -677,7 +938,7 @@ fn line_speaker(line: &str) -> Option<LineSpeaker> {
     }
 }
 
-fn block_speakers(lines: &[&str]) -> Vec<LineSpeaker> {
+pub(crate) fn block_speakers(lines: &[&str]) -> Vec<LineSpeaker> {
     let mut current = LineSpeaker::Other;
     lines
         .iter()
@@ -699,6 +960,7 @@ fn search_file(
 ) -> Result<(), CassioError> {
     let content = fs::read_to_string(path)?;
     let lines: Vec<&str> = content.lines().collect();
+    let frontmatter_lines = frontmatter::line_count(&content);
 
     let speakers = options.speaker.map(|speaker| {
         let wanted = speaker.line_kind();
@@ -709,12 +971,16 @@ fn search_file(
     });
 
     let mut match_lines = Vec::new();
-    for (index, line) in lines.iter().enumerate() {
+    for (index, line) in lines.iter().enumerate().skip(frontmatter_lines) {
         if let Some(ref wanted) = speakers
             && !wanted[index]
         {
             continue;
         }
+        // The Related footer describes other sessions, not this one.
+        if related::is_footer_line(line) {
+            continue;
+        }
         let searchable = if options.include_paths {
             (*line).to_string()
         } else {
@@ -769,38 +1035,45 @@ fn search_file(
             text: truncate_line(lines[index].trim(), 280),
             score: None,
             context: (!context.is_empty()).then_some(context),
+            retrievers: None,
+            rerank_score: None,
+            archive: None,
         });
     }
     Ok(())
 }
 
-fn print_hits(root: &Path, query: &str, options: &SearchOptions, hits: &[SearchHit]) {
+fn print_hits(roots: &[SearchRoot], query: &str, options: &SearchOptions, hits: &[SearchHit]) {
     if options.count {
-        print_counts(root, hits, options.limit);
+        print_counts(roots, hits, options.limit);
         return;
     }
     if options.files_with_matches {
-        print_matching_files(root, hits, options.limit);
+        print_matching_files(roots, hits, options.limit);
         return;
     }
 
-    let scope = scope_label(options);
-    println!(
-        "cassio search: {:?} in {} ({})",
-        query,
-        root.display(),
-        scope
-    );
+    print_search_header(roots, query, options);
 
     if hits.is_empty() {
         println!("No matches.");
         return;
     }
 
-    if options.semantic.is_some() {
-        println!("\n== semantic matches ==");
+    // Ranked results interleave artifacts, so they print as one list.
+    let ranked = if options.hybrid {
+        Some("hybrid matches (reciprocal rank fusion)")
+    } else if options.semantic.is_some() {
+        Some("semantic matches")
+    } else if hits.iter().any(|hit| hit.score.is_some()) {
+        Some("ranked matches (bm25)")
+    } else {
+        None
+    };
+    if let Some(label) = ranked {
+        println!("\n== {label} ==");
         for hit in hits {
-            print_hit(root, hit);
+            print_hit(roots, hit);
         }
         return;
     }
@@ -811,10 +1084,19 @@ fn print_hits(root: &Path, query: &str, options: &SearchOptions, hits: &[SearchH
             println!("\n== {} ==", artifact_label(hit.artifact));
             last_artifact = Some(hit.artifact);
         }
-        print_hit(root, hit);
+        print_hit(roots, hit);
     }
 }
 
+fn print_search_header(roots: &[SearchRoot], query: &str, options: &SearchOptions) {
+    println!(
+        "cassio search: {:?} in {} ({})",
+        query,
+        roots::describe_roots(roots),
+        scope_label(options)
+    );
+}
+
 fn scope_label(options: &SearchOptions) -> String {
     let when = match (&options.from, &options.to) {
         (Some(from), Some(to)) if from == to => from.clone(),
@@ -830,27 +1112,43 @@ fn scope_label(options: &SearchOptions) -> String {
     if let Some(project) = &options.project {
         parts.push(format!("project={project}"));
     }
+    if let Some(model) = &options.session_model {
+        parts.push(format!("model={model}"));
+    }
     if let Some(speaker) = options.speaker {
         parts.push(format!("speaker={}", speaker.name()));
     }
     parts.join(", ")
 }
 
-fn print_hit(root: &Path, hit: &SearchHit) {
-    let display_path = hit.path.strip_prefix(root).unwrap_or(&hit.path);
+fn print_hit(roots: &[SearchRoot], hit: &SearchHit) {
+    let display_path = roots::display_path(roots, hit.archive.as_deref(), &hit.path);
     let line = match hit.line_end {
         Some(end) if end > hit.line => format!("{}-{}", hit.line, end),
         _ => hit.line.to_string(),
     };
-    if let Some(score) = hit.score {
-        println!(
-            "{}:{} [{score:.3}]: {}",
-            display_path.display(),
-            line,
-            hit.text
-        );
+    let mut label = if let Some(retrievers) = &hit.retrievers {
+        let sources: Vec<_> = retrievers
+            .iter()
+            .map(|source| format!("{}#{}", source.retriever.name(), source.rank))
+            .collect();
+        Some(format!(
+            "{:.4} {}",
+            hit.score.unwrap_or_default(),
+            sources.join(" ")
+        ))
     } else {
-        println!("{}:{}: {}", display_path.display(), line, hit.text);
+        hit.score.map(|score| format!("{score:.3}"))
+    };
+    if let Some(rerank) = hit.rerank_score {
+        label = Some(match label {
+            Some(label) => format!("rerank {rerank:.3}, {label}"),
+            None => format!("rerank {rerank:.3}"),
+        });
+    }
+    match label {
+        Some(label) => println!("{display_path}:{line} [{label}]: {}", hit.text),
+        None => println!("{display_path}:{line}: {}", hit.text),
     }
     if let Some(context) = &hit.context {
         for ctx in context {
@@ -859,43 +1157,54 @@ fn print_hit(root: &Path, hit: &SearchHit) {
     }
 }
 
-fn print_counts(root: &Path, hits: &[SearchHit], limit: usize) {
-    for entry in count_entries(root, hits, limit) {
-        println!("{}: {}", entry.path, entry.count);
+fn print_counts(roots: &[SearchRoot], hits: &[SearchHit], limit: usize) {
+    for entry in count_entries(roots, hits, limit) {
+        match &entry.archive {
+            Some(archive) => println!("[{archive}] {}: {}", entry.path, entry.count),
+            None => println!("{}: {}", entry.path, entry.count),
+        }
     }
 }
 
-fn print_matching_files(root: &Path, hits: &[SearchHit], limit: usize) {
-    for path in matching_file_paths(root, hits, limit) {
+fn print_matching_files(roots: &[SearchRoot], hits: &[SearchHit], limit: usize) {
+    for path in matching_file_paths(roots, hits, limit) {
         println!("{path}");
     }
 }
 
 #[derive(Debug, Serialize)]
 struct CountEntry {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    archive: Option<String>,
     path: String,
     count: usize,
 }
 
-fn count_entries(root: &Path, hits: &[SearchHit], limit: usize) -> Vec<CountEntry> {
+fn count_entries(roots: &[SearchRoot], hits: &[SearchHit], limit: usize) -> Vec<CountEntry> {
     let mut entries: Vec<CountEntry> = Vec::new();
     for hit in hits {
-        let path = hit.path.strip_prefix(root).unwrap_or(&hit.path);
-        let path = path.display().to_string();
-        match entries.iter_mut().find(|e| e.path == path) {
+        // Relative to the hit's root; the archive is a separate field.
+        let path = roots::display_path(roots, None, &hit.path);
+        match entries
+            .iter_mut()
+            .find(|e| e.path == path && e.archive == hit.archive)
+        {
             Some(entry) => entry.count += 1,
-            None => entries.push(CountEntry { path, count: 1 }),
+            None => entries.push(CountEntry {
+                archive: hit.archive.clone(),
+                path,
+                count: 1,
+            }),
         }
     }
     entries.truncate(limit);
     entries
 }
 
-fn matching_file_paths(root: &Path, hits: &[SearchHit], limit: usize) -> Vec<String> {
+fn matching_file_paths(roots: &[SearchRoot], hits: &[SearchHit], limit: usize) -> Vec<String> {
     let mut paths = Vec::new();
     for hit in hits {
-        let path = hit.path.strip_prefix(root).unwrap_or(&hit.path);
-        let path = path.display().to_string();
+        let path = roots::display_path(roots, hit.archive.as_deref(), &hit.path);
         if !paths.contains(&path) {
             paths.push(path);
         }
@@ -913,6 +1222,318 @@ fn artifact_label(artifact: SearchArtifact) -> &'static str {
     }
 }
 
+/// Run a literal query against the lexical index, or `None` when the scan must
+/// handle it: `--scan`, `--regex`, a query with no indexable words, or a
+/// missing or stale index.
+fn indexed_search(
+    root: &Path,
+    query: &str,
+    options: &SearchOptions,
+    bounds: &DateBounds,
+    matcher: &Matcher,
+    catalog: &SessionCatalog,
+) -> Result<Option<Vec<SearchHit>>, CassioError> {
+    if options.scan || options.regex {
+        return Ok(None);
+    }
+    let Some(fts_query) = fts_query(&query::parse(query)?.groups) else {
+        return Ok(None);
+    };
+    let index_options = IndexOptions {
+        include_training: options.include_training,
+        training_root: options.training_root.clone(),
+        ..IndexOptions::default()
+    };
+    let files = index::files_to_index(root, &index_options, catalog);
+    let training_root = options.training_root.as_deref();
+    let Some(conn) =
+        lexical::open_fresh_lexical_index(root, training_root, &files, options.include_training)?
+    else {
+        if lexical::lexical_index_path(root).is_file() {
+            eprintln!("search: lexical index is stale, scanning files (run `cassio index`)");
+        }
+        return Ok(None);
+    };
+
+    let artifacts = artifact_order(options)
+        .into_iter()
+        .map(|artifact| format!("'{}'", index::artifact_name(artifact)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut sql = format!(
+        "SELECT source_path, artifact, line, text, bm25(lines) FROM lines \
+         WHERE lines MATCH ? AND artifact IN ({artifacts})"
+    );
+    let mut args = vec![fts_query];
+    if let Some(tool) = &options.tool {
+        sql.push_str(" AND tool = ? COLLATE NOCASE");
+        args.push(tool.clone());
+    }
+    if let Some(project) = &options.project {
+        sql.push_str(" AND instr(lower(project), ?) > 0");
+        args.push(project.to_lowercase());
+    }
+    if let Some(model) = &options.session_model {
+        sql.push_str(" AND instr(lower(model), ?) > 0");
+        args.push(model.to_lowercase());
+    }
+    if let Some(speaker) = options.speaker {
+        sql.push_str(" AND speaker = ?");
+        args.push(speaker.name().to_string());
+    }
+    // Files without a month (root-level notes) never satisfy a bound. Rows
+    // without a day (monthly summaries) pass a day bound whenever their month
+    // is in range, as `DateBounds::contains` lets them through the scan.
+    if let Some(from) = &bounds.from {
+        sql.push_str(" AND month >= ?");
+        args.push(from.month.clone());
+        if let Some(day) = &from.day {
+            sql.push_str(" AND (date IS NULL OR date >= ?)");
+            args.push(day.clone());
+        }
+    }
+    if let Some(to) = &bounds.to {
+        sql.push_str(" AND month <= ?");
+        args.push(to.month.clone());
+        if let Some(day) = &to.day {
+            sql.push_str(" AND (date IS NULL OR date <= ?)");
+            args.push(day.clone());
+        }
+    }
+    sql.push_str(if options.oldest_first {
+        " ORDER BY month, date, source_path, line"
+    } else {
+        " ORDER BY bm25(lines), source_path DESC, line"
+    });
+
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| CassioError::Other(format!("Failed to query lexical index: {e}")))?;
+    let mut rows = stmt
+        .query(rusqlite::params_from_iter(args))
+        .map_err(|e| CassioError::Other(format!("Failed to query lexical index: {e}")))?;
+    let scan_all = options.count || options.files_with_matches;
+    let mut hits = Vec::new();
+    while let Some(row) = rows
+        .next()
+        .map_err(|e| CassioError::Other(format!("Failed to read lexical index: {e}")))?
+    {
+        let read = |e| CassioError::Other(format!("Failed to read lexical row: {e}"));
+        let source_path: String = row.get(0).map_err(read)?;
+        let artifact: String = row.get(1).map_err(read)?;
+        let line: i64 = row.get(2).map_err(read)?;
+        let text: String = row.get(3).map_err(read)?;
+        let rank: f64 = row.get(4).map_err(read)?;
+        let Some(artifact) = artifact_from_index_name(&artifact) else {
+            continue;
+        };
+        // FTS5 matches tokens; the scan's matcher keeps substring, case, and
+        // path-noise semantics identical to an unindexed search.
+        let searchable = if options.include_paths {
+            text.clone()
+        } else {
+            strip_path_noise(&text)
+        };
+        if !matcher.is_match(&searchable) {
+            continue;
+        }
+        hits.push(SearchHit {
+            artifact,
+            path: lexical::resolve_source_path(root, training_root, artifact, &source_path),
+            line: line as usize,
+            line_end: None,
+            text: truncate_line(text.trim(), 280),
+            score: Some(-rank as f32),
+            context: None,
+            retrievers: None,
+            rerank_score: None,
+            archive: None,
+        });
+        if !scan_all && hits.len() >= options.limit {
+            break;
+        }
+    }
+    if options.context > 0 && !scan_all {
+        attach_context(&mut hits, options.context)?;
+    }
+    Ok(Some(hits))
+}
+
+/// FTS5 expression for parsed term groups: each included term becomes a quoted
+/// phrase of its words with the last word a prefix, ANDed within a group and
+/// ORed across groups. Exclusions are left to the matcher that re-checks every
+/// candidate. `None` when a group has no included term or a term has no words
+/// for the tokenizer to index (`->`, `#`).
+fn fts_query(groups: &[TermGroup]) -> Option<String> {
+    let mut alternatives = Vec::new();
+    for group in groups {
+        let mut phrases = Vec::new();
+        for term in &group.include {
+            let words: Vec<&str> = term
+                .split(|ch: char| !ch.is_alphanumeric())
+                .filter(|word| !word.is_empty())
+                .collect();
+            if words.is_empty() {
+                return None;
+            }
+            phrases.push(format!("\"{}\"*", words.join(" ")));
+        }
+        if phrases.is_empty() {
+            return None;
+        }
+        alternatives.push(format!("({})", phrases.join(" AND ")));
+    }
+    (!alternatives.is_empty()).then(|| alternatives.join(" OR "))
+}
+
+/// Read context windows for ranked hits. Hits from the same file share one read.
+fn attach_context(hits: &mut [SearchHit], context: usize) -> Result<(), CassioError> {
+    let mut files: std::collections::HashMap<PathBuf, Vec<String>> =
+        std::collections::HashMap::new();
+    for hit in hits {
+        if !files.contains_key(&hit.path) {
+            let content = fs::read_to_string(&hit.path)?;
+            files.insert(
+                hit.path.clone(),
+                content.lines().map(str::to_string).collect(),
+            );
+        }
+        let Some(lines) = files.get(&hit.path) else {
+            continue;
+        };
+        let index = hit.line.saturating_sub(1);
+        let lo = index.saturating_sub(context);
+        let hi = (index + context + 1).min(lines.len());
+        let window: Vec<_> = (lo..hi)
+            .filter(|li| *li != index)
+            .map(|li| SearchContextLine {
+                line: li + 1,
+                text: truncate_line(lines[li].trim(), 200),
+            })
+            .collect();
+        hit.context = (!window.is_empty()).then_some(window);
+    }
+    Ok(())
+}
+
+/// Reciprocal rank fusion constant: damps the weight of top ranks so one
+/// retriever's first hit cannot outvote agreement further down both lists.
+const RRF_K: f32 = 60.0;
+
+/// Run the lexical and semantic retrievers and fuse their rankings.
+///
+/// Each retriever contributes `1 / (RRF_K + rank)` per hit. A lexical line that
+/// falls inside a semantic chunk of the same file counts as the same hit, so
+/// agreement between the retrievers lifts it above either list alone.
+fn hybrid_search(
+    root: &Path,
+    query: &str,
+    options: &SearchOptions,
+    bounds: &DateBounds,
+    allow_missing_month: bool,
+) -> Result<Vec<SearchHit>, CassioError> {
+    let depth = options.limit.saturating_mul(3).max(50);
+
+    let mut lexical_options = options.clone();
+    lexical_options.hybrid = false;
+    lexical_options.semantic = None;
+    lexical_options.limit = depth;
+    let lexical = search_root(root, query, &lexical_options, allow_missing_month)?;
+
+    let mut semantic_options = options.clone();
+    semantic_options.hybrid = false;
+    semantic_options.limit = depth;
+    let text = if options.regex {
+        query.to_string()
+    } else {
+        query::parse(query)?.text()
+    };
+    let semantic = semantic_search(root, &text, &semantic_options, bounds)?;
+
+    Ok(fuse_rankings(lexical, semantic, options.limit))
+}
+
+/// Merge two ranked lists with reciprocal rank fusion, keeping at most `limit`.
+fn fuse_rankings(
+    lexical: Vec<SearchHit>,
+    semantic: Vec<SearchHit>,
+    limit: usize,
+) -> Vec<SearchHit> {
+    struct Fused {
+        hit: SearchHit,
+        score: f32,
+        /// Line range of the semantic chunk, which later lexical lines join.
+        chunk: Option<(usize, usize)>,
+        has_lexical: bool,
+    }
+
+    let mut fused: Vec<Fused> = Vec::new();
+    for (index, hit) in semantic.into_iter().enumerate() {
+        let source = RetrieverRank {
+            retriever: Retriever::Semantic,
+            rank: index + 1,
+            score: hit.score,
+        };
+        fused.push(Fused {
+            score: 1.0 / (RRF_K + source.rank as f32),
+            chunk: Some((hit.line, hit.line_end.unwrap_or(hit.line))),
+            has_lexical: false,
+            hit: SearchHit {
+                retrievers: Some(vec![source]),
+                ..hit
+            },
+        });
+    }
+    for (index, hit) in lexical.into_iter().enumerate() {
+        let source = RetrieverRank {
+            retriever: Retriever::Lexical,
+            rank: index + 1,
+            score: hit.score,
+        };
+        let weight = 1.0 / (RRF_K + source.rank as f32);
+        let chunk = fused.iter_mut().find(|entry| {
+            entry.hit.path == hit.path
+                && entry
+                    .chunk
+                    .is_some_and(|(start, end)| (start..=end).contains(&hit.line))
+        });
+        match chunk {
+            // Only a chunk's best lexical line counts; that line replaces the
+            // chunk text because it is the exact evidence.
+            Some(entry) if !entry.has_lexical => {
+                let mut sources = entry.hit.retrievers.take().unwrap_or_default();
+                sources.insert(0, source);
+                entry.hit = SearchHit {
+                    retrievers: Some(sources),
+                    ..hit
+                };
+                entry.score += weight;
+                entry.has_lexical = true;
+            }
+            Some(_) => {}
+            None => fused.push(Fused {
+                score: weight,
+                chunk: None,
+                has_lexical: true,
+                hit: SearchHit {
+                    retrievers: Some(vec![source]),
+                    ..hit
+                },
+            }),
+        }
+    }
+
+    fused.sort_by(|a, b| b.score.total_cmp(&a.score));
+    fused
+        .into_iter()
+        .take(limit)
+        .map(|entry| SearchHit {
+            score: Some(entry.score),
+            ..entry.hit
+        })
+        .collect()
+}
+
 fn semantic_search(
     root: &Path,
     query: &str,
@@ -955,16 +1576,33 @@ fn semantic_search(
 
     let conn = Connection::open(&index_path)
         .map_err(|e| CassioError::Other(format!("Failed to open semantic index: {e}")))?;
+    let probe = if semantic.exact {
+        None
+    } else {
+        crate::ann::probe_clusters(&conn, query_embedding)?
+    };
+    let mut sql = String::from(
+        "SELECT source_path, artifact, line_start, line_end, chunk_text, embedding FROM chunks",
+    );
+    let (mut filters, args) = chunk_metadata_filters(&conn, options)?;
+    // Chunks embedded since the last assignment have no cluster yet and are
+    // always scored.
+    if let Some(clusters) = probe {
+        let ids: Vec<String> = clusters.iter().map(i64::to_string).collect();
+        filters.push(format!(
+            "(cluster IS NULL OR cluster IN ({}))",
+            ids.join(", ")
+        ));
+    }
+    if !filters.is_empty() {
+        sql.push_str(" WHERE ");
+        sql.push_str(&filters.join(" AND "));
+    }
     let mut stmt = conn
-        .prepare(
-            r#"
-            SELECT source_path, artifact, line_start, line_end, chunk_text, embedding
-            FROM chunks
-            "#,
-        )
+        .prepare(&sql)
         .map_err(|e| CassioError::Other(format!("Failed to query semantic index: {e}")))?;
     let rows = stmt
-        .query_map(params![], |row| {
+        .query_map(rusqlite::params_from_iter(args), |row| {
             Ok(IndexedChunkRow {
                 source_path: row.get(0)?,
                 artifact: row.get(1)?,
@@ -976,6 +1614,7 @@ fn semantic_search(
         })
         .map_err(|e| CassioError::Other(format!("Failed to read semantic index: {e}")))?;
 
+    let catalog = session_catalog(root, options);
     let mut hits = Vec::new();
     for row in rows {
         let row =
@@ -983,7 +1622,7 @@ fn semantic_search(
         let Some(artifact) = artifact_from_index_name(&row.artifact) else {
             continue;
         };
-        if !artifact_in_scope(artifact, &row.source_path, options, bounds) {
+        if !artifact_in_scope(artifact, &row.source_path, options, bounds, &catalog) {
             continue;
         }
         let embedding = index::decode_embedding(&row.embedding)?;
@@ -998,6 +1637,9 @@ fn semantic_search(
             text: truncate_line(&row.chunk_text.replace('\n', " / "), 500),
             score: Some(score),
             context: None,
+            retrievers: None,
+            rerank_score: None,
+            archive: None,
         });
     }
 
@@ -1010,6 +1652,45 @@ fn semantic_search(
     Ok(hits)
 }
 
+/// SQL conditions and arguments for `--speaker`, `--project`, and `model:`
+/// against the chunk metadata columns, matching the lexical index's semantics.
+fn chunk_metadata_filters(
+    conn: &Connection,
+    options: &SearchOptions,
+) -> Result<(Vec<String>, Vec<String>), CassioError> {
+    let mut filters = Vec::new();
+    let mut args = Vec::new();
+    if options.speaker.is_none() && options.project.is_none() && options.session_model.is_none() {
+        return Ok((filters, args));
+    }
+    if !index::has_chunk_metadata(conn)? {
+        return Err(CassioError::Other(
+            "Semantic index predates speaker/project/model metadata (run `cassio index` to upgrade it)"
+                .into(),
+        ));
+    }
+    if let Some(speaker) = options.speaker {
+        args.push(format!(",{},", speaker.name()));
+        filters.push(format!(
+            "instr(',' || coalesce(speakers, '') || ',', ?{}) > 0",
+            args.len()
+        ));
+    }
+    for (column, needle) in [
+        ("project", &options.project),
+        ("model", &options.session_model),
+    ] {
+        if let Some(needle) = needle {
+            args.push(needle.to_lowercase());
+            filters.push(format!(
+                "instr(lower(coalesce({column}, '')), ?{}) > 0",
+                args.len()
+            ));
+        }
+    }
+    Ok((filters, args))
+}
+
 struct IndexedChunkRow {
     source_path: String,
     artifact: String,
@@ -1024,15 +1705,24 @@ fn artifact_in_scope(
     source_path: &str,
     options: &SearchOptions,
     bounds: &DateBounds,
+    catalog: &SessionCatalog,
 ) -> bool {
-    // source_path is root-relative like `2026-04/2026-04-30.daily.md`.
-    let month = source_path
-        .split('/')
-        .next()
-        .filter(|month| is_month(month));
+    // source_path is root-relative like `2026-04/2026-04-30.daily.md`, or any
+    // layout the manifest records for a custom `output_template`.
+    let cataloged = catalog.lookup_relative(source_path);
+    let (month, date) = match cataloged {
+        Some(session) => (Some(session.month()), Some(session.date.clone())),
+        None => (
+            source_path
+                .split('/')
+                .next()
+                .filter(|month| is_month(month)),
+            file_date_of(Path::new(source_path)),
+        ),
+    };
     match month {
         Some(month) => {
-            if !bounds.contains(month, file_date_of(Path::new(source_path)).as_deref()) {
+            if !bounds.contains(month, date.as_deref()) {
                 return false;
             }
         }
@@ -1047,7 +1737,11 @@ fn artifact_in_scope(
             return false;
         }
         let stem = file_stem(Path::new(source_path));
-        if !session_tool_suffix(&stem).is_some_and(|suffix| suffix.eq_ignore_ascii_case(tool)) {
+        let file_tool = match cataloged {
+            Some(session) => Some(session.tool.as_str()),
+            None => session_tool_suffix(&stem),
+        };
+        if !file_tool.is_some_and(|file_tool| file_tool.eq_ignore_ascii_case(tool)) {
             return false;
         }
     }
@@ -1062,7 +1756,7 @@ fn artifact_in_scope(
     true
 }
 
-fn artifact_from_index_name(name: &str) -> Option<SearchArtifact> {
+pub(crate) fn artifact_from_index_name(name: &str) -> Option<SearchArtifact> {
     match name {
         "monthly" => Some(SearchArtifact::Monthly),
         "daily" => Some(SearchArtifact::Daily),
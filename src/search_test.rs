@@ -6,6 +6,7 @@ fn test_options() -> SearchOptions {
         to: None,
         tool: None,
         project: None,
+        session_model: None,
         speaker: None,
         limit: 50,
         summaries_only: false,
@@ -20,6 +21,10 @@ fn test_options() -> SearchOptions {
         oldest_first: false,
         semantic: None,
         training_root: None,
+        scan: false,
+        hybrid: false,
+        rerank_model: None,
+        sessions: false,
     }
 }
 
@@ -257,6 +262,57 @@ fn tool_filter_restricts_sessions_and_skips_summaries() {
     std::fs::remove_dir_all(&root).ok();
 }
 
+#[test]
+fn templated_sessions_are_scoped_through_the_manifest() {
+    use crate::manifest::{Manifest, ManifestEntry, entry_key};
+    use chrono::TimeZone;
+
+    let root = temp_root("templated");
+    std::fs::remove_dir_all(&root).ok();
+    let mut manifest = Manifest::default();
+    for (stem, tool, day) in [("a", "codex", 10), ("b", "grok", 20)] {
+        let dir = root.join("cassio");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(format!("{stem}.md")), "👤 hello zepp\n").unwrap();
+        manifest.record(
+            entry_key("cassio", stem),
+            ManifestEntry {
+                tool: tool.to_string(),
+                source_path: format!("/logs/{stem}.jsonl"),
+                session_id: stem.to_string(),
+                started_at: chrono::Utc.with_ymd_and_hms(2026, 4, day, 9, 0, 0).unwrap(),
+                project_path: "/tmp/cassio".to_string(),
+                format: "emoji-text".to_string(),
+                training: false,
+                written_at: chrono::Utc::now(),
+                fingerprint: None,
+            },
+        );
+    }
+    manifest.save(&root).unwrap();
+
+    let hits = search(&root, "zepp", &test_options()).unwrap();
+    assert_eq!(hits.len(), 2);
+
+    let mut options = test_options();
+    options.tool = Some("grok".to_string());
+    let hits = search(&root, "zepp", &options).unwrap();
+    assert_eq!(hits.len(), 1);
+    assert!(hits[0].path.ends_with("cassio/b.md"));
+
+    let mut options = test_options();
+    options.from = Some("2026-04".to_string());
+    options.to = Some("2026-04".to_string());
+    assert_eq!(search(&root, "zepp", &options).unwrap().len(), 2);
+    options.from = Some("2026-04-15".to_string());
+    options.to = None;
+    let hits = search(&root, "zepp", &options).unwrap();
+    assert_eq!(hits.len(), 1);
+    assert!(hits[0].path.ends_with("cassio/b.md"));
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
 #[test]
 fn tool_filter_rejects_unknown_tools_and_summaries_only() {
     let root = temp_root("toolbad");
@@ -432,14 +488,15 @@ fn count_mode_reports_per_file_totals() {
     let hits = search(&root, "zepp", &options).unwrap();
     assert_eq!(hits.len(), 3, "count mode scans every file");
 
-    let entries = count_entries(&root, &hits, 50);
+    let roots = SearchRoot::labelled(vec![root.clone()]);
+    let entries = count_entries(&roots, &hits, 50);
     assert_eq!(entries.len(), 2);
     let mut counts: Vec<usize> = entries.iter().map(|e| e.count).collect();
     counts.sort_unstable();
     assert_eq!(counts, vec![1, 2], "one file with 2 matches, one with 1");
 
     // --limit caps the *file* list, not the scan.
-    let entries = count_entries(&root, &hits, 1);
+    let entries = count_entries(&roots, &hits, 1);
     assert_eq!(entries.len(), 1);
 
     std::fs::remove_dir_all(&root).ok();
@@ -457,7 +514,7 @@ fn files_with_matches_lists_each_file_once() {
     let mut options = test_options();
     options.files_with_matches = true;
     let hits = search(&root, "zepp", &options).unwrap();
-    let paths = matching_file_paths(&root, &hits, 50);
+    let paths = matching_file_paths(&SearchRoot::labelled(vec![root.clone()]), &hits, 50);
     assert_eq!(paths.len(), 2);
 
     std::fs::remove_dir_all(&root).ok();
@@ -495,25 +552,29 @@ fn semantic_scope_filters_by_range_tool_and_artifact_options() {
         SearchArtifact::Daily,
         "2026-04/2026-04-30.daily.md",
         &options,
-        &bounds
+        &bounds,
+        &SessionCatalog::default()
     ));
     assert!(!artifact_in_scope(
         SearchArtifact::Session,
         "2026-04/2026-04-30T10-00-00-codex.md",
         &options,
-        &bounds
+        &bounds,
+        &SessionCatalog::default()
     ));
     assert!(!artifact_in_scope(
         SearchArtifact::Daily,
         "2026-03/2026-03-30.daily.md",
         &options,
-        &bounds
+        &bounds,
+        &SessionCatalog::default()
     ));
     assert!(artifact_in_scope(
         SearchArtifact::Daily,
         "2026-05/2026-05-30.daily.md",
         &options,
-        &bounds
+        &bounds,
+        &SessionCatalog::default()
     ));
 
     options.summaries_only = false;
@@ -522,19 +583,22 @@ fn semantic_scope_filters_by_range_tool_and_artifact_options() {
         SearchArtifact::Session,
         "2026-04/2026-04-30T10-00-00-codex.md",
         &options,
-        &bounds
+        &bounds,
+        &SessionCatalog::default()
     ));
     assert!(!artifact_in_scope(
         SearchArtifact::Session,
         "2026-04/2026-04-30T10-00-00-grok.md",
         &options,
-        &bounds
+        &bounds,
+        &SessionCatalog::default()
     ));
     assert!(!artifact_in_scope(
         SearchArtifact::Daily,
         "2026-04/2026-04-30.daily.md",
         &options,
-        &bounds
+        &bounds,
+        &SessionCatalog::default()
     ));
 }
 
@@ -547,13 +611,15 @@ fn semantic_day_bounds_constrain_sessions() {
         SearchArtifact::Session,
         "2026-04/2026-04-10T10-00-00-codex.md",
         &options,
-        &bounds
+        &bounds,
+        &SessionCatalog::default()
     ));
     assert!(artifact_in_scope(
         SearchArtifact::Session,
         "2026-04/2026-04-20T10-00-00-codex.md",
         &options,
-        &bounds
+        &bounds,
+        &SessionCatalog::default()
     ));
 }
 
@@ -571,3 +637,331 @@ fn date_bounds_validate_calendar_dates() {
     assert!(is_day("2026-04-15"));
     assert!(!is_day("2026-04-32"));
 }
+
+// ---------------------------------------------------------------------------
+// What: lexical index
+// ---------------------------------------------------------------------------
+
+fn build_lexical(root: &std::path::Path) {
+    let catalog = SessionCatalog::load(root);
+    let files = index::files_to_index(root, &IndexOptions::default(), &catalog);
+    lexical::update_lexical_index(root, None, &files, &catalog).unwrap();
+}
+
+#[test]
+fn lexical_index_ranks_by_bm25_and_filters_in_sql() {
+    let root = temp_root("lexical");
+    write_session(
+        &root,
+        "2026-03-10T10-00-00-codex.md",
+        "📋 Project: /work/faber\n👤 zeppelin zeppelin zeppelin\n🤖 zeppelin noted\n",
+    );
+    write_session(
+        &root,
+        "2026-06-15T10-00-00-grok.md",
+        "📋 Project: /work/cassio\n👤 one zeppelin among many other words in a long line\n",
+    );
+    build_lexical(&root);
+
+    let hits = search(&root, "zepp", &test_options()).unwrap();
+    assert_eq!(hits.len(), 3);
+    assert!(hits.iter().all(|hit| hit.score.is_some()));
+    assert!(hits[0].path.to_string_lossy().contains("2026-03"));
+    assert_eq!(hits[0].line, 2);
+
+    let mut options = test_options();
+    options.speaker = Some(Speaker::Assistant);
+    let hits = search(&root, "zeppelin", &options).unwrap();
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].line, 3);
+
+    let mut options = test_options();
+    options.tool = Some("grok".to_string());
+    options.project = Some("CASSIO".to_string());
+    options.from = Some("2026-06-01".to_string());
+    let hits = search(&root, "zeppelin", &options).unwrap();
+    assert_eq!(hits.len(), 1);
+    assert!(hits[0].path.to_string_lossy().ends_with("-grok.md"));
+
+    // Token prefixes match through the index; mid-word substrings need --scan.
+    assert!(search(&root, "pelin", &test_options()).unwrap().is_empty());
+    let mut options = test_options();
+    options.scan = true;
+    let hits = search(&root, "pelin", &options).unwrap();
+    assert_eq!(hits.len(), 3);
+    assert!(hits.iter().all(|hit| hit.score.is_none()));
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn stale_lexical_index_falls_back_to_scan() {
+    let root = temp_root("lexical_stale");
+    write_session(&root, "2026-03-10T10-00-00-codex.md", "👤 hello zepp\n");
+    build_lexical(&root);
+    assert!(
+        search(&root, "zepp", &test_options()).unwrap()[0]
+            .score
+            .is_some()
+    );
+
+    write_session(&root, "2026-06-15T10-00-00-codex.md", "👤 hello zepp\n");
+    let hits = search(&root, "zepp", &test_options()).unwrap();
+    assert_eq!(hits.len(), 2);
+    assert!(hits.iter().all(|hit| hit.score.is_none()));
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn lexical_day_bounds_keep_undated_rows_in_month_range() {
+    let root = temp_root("lexical_undated");
+    write_session(&root, "2026-04-10T10-00-00-codex.md", "👤 zepp early\n");
+    write_session(&root, "2026-04-20T10-00-00-codex.md", "👤 zepp late\n");
+    write_session(&root, "2026-04.monthly.md", "# April\nzepp all month\n");
+    write_session(&root, "2026-05.monthly.md", "# May\nzepp next month\n");
+    std::fs::write(root.join("notes.md"), "zepp at the root\n").unwrap();
+    build_lexical(&root);
+
+    let mut options = test_options();
+    options.from = Some("2026-04-15".to_string());
+    options.to = Some("2026-04-30".to_string());
+    let names = |scan: bool| {
+        let mut options = options.clone();
+        options.scan = scan;
+        let mut names: Vec<String> = search(&root, "zepp", &options)
+            .unwrap()
+            .iter()
+            .map(|hit| hit.path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    };
+    assert!(
+        search(&root, "zepp", &options)
+            .unwrap()
+            .iter()
+            .all(|hit| hit.score.is_some())
+    );
+    let indexed = names(false);
+    assert_eq!(
+        indexed,
+        ["2026-04-20T10-00-00-codex.md", "2026-04.monthly.md"]
+    );
+    assert_eq!(indexed, names(true));
+
+    std::fs::remove_dir_all(&root).ok();
+}
+
+// ---------------------------------------------------------------------------
+// What: --hybrid
+// ---------------------------------------------------------------------------
+
+fn hit(path: &str, line: usize, line_end: Option<usize>, score: f32) -> SearchHit {
+    SearchHit {
+        artifact: SearchArtifact::Session,
+        path: PathBuf::from(path),
+        line,
+        line_end,
+        text: format!("{path}:{line}"),
+        score: Some(score),
+        context: None,
+        retrievers: None,
+        rerank_score: None,
+        archive: None,
+    }
+}
+
+#[test]
+fn rank_fusion_merges_lines_into_semantic_chunks() {
+    let lexical = vec![
+        hit("b.md", 7, None, 4.0),
+        hit("a.md", 12, None, 3.0),
+        hit("a.md", 14, None, 2.0),
+    ];
+    let semantic = vec![hit("a.md", 10, Some(20), 0.9), hit("c.md", 1, Some(5), 0.8)];
+    let fused = fuse_rankings(lexical, semantic, 10);
+
+    assert_eq!(
+        fused.len(),
+        3,
+        "a.md:14 folds into the chunk already matched"
+    );
+    assert_eq!(
+        fused[0].text, "a.md:12",
+        "agreement wins and keeps the exact line"
+    );
+    let sources = fused[0].retrievers.as_ref().unwrap();
+    assert_eq!(sources.len(), 2);
+    assert_eq!(sources[0].retriever, Retriever::Lexical);
+    assert_eq!(sources[0].rank, 2);
+    assert_eq!(sources[1].retriever, Retriever::Semantic);
+    assert_eq!(sources[1].rank, 1);
+    let expected = 1.0 / (RRF_K + 1.0) + 1.0 / (RRF_K + 2.0);
+    assert!((fused[0].score.unwrap() - expected).abs() < 1e-6);
+
+    // Single-retriever hits order by their own rank and keep their source.
+    assert_eq!(fused[1].text, "b.md:7");
+    assert_eq!(
+        fused[1].retrievers.as_ref().unwrap()[0].retriever,
+        Retriever::Lexical
+    );
+    assert_eq!(fused[2].text, "c.md:1");
+
+    assert_eq!(
+        fuse_rankings(vec![hit("b.md", 7, None, 4.0)], Vec::new(), 0).len(),
+        0
+    );
+}
+
+#[test]
+fn semantic_filters_query_chunk_metadata_columns() {
+    let conn = Connection::open_in_memory().unwrap();
+    conn.execute_batch(
+        "CREATE TABLE chunks (id TEXT, speakers TEXT, project TEXT, tool TEXT, model TEXT, date TEXT);
+         INSERT INTO chunks VALUES ('a', 'user,assistant', '/work/faber', 'codex', 'gpt-5, opus-4.5', '2026-04-10');
+         INSERT INTO chunks VALUES ('b', 'assistant,tool', '/work/faber', 'codex', 'gpt-5', '2026-04-10');
+         INSERT INTO chunks VALUES ('c', NULL, NULL, NULL, NULL, '2026-04-10');",
+    )
+    .unwrap();
+    let matching = |options: &SearchOptions| {
+        let (filters, args) = chunk_metadata_filters(&conn, options).unwrap();
+        let mut sql = "SELECT id FROM chunks".to_string();
+        if !filters.is_empty() {
+            sql.push_str(&format!(" WHERE {} ORDER BY id", filters.join(" AND ")));
+        }
+        let mut stmt = conn.prepare(&sql).unwrap();
+        stmt.query_map(rusqlite::params_from_iter(args), |row| {
+            row.get::<_, String>(0)
+        })
+        .unwrap()
+        .map(Result::unwrap)
+        .collect::<Vec<_>>()
+    };
+
+    let mut options = test_options();
+    assert_eq!(matching(&options).len(), 3);
+    options.speaker = Some(Speaker::User);
+    assert_eq!(matching(&options), vec!["a"]);
+    options.speaker = Some(Speaker::Tool);
+    options.project = Some("FABER".to_string());
+    assert_eq!(matching(&options), vec!["b"]);
+    options.speaker = None;
+    options.session_model = Some("Opus".to_string());
+    assert_eq!(matching(&options), vec!["a"]);
+
+    let legacy = Connection::open_in_memory().unwrap();
+    legacy
+        .execute_batch("CREATE TABLE chunks (id TEXT, source_path TEXT);")
+        .unwrap();
+    assert!(chunk_metadata_filters(&legacy, &options).is_err());
+}
+
+#[test]
+fn hybrid_validation() {
+    let semantic = SemanticSearchOptions {
+        provider: "ollama".to_string(),
+        model: "m".to_string(),
+        base_url: String::new(),
+        timeout_secs: 1,
+        exact: false,
+    };
+    let mut options = test_options();
+    options.hybrid = true;
+    assert!(
+        validate_options(&options).is_err(),
+        "needs embedding settings"
+    );
+
+    options.semantic = Some(semantic);
+    options.speaker = Some(Speaker::User);
+    options.project = Some("faber".to_string());
+    options.regex = true;
+    assert!(validate_options(&options).is_ok());
+
+    options.count = true;
+    assert!(validate_options(&options).is_err());
+
+    options.count = false;
+    options.hybrid = false;
+    assert!(
+        validate_options(&options).is_err(),
+        "--semantic still rejects --regex"
+    );
+    options.regex = false;
+    options.session_model = Some("opus".to_string());
+    assert!(
+        validate_options(&options).is_ok(),
+        "chunk metadata carries speaker, project, and model"
+    );
+
+    let mut options = test_options();
+    options.rerank_model = Some(PathBuf::from("reranker.gguf"));
+    assert!(
+        validate_options(&options).is_err(),
+        "--rerank rescores semantic or hybrid hits only"
+    );
+}
+
+// ---------------------------------------------------------------------------
+// What: inline query syntax
+// ---------------------------------------------------------------------------
+
+#[test]
+fn inline_query_filters_and_boolean_terms() {
+    let root = temp_root("inline");
+    write_session(
+        &root,
+        "2026-05-10T10-00-00-codex.md",
+        "📋 Project: /work/faber\n📋 Model: gpt-5\n👤 deploy the zepp api\n🤖 deploy done\n",
+    );
+    write_session(
+        &root,
+        "2026-06-10T10-00-00-claude.md",
+        "📋 Project: /work/faber\n👤 deploy the zepp web\n📋 Model: opus-4.5\n🤖 rollback zepp\n",
+    );
+
+    let search_lines = |query: &str| {
+        search(&root, query, &test_options())
+            .unwrap()
+            .iter()
+            .map(|hit| {
+                format!(
+                    "{}:{}",
+                    &hit.path.file_name().unwrap().to_string_lossy()[..7],
+                    hit.line
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+    let scan = search_lines(r#""deploy the" -web OR rollback"#);
+    assert_eq!(scan, vec!["2026-06:4", "2026-05:3"]);
+    assert_eq!(
+        search_lines("zepp speaker:user after:2026-06"),
+        vec!["2026-06:2"]
+    );
+    assert_eq!(
+        search_lines("zepp model:OPUS"),
+        vec!["2026-06:2", "2026-06:4"]
+    );
+    assert_eq!(
+        search_lines("deploy tool:codex project:faber"),
+        vec!["2026-05:3", "2026-05:4"]
+    );
+
+    // The lexical index answers the same queries, BM25-ranked.
+    build_lexical(&root);
+    let mut indexed = search_lines(r#""deploy the" -web OR rollback"#);
+    indexed.sort();
+    assert_eq!(indexed, vec!["2026-05:3", "2026-06:4"]);
+    assert_eq!(
+        search_lines("zepp model:opus speaker:user"),
+        vec!["2026-06:2"]
+    );
+
+    let mut options = test_options();
+    options.summaries_only = true;
+    assert!(search(&root, "zepp model:opus", &options).is_err());
+
+    std::fs::remove_dir_all(&root).ok();
+}
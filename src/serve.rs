@@ -0,0 +1,382 @@
+//! Local HTTP API and browser UI (`cassio serve`).
+//!
+//! A small HTTP/1.1 server on `127.0.0.1` for people who would rather browse
+//! the archive than use a terminal. It serves one embedded page (no external
+//! scripts, fonts, or styles, so it works offline) on top of JSON endpoints:
+//!
+//! - `GET /api/search?q=…&mode=lexical|semantic|hybrid`: `cassio search`
+//! - `GET /api/sessions?from=&to=&tool=&project=&limit=`: manifest sessions
+//! - `GET /api/session?id=…`: one transcript, rendered as HTML
+//! - `GET /api/daily?date=…`, `/api/monthly?month=…`: compaction summaries
+//! - `GET /api/summary?from=&to=`: the `cassio summary` month × tool table
+//! - `GET /api/metrics?day=…` or `?week=…`: `cassio metrics` for a period
+//! - `GET /session?id=…`: a transcript as a standalone page
+//!
+//! Queries go through `archive::Archive`, the same read-only, redacted view
+//! `cassio mcp` serves.
+//!
+//! # TRADE-OFFS
+//!
+//! - Hand-rolled HTTP over `std::net` instead of a web framework: the server
+//!   only answers GET requests from one local browser, which does not justify
+//!   an async runtime in a CLI that has none.
+//! - Requests are handled one at a time. A semantic search holds up the next
+//!   request while the query is embedded.
+//! - Binding to loopback keeps the archive off the network. Requests whose
+//!   `Host` header names another host are refused, so a web page cannot reach
+//!   the server through DNS rebinding.
+//! - Markdown summaries are shown as preformatted text, not rendered markdown.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde_json::{Map, Value, json};
+
+use crate::archive::{Archive, required_arg, string_arg};
+use crate::error::CassioError;
+use crate::formatter::emoji_text::{
+    EMOJI_ASSISTANT, EMOJI_FAILURE, EMOJI_META, EMOJI_SUCCESS, EMOJI_USER,
+};
+use crate::formatter::html::escape_html;
+use crate::frontmatter;
+use crate::metrics::{collect_day_metrics, collect_week_metrics};
+use crate::search::SemanticSearchOptions;
+
+pub const DEFAULT_PORT: u16 = 8767;
+const INDEX_HTML: &str = include_str!("serve_ui.html");
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+/// Request line and headers beyond this are rejected.
+const MAX_HEAD_BYTES: usize = 16 * 1024;
+
+/// Serve the archive at `root` on `127.0.0.1:port` until interrupted.
+pub fn run_serve(
+    root: &Path,
+    port: u16,
+    semantic: SemanticSearchOptions,
+) -> Result<(), CassioError> {
+    if !root.is_dir() {
+        return Err(CassioError::Other(format!(
+            "Output directory not found: {}",
+            root.display()
+        )));
+    }
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))?;
+    eprintln!(
+        "cassio serve: http://{}/ ({})",
+        listener.local_addr()?,
+        root.display()
+    );
+    let server = Server::new(root.to_path_buf(), semantic);
+    for stream in listener.incoming() {
+        let result = stream
+            .map_err(CassioError::from)
+            .and_then(|stream| server.handle_connection(stream));
+        if let Err(e) = result {
+            eprintln!("cassio serve: {e}");
+        }
+    }
+    Ok(())
+}
+
+/// A parsed GET request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Request {
+    pub(crate) method: String,
+    pub(crate) path: String,
+    pub(crate) query: Vec<(String, String)>,
+    pub(crate) host: Option<String>,
+}
+
+#[derive(Debug)]
+pub(crate) struct Response {
+    pub(crate) status: u16,
+    pub(crate) content_type: &'static str,
+    pub(crate) body: String,
+}
+
+impl Response {
+    fn json(status: u16, value: &Value) -> Self {
+        Self {
+            status,
+            content_type: "application/json",
+            body: format!("{value:#}"),
+        }
+    }
+
+    fn html(body: String) -> Self {
+        Self {
+            status: 200,
+            content_type: "text/html; charset=utf-8",
+            body,
+        }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Self::json(status, &json!({"error": message}))
+    }
+}
+
+pub(crate) struct Server {
+    archive: Archive,
+}
+
+impl Server {
+    pub(crate) fn new(root: PathBuf, semantic: SemanticSearchOptions) -> Self {
+        Self {
+            archive: Archive::new(root, semantic),
+        }
+    }
+
+    fn handle_connection(&self, mut stream: TcpStream) -> Result<(), CassioError> {
+        stream.set_read_timeout(Some(READ_TIMEOUT))?;
+        let request = read_request(&mut BufReader::new(&stream));
+        let response = match &request {
+            Ok(request) => self.respond(request),
+            Err(e) => Response::error(400, &e.to_string()),
+        };
+        let head_only = request.is_ok_and(|request| request.method == "HEAD");
+        write!(
+            stream,
+            "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\n\
+             Cache-Control: no-store\r\nX-Content-Type-Options: nosniff\r\n\
+             Connection: close\r\n\r\n",
+            response.status,
+            reason(response.status),
+            response.content_type,
+            response.body.len()
+        )?;
+        if !head_only {
+            stream.write_all(response.body.as_bytes())?;
+        }
+        stream.flush()?;
+        Ok(())
+    }
+
+    pub(crate) fn respond(&self, request: &Request) -> Response {
+        if request.method != "GET" && request.method != "HEAD" {
+            return Response::error(405, "Only GET requests are supported");
+        }
+        if !request.host.as_deref().is_none_or(is_loopback_host) {
+            return Response::error(403, "Requests must address localhost");
+        }
+        let args = query_args(&request.query);
+        let archive = &self.archive;
+        match request.path.as_str() {
+            "/" | "/index.html" => Response::html(INDEX_HTML.to_string()),
+            "/api/search" => query(archive.search(&args)),
+            "/api/sessions" => query(archive.list_sessions(&args)),
+            "/api/summary" => query(archive.summary_stats(&args)),
+            "/api/metrics" => query(self.metrics(&args)),
+            "/api/session" => lookup(
+                self.session(&args)
+                    .map(|(path, text)| json!({"path": path, "html": transcript_html(&text)})),
+            ),
+            "/api/daily" => lookup(
+                required_arg(&args, "date")
+                    .and_then(|date| archive.daily(date))
+                    .map(|text| json!({"html": summary_html(&text)})),
+            ),
+            "/api/monthly" => lookup(
+                required_arg(&args, "month")
+                    .and_then(|month| archive.monthly(month))
+                    .map(|text| json!({"html": summary_html(&text)})),
+            ),
+            "/session" => match self.session(&args) {
+                Ok((path, text)) => Response::html(session_page(&path, &text)),
+                Err(e) => Response::error(404, &e.to_string()),
+            },
+            _ => Response::error(404, &format!("No such endpoint: {}", request.path)),
+        }
+    }
+
+    /// Transcript named by `?id=` (a path or session id prefix).
+    fn session(&self, args: &Value) -> Result<(String, String), CassioError> {
+        self.archive.session(required_arg(args, "id")?)
+    }
+
+    fn metrics(&self, args: &Value) -> Result<Value, CassioError> {
+        let root = self.archive.root();
+        let value = if let Some(day) = string_arg(args, "day") {
+            serde_json::to_value(collect_day_metrics(root, day)?)
+        } else if let Some(week) = string_arg(args, "week") {
+            serde_json::to_value(collect_week_metrics(root, week)?)
+        } else {
+            return Err(CassioError::Other(
+                "metrics needs ?day=YYYY-MM-DD or ?week=YYYY-Www".to_string(),
+            ));
+        };
+        value.map_err(|e| CassioError::Other(format!("metrics serialize: {e}")))
+    }
+}
+
+/// JSON response for a query; failures (bad arguments) are 400s.
+fn query(result: Result<Value, CassioError>) -> Response {
+    match result {
+        Ok(value) => Response::json(200, &value),
+        Err(e) => Response::error(400, &e.to_string()),
+    }
+}
+
+/// JSON response for a single-item lookup; failures are 404s.
+fn lookup(result: Result<Value, CassioError>) -> Response {
+    match result {
+        Ok(value) => Response::json(200, &value),
+        Err(e) => Response::error(404, &e.to_string()),
+    }
+}
+
+pub(crate) fn read_request(reader: &mut impl BufRead) -> Result<Request, CassioError> {
+    let mut head_bytes = 0;
+    let mut next_line = |reader: &mut dyn BufRead| -> Result<String, CassioError> {
+        let mut line = String::new();
+        head_bytes += reader.read_line(&mut line)?;
+        if head_bytes > MAX_HEAD_BYTES {
+            return Err(CassioError::Other("Request head too large".to_string()));
+        }
+        Ok(line.trim_end_matches(['\r', '\n']).to_string())
+    };
+    let request_line = next_line(reader)?;
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err(CassioError::Other(format!(
+            "Malformed request line: {request_line}"
+        )));
+    };
+    let mut host = None;
+    loop {
+        let line = next_line(reader)?;
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':')
+            && name.eq_ignore_ascii_case("host")
+        {
+            host = Some(value.trim().to_string());
+        }
+    }
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    Ok(Request {
+        method: method.to_string(),
+        path: percent_decode(path),
+        query: query
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+                (
+                    percent_decode(&name.replace('+', " ")),
+                    percent_decode(&value.replace('+', " ")),
+                )
+            })
+            .collect(),
+        host,
+    })
+}
+
+/// `%XX` escapes decoded; invalid escapes are kept as written.
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = (bytes[i] == b'%')
+            .then(|| input.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match hex {
+            Some(byte) => {
+                out.push(byte);
+                i += 3;
+            }
+            None => {
+                out.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Query parameters as archive arguments: `q` is the search query, `limit` a
+/// number, `regex` a flag, everything else a string.
+fn query_args(query: &[(String, String)]) -> Value {
+    let mut args = Map::new();
+    for (name, value) in query {
+        let value = match name.as_str() {
+            "limit" => value.parse::<u64>().map_or(Value::Null, Value::from),
+            "regex" => Value::Bool(matches!(value.as_str(), "1" | "true" | "on")),
+            _ => Value::String(value.clone()),
+        };
+        let name = if name == "q" { "query" } else { name.as_str() };
+        args.insert(name.to_string(), value);
+    }
+    Value::Object(args)
+}
+
+fn is_loopback_host(host: &str) -> bool {
+    let name = match host.strip_prefix('[') {
+        Some(bracketed) => bracketed.split(']').next().unwrap_or(""),
+        None => host.rsplit_once(':').map_or(host, |(name, _)| name),
+    };
+    matches!(name, "localhost" | "127.0.0.1" | "::1")
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Internal Server Error",
+    }
+}
+
+/// An emoji-text transcript as HTML, one element per line, classed by speaker
+/// so the page can style user, assistant, tool, and header lines.
+pub(crate) fn transcript_html(text: &str) -> String {
+    let mut html = String::from("<div class=\"transcript\">\n");
+    for line in frontmatter::body(text).lines() {
+        let class = if line.starts_with(EMOJI_USER) {
+            "user"
+        } else if line.starts_with(EMOJI_ASSISTANT) {
+            "assistant"
+        } else if line.starts_with(EMOJI_SUCCESS) {
+            "tool"
+        } else if line.starts_with(EMOJI_FAILURE) {
+            "tool failed"
+        } else if line.starts_with(EMOJI_META) {
+            "meta"
+        } else {
+            "text"
+        };
+        html.push_str(&format!(
+            "<div class=\"line {class}\">{}</div>\n",
+            escape_html(line)
+        ));
+    }
+    html.push_str("</div>\n");
+    html
+}
+
+fn summary_html(text: &str) -> String {
+    format!("<pre class=\"summary\">{}</pre>\n", escape_html(text))
+}
+
+fn session_page(path: &str, text: &str) -> String {
+    format!(
+        "<!doctype html>\n<html><head><meta charset=\"utf-8\"><title>{title}</title>\n\
+         <style>body{{font:14px/1.5 system-ui,sans-serif;max-width:60rem;margin:2rem auto;padding:0 1rem}}\
+         .line{{white-space:pre-wrap;padding:.1rem .4rem}}.user{{background:#eef4ff}}\
+         .meta{{color:#666}}.tool{{color:#555;font-family:monospace}}.failed{{color:#a00}}</style>\n\
+         </head><body><h1>{title}</h1>\n{body}</body></html>\n",
+        title = escape_html(path),
+        body = transcript_html(text)
+    )
+}
+
+#[cfg(test)]
+#[path = "serve_test.rs"]
+mod tests;
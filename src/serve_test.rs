@@ -0,0 +1,113 @@
+use super::*;
+use std::fs;
+
+fn temp_root(label: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("cassio_serve_{label}_{}", std::process::id()))
+}
+
+fn server(root: &Path) -> Server {
+    Server::new(
+        root.to_path_buf(),
+        SemanticSearchOptions {
+            provider: "ollama".to_string(),
+            model: "cassio-embedding".to_string(),
+            base_url: "http://127.0.0.1:1".to_string(),
+            timeout_secs: 1,
+            exact: false,
+        },
+    )
+}
+
+fn get(server: &Server, target: &str) -> (u16, String) {
+    let raw = format!("GET {target} HTTP/1.1\r\nHost: 127.0.0.1:8767\r\n\r\n");
+    let request = read_request(&mut raw.as_bytes()).unwrap();
+    let response = server.respond(&request);
+    (response.status, response.body)
+}
+
+#[test]
+fn parses_request_line_query_and_host() {
+    let raw = "GET /api/search?q=launchd+plist&mode=hybrid&from=2026%2D04&flag HTTP/1.1\r\n\
+               Host: localhost:8767\r\nAccept: */*\r\n\r\n";
+    let request = read_request(&mut raw.as_bytes()).unwrap();
+    assert_eq!(request.method, "GET");
+    assert_eq!(request.path, "/api/search");
+    assert_eq!(
+        request.query,
+        vec![
+            ("q".to_string(), "launchd plist".to_string()),
+            ("mode".to_string(), "hybrid".to_string()),
+            ("from".to_string(), "2026-04".to_string()),
+            ("flag".to_string(), String::new()),
+        ]
+    );
+    assert_eq!(request.host.as_deref(), Some("localhost:8767"));
+    assert_eq!(percent_decode("100%"), "100%");
+    assert!(read_request(&mut "\r\n".as_bytes()).is_err());
+
+    assert!(is_loopback_host("127.0.0.1:8767"));
+    assert!(is_loopback_host("[::1]:8767"));
+    assert!(!is_loopback_host("evil.example:8767"));
+}
+
+#[test]
+fn serves_search_sessions_and_rendered_transcripts() {
+    let root = temp_root("api");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("2026-04")).unwrap();
+    fs::write(
+        root.join("2026-04/2026-04-10T10-00-00-codex.md"),
+        "📋 Project: /home/u/acme\n👤 fix <script> escaping\n🤖 escaping fixed\n",
+    )
+    .unwrap();
+    let server = server(&root);
+
+    let (status, body) = get(&server, "/");
+    assert_eq!(status, 200);
+    assert!(body.contains("<title>cassio</title>"));
+
+    let (status, body) = get(&server, "/api/search?q=escaping&limit=1");
+    assert_eq!(status, 200);
+    let search: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(search["hits"].as_array().unwrap().len(), 1);
+    assert_eq!(
+        search["hits"][0]["path"],
+        "2026-04/2026-04-10T10-00-00-codex.md"
+    );
+
+    let (status, body) = get(
+        &server,
+        "/api/session?id=2026-04/2026-04-10T10-00-00-codex.md",
+    );
+    assert_eq!(status, 200);
+    let session: Value = serde_json::from_str(&body).unwrap();
+    let html = session["html"].as_str().unwrap();
+    assert!(html.contains("<div class=\"line user\">👤 fix &lt;script&gt; escaping</div>"));
+    assert!(html.contains("<div class=\"line meta\">"));
+
+    let (status, body) = get(&server, "/session?id=2026-04/2026-04-10T10-00-00-codex.md");
+    assert_eq!(status, 200);
+    assert!(body.starts_with("<!doctype html>"));
+
+    assert_eq!(get(&server, "/api/session?id=nope").0, 404);
+    assert_eq!(get(&server, "/api/daily?date=2026-04-10").0, 404);
+    assert_eq!(get(&server, "/api/search").0, 400);
+    assert_eq!(get(&server, "/api/metrics").0, 400);
+    assert_eq!(get(&server, "/etc/passwd").0, 404);
+    let (status, body) = get(&server, "/api/summary");
+    assert_eq!(status, 200);
+    let summary: Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(summary["totals"]["sessions"], 1);
+
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn refuses_other_methods_and_foreign_hosts() {
+    let server = server(&temp_root("refuse"));
+    let post = read_request(&mut "POST /api/search HTTP/1.1\r\n\r\n".as_bytes()).unwrap();
+    assert_eq!(server.respond(&post).status, 405);
+    let rebound =
+        read_request(&mut "GET / HTTP/1.1\r\nHost: attacker.example\r\n\r\n".as_bytes()).unwrap();
+    assert_eq!(server.respond(&rebound).status, 403);
+}
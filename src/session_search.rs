@@ -0,0 +1,253 @@
+//! Session-level search results (`cassio search --sessions`).
+//!
+//! Line search answers "where is this phrase?"; one chatty transcript can fill
+//! every `--limit` slot. Session mode collects every matching line, groups the
+//! hits per transcript, and ranks transcripts so the output answers "which
+//! session was that?": each result carries its header (project, tool, models,
+//! date, title), match count, and best snippets.
+//!
+//! Score = speaker-weighted hits / sqrt(transcript lines) × recency, where
+//! recency halves every `RECENCY_HALF_LIFE_DAYS` before the newest matching
+//! session.
+//!
+//! # TRADE-OFFS
+//!
+//! - Speaker weights favour what the user said (`👤` ×1.5) over assistant prose
+//!   (×1.0), metadata (×0.75), and tool output (×0.5): tool output repeats file
+//!   contents and inflates counts without saying what the session was about.
+//! - Dividing by the square root of the length rewards density without letting
+//!   a two-line session with one hit outrank a long session that discussed the
+//!   topic throughout.
+//! - Recency is relative to the newest matching session, not today, so an old
+//!   archive is not flattened to zero and results do not drift day to day.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::ast::session_tool_suffix;
+use crate::error::CassioError;
+use crate::formatter::emoji_text::EMOJI_META;
+use crate::frontmatter;
+use crate::roots::{SearchRoot, display_path};
+use crate::search::{
+    LineSpeaker, SearchHit, SearchOptions, block_speakers, file_date_of, file_stem, header_project,
+    search_root, session_catalog, session_models,
+};
+
+const RECENCY_HALF_LIFE_DAYS: f64 = 180.0;
+const SNIPPETS_PER_SESSION: usize = 3;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionResult {
+    pub path: PathBuf,
+    pub score: f64,
+    pub matches: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub models: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    pub snippets: Vec<SearchHit>,
+    /// Archive label when several roots were searched (see `roots`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub archive: Option<String>,
+}
+
+/// Run a line search over session transcripts and rank the sessions it hit.
+pub fn search_sessions(
+    root: &Path,
+    query: &str,
+    options: &SearchOptions,
+) -> Result<Vec<SessionResult>, CassioError> {
+    search_sessions_in(root, query, options, false)
+}
+
+/// `search_sessions`; see `search::search_root` for `allow_missing_month`.
+pub(crate) fn search_sessions_in(
+    root: &Path,
+    query: &str,
+    options: &SearchOptions,
+    allow_missing_month: bool,
+) -> Result<Vec<SessionResult>, CassioError> {
+    if options.summaries_only {
+        return Err(CassioError::Other(
+            "--sessions cannot be combined with --summaries-only".into(),
+        ));
+    }
+    if options.count || options.files_with_matches {
+        return Err(CassioError::Other(
+            "--sessions cannot be combined with --count or --files-with-matches".into(),
+        ));
+    }
+    if options.semantic.is_some() || options.hybrid {
+        return Err(CassioError::Other(
+            "--sessions cannot be combined with --semantic or --hybrid".into(),
+        ));
+    }
+    if options.limit == 0 {
+        return Ok(Vec::new());
+    }
+
+    // Counting mode visits every file instead of stopping at --limit lines,
+    // which is exactly what per-session totals need.
+    let mut line_options = options.clone();
+    line_options.count = true;
+    line_options.context = 0;
+    let hits = search_root(root, query, &line_options, allow_missing_month)?;
+
+    let mut by_path: Vec<(PathBuf, Vec<SearchHit>)> = Vec::new();
+    let mut positions: HashMap<PathBuf, usize> = HashMap::new();
+    for hit in hits {
+        let slot = *positions.entry(hit.path.clone()).or_insert_with(|| {
+            by_path.push((hit.path.clone(), Vec::new()));
+            by_path.len() - 1
+        });
+        by_path[slot].1.push(hit);
+    }
+
+    let catalog = session_catalog(root, options);
+    let mut sessions = Vec::new();
+    for (path, hits) in by_path {
+        let content = fs::read_to_string(&path)?;
+        sessions.push(session_result(&path, &content, hits, &catalog));
+    }
+    apply_recency(&mut sessions);
+    sessions.sort_by(|a, b| b.score.total_cmp(&a.score).then(b.path.cmp(&a.path)));
+    sessions.truncate(options.limit);
+    Ok(sessions)
+}
+
+fn session_result(
+    path: &Path,
+    content: &str,
+    mut hits: Vec<SearchHit>,
+    catalog: &crate::manifest::SessionCatalog,
+) -> SessionResult {
+    let lines: Vec<&str> = content.lines().collect();
+    let speakers = block_speakers(&lines);
+    let weight_of = |hit: &SearchHit| -> f64 {
+        speakers
+            .get(hit.line.saturating_sub(1))
+            .map_or(1.0, |speaker| speaker_weight(*speaker))
+    };
+    let weighted: f64 = hits.iter().map(weight_of).sum();
+    let length = lines.iter().filter(|line| !line.trim().is_empty()).count();
+
+    let cataloged = catalog.lookup(path);
+    let date = match cataloged {
+        Some(session) => Some(session.date.clone()),
+        None => file_date_of(path).or_else(|| header_value(content, "Started:").map(day_of)),
+    };
+    let tool = match cataloged {
+        Some(session) => Some(session.tool.clone()),
+        None => session_tool_suffix(&file_stem(path)).map(str::to_string),
+    };
+
+    let matches = hits.len();
+    // Best snippets: the most heavily weighted speakers first, then in
+    // transcript order.
+    hits.sort_by(|a, b| {
+        weight_of(b)
+            .total_cmp(&weight_of(a))
+            .then(a.line.cmp(&b.line))
+    });
+    hits.truncate(SNIPPETS_PER_SESSION);
+
+    SessionResult {
+        path: path.to_path_buf(),
+        score: weighted / (length.max(1) as f64).sqrt(),
+        matches,
+        date,
+        tool,
+        project: header_project(content),
+        models: session_models(content),
+        title: header_value(content, "Title:").map(str::to_string),
+        snippets: hits,
+        archive: None,
+    }
+}
+
+fn speaker_weight(speaker: LineSpeaker) -> f64 {
+    match speaker {
+        LineSpeaker::User => 1.5,
+        LineSpeaker::Assistant => 1.0,
+        LineSpeaker::Other => 0.75,
+        LineSpeaker::Tool => 0.5,
+    }
+}
+
+/// Scale each score by `0.5^(days before the newest session / half-life)`.
+fn apply_recency(sessions: &mut [SessionResult]) {
+    let parse = |date: &Option<String>| {
+        date.as_deref()
+            .and_then(|date| chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").ok())
+    };
+    let Some(newest) = sessions.iter().filter_map(|s| parse(&s.date)).max() else {
+        return;
+    };
+    for session in sessions {
+        if let Some(date) = parse(&session.date) {
+            let age = (newest - date).num_days() as f64;
+            session.score *= 0.5f64.powf(age / RECENCY_HALF_LIFE_DAYS);
+        }
+    }
+}
+
+/// Value of a `📋 <label> ...` line in the transcript header.
+pub(crate) fn header_value<'a>(content: &'a str, label: &str) -> Option<&'a str> {
+    frontmatter::body(content)
+        .lines()
+        .take_while(|line| line.is_empty() || line.starts_with(EMOJI_META))
+        .filter_map(|line| line.strip_prefix(EMOJI_META))
+        .find_map(|rest| rest.trim_start().strip_prefix(label))
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+fn day_of(timestamp: &str) -> String {
+    timestamp.chars().take(10).collect()
+}
+
+pub(crate) fn print_sessions(roots: &[SearchRoot], sessions: &[SessionResult]) {
+    if sessions.is_empty() {
+        println!("No matching sessions.");
+        return;
+    }
+    println!("\n== sessions ==");
+    for session in sessions {
+        let display_path = display_path(roots, session.archive.as_deref(), &session.path);
+        println!(
+            "\n{}  {}  [{} match{}, score {:.3}]",
+            session.date.as_deref().unwrap_or("unknown date"),
+            session.tool.as_deref().unwrap_or("unknown tool"),
+            session.matches,
+            if session.matches == 1 { "" } else { "es" },
+            session.score
+        );
+        println!("  {display_path}");
+        if let Some(project) = &session.project {
+            println!("  Project: {project}");
+        }
+        if let Some(title) = &session.title {
+            println!("  Title: {title}");
+        }
+        if !session.models.is_empty() {
+            println!("  Model: {}", session.models.join(", "));
+        }
+        for snippet in &session.snippets {
+            println!("    {}: {}", snippet.line, snippet.text);
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "session_search_test.rs"]
+mod tests;
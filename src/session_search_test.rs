@@ -0,0 +1,126 @@
+use super::*;
+
+fn test_options() -> SearchOptions {
+    SearchOptions {
+        from: None,
+        to: None,
+        tool: None,
+        project: None,
+        session_model: None,
+        speaker: None,
+        limit: 50,
+        summaries_only: false,
+        include_training: false,
+        include_paths: false,
+        json: false,
+        regex: false,
+        case_sensitive: false,
+        context: 0,
+        files_with_matches: false,
+        count: false,
+        oldest_first: false,
+        semantic: None,
+        training_root: None,
+        scan: false,
+        hybrid: false,
+        rerank_model: None,
+        sessions: true,
+    }
+}
+
+fn temp_root(label: &str) -> PathBuf {
+    std::env::temp_dir().join(format!(
+        "cassio_session_search_{label}_{}",
+        std::process::id()
+    ))
+}
+
+fn write_file(root: &Path, rel: &str, content: &str) -> PathBuf {
+    let path = root.join(rel);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(&path, content).unwrap();
+    path
+}
+
+#[test]
+fn groups_hits_per_session_with_header_and_best_snippets() {
+    let root = temp_root("groups");
+    let _ = fs::remove_dir_all(&root);
+    let chatty = write_file(
+        &root,
+        "2026-06/2026-06-10T09-00-00-claude.md",
+        "📋 Session: abc\n📋 Project: /work/faber\n📋 Started: 2026-06-10T09:00:00+00:00\n\
+         📋 Title: Deploy pipeline\n📋 Model: opus-4.5\n\
+         ✅ Bash: zepp build log\n✅ Bash: zepp test log\n🤖 zepp looks healthy\n👤 ship zepp today\n\
+         ✅ Bash: zepp deploy log\n",
+    );
+    write_file(
+        &root,
+        "2026-06/2026-06-11T09-00-00-codex.md",
+        "📋 Session: def\n📋 Project: /work/cassio\n👤 unrelated chatter\n🤖 nothing here\n",
+    );
+    write_file(&root, "2026-06/2026-06.summary.md", "zepp everywhere\n");
+
+    let mut options = test_options();
+    options.limit = 1;
+    let sessions = search_sessions(&root, "zepp", &options).unwrap();
+    assert_eq!(sessions.len(), 1);
+    let session = &sessions[0];
+    assert_eq!(session.path, chatty);
+    assert_eq!(session.matches, 5);
+    assert_eq!(session.date.as_deref(), Some("2026-06-10"));
+    assert_eq!(session.tool.as_deref(), Some("claude"));
+    assert_eq!(session.project.as_deref(), Some("/work/faber"));
+    assert_eq!(session.title.as_deref(), Some("Deploy pipeline"));
+    assert_eq!(session.models, vec!["opus-4.5".to_string()]);
+    // User first, then assistant, then tool output in transcript order.
+    let snippets: Vec<usize> = session.snippets.iter().map(|hit| hit.line).collect();
+    assert_eq!(snippets, vec![9, 8, 6]);
+
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn ranks_dense_recent_user_sessions_first() {
+    let root = temp_root("ranks");
+    let _ = fs::remove_dir_all(&root);
+    let long_tail = "🤖 filler line\n".repeat(40);
+    let dense = write_file(
+        &root,
+        "2026-06/2026-06-01T09-00-00-codex.md",
+        "👤 zeppelin plan\n👤 zeppelin budget\n🤖 zeppelin notes\n",
+    );
+    let diluted = write_file(
+        &root,
+        "2026-06/2026-06-02T09-00-00-codex.md",
+        &format!("👤 zeppelin plan\n👤 zeppelin budget\n🤖 zeppelin notes\n{long_tail}"),
+    );
+    let tool_only = write_file(
+        &root,
+        "2026-06/2026-06-03T09-00-00-codex.md",
+        "✅ Read: zeppelin\n✅ Read: zeppelin\n✅ Read: zeppelin\n",
+    );
+    let stale = write_file(
+        &root,
+        "2025-01/2025-01-01T09-00-00-codex.md",
+        "👤 zeppelin plan\n👤 zeppelin budget\n🤖 zeppelin notes\n",
+    );
+
+    let sessions = search_sessions(&root, "zeppelin", &test_options()).unwrap();
+    let order: Vec<&Path> = sessions.iter().map(|s| s.path.as_path()).collect();
+    assert_eq!(order, vec![&*dense, &*tool_only, &*diluted, &*stale]);
+    assert!(sessions.iter().all(|s| s.matches == 3));
+
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn rejects_line_output_modes() {
+    let root = temp_root("rejects");
+    let mut options = test_options();
+    options.count = true;
+    assert!(search_sessions(&root, "zepp", &options).is_err());
+    let mut options = test_options();
+    options.summaries_only = true;
+    assert!(search_sessions(&root, "zepp", &options).is_err());
+}
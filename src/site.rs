@@ -0,0 +1,825 @@
+//! Static website for the archive (`cassio site <DIR>`).
+//!
+//! Writes a browsable, self-contained copy of the archive that any static file
+//! host (or `file://`) can serve:
+//!
+//! - `index.html`: totals, and links to every month, tool, and project
+//! - `months/YYYY-MM.html`: the month's summaries and sessions
+//! - `tools/<tool>.html`, `projects/<slug>.html`: sessions per tool or project
+//! - `daily/`, `weekly/`, `monthly/`: each compaction summary with links to the
+//!   sessions it covers
+//! - `stats.html`: the `cassio summary` month × tool table, plus per project
+//! - `sessions/<YYYY-MM>/<stem>.html`: one page per transcript
+//! - `search.html`: client-side search over a prebuilt inverted index
+//!   (`search-index.js`)
+//!
+//! All transcript and summary text is passed through `redact::redact_text`
+//! before it is written. A transcript that was also exported with `-f html` is
+//! published as that richer page; otherwise its emoji-text is rendered the way
+//! `cassio serve` renders it.
+//!
+//! # TRADE-OFFS
+//!
+//! - Every run regenerates the whole site. The destination must be empty or a
+//!   previous site (marked by a `.cassio-site` file), which is cleared first;
+//!   pointing `cassio site` at the archive or a home directory is refused.
+//! - The search index is loaded as a script rather than fetched as JSON, so the
+//!   site works from `file://`. It indexes words of three or more letters from
+//!   user and assistant lines and summaries, not tool output, which keeps it
+//!   small enough to load at once. Matching is by word prefix, not substring.
+//! - Summaries are shown as preformatted markdown, as in `cassio serve`.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDate;
+use serde_json::json;
+use walkdir::WalkDir;
+
+use crate::error::CassioError;
+use crate::formatter::emoji_text::{EMOJI_ASSISTANT, EMOJI_USER, format_duration, format_tokens};
+use crate::formatter::html::escape_html;
+use crate::manifest::is_state_dir;
+use crate::metrics::iso_week_id;
+use crate::redact::redact_text;
+use crate::serve::transcript_html;
+use crate::summary::{SessionRow, session_rows, shorten_project, stats_by_month};
+
+/// Marks a directory as written by `cassio site`, so a re-run may clear it.
+pub const SITE_MARKER: &str = ".cassio-site";
+
+const STYLE: &str = include_str!("site.css");
+const SEARCH_JS: &str = include_str!("site_search.js");
+const TITLE_CHARS: usize = 80;
+const MIN_TERM_CHARS: usize = 3;
+const MAX_TERM_CHARS: usize = 32;
+
+/// Counts reported once the site is written.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SiteReport {
+    pub sessions: usize,
+    pub summaries: usize,
+    pub pages: usize,
+    pub terms: usize,
+}
+
+/// Render the archive at `root` into `dest` and print what was written.
+pub fn run_site(root: &Path, dest: &Path) -> Result<(), CassioError> {
+    let report = build_site(root, dest)?;
+    eprintln!(
+        "Wrote {} pages ({} sessions, {} summaries, {} search terms) to {}",
+        report.pages,
+        report.sessions,
+        report.summaries,
+        report.terms,
+        dest.display()
+    );
+    Ok(())
+}
+
+/// Render the archive at `root` into `dest`, replacing any previous site there.
+pub fn build_site(root: &Path, dest: &Path) -> Result<SiteReport, CassioError> {
+    if !root.is_dir() {
+        return Err(CassioError::Other(format!(
+            "archive directory not found: {}",
+            root.display()
+        )));
+    }
+    prepare_destination(root, dest)?;
+
+    let mut site = Site {
+        dest: dest.to_path_buf(),
+        pages: 0,
+        search: SearchIndex::default(),
+    };
+    let summaries = find_summaries(root);
+    let dailies: BTreeSet<&str> = summaries
+        .iter()
+        .filter(|s| s.kind == SummaryKind::Daily)
+        .map(|s| s.period.as_str())
+        .collect();
+    let sessions = write_sessions(root, &dailies, &mut site)?;
+    for summary in &summaries {
+        write_summary_page(summary, &sessions, &mut site)?;
+    }
+    write_month_pages(&sessions, &summaries, &mut site)?;
+    write_group_pages(&sessions, &mut site)?;
+    write_stats_page(root, &sessions, &mut site)?;
+    write_index_page(&sessions, &summaries, &mut site)?;
+    write_search_page(&mut site)?;
+
+    fs::write(dest.join("style.css"), STYLE)?;
+    fs::write(dest.join("search.js"), SEARCH_JS)?;
+    let terms = site.search.terms.len();
+    fs::write(dest.join("search-index.js"), site.search.to_script())?;
+    fs::write(dest.join(SITE_MARKER), "generated by cassio site\n")?;
+
+    Ok(SiteReport {
+        sessions: sessions.len(),
+        summaries: summaries.len(),
+        pages: site.pages,
+        terms,
+    })
+}
+
+/// Refuse to write into anything but an empty directory or an earlier site,
+/// and clear an earlier site so removed sessions do not linger.
+fn prepare_destination(root: &Path, dest: &Path) -> Result<(), CassioError> {
+    if dest.exists() {
+        let same = match (fs::canonicalize(root), fs::canonicalize(dest)) {
+            (Ok(root), Ok(dest)) => root.starts_with(&dest) || dest.starts_with(&root),
+            _ => false,
+        };
+        if same {
+            return Err(CassioError::Other(format!(
+                "site directory {} overlaps the archive; choose a separate directory",
+                dest.display()
+            )));
+        }
+        if !dest.is_dir() {
+            return Err(CassioError::Other(format!(
+                "{} exists and is not a directory",
+                dest.display()
+            )));
+        }
+        let empty = fs::read_dir(dest)?.next().is_none();
+        if !empty && !dest.join(SITE_MARKER).is_file() {
+            return Err(CassioError::Other(format!(
+                "{} is not empty and was not written by `cassio site`; refusing to overwrite it",
+                dest.display()
+            )));
+        }
+        for entry in fs::read_dir(dest)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                fs::remove_dir_all(&path)?;
+            } else {
+                fs::remove_file(&path)?;
+            }
+        }
+    }
+    fs::create_dir_all(dest)?;
+    Ok(())
+}
+
+struct Site {
+    dest: PathBuf,
+    pages: usize,
+    search: SearchIndex,
+}
+
+impl Site {
+    /// Write one page at `rel` (a `/`-separated path under the site root).
+    /// `body` receives the prefix that leads from the page back to the root.
+    fn page(
+        &mut self,
+        rel: &str,
+        title: &str,
+        body: impl FnOnce(&str) -> String,
+    ) -> Result<(), CassioError> {
+        let prefix = root_prefix(rel);
+        let html = layout(&prefix, title, &body(&prefix));
+        let path = self.dest.join(rel);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, html)?;
+        self.pages += 1;
+        Ok(())
+    }
+}
+
+fn root_prefix(rel: &str) -> String {
+    "../".repeat(rel.matches('/').count())
+}
+
+fn layout(prefix: &str, title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\"><head><meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>{title} · cassio</title>\n\
+         <link rel=\"stylesheet\" href=\"{prefix}style.css\">\n</head><body>\n\
+         <nav class=\"site\"><a class=\"brand\" href=\"{prefix}index.html\">cassio</a>\
+         <a href=\"{prefix}stats.html\">Stats</a><a href=\"{prefix}search.html\">Search</a></nav>\n\
+         <h1>{title}</h1>\n{body}</body></html>\n",
+        title = escape_html(title),
+    )
+}
+
+/// One published transcript.
+struct SiteSession {
+    row: SessionRow,
+    /// Site-relative page, e.g. `sessions/2026-04/2026-04-01T09-00-00-codex.html`.
+    url: String,
+    title: String,
+}
+
+fn write_sessions(
+    root: &Path,
+    dailies: &BTreeSet<&str>,
+    site: &mut Site,
+) -> Result<Vec<SiteSession>, CassioError> {
+    let mut sessions = Vec::new();
+    for row in session_rows(root)? {
+        let rel = row
+            .path
+            .strip_prefix(root)
+            .unwrap_or(&row.path)
+            .with_extension("");
+        let key = rel.to_string_lossy().replace('\\', "/");
+        let url = format!("sessions/{key}.html");
+        let text = redact_text(&fs::read_to_string(&row.path)?);
+        let title = session_title(&text).unwrap_or_else(|| key.clone());
+
+        let exported = root.join(format!("{key}.html"));
+        if exported.is_file() {
+            let html = redact_text(&fs::read_to_string(&exported)?);
+            let path = site.dest.join(&url);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(path, html)?;
+            site.pages += 1;
+        } else {
+            let heading = format!("{} · {}", row.date, row.tool);
+            let links = session_context(&row, dailies.contains(row.date.as_str()));
+            site.page(&url, &heading, |prefix| {
+                format!(
+                    "<p class=\"muted\">{}</p>\n{}",
+                    links(prefix),
+                    transcript_html(&text)
+                )
+            })?;
+        }
+
+        let id = site.search.add_doc(&url, &title, &row);
+        site.search.add_text(id, conversation_lines(&text));
+        sessions.push(SiteSession { row, url, title });
+    }
+    Ok(sessions)
+}
+
+/// Links from a session page to the pages that list it.
+fn session_context(row: &SessionRow, has_daily: bool) -> impl Fn(&str) -> String + '_ {
+    move |prefix: &str| {
+        let month = &row.date[..7];
+        let mut parts = vec![format!(
+            "<a href=\"{prefix}months/{month}.html\">{month}</a>"
+        )];
+        if has_daily {
+            parts.push(format!(
+                "<a href=\"{prefix}daily/{date}.html\">{date}</a>",
+                date = row.date
+            ));
+        }
+        parts.push(format!(
+            "<a href=\"{prefix}tools/{}.html\">{}</a>",
+            slug(&row.tool),
+            escape_html(&row.tool)
+        ));
+        if !row.project.is_empty() {
+            parts.push(format!(
+                "<a href=\"{prefix}projects/{}.html\">{}</a>",
+                slug(&row.project),
+                escape_html(&row.project)
+            ));
+        }
+        parts.push(row.kind.to_string());
+        parts.push(format!("${:.2}", row.estimated_cost_usd));
+        parts.join(" · ")
+    }
+}
+
+/// The first user line, trimmed to fit a link.
+pub(crate) fn session_title(text: &str) -> Option<String> {
+    let line = text
+        .lines()
+        .find_map(|line| line.strip_prefix(EMOJI_USER))?
+        .trim();
+    if line.is_empty() {
+        return None;
+    }
+    Some(if line.chars().count() > TITLE_CHARS {
+        let cut: String = line.chars().take(TITLE_CHARS - 1).collect();
+        format!("{}…", cut.trim_end())
+    } else {
+        line.to_string()
+    })
+}
+
+/// User and assistant lines, including the continuation lines of a message.
+fn conversation_lines(text: &str) -> impl Iterator<Item = &str> {
+    let mut in_message = false;
+    text.lines().filter(move |line| {
+        if line.starts_with(EMOJI_USER) || line.starts_with(EMOJI_ASSISTANT) {
+            in_message = true;
+        } else if line.chars().next().is_some_and(is_marker) {
+            in_message = false;
+        }
+        in_message
+    })
+}
+
+/// Emoji and symbols that open a non-message line (tool results, headers).
+fn is_marker(c: char) -> bool {
+    !c.is_alphanumeric() && !c.is_whitespace() && !c.is_ascii_punctuation()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum SummaryKind {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+impl SummaryKind {
+    pub(crate) fn dir(self) -> &'static str {
+        match self {
+            SummaryKind::Daily => "daily",
+            SummaryKind::Weekly => "weekly",
+            SummaryKind::Monthly => "monthly",
+        }
+    }
+
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            SummaryKind::Daily => "Daily summary",
+            SummaryKind::Weekly => "Weekly summary",
+            SummaryKind::Monthly => "Monthly summary",
+        }
+    }
+}
+
+/// A compaction summary found in the archive.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct SiteSummary {
+    pub(crate) kind: SummaryKind,
+    /// `YYYY-MM-DD`, `YYYY-Www`, or `YYYY-MM`.
+    pub(crate) period: String,
+    pub(crate) path: PathBuf,
+}
+
+impl SiteSummary {
+    fn url(&self) -> String {
+        format!("{}/{}.html", self.kind.dir(), self.period)
+    }
+
+    /// The `YYYY-MM` month the summary is listed under.
+    fn month(&self) -> String {
+        match self.kind {
+            SummaryKind::Weekly => self
+                .path
+                .parent()
+                .and_then(Path::file_name)
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            _ => self.period[..7].to_string(),
+        }
+    }
+
+    pub(crate) fn covers(&self, date: &str) -> bool {
+        match self.kind {
+            SummaryKind::Daily => self.period == date,
+            SummaryKind::Monthly => date.starts_with(&self.period),
+            SummaryKind::Weekly => NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .is_ok_and(|day| iso_week_id(day) == self.period),
+        }
+    }
+}
+
+/// Every daily, weekly, and monthly summary under `root`, by kind and period.
+/// A `.daily.md` wins over a legacy `.compaction.md` for the same day.
+pub(crate) fn find_summaries(root: &Path) -> Vec<SiteSummary> {
+    let mut found: BTreeMap<(SummaryKind, String), PathBuf> = BTreeMap::new();
+    for entry in WalkDir::new(root)
+        .max_depth(2)
+        .into_iter()
+        .filter_entry(|e| !is_state_dir(e))
+        .filter_map(Result::ok)
+        .filter(|e| e.file_type().is_file())
+    {
+        let name = entry.file_name().to_string_lossy();
+        let Some((kind, period, preferred)) = classify_summary(&name) else {
+            continue;
+        };
+        let key = (kind, period.to_string());
+        if preferred || !found.contains_key(&key) {
+            found.insert(key, entry.path().to_path_buf());
+        }
+    }
+    found
+        .into_iter()
+        .map(|((kind, period), path)| SiteSummary { kind, period, path })
+        .collect()
+}
+
+fn classify_summary(name: &str) -> Option<(SummaryKind, &str, bool)> {
+    if let Some(date) = name.strip_suffix(".daily.md") {
+        return is_date(date).then_some((SummaryKind::Daily, date, true));
+    }
+    if let Some(date) = name.strip_suffix(".compaction.md") {
+        return is_date(date).then_some((SummaryKind::Daily, date, false));
+    }
+    if let Some(week) = name.strip_suffix(".weekly.md") {
+        let valid = week.len() == 8 && week.as_bytes()[4..6] == *b"-W";
+        return valid.then_some((SummaryKind::Weekly, week, true));
+    }
+    let month = name.strip_suffix(".monthly.md")?;
+    NaiveDate::parse_from_str(&format!("{month}-01"), "%Y-%m-%d")
+        .is_ok()
+        .then_some((SummaryKind::Monthly, month, true))
+}
+
+fn is_date(text: &str) -> bool {
+    text.len() == 10 && NaiveDate::parse_from_str(text, "%Y-%m-%d").is_ok()
+}
+
+fn write_summary_page(
+    summary: &SiteSummary,
+    sessions: &[SiteSession],
+    site: &mut Site,
+) -> Result<(), CassioError> {
+    let text = redact_text(&fs::read_to_string(&summary.path)?);
+    let covered: Vec<&SiteSession> = sessions
+        .iter()
+        .filter(|s| summary.covers(&s.row.date))
+        .collect();
+    let title = format!("{} {}", summary.kind.label(), summary.period);
+    let url = summary.url();
+    let month = summary.month();
+    site.page(&url, &title, |prefix| {
+        format!(
+            "<p><a href=\"{prefix}months/{month}.html\">{month}</a></p>\n\
+             <pre class=\"summary\">{}</pre>\n<h2>Sessions ({})</h2>\n{}",
+            escape_html(&text),
+            covered.len(),
+            sessions_table(prefix, &covered)
+        )
+    })?;
+    let id = site.search.add(&url, &title, &summary.period, "", "");
+    site.search.add_text(id, text.lines());
+    Ok(())
+}
+
+fn sessions_table(prefix: &str, sessions: &[&SiteSession]) -> String {
+    if sessions.is_empty() {
+        return "<p class=\"muted\">No sessions.</p>\n".to_string();
+    }
+    let mut html = String::from(
+        "<table>\n<tr><th>Date</th><th>Tool</th><th>Kind</th>\
+         <th class=\"num\">Cost</th><th>Project</th><th>Session</th></tr>\n",
+    );
+    for s in sessions {
+        html.push_str(&format!(
+            "<tr><td>{date}</td><td>{tool}</td><td>{kind}</td><td class=\"num\">${cost:.2}</td>\
+             <td>{project}</td><td><a href=\"{prefix}{url}\">{title}</a></td></tr>\n",
+            date = s.row.date,
+            tool = escape_html(&s.row.tool),
+            kind = s.row.kind,
+            cost = s.row.estimated_cost_usd,
+            project = escape_html(&shorten_project(&s.row.project)),
+            url = s.url,
+            title = escape_html(&s.title),
+        ));
+    }
+    html.push_str("</table>\n");
+    html
+}
+
+fn summary_links(prefix: &str, summaries: &[&SiteSummary]) -> String {
+    let items: String = summaries
+        .iter()
+        .map(|s| {
+            format!(
+                "<li><a href=\"{prefix}{}\">{}</a></li>",
+                s.url(),
+                escape_html(&s.period)
+            )
+        })
+        .collect();
+    format!("<ul class=\"links\">{items}</ul>\n")
+}
+
+fn write_month_pages(
+    sessions: &[SiteSession],
+    summaries: &[SiteSummary],
+    site: &mut Site,
+) -> Result<(), CassioError> {
+    for month in months(sessions, summaries) {
+        let listed: Vec<&SiteSession> = sessions
+            .iter()
+            .filter(|s| s.row.date.starts_with(&month))
+            .collect();
+        let in_month = |kind: SummaryKind| -> Vec<&SiteSummary> {
+            summaries
+                .iter()
+                .filter(|s| s.kind == kind && s.month() == month)
+                .collect()
+        };
+        let (monthly, weekly, daily) = (
+            in_month(SummaryKind::Monthly),
+            in_month(SummaryKind::Weekly),
+            in_month(SummaryKind::Daily),
+        );
+        site.page(&format!("months/{month}.html"), &month, |prefix| {
+            let mut body = String::new();
+            if let Some(summary) = monthly.first() {
+                body.push_str(&format!(
+                    "<p><a href=\"{prefix}{}\">Monthly summary</a></p>\n",
+                    summary.url()
+                ));
+            }
+            if !weekly.is_empty() {
+                body.push_str("<h2>Weekly summaries</h2>\n");
+                body.push_str(&summary_links(prefix, &weekly));
+            }
+            if !daily.is_empty() {
+                body.push_str("<h2>Daily summaries</h2>\n");
+                body.push_str(&summary_links(prefix, &daily));
+            }
+            body.push_str(&format!("<h2>Sessions ({})</h2>\n", listed.len()));
+            body.push_str(&sessions_table(prefix, &listed));
+            body
+        })?;
+    }
+    Ok(())
+}
+
+/// Every month with a session or a summary, newest first.
+fn months(sessions: &[SiteSession], summaries: &[SiteSummary]) -> Vec<String> {
+    let mut months: BTreeSet<String> = sessions
+        .iter()
+        .map(|s| s.row.date[..7].to_string())
+        .collect();
+    months.extend(summaries.iter().map(SiteSummary::month));
+    months.retain(|month| !month.is_empty());
+    months.into_iter().rev().collect()
+}
+
+fn write_group_pages(sessions: &[SiteSession], site: &mut Site) -> Result<(), CassioError> {
+    for (tool, listed) in group_by(sessions, |s| &s.row.tool) {
+        site.page(&format!("tools/{}.html", slug(tool)), tool, |prefix| {
+            format!(
+                "<h2>Sessions ({})</h2>\n{}",
+                listed.len(),
+                sessions_table(prefix, &listed)
+            )
+        })?;
+    }
+    for (project, listed) in group_by(sessions, |s| &s.row.project) {
+        if project.is_empty() {
+            continue;
+        }
+        site.page(
+            &format!("projects/{}.html", slug(project)),
+            project,
+            |prefix| {
+                format!(
+                    "<h2>Sessions ({})</h2>\n{}",
+                    listed.len(),
+                    sessions_table(prefix, &listed)
+                )
+            },
+        )?;
+    }
+    Ok(())
+}
+
+fn group_by<'a>(
+    sessions: &'a [SiteSession],
+    key: impl Fn(&'a SiteSession) -> &'a String,
+) -> BTreeMap<&'a str, Vec<&'a SiteSession>> {
+    let mut groups: BTreeMap<&str, Vec<&SiteSession>> = BTreeMap::new();
+    for session in sessions {
+        groups
+            .entry(key(session).as_str())
+            .or_default()
+            .push(session);
+    }
+    groups
+}
+
+fn write_stats_page(
+    root: &Path,
+    sessions: &[SiteSession],
+    site: &mut Site,
+) -> Result<(), CassioError> {
+    let rows = stats_by_month(root, None, None)?;
+    let mut by_project: BTreeMap<&str, (usize, f64)> = BTreeMap::new();
+    for s in sessions {
+        let entry = by_project.entry(s.row.project.as_str()).or_default();
+        entry.0 += 1;
+        entry.1 += s.row.estimated_cost_usd;
+    }
+    site.page("stats.html", "Stats", |prefix| {
+        let mut html = String::from(
+            "<table>\n<tr><th>Month</th><th>Tool</th><th class=\"num\">Sessions</th>\
+             <th class=\"num\">I/A/B</th><th class=\"num\">Tools ok/fail</th>\
+             <th class=\"num\">Tokens in/out</th><th class=\"num\">Duration</th>\
+             <th class=\"num\">Cost</th></tr>\n",
+        );
+        let mut total = (0u32, 0u64, 0u64, 0i64, 0f64);
+        for r in &rows {
+            html.push_str(&format!(
+                "<tr><td><a href=\"{prefix}months/{month}.html\">{month}</a></td>\
+                 <td><a href=\"{prefix}tools/{slug}.html\">{tool}</a></td>\
+                 <td class=\"num\">{}</td><td class=\"num\">{}/{}/{}</td>\
+                 <td class=\"num\">{}/{}</td><td class=\"num\">{}/{}</td>\
+                 <td class=\"num\">{}</td><td class=\"num\">${:.2}</td></tr>\n",
+                r.sessions,
+                r.interactive_sessions,
+                r.agentic_sessions,
+                r.abandoned_sessions,
+                r.tool_calls_ok,
+                r.tool_calls_failed,
+                format_tokens(r.input_tokens),
+                format_tokens(r.output_tokens),
+                format_duration(r.duration_secs),
+                r.estimated_cost_usd,
+                month = r.month,
+                slug = slug(&r.tool),
+                tool = escape_html(&r.tool),
+            ));
+            total.0 += r.sessions;
+            total.1 += r.input_tokens;
+            total.2 += r.output_tokens;
+            total.3 += r.duration_secs;
+            total.4 += r.estimated_cost_usd;
+        }
+        html.push_str(&format!(
+            "<tr><th>Total</th><th></th><th class=\"num\">{}</th><th></th><th></th>\
+             <th class=\"num\">{}/{}</th><th class=\"num\">{}</th><th class=\"num\">${:.2}</th></tr>\n\
+             </table>\n<h2>Projects</h2>\n<table>\n<tr><th>Project</th>\
+             <th class=\"num\">Sessions</th><th class=\"num\">Cost</th></tr>\n",
+            total.0,
+            format_tokens(total.1),
+            format_tokens(total.2),
+            format_duration(total.3),
+            total.4,
+        ));
+        for (project, (count, cost)) in &by_project {
+            let name = if project.is_empty() {
+                "(no project)".to_string()
+            } else {
+                format!(
+                    "<a href=\"{prefix}projects/{}.html\">{}</a>",
+                    slug(project),
+                    escape_html(project)
+                )
+            };
+            html.push_str(&format!(
+                "<tr><td>{name}</td><td class=\"num\">{count}</td>\
+                 <td class=\"num\">${cost:.2}</td></tr>\n"
+            ));
+        }
+        html.push_str("</table>\n");
+        html
+    })
+}
+
+fn write_index_page(
+    sessions: &[SiteSession],
+    summaries: &[SiteSummary],
+    site: &mut Site,
+) -> Result<(), CassioError> {
+    let total_cost: f64 = sessions.iter().map(|s| s.row.estimated_cost_usd).sum();
+    let tools = group_by(sessions, |s| &s.row.tool);
+    let mut projects: Vec<(&str, usize)> = group_by(sessions, |s| &s.row.project)
+        .into_iter()
+        .filter(|(project, _)| !project.is_empty())
+        .map(|(project, listed)| (project, listed.len()))
+        .collect();
+    projects.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    let months = months(sessions, summaries);
+    let recent: Vec<&SiteSession> = sessions.iter().take(20).collect();
+
+    site.page("index.html", "Archive", |prefix| {
+        let mut html = format!(
+            "<p>{} sessions, {} summaries, ${:.2} estimated cost. \
+             <a href=\"{prefix}search.html\">Search</a> · <a href=\"{prefix}stats.html\">Stats</a></p>\n",
+            sessions.len(),
+            summaries.len(),
+            total_cost
+        );
+        html.push_str("<h2>Months</h2>\n<ul class=\"links\">");
+        for month in &months {
+            let count = sessions
+                .iter()
+                .filter(|s| s.row.date.starts_with(month.as_str()))
+                .count();
+            html.push_str(&format!(
+                "<li><a href=\"{prefix}months/{month}.html\">{month}</a> \
+                 <span class=\"muted\">{count}</span></li>"
+            ));
+        }
+        html.push_str("</ul>\n<h2>Tools</h2>\n<ul class=\"links\">");
+        for (tool, listed) in &tools {
+            html.push_str(&format!(
+                "<li><a href=\"{prefix}tools/{}.html\">{}</a> <span class=\"muted\">{}</span></li>",
+                slug(tool),
+                escape_html(tool),
+                listed.len()
+            ));
+        }
+        html.push_str("</ul>\n<h2>Projects</h2>\n<ul class=\"links\">");
+        for (project, count) in &projects {
+            html.push_str(&format!(
+                "<li><a href=\"{prefix}projects/{}.html\" title=\"{}\">{}</a> \
+                 <span class=\"muted\">{count}</span></li>",
+                slug(project),
+                escape_html(project),
+                escape_html(&shorten_project(project))
+            ));
+        }
+        html.push_str("</ul>\n<h2>Recent sessions</h2>\n");
+        html.push_str(&sessions_table(prefix, &recent));
+        html
+    })
+}
+
+fn write_search_page(site: &mut Site) -> Result<(), CassioError> {
+    site.page("search.html", "Search", |_| {
+        "<input id=\"q\" type=\"search\" placeholder=\"Search sessions and summaries\" autofocus>\n\
+         <p id=\"status\" class=\"muted\"></p>\n<ol id=\"results\"></ol>\n\
+         <script src=\"search-index.js\"></script>\n<script src=\"search.js\"></script>\n"
+            .to_string()
+    })
+}
+
+/// A filesystem- and URL-safe name for a tool or project page.
+pub(crate) fn slug(text: &str) -> String {
+    let mut slug = String::new();
+    for c in text.trim_matches('/').chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_matches('-');
+    if slug.is_empty() {
+        "unknown".to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+/// Inverted index shipped to the browser: documents are
+/// `[url, title, date, tool, project]` and each term lists document ids.
+#[derive(Debug, Default)]
+struct SearchIndex {
+    docs: Vec<[String; 5]>,
+    terms: BTreeMap<String, BTreeSet<usize>>,
+}
+
+impl SearchIndex {
+    fn add_doc(&mut self, url: &str, title: &str, row: &SessionRow) -> usize {
+        self.add(
+            url,
+            title,
+            &row.date,
+            &row.tool,
+            &shorten_project(&row.project),
+        )
+    }
+
+    fn add(&mut self, url: &str, title: &str, date: &str, tool: &str, project: &str) -> usize {
+        self.docs.push([
+            url.to_string(),
+            title.to_string(),
+            date.to_string(),
+            tool.to_string(),
+            project.to_string(),
+        ]);
+        let id = self.docs.len() - 1;
+        self.add_text(id, [title, tool, project]);
+        id
+    }
+
+    fn add_text<'a>(&mut self, id: usize, lines: impl IntoIterator<Item = &'a str>) {
+        for line in lines {
+            for word in line.split(|c: char| !c.is_alphanumeric() && c != '_') {
+                let count = word.chars().count();
+                if !(MIN_TERM_CHARS..=MAX_TERM_CHARS).contains(&count)
+                    || word.chars().all(|c| c.is_ascii_digit())
+                {
+                    continue;
+                }
+                self.terms
+                    .entry(word.to_lowercase())
+                    .or_default()
+                    .insert(id);
+            }
+        }
+    }
+
+    fn to_script(&self) -> String {
+        let index = json!({ "docs": self.docs, "terms": self.terms });
+        format!("window.CASSIO_SEARCH = {index};\n")
+    }
+}
+
+#[cfg(test)]
+#[path = "site_test.rs"]
+mod tests;
@@ -0,0 +1,70 @@
+// Client-side search over the prebuilt index in search-index.js.
+// Every query word must match a term prefix; documents are ranked by how many
+// distinct index terms they matched.
+(function () {
+  var index = window.CASSIO_SEARCH || { docs: [], terms: {} };
+  var terms = Object.keys(index.terms).sort();
+  var input = document.getElementById("q");
+  var results = document.getElementById("results");
+  var status = document.getElementById("status");
+
+  function lowerBound(prefix) {
+    var lo = 0, hi = terms.length;
+    while (lo < hi) {
+      var mid = (lo + hi) >> 1;
+      if (terms[mid] < prefix) lo = mid + 1; else hi = mid;
+    }
+    return lo;
+  }
+
+  function matching(word) {
+    var hits = {};
+    for (var i = lowerBound(word); i < terms.length && terms[i].indexOf(word) === 0; i++) {
+      index.terms[terms[i]].forEach(function (id) { hits[id] = (hits[id] || 0) + 1; });
+    }
+    return hits;
+  }
+
+  function search(query) {
+    var words = query.toLowerCase().split(/[^\p{L}\p{N}_]+/u).filter(function (w) { return w.length >= 2; });
+    if (!words.length) return [];
+    var scores = null;
+    words.forEach(function (word) {
+      var hits = matching(word);
+      var next = {};
+      Object.keys(hits).forEach(function (id) {
+        if (scores === null || id in scores) next[id] = (scores ? scores[id] : 0) + hits[id];
+      });
+      scores = next;
+    });
+    return Object.keys(scores).sort(function (a, b) {
+      return scores[b] - scores[a] || (index.docs[b][2] > index.docs[a][2] ? 1 : -1);
+    });
+  }
+
+  function render() {
+    var ids = search(input.value);
+    results.textContent = "";
+    ids.slice(0, 200).forEach(function (id) {
+      var doc = index.docs[id];
+      var li = document.createElement("li");
+      var link = document.createElement("a");
+      link.href = doc[0];
+      link.textContent = doc[1];
+      li.appendChild(link);
+      var meta = document.createElement("span");
+      meta.className = "muted";
+      meta.textContent = " " + [doc[2], doc[3], doc[4]].filter(Boolean).join(" · ");
+      li.appendChild(meta);
+      results.appendChild(li);
+    });
+    status.textContent = input.value.trim() ? ids.length + " result(s)" : "";
+    history.replaceState(null, "", input.value ? "#" + encodeURIComponent(input.value) : "#");
+  }
+
+  input.addEventListener("input", render);
+  if (location.hash.length > 1) {
+    input.value = decodeURIComponent(location.hash.slice(1));
+    render();
+  }
+})();
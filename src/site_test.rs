@@ -0,0 +1,181 @@
+use super::*;
+
+fn temp_root(label: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("cassio_site_{label}_{}", std::process::id()))
+}
+
+fn sample_archive(root: &Path) {
+    let _ = fs::remove_dir_all(root);
+    fs::create_dir_all(root.join("2026-04")).unwrap();
+    fs::write(
+        root.join("2026-04/2026-04-01T09-00-00-codex.md"),
+        "📋 Project: /work/faber\n👤 fix the launchd plist\nOPENAI_API_KEY=sk-secret\n\
+         ✅ Bash: cargo build zebraword\n🤖 rewrote the plist loader\n📋 Messages: 5 user, 6 assistant\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("2026-04/2026-04-03T10-00-00-claude.md"),
+        "📋 Project: /work/cassio\n👤 hello\n📋 Messages: 1 user, 9 assistant\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("2026-04/2026-04-03T10-00-00-claude.html"),
+        "<!DOCTYPE html><p>exported page</p>",
+    )
+    .unwrap();
+    fs::write(
+        root.join("2026-04/2026-04-01.daily.md"),
+        "# Daily 2026-04-01\nmigrated sync\n",
+    )
+    .unwrap();
+    fs::write(root.join("2026-04/2026-04-01.compaction.md"), "legacy\n").unwrap();
+    fs::create_dir_all(root.join("2026-03")).unwrap();
+    fs::write(root.join("2026-03/2026-W14.weekly.md"), "# Week 14\n").unwrap();
+    fs::write(root.join("2026-04/2026-04.monthly.md"), "# April\n").unwrap();
+}
+
+fn read(dest: &Path, rel: &str) -> String {
+    fs::read_to_string(dest.join(rel)).unwrap()
+}
+
+#[test]
+fn builds_index_listing_summary_and_session_pages() {
+    let root = temp_root("build_archive");
+    let dest = temp_root("build_site");
+    sample_archive(&root);
+    let _ = fs::remove_dir_all(&dest);
+
+    let report = build_site(&root, &dest).unwrap();
+    assert_eq!(report.sessions, 2);
+    assert_eq!(report.summaries, 3);
+
+    let index = read(&dest, "index.html");
+    assert!(index.contains("href=\"months/2026-04.html\""));
+    assert!(index.contains("href=\"months/2026-03.html\""));
+    assert!(index.contains("href=\"tools/codex.html\""));
+    assert!(index.contains("href=\"projects/work-faber.html\""));
+
+    let month = read(&dest, "months/2026-04.html");
+    assert!(month.contains("href=\"../monthly/2026-04.html\""));
+    assert!(month.contains("href=\"../daily/2026-04-01.html\""));
+    assert!(month.contains(
+        "<a href=\"../sessions/2026-04/2026-04-01T09-00-00-codex.html\">fix the launchd plist</a>"
+    ));
+    assert!(read(&dest, "months/2026-03.html").contains("href=\"../weekly/2026-W14.html\""));
+
+    let daily = read(&dest, "daily/2026-04-01.html");
+    assert!(daily.contains("migrated sync"), "the .daily.md wins");
+    assert!(daily.contains("<h2>Sessions (1)</h2>"));
+    let weekly = read(&dest, "weekly/2026-W14.html");
+    assert!(weekly.contains("<h2>Sessions (2)</h2>"));
+
+    let session = read(&dest, "sessions/2026-04/2026-04-01T09-00-00-codex.html");
+    assert!(session.contains("href=\"../../style.css\""));
+    assert!(session.contains("href=\"../../daily/2026-04-01.html\""));
+    assert!(session.contains("<div class=\"line user\">"));
+    assert!(!session.contains("sk-secret"));
+    assert_eq!(
+        read(&dest, "sessions/2026-04/2026-04-03T10-00-00-claude.html"),
+        "<!DOCTYPE html><p>exported page</p>"
+    );
+    assert!(
+        read(&dest, "stats.html").contains("<td><a href=\"tools/claude.html\">claude</a></td>")
+    );
+    assert!(read(&dest, "search.html").contains("<script src=\"search-index.js\"></script>"));
+
+    let _ = fs::remove_dir_all(&root);
+    let _ = fs::remove_dir_all(&dest);
+}
+
+#[test]
+fn search_index_covers_conversation_and_summaries_only() {
+    let root = temp_root("index_archive");
+    let dest = temp_root("index_site");
+    sample_archive(&root);
+    let _ = fs::remove_dir_all(&dest);
+    build_site(&root, &dest).unwrap();
+
+    let script = read(&dest, "search-index.js");
+    let json = script
+        .strip_prefix("window.CASSIO_SEARCH = ")
+        .and_then(|s| s.trim_end().strip_suffix(';'))
+        .unwrap();
+    let index: serde_json::Value = serde_json::from_str(json).unwrap();
+    let docs = index["docs"].as_array().unwrap();
+    let doc_urls = |term: &str| -> Vec<&str> {
+        index["terms"][term]
+            .as_array()
+            .map(|ids| {
+                ids.iter()
+                    .map(|id| docs[id.as_u64().unwrap() as usize][0].as_str().unwrap())
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    assert_eq!(
+        doc_urls("loader"),
+        vec!["sessions/2026-04/2026-04-01T09-00-00-codex.html"]
+    );
+    assert_eq!(doc_urls("migrated"), vec!["daily/2026-04-01.html"]);
+    assert!(
+        doc_urls("zebraword").is_empty(),
+        "tool output is not indexed"
+    );
+    assert!(doc_urls("secret").is_empty(), "redacted before indexing");
+
+    let _ = fs::remove_dir_all(&root);
+    let _ = fs::remove_dir_all(&dest);
+}
+
+#[test]
+fn refuses_foreign_directories_and_replaces_its_own() {
+    let root = temp_root("dest_archive");
+    let dest = temp_root("dest_site");
+    sample_archive(&root);
+    let _ = fs::remove_dir_all(&dest);
+    fs::create_dir_all(&dest).unwrap();
+    fs::write(dest.join("notes.txt"), "keep me").unwrap();
+
+    let err = build_site(&root, &dest).unwrap_err().to_string();
+    assert!(err.contains("refusing to overwrite"), "{err}");
+    assert!(build_site(&root, &root.join("2026-04")).is_err());
+
+    fs::remove_file(dest.join("notes.txt")).unwrap();
+    build_site(&root, &dest).unwrap();
+    fs::write(dest.join("stale.html"), "old").unwrap();
+    build_site(&root, &dest).unwrap();
+    assert!(!dest.join("stale.html").exists());
+    assert!(dest.join(SITE_MARKER).is_file());
+
+    let _ = fs::remove_dir_all(&root);
+    let _ = fs::remove_dir_all(&dest);
+}
+
+#[test]
+fn titles_slugs_and_summary_names() {
+    assert_eq!(
+        session_title("📋 Project: x\n👤  fix it \n👤 later").as_deref(),
+        Some("fix it")
+    );
+    let long = format!("👤 {}", "word ".repeat(30));
+    assert_eq!(session_title(&long).unwrap().chars().count(), TITLE_CHARS);
+    assert_eq!(session_title("🤖 no user"), None);
+
+    assert_eq!(slug("/Users/me/My Project/"), "users-me-my-project");
+    assert_eq!(slug("///"), "unknown");
+
+    assert_eq!(
+        classify_summary("2026-04-01.compaction.md"),
+        Some((SummaryKind::Daily, "2026-04-01", false))
+    );
+    assert_eq!(
+        classify_summary("2026-W14.weekly.md"),
+        Some((SummaryKind::Weekly, "2026-W14", true))
+    );
+    assert_eq!(
+        classify_summary("2026-04.monthly.md"),
+        Some((SummaryKind::Monthly, "2026-04", true))
+    );
+    assert_eq!(classify_summary("notes.daily.md"), None);
+}
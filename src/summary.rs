@@ -5,16 +5,21 @@
 //! duration, and interactive/agentic/abandoned session classification.
 
 use std::collections::{BTreeMap, BTreeSet};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
+use serde::Serialize;
 use walkdir::WalkDir;
 
 use crate::error::CassioError;
+use crate::frontmatter;
+use crate::manifest::SessionCatalog;
 use crate::pricing;
+use crate::search::DateBounds;
 
 /// Stats parsed from a single session transcript file.
 #[derive(Default)]
 struct TranscriptStats {
+    path: PathBuf,
     tool_name: String,
     date: String, // YYYY-MM-DD
     project: String,
@@ -29,6 +34,8 @@ struct TranscriptStats {
     cache_read_tokens: u64,
     cache_write_tokens: u64,
     duration_secs: i64,
+    /// Cost from the frontmatter block; `None` falls back to an estimate.
+    cost_usd: Option<f64>,
 }
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
@@ -51,6 +58,14 @@ impl TranscriptKind {
             Self::Interactive
         }
     }
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Interactive => "interactive",
+            Self::Agentic => "agentic",
+            Self::Abandoned => "abandoned",
+        }
+    }
 }
 
 /// Aggregated stats for a group (month×tool or project).
@@ -89,15 +104,19 @@ impl Aggregate {
         self.cache_read_tokens += s.cache_read_tokens;
         self.cache_write_tokens += s.cache_write_tokens;
         self.duration_secs += s.duration_secs;
-        self.cost += pricing::estimate_cost(
-            s.model.as_deref(),
-            s.input_tokens,
-            s.output_tokens,
-            s.cache_read_tokens,
-            s.cache_write_tokens,
-            None,
-        )
-        .unwrap_or(0.0);
+        self.cost += s
+            .cost_usd
+            .or_else(|| {
+                pricing::estimate_cost(
+                    s.model.as_deref(),
+                    s.input_tokens,
+                    s.output_tokens,
+                    s.cache_read_tokens,
+                    s.cache_write_tokens,
+                    None,
+                )
+            })
+            .unwrap_or(0.0);
     }
 
     fn add_agg(&mut self, other: &Aggregate) {
@@ -147,10 +166,117 @@ pub fn run_summary(dir: &Path, detailed: bool, daily: bool) -> Result<(), Cassio
     Ok(())
 }
 
+/// Totals for one month and tool, as returned by [`stats_by_month`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MonthToolStats {
+    /// `YYYY-MM`
+    pub month: String,
+    pub tool: String,
+    pub sessions: u32,
+    pub interactive_sessions: u32,
+    pub agentic_sessions: u32,
+    pub abandoned_sessions: u32,
+    pub user_messages: u32,
+    pub assistant_messages: u32,
+    pub tool_calls_ok: u32,
+    pub tool_calls_failed: u32,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_read_tokens: u64,
+    pub cache_write_tokens: u64,
+    pub duration_secs: i64,
+    pub estimated_cost_usd: f64,
+}
+
+/// The regular (month × tool) summary as data, for callers that cannot print
+/// to stdout. `from`/`to` take `YYYY-MM` or `YYYY-MM-DD` like `cassio search`.
+pub fn stats_by_month(
+    dir: &Path,
+    from: Option<&str>,
+    to: Option<&str>,
+) -> Result<Vec<MonthToolStats>, CassioError> {
+    let bounds = DateBounds::parse(from, to)?;
+    let mut by_month_tool: BTreeMap<(String, String), Aggregate> = BTreeMap::new();
+    for s in collect_stats(dir)? {
+        let month = &s.date[..7];
+        if !bounds.contains(month, Some(&s.date)) {
+            continue;
+        }
+        by_month_tool
+            .entry((month.to_string(), s.tool_name.clone()))
+            .or_default()
+            .add(&s);
+    }
+    Ok(by_month_tool
+        .into_iter()
+        .map(|((month, tool), agg)| MonthToolStats {
+            month,
+            tool,
+            sessions: agg.sessions,
+            interactive_sessions: agg.interactive_sessions,
+            agentic_sessions: agg.agentic_sessions,
+            abandoned_sessions: agg.abandoned_sessions,
+            user_messages: agg.user_msgs,
+            assistant_messages: agg.asst_msgs,
+            tool_calls_ok: agg.tool_ok,
+            tool_calls_failed: agg.tool_fail,
+            input_tokens: agg.input_tokens,
+            output_tokens: agg.output_tokens,
+            cache_read_tokens: agg.cache_read_tokens,
+            cache_write_tokens: agg.cache_write_tokens,
+            duration_secs: agg.duration_secs,
+            estimated_cost_usd: agg.cost,
+        })
+        .collect())
+}
+
+/// One transcript's listing fields, as returned by [`session_rows`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SessionRow {
+    pub path: PathBuf,
+    /// `YYYY-MM-DD`
+    pub date: String,
+    pub tool: String,
+    pub project: String,
+    /// Last model seen, as recorded in the transcript.
+    pub model: Option<String>,
+    /// `interactive`, `agentic`, or `abandoned`.
+    pub kind: &'static str,
+    pub estimated_cost_usd: f64,
+}
+
+/// Every transcript under `dir` with the fields `cassio summary` derives from
+/// it, newest first.
+pub fn session_rows(dir: &Path) -> Result<Vec<SessionRow>, CassioError> {
+    let mut rows: Vec<SessionRow> = collect_stats(dir)?
+        .into_iter()
+        .map(|s| {
+            let mut agg = Aggregate::default();
+            agg.add(&s);
+            SessionRow {
+                path: s.path,
+                date: s.date,
+                tool: s.tool_name,
+                project: s.project,
+                model: s.model,
+                kind: s.kind.label(),
+                estimated_cost_usd: agg.cost,
+            }
+        })
+        .collect();
+    rows.sort_by(|a, b| b.date.cmp(&a.date).then_with(|| b.path.cmp(&a.path)));
+    Ok(rows)
+}
+
 fn collect_stats(dir: &Path) -> Result<Vec<TranscriptStats>, CassioError> {
     let mut results = Vec::new();
+    let catalog = SessionCatalog::load(dir);
 
-    for entry in WalkDir::new(dir).into_iter().filter_map(|e| e.ok()) {
+    for entry in WalkDir::new(dir)
+        .into_iter()
+        .filter_entry(|e| !crate::manifest::is_state_dir(e))
+        .filter_map(|e| e.ok())
+    {
         let path = entry.path();
         if !path.is_file() {
             continue;
@@ -159,7 +285,9 @@ fn collect_stats(dir: &Path) -> Result<Vec<TranscriptStats>, CassioError> {
             Some(n) => n,
             None => continue,
         };
-        let Some((date, tool_name)) = parse_session_filename(name) else {
+        let Some((date, tool_name)) =
+            parse_session_filename(name).or_else(|| catalog.lookup_transcript(path))
+        else {
             continue;
         };
 
@@ -199,11 +327,29 @@ fn parse_transcript_stats(
 ) -> Result<TranscriptStats, CassioError> {
     let content = std::fs::read_to_string(path)?;
     let mut stats = TranscriptStats {
+        path: path.to_path_buf(),
         date: date.to_string(),
         tool_name: tool_name.to_string(),
         ..Default::default()
     };
 
+    if let Some(fm) = frontmatter::parse(&content) {
+        stats.project = fm.project_path;
+        stats.model = fm.model;
+        stats.duration_secs = fm.duration_seconds.unwrap_or(0);
+        stats.user_msgs = fm.user_messages;
+        stats.asst_msgs = fm.assistant_messages;
+        stats.tool_ok = fm.tool_calls.saturating_sub(fm.tool_errors);
+        stats.tool_fail = fm.tool_errors;
+        stats.input_tokens = fm.input_tokens;
+        stats.output_tokens = fm.output_tokens;
+        stats.cache_read_tokens = fm.cache_read_tokens;
+        stats.cache_write_tokens = fm.cache_write_tokens;
+        stats.cost_usd = fm.cost_usd;
+        stats.kind = TranscriptKind::classify(stats.user_msgs, stats.asst_msgs);
+        return Ok(stats);
+    }
+
     for line in content.lines() {
         if let Some(rest) = strip_emoji_prefix(line, "📋") {
             if let Some(val) = rest.strip_prefix(" Project: ") {
@@ -474,7 +620,7 @@ fn print_detailed(stats: &[TranscriptStats]) {
 }
 
 /// Shorten a project path: keep last 3 components (or fewer).
-fn shorten_project(path: &str) -> String {
+pub(crate) fn shorten_project(path: &str) -> String {
     let normalized = path.replace('\\', "/").trim_end_matches('/').to_string();
     let parts: Vec<&str> = normalized.split('/').collect();
     if parts.len() <= 3 {
@@ -150,6 +150,7 @@ fn test_transcript_kind_classification_interactive_after_two_user_messages() {
 fn test_aggregate_add() {
     let mut agg = Aggregate::default();
     let stats = TranscriptStats {
+        path: PathBuf::from("2025-01/2025-01-15T09-00-00-claude.md"),
         tool_name: "claude".to_string(),
         date: "2025-01-15".to_string(),
         project: "/proj".to_string(),
@@ -164,6 +165,7 @@ fn test_aggregate_add() {
         cache_read_tokens: 0,
         cache_write_tokens: 0,
         duration_secs: 60,
+        cost_usd: None,
     };
     agg.add(&stats);
     assert_eq!(agg.sessions, 1);
@@ -213,3 +215,68 @@ fn test_aggregate_add_agg() {
     assert_eq!(a.kind_breakdown(), "1/1/1");
     assert_eq!(a.user_msgs, 15);
 }
+
+#[test]
+fn test_session_rows_newest_first_with_kind_and_project() {
+    let dir = std::env::temp_dir().join(format!("cassio_summary_rows_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(dir.join("2026-04")).unwrap();
+    std::fs::write(
+        dir.join("2026-04/2026-04-01T09-00-00-codex.md"),
+        "📋 Project: /work/faber\n👤 hi\n📋 Messages: 5 user, 6 assistant\n",
+    )
+    .unwrap();
+    std::fs::write(
+        dir.join("2026-04/2026-04-03T10-00-00-claude.md"),
+        "📋 Project: /work/cassio\n📋 Messages: 1 user, 9 assistant\n",
+    )
+    .unwrap();
+    std::fs::write(dir.join("2026-04/2026-04-03.daily.md"), "# daily\n").unwrap();
+
+    let rows = session_rows(&dir).unwrap();
+    let summary: Vec<(&str, &str, &str, &str)> = rows
+        .iter()
+        .map(|r| (r.date.as_str(), r.tool.as_str(), r.project.as_str(), r.kind))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("2026-04-03", "claude", "/work/cassio", "agentic"),
+            ("2026-04-01", "codex", "/work/faber", "interactive"),
+        ]
+    );
+    assert!(rows[0].path.ends_with("2026-04-03T10-00-00-claude.md"));
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn test_parse_transcript_stats_prefers_frontmatter() {
+    let dir =
+        std::env::temp_dir().join(format!("cassio_summary_frontmatter_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("2026-04-01T09-00-00-claude.md");
+    std::fs::write(
+        &path,
+        "---\nsession_id: s-1\ntool: claude\nproject_path: /work/cassio\n\
+         started_at: 2026-04-01T09:00:00Z\nsession_kind: human\nmodel: claude-opus-4-5\n\
+         user_messages: 3\nassistant_messages: 7\ntool_calls: 5\ntool_errors: 2\n\
+         duration_seconds: 600\ninput_tokens: 1234567\ncost_usd: 0.5\n---\n\
+         📋 Project: /stale/header\n📋 Messages: 1 user, 1 assistant\n",
+    )
+    .unwrap();
+
+    let stats = parse_transcript_stats(&path, "2026-04-01", "claude").unwrap();
+    assert_eq!(stats.project, "/work/cassio");
+    assert_eq!((stats.user_msgs, stats.asst_msgs), (3, 7));
+    assert_eq!((stats.tool_ok, stats.tool_fail), (3, 2));
+    assert_eq!(stats.input_tokens, 1_234_567, "exact, not the rounded 1.2M");
+    assert_eq!(stats.kind, TranscriptKind::Interactive);
+
+    let mut agg = Aggregate::default();
+    agg.add(&stats);
+    assert_eq!(agg.cost, 0.5);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,192 @@
+//! Output path templates: where batch mode writes each session's artifacts.
+//!
+//! The default layout is `YYYY-MM/YYYY-MM-DDTHH-MM-SS-{tool}`, derived from the
+//! source file before parsing (`discover::derive_output_path`). An
+//! `output_template` in config replaces it with a root-relative pattern rendered
+//! from the parsed session, for example `{project}/{yyyy}/{mm}/{dd}T{HH}-{MM}-{tool}`.
+//! Artifact extensions (`.md`, `.jsonl`, `.training.json`) are appended to the
+//! rendered stem.
+//!
+//! | Placeholder    | Value                                               |
+//! |----------------|-----------------------------------------------------|
+//! | `{yyyy}` `{mm}` `{dd}` | Session start date (UTC)                    |
+//! | `{HH}` `{MM}` `{SS}`   | Session start time (UTC)                    |
+//! | `{tool}`       | Tool name (`claude`, `codex`, `claude-chat`, ...)   |
+//! | `{project}`    | Slug of the project directory name                  |
+//! | `{session_id}` | Source session id                                   |
+//! | `{branch}`     | Git branch, or `no-branch`                          |
+//!
+//! # TRADE-OFFS
+//!
+//! - Templated names carry no guaranteed date or tool, so readers (summary,
+//!   search, compaction, index) fall back to the manifest entry recorded when
+//!   the file was written (`manifest::SessionCatalog`).
+//! - Up-to-date checks look the previous output up in the manifest by source
+//!   path; after changing the template, run once with `--force` to move
+//!   existing transcripts.
+
+use chrono::{Datelike, Timelike};
+
+use crate::ast::Session;
+use crate::error::CassioError;
+
+/// The built-in layout, expressed as a template.
+pub const DEFAULT_OUTPUT_TEMPLATE: &str = "{yyyy}-{mm}/{yyyy}-{mm}-{dd}T{HH}-{MM}-{SS}-{tool}";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputTemplate {
+    parts: Vec<Part>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Part {
+    Literal(String),
+    Year,
+    Month,
+    Day,
+    Hour,
+    Minute,
+    Second,
+    Tool,
+    Project,
+    SessionId,
+    Branch,
+}
+
+impl OutputTemplate {
+    /// Parse and validate a template string.
+    pub fn parse(template: &str) -> Result<Self, CassioError> {
+        let invalid = |reason: &str| {
+            CassioError::Other(format!("Invalid output_template '{template}': {reason}"))
+        };
+        if template.trim().is_empty() {
+            return Err(invalid("template is empty"));
+        }
+        if template.starts_with('/') || template.ends_with('/') || template.contains('\\') {
+            return Err(invalid(
+                "must be a relative path without leading/trailing '/' or '\\'",
+            ));
+        }
+        if template
+            .split('/')
+            .any(|segment| segment.is_empty() || segment == "." || segment == "..")
+        {
+            return Err(invalid("path segments must not be empty, '.' or '..'"));
+        }
+
+        let mut parts = Vec::new();
+        let mut rest = template;
+        while let Some(open) = rest.find('{') {
+            if open > 0 {
+                parts.push(Part::Literal(rest[..open].to_string()));
+            }
+            let after = &rest[open + 1..];
+            let close = after.find('}').ok_or_else(|| invalid("unclosed '{'"))?;
+            let part = match &after[..close] {
+                "yyyy" => Part::Year,
+                "mm" => Part::Month,
+                "dd" => Part::Day,
+                "HH" => Part::Hour,
+                "MM" => Part::Minute,
+                "SS" => Part::Second,
+                "tool" => Part::Tool,
+                "project" => Part::Project,
+                "session_id" => Part::SessionId,
+                "branch" => Part::Branch,
+                other => return Err(invalid(&format!("unknown placeholder '{{{other}}}'"))),
+            };
+            parts.push(part);
+            rest = &after[close + 1..];
+        }
+        if rest.contains('}') {
+            return Err(invalid("unmatched '}'"));
+        }
+        if !rest.is_empty() {
+            parts.push(Part::Literal(rest.to_string()));
+        }
+        Ok(Self { parts })
+    }
+
+    /// Render the `(folder, stem)` output name for `session`.
+    ///
+    /// `folder` is everything before the last `/` (empty for a flat template),
+    /// matching the pair `derive_output_stem_for` returns for the default layout.
+    pub fn render(&self, session: &Session) -> (String, String) {
+        let meta = &session.metadata;
+        let dt = meta.started_at;
+        let mut out = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Literal(text) => out.push_str(text),
+                Part::Year => out.push_str(&format!("{:04}", dt.year())),
+                Part::Month => out.push_str(&format!("{:02}", dt.month())),
+                Part::Day => out.push_str(&format!("{:02}", dt.day())),
+                Part::Hour => out.push_str(&format!("{:02}", dt.hour())),
+                Part::Minute => out.push_str(&format!("{:02}", dt.minute())),
+                Part::Second => out.push_str(&format!("{:02}", dt.second())),
+                Part::Tool => out.push_str(&meta.tool.to_string()),
+                Part::Project => out.push_str(&project_slug(&meta.project_path)),
+                Part::SessionId => out.push_str(&path_safe(&meta.session_id, "unknown")),
+                Part::Branch => out.push_str(&path_safe(
+                    meta.git_branch.as_deref().unwrap_or_default(),
+                    "no-branch",
+                )),
+            }
+        }
+        match out.rsplit_once('/') {
+            Some((folder, stem)) => (folder.to_string(), stem.to_string()),
+            None => (String::new(), out),
+        }
+    }
+}
+
+/// Lowercase slug of the project directory name (`/Users/me/github/Cassio` → `cassio`).
+fn project_slug(project_path: &str) -> String {
+    let name = project_path
+        .trim_end_matches(['/', '\\'])
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or_default();
+    let slug = name
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() || ch == '-' || ch == '_' || ch == '.' {
+                ch.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect::<String>()
+        .trim_matches(['-', '.'])
+        .to_string();
+    if slug.is_empty() {
+        "unknown".to_string()
+    } else {
+        slug
+    }
+}
+
+/// Make a placeholder value safe as (part of) one path segment.
+fn path_safe(value: &str, fallback: &str) -> String {
+    let safe = value
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() || ch == '-' || ch == '_' || ch == '.' {
+                ch
+            } else {
+                '-'
+            }
+        })
+        .collect::<String>()
+        .trim_matches(['-', '.'])
+        .to_string();
+    if safe.is_empty() {
+        fallback.to_string()
+    } else {
+        safe
+    }
+}
+
+#[cfg(test)]
+#[path = "template_test.rs"]
+mod tests;
@@ -0,0 +1,70 @@
+use super::*;
+use crate::ast::{SessionKind, SessionMetadata, SessionStats, Tool};
+
+fn session(tool: Tool, project: &str, branch: Option<&str>) -> Session {
+    Session {
+        metadata: SessionMetadata {
+            session_id: "abc/123".to_string(),
+            tool,
+            project_path: project.to_string(),
+            started_at: "2025-11-12T21:52:16Z".parse().unwrap(),
+            session_kind: SessionKind::Human,
+            version: None,
+            git_branch: branch.map(str::to_string),
+            model: None,
+            title: None,
+        },
+        messages: Vec::new(),
+        stats: SessionStats::default(),
+    }
+}
+
+#[test]
+fn default_template_matches_builtin_layout() {
+    let template = OutputTemplate::parse(DEFAULT_OUTPUT_TEMPLATE).unwrap();
+    let (folder, stem) = template.render(&session(Tool::Codex, "/tmp/p", None));
+    assert_eq!(folder, "2025-11");
+    assert_eq!(stem, "2025-11-12T21-52-16-codex");
+}
+
+#[test]
+fn project_branch_and_session_placeholders() {
+    let template =
+        OutputTemplate::parse("{project}/{tool}/{yyyy}/{mm}/{dd}-{branch}-{session_id}").unwrap();
+    let (folder, stem) = template.render(&session(
+        Tool::Claude,
+        "/Users/me/github/My Project/",
+        Some("feature/login"),
+    ));
+    assert_eq!(folder, "my-project/claude/2025/11");
+    assert_eq!(stem, "12-feature-login-abc-123");
+
+    let (folder, stem) = template.render(&session(Tool::Claude, "", None));
+    assert_eq!(folder, "unknown/claude/2025/11");
+    assert_eq!(stem, "12-no-branch-abc-123");
+}
+
+#[test]
+fn flat_template_has_empty_folder() {
+    let template = OutputTemplate::parse("{yyyy}{mm}{dd}-{tool}").unwrap();
+    let (folder, stem) = template.render(&session(Tool::Pi, "/tmp/p", None));
+    assert_eq!(folder, "");
+    assert_eq!(stem, "20251112-pi");
+}
+
+#[test]
+fn rejects_invalid_templates() {
+    for bad in [
+        "",
+        "/abs/{tool}",
+        "{tool}/",
+        "../{tool}",
+        "a//{tool}",
+        "a\\{tool}",
+        "{nope}",
+        "{tool",
+        "tool}",
+    ] {
+        assert!(OutputTemplate::parse(bad).is_err(), "accepted {bad:?}");
+    }
+}
@@ -0,0 +1,358 @@
+//! Topic clustering of the archive (`cassio topics`).
+//!
+//! Answers "what did these months consist of?" without an LLM: the pooled
+//! session embeddings from the semantic index (see `related`) are clustered
+//! with spherical k-means, and each cluster is labelled from its transcripts —
+//! the terms that occur in its sessions far more often than in the rest of the
+//! range, and the opening user messages of the sessions nearest its centroid.
+//! The report lists every session's membership, as markdown or JSON.
+//!
+//! # TRADE-OFFS
+//!
+//! - k-means rather than HDBSCAN: it reuses the IVF trainer in `ann`, needs no
+//!   density parameters, and is deterministic for a given index. It does force
+//!   every session into some topic, so one-off sessions land in the nearest
+//!   theme instead of a noise bucket; their low similarity shows in the report.
+//! - The cluster count defaults to `sqrt(sessions / 2)`, capped at
+//!   `MAX_TOPICS`. `--clusters` overrides it when a month is obviously more or
+//!   less varied than that.
+//! - Terms come from user and assistant lines only; tool output repeats file
+//!   contents and would label clusters by whichever files they read.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::ann::{self, dot};
+use crate::error::CassioError;
+use crate::formatter::emoji_text::{EMOJI_ASSISTANT, EMOJI_USER};
+use crate::related::{PooledSession, RelatedSession, SessionVectors};
+use crate::search::DateBounds;
+
+/// Upper bound on the default cluster count.
+const MAX_TOPICS: usize = 12;
+const TERMS_PER_TOPIC: usize = 8;
+const QUOTES_PER_TOPIC: usize = 3;
+/// Quotes shorter than this are passed over for a later user message.
+const MIN_QUOTE_CHARS: usize = 20;
+const MAX_QUOTE_CHARS: usize = 200;
+
+const STOP_WORDS: &[&str] = &[
+    "about", "after", "again", "all", "also", "and", "any", "are", "because", "been", "before",
+    "being", "but", "can", "could", "did", "does", "doing", "done", "each", "for", "from", "get",
+    "had", "has", "have", "here", "how", "into", "its", "just", "let", "like", "make", "more",
+    "most", "need", "not", "now", "off", "one", "only", "other", "our", "out", "over", "please",
+    "should", "some", "still", "such", "than", "that", "the", "their", "them", "then", "there",
+    "these", "they", "this", "those", "through", "use", "using", "very", "want", "was", "way",
+    "were", "what", "when", "where", "which", "while", "who", "why", "will", "with", "would",
+    "yes", "you", "your",
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TopicsReport {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<String>,
+    /// Sessions clustered.
+    pub sessions: usize,
+    /// Largest topic first.
+    pub topics: Vec<Topic>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Topic {
+    pub id: usize,
+    /// Most distinctive terms, best first.
+    pub terms: Vec<String>,
+    pub quotes: Vec<Quote>,
+    /// Member sessions; `score` is cosine similarity to the topic centroid.
+    pub sessions: Vec<RelatedSession>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Quote {
+    pub path: String,
+    pub text: String,
+}
+
+/// Cluster the indexed sessions dated within `from`..=`to` into topics.
+pub fn build_topics(
+    root: &Path,
+    index_path: &Path,
+    from: Option<&str>,
+    to: Option<&str>,
+    clusters: Option<usize>,
+) -> Result<TopicsReport, CassioError> {
+    if clusters == Some(0) {
+        return Err(CassioError::Other("--clusters must be at least 1".into()));
+    }
+    let bounds = DateBounds::parse(from, to)?;
+    let vectors = SessionVectors::load(index_path)?;
+    let sessions: Vec<&PooledSession> = vectors
+        .sessions()
+        .iter()
+        .filter(|session| in_range(&bounds, session))
+        .collect();
+    let mut report = TopicsReport {
+        from: from.map(str::to_string),
+        to: to.map(str::to_string),
+        sessions: sessions.len(),
+        topics: Vec::new(),
+    };
+    let Some(dims) = sessions.first().map(|session| session.vector.len()) else {
+        return Ok(report);
+    };
+    let sessions: Vec<&PooledSession> = sessions
+        .into_iter()
+        .filter(|session| session.vector.len() == dims)
+        .collect();
+    report.sessions = sessions.len();
+
+    let k = clusters
+        .unwrap_or_else(|| default_topic_count(sessions.len()))
+        .min(sessions.len());
+    let sample: Vec<Vec<f32>> = sessions.iter().map(|s| s.vector.clone()).collect();
+    let centroids = ann::kmeans(&sample, k);
+    let mut members: Vec<Vec<RelatedSession>> = vec![Vec::new(); centroids.len()];
+    for session in &sessions {
+        let nearest = ann::nearest_centroid(&centroids, &session.vector);
+        members[nearest].push(RelatedSession {
+            path: session.source_path.clone(),
+            score: dot(&centroids[nearest], &session.vector),
+            date: session.date.clone(),
+            tool: session.tool.clone(),
+            project: session.project.clone(),
+        });
+    }
+
+    let texts: HashMap<&str, SessionText> = sessions
+        .iter()
+        .map(|session| {
+            let content = fs::read_to_string(root.join(&session.source_path)).unwrap_or_default();
+            (session.source_path.as_str(), SessionText::parse(&content))
+        })
+        .collect();
+    let mut document_frequency: HashMap<&str, usize> = HashMap::new();
+    for text in texts.values() {
+        for term in &text.terms {
+            *document_frequency.entry(term.as_str()).or_default() += 1;
+        }
+    }
+
+    members.retain(|sessions| !sessions.is_empty());
+    members.sort_by(|a, b| b.len().cmp(&a.len()).then(a[0].path.cmp(&b[0].path)));
+    for (index, mut sessions) in members.into_iter().enumerate() {
+        sessions.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.path.cmp(&b.path)));
+        let cluster_texts: Vec<&SessionText> = sessions
+            .iter()
+            .filter_map(|session| texts.get(session.path.as_str()))
+            .collect();
+        let quotes = sessions
+            .iter()
+            .filter_map(|session| {
+                let quote = texts.get(session.path.as_str())?.quote.clone()?;
+                Some(Quote {
+                    path: session.path.clone(),
+                    text: quote,
+                })
+            })
+            .take(QUOTES_PER_TOPIC)
+            .collect();
+        report.topics.push(Topic {
+            id: index + 1,
+            terms: distinctive_terms(&cluster_texts, &document_frequency, report.sessions),
+            quotes,
+            sessions,
+        });
+    }
+    Ok(report)
+}
+
+pub fn run_topics(
+    root: &Path,
+    index_path: &Path,
+    from: Option<&str>,
+    to: Option<&str>,
+    clusters: Option<usize>,
+    json: bool,
+) -> Result<(), CassioError> {
+    let report = build_topics(root, index_path, from, to, clusters)?;
+    if json {
+        serde_json::to_writer_pretty(std::io::stdout(), &report)?;
+        println!();
+    } else {
+        print!("{}", render_markdown(&report));
+    }
+    Ok(())
+}
+
+/// `sqrt(sessions / 2)` topics, between 1 and `MAX_TOPICS`.
+fn default_topic_count(sessions: usize) -> usize {
+    ((sessions as f64 / 2.0).sqrt().round() as usize).clamp(1, MAX_TOPICS)
+}
+
+fn in_range(bounds: &DateBounds, session: &PooledSession) -> bool {
+    let date = session.date.as_deref();
+    let month_dir = session
+        .source_path
+        .split('/')
+        .next()
+        .filter(|dir| dir.len() == 7)
+        .or_else(|| date.and_then(|date| date.get(..7)))
+        .unwrap_or_default();
+    bounds.contains(month_dir, date)
+}
+
+/// What a transcript contributes to topic labels.
+struct SessionText {
+    /// Distinct terms in user and assistant lines.
+    terms: HashSet<String>,
+    /// The first substantial user message.
+    quote: Option<String>,
+}
+
+impl SessionText {
+    fn parse(content: &str) -> Self {
+        let mut terms = HashSet::new();
+        let mut first_user: Option<&str> = None;
+        let mut quote: Option<&str> = None;
+        for line in content.lines() {
+            let user = line.strip_prefix(EMOJI_USER);
+            let Some(text) = user.or_else(|| line.strip_prefix(EMOJI_ASSISTANT)) else {
+                continue;
+            };
+            let text = text.trim();
+            if let Some(user) = user.map(str::trim).filter(|user| !user.is_empty()) {
+                first_user.get_or_insert(user);
+                if quote.is_none() && user.chars().count() >= MIN_QUOTE_CHARS {
+                    quote = Some(user);
+                }
+            }
+            terms.extend(tokenize(text));
+        }
+        Self {
+            terms,
+            quote: quote.or(first_user).map(truncate_quote),
+        }
+    }
+}
+
+fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|ch: char| !(ch.is_alphanumeric() || ch == '_' || ch == '-'))
+        .map(|word| word.trim_matches('-').to_lowercase())
+        .filter(|word| word.chars().count() >= 3)
+        .filter(|word| !word.chars().all(|ch| ch.is_ascii_digit() || ch == '-'))
+        .filter(|word| !STOP_WORDS.contains(&word.as_str()))
+}
+
+/// Terms ranked by how much more of the cluster's sessions use them than the
+/// whole range does: `(cluster share) × ln((total + 1) / range frequency)`.
+/// Terms used by a single session are ignored in multi-session clusters.
+fn distinctive_terms(
+    cluster: &[&SessionText],
+    document_frequency: &HashMap<&str, usize>,
+    total: usize,
+) -> Vec<String> {
+    let mut cluster_frequency: HashMap<&str, usize> = HashMap::new();
+    for text in cluster {
+        for term in &text.terms {
+            *cluster_frequency.entry(term.as_str()).or_default() += 1;
+        }
+    }
+    let min_sessions = cluster.len().min(2);
+    let mut scored: Vec<(&str, f64)> = cluster_frequency
+        .into_iter()
+        .filter(|(_, count)| *count >= min_sessions)
+        .map(|(term, count)| {
+            let share = count as f64 / cluster.len() as f64;
+            let range = document_frequency.get(term).copied().unwrap_or(count) as f64;
+            (term, share * ((total as f64 + 1.0) / range).ln())
+        })
+        .collect();
+    scored.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(b.0)));
+    scored
+        .into_iter()
+        .take(TERMS_PER_TOPIC)
+        .map(|(term, _)| term.to_string())
+        .collect()
+}
+
+fn truncate_quote(text: &str) -> String {
+    if text.chars().count() <= MAX_QUOTE_CHARS {
+        return text.to_string();
+    }
+    let cut: String = text.chars().take(MAX_QUOTE_CHARS - 1).collect();
+    format!("{}…", cut.trim_end())
+}
+
+/// The report as markdown.
+pub fn render_markdown(report: &TopicsReport) -> String {
+    let range = match (&report.from, &report.to) {
+        (Some(from), Some(to)) if from == to => from.clone(),
+        (Some(from), Some(to)) => format!("{from} to {to}"),
+        (Some(from), None) => format!("since {from}"),
+        (None, Some(to)) => format!("through {to}"),
+        (None, None) => "all sessions".to_string(),
+    };
+    let mut out = format!("# Topics: {range}\n\n");
+    if report.topics.is_empty() {
+        out.push_str("No indexed sessions in range (run `cassio index` first).\n");
+        return out;
+    }
+    out.push_str(&format!(
+        "{} session{} in {} topic{}.\n",
+        report.sessions,
+        if report.sessions == 1 { "" } else { "s" },
+        report.topics.len(),
+        if report.topics.len() == 1 { "" } else { "s" },
+    ));
+    for topic in &report.topics {
+        let title = if topic.terms.is_empty() {
+            "untitled".to_string()
+        } else {
+            topic
+                .terms
+                .iter()
+                .take(3)
+                .cloned()
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        out.push_str(&format!(
+            "\n## {}. {} ({} session{})\n\n",
+            topic.id,
+            title,
+            topic.sessions.len(),
+            if topic.sessions.len() == 1 { "" } else { "s" },
+        ));
+        if !topic.terms.is_empty() {
+            out.push_str(&format!("Terms: {}\n\n", topic.terms.join(", ")));
+        }
+        for quote in &topic.quotes {
+            out.push_str(&format!("> {}\n> — `{}`\n\n", quote.text, quote.path));
+        }
+        for session in &topic.sessions {
+            let mut line = format!("- {:.3}", session.score);
+            if let Some(date) = &session.date {
+                line.push_str(&format!(" {date}"));
+            }
+            if let Some(tool) = &session.tool {
+                line.push_str(&format!(" {tool}"));
+            }
+            line.push_str(&format!(" `{}`", session.path));
+            if let Some(project) = &session.project {
+                line.push_str(&format!(" ({project})"));
+            }
+            out.push_str(&line);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+#[path = "topics_test.rs"]
+mod tests;
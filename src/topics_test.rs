@@ -0,0 +1,137 @@
+use super::*;
+use crate::index::encode_embedding;
+use rusqlite::{Connection, params};
+
+fn temp_root(label: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("cassio_topics_{label}_{}", std::process::id()))
+}
+
+/// An archive of `(source_path, embedding, transcript)` sessions and their index.
+fn write_archive(root: &Path, sessions: &[(&str, [f32; 2], &str)]) -> std::path::PathBuf {
+    let _ = fs::remove_dir_all(root);
+    fs::create_dir_all(root).unwrap();
+    let index_path = root.join("index.sqlite");
+    let conn = Connection::open(&index_path).unwrap();
+    conn.execute_batch(
+        "CREATE TABLE chunks (
+            source_path TEXT, artifact TEXT, embedding BLOB,
+            speakers TEXT, project TEXT, tool TEXT, model TEXT, date TEXT
+        );",
+    )
+    .unwrap();
+    for (source_path, embedding, transcript) in sessions {
+        let date = &source_path[8..18];
+        conn.execute(
+            "INSERT INTO chunks VALUES (?1, 'session', ?2, 'user', NULL, 'codex', NULL, ?3)",
+            params![source_path, encode_embedding(embedding), date],
+        )
+        .unwrap();
+        let path = root.join(source_path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, transcript).unwrap();
+    }
+    index_path
+}
+
+fn archive(root: &Path) -> std::path::PathBuf {
+    write_archive(
+        root,
+        &[
+            (
+                "2026-04/2026-04-01T09-00-00-codex.md",
+                [1.0, 0.0],
+                "📋 Session: a\n👤 fix\n👤 the launchd plist keeps unloading after sleep\n🤖 launchd throttles the job\n",
+            ),
+            (
+                "2026-04/2026-04-02T09-00-00-codex.md",
+                [0.95, 0.1],
+                "📋 Session: b\n👤 launchd job stopped syncing overnight\n✅ Bash: zeppelin zeppelin\n",
+            ),
+            (
+                "2026-04/2026-04-03T09-00-00-codex.md",
+                [0.0, 1.0],
+                "📋 Session: c\n👤 sketch the invoice export for billing\n🤖 invoice rows\n",
+            ),
+            (
+                "2026-04/2026-04-04T09-00-00-codex.md",
+                [0.1, 0.95],
+                "📋 Session: d\n👤 invoice totals are off by one cent\n",
+            ),
+            (
+                "2026-05/2026-05-01T09-00-00-codex.md",
+                [0.7, 0.7],
+                "📋 Session: e\n👤 out of range\n",
+            ),
+        ],
+    )
+}
+
+#[test]
+fn clusters_sessions_and_labels_each_topic() {
+    let root = temp_root("clusters");
+    let index_path = archive(&root);
+
+    let report = build_topics(
+        &root,
+        &index_path,
+        Some("2026-04"),
+        Some("2026-04"),
+        Some(2),
+    )
+    .unwrap();
+    assert_eq!(report.sessions, 4, "May is out of range");
+    assert_eq!(report.topics.len(), 2);
+    let launchd = report
+        .topics
+        .iter()
+        .find(|topic| topic.terms.contains(&"launchd".to_string()))
+        .expect("a launchd topic");
+    let paths: Vec<&str> = launchd.sessions.iter().map(|s| s.path.as_str()).collect();
+    assert_eq!(
+        paths,
+        vec![
+            "2026-04/2026-04-01T09-00-00-codex.md",
+            "2026-04/2026-04-02T09-00-00-codex.md"
+        ]
+    );
+    // Tool output does not label topics.
+    assert_eq!(launchd.terms, vec!["job", "launchd"]);
+    // Short messages yield to longer ones.
+    assert_eq!(
+        launchd.quotes[0].text,
+        "the launchd plist keeps unloading after sleep"
+    );
+    let invoice = report.topics.iter().find(|t| t.id != launchd.id).unwrap();
+    assert_eq!(invoice.terms[0], "invoice");
+    assert_eq!(invoice.quotes.len(), 2);
+
+    let markdown = render_markdown(&report);
+    assert!(markdown.starts_with("# Topics: 2026-04\n\n4 sessions in 2 topics.\n"));
+    assert!(markdown.contains("> the launchd plist keeps unloading after sleep\n"));
+    assert!(markdown.contains(" 2026-04-03 codex `2026-04/2026-04-03T09-00-00-codex.md`\n"));
+
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn default_topic_count_scales_with_sessions() {
+    assert_eq!(default_topic_count(1), 1);
+    assert_eq!(default_topic_count(8), 2);
+    assert_eq!(default_topic_count(200), 10);
+    assert_eq!(default_topic_count(10_000), MAX_TOPICS);
+}
+
+#[test]
+fn empty_range_reports_no_topics() {
+    let root = temp_root("empty");
+    let index_path = archive(&root);
+
+    let report = build_topics(&root, &index_path, Some("2025-01"), None, None).unwrap();
+    assert_eq!(report.sessions, 5);
+    let report = build_topics(&root, &index_path, None, Some("2025-01"), None).unwrap();
+    assert!(report.topics.is_empty());
+    assert!(render_markdown(&report).contains("No indexed sessions in range"));
+    assert!(build_topics(&root, &index_path, None, None, Some(0)).is_err());
+
+    let _ = fs::remove_dir_all(&root);
+}
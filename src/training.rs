@@ -6,6 +6,8 @@
 //! training metadata without re-parsing raw tool logs.
 
 use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -13,6 +15,7 @@ use serde_json::Value;
 use sha2::{Digest, Sha256};
 
 use crate::ast::{Session, SessionStats, TokenUsage};
+use crate::error::CassioError;
 
 pub const TRAINING_SCHEMA_VERSION: &str = "training_session.v1";
 pub const SANITIZATION_POLICY_VERSION: &str = "sanitization.v1";
@@ -47,6 +50,21 @@ pub struct TrainingSource {
     pub source_format: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub source_root: Option<String>,
+    /// Other source logs holding the same conversation (or a prefix of it)
+    /// that batch mode skipped in favor of this one.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub duplicates: Vec<TrainingDuplicate>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrainingDuplicate {
+    pub tool: String,
+    pub source_path: String,
+    pub session_id: String,
+    /// `exact` or `prefix` (see `fingerprint::DuplicateKind`).
+    pub kind: String,
+    /// Fingerprinted messages in the duplicate copy.
+    pub messages: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -218,6 +236,35 @@ where
     format!("sha256:{:x}", hasher.finalize())
 }
 
+/// Add `duplicates` to the `source.duplicates` list of an already written
+/// `*.training.json`, skipping source paths it already lists.
+pub fn append_duplicates(path: &Path, duplicates: &[TrainingDuplicate]) -> Result<(), CassioError> {
+    let content = fs::read_to_string(path)?;
+    let mut training: TrainingSession =
+        serde_json::from_str(&content).map_err(|source| CassioError::Json {
+            path: path.to_path_buf(),
+            source,
+        })?;
+    let before = training.source.duplicates.len();
+    for duplicate in duplicates {
+        if !training
+            .source
+            .duplicates
+            .iter()
+            .any(|existing| existing.source_path == duplicate.source_path)
+        {
+            training.source.duplicates.push(duplicate.clone());
+        }
+    }
+    if training.source.duplicates.len() == before {
+        return Ok(());
+    }
+    let mut json = serde_json::to_string_pretty(&training)?;
+    json.push('\n');
+    fs::write(path, json)?;
+    Ok(())
+}
+
 fn push_unique(values: &mut Vec<String>, value: &str) {
     if !values.iter().any(|existing| existing == value) {
         values.push(value.to_string());
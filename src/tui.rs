@@ -0,0 +1,808 @@
+//! Full-screen terminal browser for the archive (`cassio tui`).
+//!
+//! The left pane lists every transcript, newest first, with the date, tool,
+//! project, kind, and estimated cost that `cassio summary` derives from it. The
+//! list is narrowed by a filter line (`f`): bare words match any column, and
+//! `date:`, `tool:`, `project:`, `kind:`, `cost>N`, and `cost<N` narrow one.
+//! The right pane shows the selected transcript, or the daily (`d`), weekly
+//! (`w`), or monthly (`m`) summary for its date.
+//!
+//! Runs of consecutive tool lines (✅, ❌, ⏳, with any 🔧 input and indented
+//! failure output) are folded into one line such as `▸ 14 tool calls, 2
+//! failed`, and thinking blocks (💭, present with `--show-thinking`) into
+//! `▸ thinking, 3 lines`; `z` expands them all. Search (`/`) is
+//! incremental: each keystroke re-highlights the matches and moves to the
+//! first one below the top of the pane, and `n`/`N` step through them. A folded
+//! run that contains a match is shown expanded while the search is active.
+//!
+//! # TRADE-OFFS
+//!
+//! - The list is built by reading every transcript once at startup, the same
+//!   scan `cassio summary` does. Nothing is watched; restart to pick up new
+//!   sessions.
+//! - A run ends at the next line that starts with a transcript emoji, so a
+//!   multi-line thinking block or failure excerpt folds with the line that
+//!   opened it. Text inside a message that happens to start with one of those
+//!   emoji ends the run early.
+//! - Search is a case-insensitive substring match for ASCII letters, with no
+//!   regex support. `cassio search` covers the archive-wide cases.
+//! - Folding is all-or-nothing rather than per run; a single key keeps the
+//!   keymap small.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDate;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+
+use crate::archive::{daily_summary_path, monthly_summary_path, weekly_summary_path};
+use crate::error::CassioError;
+use crate::formatter::emoji_text::{
+    EMOJI_ASSISTANT, EMOJI_FAILURE, EMOJI_META, EMOJI_QUEUE, EMOJI_SUCCESS, EMOJI_THINKING,
+    EMOJI_TOOL_INPUT, EMOJI_USER,
+};
+use crate::metrics::iso_week_id;
+use crate::summary::{SessionRow, session_rows, shorten_project};
+
+/// Shortest run of tool lines worth folding; thinking always folds.
+const MIN_FOLD_RUN: usize = 2;
+const HELP: &str = "q quit  Tab focus  f filter  / search  n/N next/prev  z fold  \
+                    d/w/m daily/weekly/monthly  t transcript";
+
+/// Browse the archive at `root` until the user quits.
+pub fn run_tui(root: &Path) -> Result<(), CassioError> {
+    let rows = session_rows(root)?;
+    if rows.is_empty() {
+        return Err(CassioError::Other(format!(
+            "No transcripts found under {}",
+            root.display()
+        )));
+    }
+    let mut app = App::new(root.to_path_buf(), rows);
+    let mut terminal = ratatui::try_init()?;
+    let result = event_loop(&mut terminal, &mut app);
+    ratatui::restore();
+    result
+}
+
+fn event_loop(terminal: &mut DefaultTerminal, app: &mut App) -> Result<(), CassioError> {
+    loop {
+        terminal.draw(|frame| app.render(frame))?;
+        if let Event::Key(key) = event::read()?
+            && key.kind == KeyEventKind::Press
+            && app.handle_key(key)
+        {
+            return Ok(());
+        }
+    }
+}
+
+/// Session list filter parsed from the filter line.
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct SessionFilter {
+    date: Option<String>,
+    tool: Option<String>,
+    project: Option<String>,
+    kind: Option<String>,
+    min_cost: Option<f64>,
+    max_cost: Option<f64>,
+    /// Bare words; each must appear in some column.
+    words: Vec<String>,
+}
+
+impl SessionFilter {
+    pub(crate) fn parse(input: &str) -> Self {
+        let mut filter = Self::default();
+        for term in input.split_whitespace() {
+            let term = term.to_lowercase();
+            if let Some(cost) = cost_bound(&term, "cost>") {
+                filter.min_cost = Some(cost);
+                continue;
+            }
+            if let Some(cost) = cost_bound(&term, "cost<") {
+                filter.max_cost = Some(cost);
+                continue;
+            }
+            let field = match term.split_once(':') {
+                Some(("date", _)) => &mut filter.date,
+                Some(("tool", _)) => &mut filter.tool,
+                Some(("project", _)) => &mut filter.project,
+                Some(("kind", _)) => &mut filter.kind,
+                _ => {
+                    filter.words.push(term);
+                    continue;
+                }
+            };
+            *field = term
+                .split_once(':')
+                .map(|(_, value)| value.to_string())
+                .filter(|value| !value.is_empty());
+        }
+        filter
+    }
+
+    pub(crate) fn matches(&self, row: &SessionRow) -> bool {
+        let tool = row.tool.to_lowercase();
+        let project = row.project.to_lowercase();
+        let contains = |field: &Option<String>, value: &str| {
+            field
+                .as_ref()
+                .is_none_or(|want| value.contains(want.as_str()))
+        };
+        self.date
+            .as_ref()
+            .is_none_or(|want| row.date.starts_with(want.as_str()))
+            && contains(&self.tool, &tool)
+            && contains(&self.project, &project)
+            && self
+                .kind
+                .as_ref()
+                .is_none_or(|want| row.kind.starts_with(want.as_str()))
+            && self
+                .min_cost
+                .is_none_or(|min| row.estimated_cost_usd >= min)
+            && self.max_cost.is_none_or(|max| row.estimated_cost_usd < max)
+            && self.words.iter().all(|word| {
+                row.date.contains(word.as_str())
+                    || tool.contains(word.as_str())
+                    || project.contains(word.as_str())
+                    || row.kind.contains(word.as_str())
+            })
+    }
+}
+
+fn cost_bound(term: &str, prefix: &str) -> Option<f64> {
+    term.strip_prefix(prefix)?
+        .trim_start_matches('$')
+        .parse()
+        .ok()
+}
+
+/// A line of the transcript pane: a transcript line, or a folded run of
+/// `hidden` tool or thinking lines.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct DisplayLine {
+    pub(crate) text: String,
+    pub(crate) hidden: usize,
+}
+
+/// `lines` as shown in the pane. With `fold`, runs of tool lines and thinking
+/// blocks collapse to a count, except runs containing a match for `query`.
+pub(crate) fn display_lines(lines: &[String], fold: bool, query: &str) -> Vec<DisplayLine> {
+    let mut out = Vec::new();
+    let mut index = 0;
+    while index < lines.len() {
+        let Some(kind) = fold_kind(&lines[index]) else {
+            out.push(DisplayLine {
+                text: lines[index].clone(),
+                hidden: 0,
+            });
+            index += 1;
+            continue;
+        };
+        let run = 1 + lines[index + 1..]
+            .iter()
+            .take_while(|line| match fold_kind(line) {
+                Some(next) => next == kind,
+                None => !line.trim().is_empty() && !is_marked(line),
+            })
+            .count();
+        let lines_in_run = &lines[index..index + run];
+        let calls = lines_in_run
+            .iter()
+            .filter(|line| is_tool_line(line))
+            .count();
+        let worth_folding = match kind {
+            FoldKind::Tool => run >= MIN_FOLD_RUN,
+            FoldKind::Thinking => true,
+        };
+        if fold
+            && worth_folding
+            && !lines_in_run
+                .iter()
+                .any(|line| !match_ranges(line, query).is_empty())
+        {
+            let failed = lines_in_run
+                .iter()
+                .filter(|line| line.starts_with(EMOJI_FAILURE))
+                .count();
+            let text = match (kind, failed) {
+                (FoldKind::Thinking, _) => format!("▸ thinking, {run} {}", plural(run, "line")),
+                (FoldKind::Tool, 0) => format!("▸ {calls} {}", plural(calls, "tool call")),
+                (FoldKind::Tool, _) => {
+                    format!("▸ {calls} {}, {failed} failed", plural(calls, "tool call"))
+                }
+            };
+            out.push(DisplayLine { text, hidden: run });
+        } else {
+            out.extend(lines_in_run.iter().map(|line| DisplayLine {
+                text: line.clone(),
+                hidden: 0,
+            }));
+        }
+        index += run;
+    }
+    out
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FoldKind {
+    Tool,
+    Thinking,
+}
+
+/// The kind of foldable run a line opens or continues, if any.
+fn fold_kind(line: &str) -> Option<FoldKind> {
+    if is_tool_line(line) || line.starts_with(EMOJI_TOOL_INPUT) {
+        Some(FoldKind::Tool)
+    } else if line.starts_with(EMOJI_THINKING) {
+        Some(FoldKind::Thinking)
+    } else {
+        None
+    }
+}
+
+fn is_tool_line(line: &str) -> bool {
+    [EMOJI_SUCCESS, EMOJI_FAILURE, EMOJI_QUEUE]
+        .iter()
+        .any(|emoji| line.starts_with(emoji))
+}
+
+/// True for lines that start a new transcript entry; other lines continue the
+/// entry above (multi-line messages, thinking, and failure excerpts).
+fn is_marked(line: &str) -> bool {
+    [EMOJI_USER, EMOJI_ASSISTANT, EMOJI_META]
+        .iter()
+        .any(|emoji| line.starts_with(emoji))
+        || fold_kind(line).is_some()
+}
+
+fn plural(count: usize, noun: &str) -> String {
+    if count == 1 {
+        noun.to_string()
+    } else {
+        format!("{noun}s")
+    }
+}
+
+/// Byte ranges of non-overlapping matches of `query` in `text`, ignoring ASCII
+/// case.
+pub(crate) fn match_ranges(text: &str, query: &str) -> Vec<(usize, usize)> {
+    let needle = query.as_bytes();
+    let haystack = text.as_bytes();
+    let mut ranges = Vec::new();
+    if needle.is_empty() {
+        return ranges;
+    }
+    let mut start = 0;
+    while start + needle.len() <= haystack.len() {
+        let end = start + needle.len();
+        if text.is_char_boundary(start) && haystack[start..end].eq_ignore_ascii_case(needle) {
+            ranges.push((start, end));
+            start = end;
+        } else {
+            start += 1;
+        }
+    }
+    ranges
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Focus {
+    List,
+    Pane,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Input {
+    None,
+    Filter,
+    Search,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SummaryKind {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+pub(crate) struct App {
+    root: PathBuf,
+    rows: Vec<SessionRow>,
+    /// Indexes into `rows` that pass the filter.
+    visible: Vec<usize>,
+    list: ListState,
+    pub(crate) focus: Focus,
+    pub(crate) input: Input,
+    pub(crate) filter: String,
+    pub(crate) query: String,
+    pub(crate) folded: bool,
+    pub(crate) title: String,
+    /// Raw lines of the transcript or summary being shown.
+    lines: Vec<String>,
+    pub(crate) display: Vec<DisplayLine>,
+    /// Indexes into `display` of lines matching `query`.
+    pub(crate) matches: Vec<usize>,
+    pub(crate) current_match: usize,
+    /// Scroll position in wrapped rows.
+    pub(crate) scroll: usize,
+    /// Wrapped row at which each display line starts, plus the total; computed
+    /// for `pane_width`.
+    offsets: Option<Vec<usize>>,
+    pub(crate) pane_width: u16,
+    pub(crate) pane_height: u16,
+    pub(crate) status: String,
+}
+
+impl App {
+    pub(crate) fn new(root: PathBuf, rows: Vec<SessionRow>) -> Self {
+        let visible = (0..rows.len()).collect();
+        let mut app = Self {
+            root,
+            rows,
+            visible,
+            list: ListState::default().with_selected(Some(0)),
+            focus: Focus::List,
+            input: Input::None,
+            filter: String::new(),
+            query: String::new(),
+            folded: true,
+            title: String::new(),
+            lines: Vec::new(),
+            display: Vec::new(),
+            matches: Vec::new(),
+            current_match: 0,
+            scroll: 0,
+            offsets: None,
+            pane_width: 80,
+            pane_height: 20,
+            status: String::new(),
+        };
+        app.load_transcript();
+        app
+    }
+
+    fn selected_row(&self) -> Option<&SessionRow> {
+        let index = self.list.selected()?;
+        self.rows.get(*self.visible.get(index)?)
+    }
+
+    /// Handle one key press. Returns true when the user quits.
+    pub(crate) fn handle_key(&mut self, key: KeyEvent) -> bool {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return true;
+        }
+        match self.input {
+            Input::Filter => self.filter_key(key.code),
+            Input::Search => self.search_key(key.code),
+            Input::None => return self.normal_key(key.code),
+        }
+        false
+    }
+
+    fn normal_key(&mut self, code: KeyCode) -> bool {
+        self.status.clear();
+        let page = usize::from(self.pane_height.max(2) - 1);
+        match code {
+            KeyCode::Char('q') => return true,
+            KeyCode::Tab => {
+                self.focus = match self.focus {
+                    Focus::List => Focus::Pane,
+                    Focus::Pane => Focus::List,
+                }
+            }
+            KeyCode::Enter => self.focus = Focus::Pane,
+            KeyCode::Down | KeyCode::Char('j') => self.move_by(1, 1),
+            KeyCode::Up | KeyCode::Char('k') => self.move_by(-1, -1),
+            KeyCode::PageDown => self.move_by(page as isize, page as isize),
+            KeyCode::PageUp => self.move_by(-(page as isize), -(page as isize)),
+            KeyCode::Home | KeyCode::Char('g') => self.move_by(isize::MIN / 2, isize::MIN / 2),
+            KeyCode::End | KeyCode::Char('G') => self.move_by(isize::MAX / 2, isize::MAX / 2),
+            KeyCode::Char('f') => {
+                self.input = Input::Filter;
+                self.focus = Focus::List;
+            }
+            KeyCode::Char('/') => {
+                self.input = Input::Search;
+                self.focus = Focus::Pane;
+                self.query.clear();
+                self.refresh();
+            }
+            KeyCode::Char('n') => self.step_match(true),
+            KeyCode::Char('N') => self.step_match(false),
+            KeyCode::Char('z') => {
+                self.folded = !self.folded;
+                self.refresh();
+            }
+            KeyCode::Char('d') => self.show_summary(SummaryKind::Daily),
+            KeyCode::Char('w') => self.show_summary(SummaryKind::Weekly),
+            KeyCode::Char('m') => self.show_summary(SummaryKind::Monthly),
+            KeyCode::Char('t') => self.load_transcript(),
+            KeyCode::Esc => {
+                if !self.query.is_empty() {
+                    self.query.clear();
+                    self.refresh();
+                } else {
+                    self.focus = Focus::List;
+                }
+            }
+            _ => {}
+        }
+        false
+    }
+
+    fn filter_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Enter => self.input = Input::None,
+            KeyCode::Esc => {
+                self.input = Input::None;
+                self.filter.clear();
+                self.apply_filter();
+            }
+            KeyCode::Backspace => {
+                self.filter.pop();
+                self.apply_filter();
+            }
+            KeyCode::Char(c) => {
+                self.filter.push(c);
+                self.apply_filter();
+            }
+            _ => {}
+        }
+    }
+
+    fn search_key(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Enter => self.input = Input::None,
+            KeyCode::Esc => {
+                self.input = Input::None;
+                self.query.clear();
+                self.refresh();
+            }
+            KeyCode::Backspace => {
+                self.query.pop();
+                self.search_from_top();
+            }
+            KeyCode::Char(c) => {
+                self.query.push(c);
+                self.search_from_top();
+            }
+            _ => {}
+        }
+    }
+
+    /// Move the list selection by `list` or scroll the pane by `pane` rows,
+    /// depending on focus.
+    fn move_by(&mut self, list: isize, pane: isize) {
+        match self.focus {
+            Focus::List => {
+                let Some(last) = self.visible.len().checked_sub(1) else {
+                    return;
+                };
+                let current = self.list.selected().unwrap_or(0);
+                let next = current.saturating_add_signed(list).min(last);
+                if next != current {
+                    self.list.select(Some(next));
+                    self.load_transcript();
+                }
+            }
+            Focus::Pane => {
+                self.scroll = self.scroll.saturating_add_signed(pane);
+                self.clamp_scroll();
+            }
+        }
+    }
+
+    fn apply_filter(&mut self) {
+        let selected = self
+            .list
+            .selected()
+            .and_then(|i| self.visible.get(i).copied());
+        let filter = SessionFilter::parse(&self.filter);
+        self.visible = (0..self.rows.len())
+            .filter(|&i| filter.matches(&self.rows[i]))
+            .collect();
+        let position = selected.and_then(|row| self.visible.iter().position(|&i| i == row));
+        self.list.select(Some(position.unwrap_or(0)));
+        if position.is_none() {
+            self.load_transcript();
+        }
+    }
+
+    /// Show the selected session's transcript.
+    fn load_transcript(&mut self) {
+        let Some(row) = self.selected_row() else {
+            self.set_content("No matching sessions".to_string(), Vec::new());
+            return;
+        };
+        let path = row.path.clone();
+        let title = path
+            .strip_prefix(&self.root)
+            .unwrap_or(&path)
+            .to_string_lossy()
+            .replace('\\', "/");
+        let lines = match fs::read_to_string(&path) {
+            Ok(content) => content.lines().map(str::to_string).collect(),
+            Err(e) => vec![format!("Cannot read {}: {e}", path.display())],
+        };
+        self.set_content(title, lines);
+    }
+
+    /// Show the daily, weekly, or monthly summary for the selected session's
+    /// date.
+    fn show_summary(&mut self, kind: SummaryKind) {
+        let Some(date) = self.selected_row().map(|row| row.date.clone()) else {
+            return;
+        };
+        let (label, period, path, command) = match kind {
+            SummaryKind::Daily => (
+                "Daily",
+                date.clone(),
+                daily_summary_path(&self.root, &date),
+                "dailies",
+            ),
+            SummaryKind::Weekly => {
+                let week = NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+                    .map(iso_week_id)
+                    .unwrap_or_default();
+                let path = weekly_summary_path(&self.root, &week);
+                ("Weekly", week, path, "weeklies")
+            }
+            SummaryKind::Monthly => {
+                let month = date.get(..7).unwrap_or_default().to_string();
+                let path = monthly_summary_path(&self.root, &month);
+                ("Monthly", month, path, "monthly")
+            }
+        };
+        let Some(path) = path else {
+            self.status = format!(
+                "No {} summary for {period} (run `cassio compact {command}`)",
+                label.to_lowercase()
+            );
+            return;
+        };
+        let lines = match fs::read_to_string(&path) {
+            Ok(content) => content.lines().map(str::to_string).collect(),
+            Err(e) => vec![format!("Cannot read {}: {e}", path.display())],
+        };
+        self.set_content(format!("{label} summary {period}"), lines);
+        self.focus = Focus::Pane;
+    }
+
+    fn set_content(&mut self, title: String, lines: Vec<String>) {
+        self.title = title;
+        self.lines = lines;
+        self.scroll = 0;
+        self.refresh();
+    }
+
+    /// Recompute display lines and matches after the content, fold state, or
+    /// query changed.
+    fn refresh(&mut self) {
+        self.display = display_lines(&self.lines, self.folded, &self.query);
+        self.matches = self
+            .display
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| !match_ranges(&line.text, &self.query).is_empty())
+            .map(|(index, _)| index)
+            .collect();
+        self.current_match = 0;
+        self.offsets = None;
+        self.clamp_scroll();
+    }
+
+    /// Re-run the search and move to the first match at or below the top of
+    /// the pane, wrapping to the first match.
+    fn search_from_top(&mut self) {
+        let top = self.scroll;
+        self.refresh();
+        let offsets = self.offsets().to_vec();
+        let first_below = self
+            .matches
+            .iter()
+            .position(|&line| offsets[line] >= top)
+            .unwrap_or(0);
+        self.jump_to_match(first_below);
+    }
+
+    fn step_match(&mut self, forward: bool) {
+        if self.matches.is_empty() {
+            if !self.query.is_empty() {
+                self.status = format!("No matches for \"{}\"", self.query);
+            }
+            return;
+        }
+        let count = self.matches.len();
+        let next = if forward {
+            (self.current_match + 1) % count
+        } else {
+            (self.current_match + count - 1) % count
+        };
+        self.jump_to_match(next);
+    }
+
+    fn jump_to_match(&mut self, index: usize) {
+        let Some(&line) = self.matches.get(index) else {
+            return;
+        };
+        self.current_match = index;
+        // Keep a couple of rows of context above the match.
+        self.scroll = self.row_of(line).saturating_sub(2);
+        self.clamp_scroll();
+    }
+
+    /// Wrapped row at which display line `line` starts.
+    pub(crate) fn row_of(&mut self, line: usize) -> usize {
+        let offsets = self.offsets();
+        offsets[line.min(offsets.len() - 1)]
+    }
+
+    fn offsets(&mut self) -> &[usize] {
+        let width = self.pane_width;
+        let display = &self.display;
+        self.offsets.get_or_insert_with(|| {
+            let mut offsets = Vec::with_capacity(display.len() + 1);
+            let mut row = 0;
+            offsets.push(row);
+            for line in display {
+                row += Paragraph::new(line.text.as_str())
+                    .wrap(Wrap { trim: false })
+                    .line_count(width.max(1))
+                    .max(1);
+                offsets.push(row);
+            }
+            offsets
+        })
+    }
+
+    fn clamp_scroll(&mut self) {
+        let height = usize::from(self.pane_height);
+        let total = self.row_of(usize::MAX);
+        self.scroll = self.scroll.min(total.saturating_sub(height));
+    }
+
+    pub(crate) fn render(&mut self, frame: &mut Frame) {
+        let [main, status] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+        let [list_area, pane_area] =
+            Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)])
+                .areas(main);
+        self.render_list(frame, list_area);
+        self.render_pane(frame, pane_area);
+        frame.render_widget(Paragraph::new(self.status_line()), status);
+    }
+
+    fn render_list(&mut self, frame: &mut Frame, area: Rect) {
+        let items: Vec<ListItem> = self
+            .visible
+            .iter()
+            .map(|&i| {
+                let row = &self.rows[i];
+                ListItem::new(format!(
+                    "{} {:<8} {:<11} {:>7} {}",
+                    row.date,
+                    row.tool,
+                    row.kind,
+                    format!("${:.2}", row.estimated_cost_usd),
+                    shorten_project(&row.project)
+                ))
+            })
+            .collect();
+        let mut title = format!(" Sessions {}/{} ", self.visible.len(), self.rows.len());
+        if !self.filter.is_empty() {
+            title.push_str(&format!("[{}] ", self.filter));
+        }
+        let list = List::new(items)
+            .block(pane_block(title, self.focus == Focus::List))
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, area, &mut self.list);
+    }
+
+    fn render_pane(&mut self, frame: &mut Frame, area: Rect) {
+        let width = area.width.saturating_sub(2);
+        if width != self.pane_width {
+            self.pane_width = width;
+            self.offsets = None;
+        }
+        self.pane_height = area.height.saturating_sub(2);
+        self.clamp_scroll();
+
+        // Render from the first line on screen so long transcripts stay within
+        // the widget's u16 scroll offset.
+        let offsets = self.offsets().to_vec();
+        let first = offsets
+            .partition_point(|&row| row <= self.scroll)
+            .saturating_sub(1)
+            .min(self.display.len());
+        let skip = self.scroll - offsets[first];
+        let current = self.matches.get(self.current_match).copied();
+        let lines: Vec<Line> = self.display[first..]
+            .iter()
+            .enumerate()
+            .take(usize::from(self.pane_height) + skip)
+            .map(|(i, line)| styled_line(line, &self.query, current == Some(first + i)))
+            .collect();
+
+        let mut title = format!(" {} ", self.title);
+        if !self.query.is_empty() {
+            let position = if self.matches.is_empty() {
+                0
+            } else {
+                self.current_match + 1
+            };
+            title.push_str(&format!(
+                "/{} {position}/{} ",
+                self.query,
+                self.matches.len()
+            ));
+        }
+        if !self.folded {
+            title.push_str("[unfolded] ");
+        }
+        let paragraph = Paragraph::new(lines)
+            .block(pane_block(title, self.focus == Focus::Pane))
+            .wrap(Wrap { trim: false })
+            .scroll((u16::try_from(skip).unwrap_or(u16::MAX), 0));
+        frame.render_widget(paragraph, area);
+    }
+
+    fn status_line(&self) -> String {
+        match self.input {
+            Input::Filter => format!("filter: {}▏", self.filter),
+            Input::Search => format!("/{}▏", self.query),
+            Input::None if !self.status.is_empty() => self.status.clone(),
+            Input::None => HELP.to_string(),
+        }
+    }
+}
+
+fn pane_block(title: String, focused: bool) -> Block<'static> {
+    let style = if focused {
+        Style::new().fg(Color::Cyan)
+    } else {
+        Style::new()
+    };
+    Block::bordered().title(title).border_style(style)
+}
+
+fn styled_line<'a>(line: &'a DisplayLine, query: &str, current: bool) -> Line<'a> {
+    let text = line.text.as_str();
+    let base = if line.hidden > 0 {
+        Style::new()
+            .fg(Color::DarkGray)
+            .add_modifier(Modifier::ITALIC)
+    } else if text.starts_with(EMOJI_USER) {
+        Style::new().add_modifier(Modifier::BOLD)
+    } else if text.starts_with(EMOJI_FAILURE) {
+        Style::new().fg(Color::Red)
+    } else if text.starts_with(EMOJI_META) {
+        Style::new().fg(Color::DarkGray)
+    } else {
+        Style::new()
+    };
+    let highlight = if current {
+        Style::new().fg(Color::Black).bg(Color::LightRed)
+    } else {
+        Style::new().fg(Color::Black).bg(Color::Yellow)
+    };
+    let mut spans = Vec::new();
+    let mut end = 0;
+    for (start, stop) in match_ranges(text, query) {
+        spans.push(Span::styled(&text[end..start], base));
+        spans.push(Span::styled(&text[start..stop], highlight));
+        end = stop;
+    }
+    spans.push(Span::styled(&text[end..], base));
+    Line::from(spans)
+}
+
+#[cfg(test)]
+#[path = "tui_test.rs"]
+mod tests;
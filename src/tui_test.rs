@@ -0,0 +1,178 @@
+use super::*;
+use ratatui::Terminal;
+use ratatui::backend::TestBackend;
+
+fn temp_root(label: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("cassio_tui_{label}_{}", std::process::id()))
+}
+
+fn row(date: &str, tool: &str, project: &str, kind: &'static str, cost: f64) -> SessionRow {
+    SessionRow {
+        path: PathBuf::from(format!("{}/{date}T09-00-00-{tool}.md", &date[..7])),
+        date: date.to_string(),
+        tool: tool.to_string(),
+        project: project.to_string(),
+        model: None,
+        kind,
+        estimated_cost_usd: cost,
+    }
+}
+
+fn lines(text: &str) -> Vec<String> {
+    text.lines().map(str::to_string).collect()
+}
+
+fn type_keys(app: &mut App, keys: &str) {
+    for c in keys.chars() {
+        app.handle_key(KeyEvent::from(KeyCode::Char(c)));
+    }
+}
+
+#[test]
+fn filter_narrows_by_field_cost_and_bare_words() {
+    let codex = row("2026-04-01", "codex", "/work/faber", "interactive", 0.4);
+    let claude = row("2026-05-02", "claude", "/work/cassio", "agentic", 3.5);
+
+    let filter = SessionFilter::parse("tool:Codex date:2026-04");
+    assert!(filter.matches(&codex));
+    assert!(!filter.matches(&claude));
+
+    let filter = SessionFilter::parse("cost>1 kind:agent");
+    assert!(!filter.matches(&codex));
+    assert!(filter.matches(&claude));
+    assert!(!SessionFilter::parse("cost<$1 cassio").matches(&claude));
+
+    let filter = SessionFilter::parse("faber");
+    assert!(filter.matches(&codex));
+    assert!(!filter.matches(&claude));
+    assert!(SessionFilter::parse("tool:").matches(&claude));
+}
+
+#[test]
+fn tool_runs_fold_unless_they_hold_a_search_match() {
+    let transcript = lines(
+        "👤 fix the build\n✅ Bash: cargo build\n❌ Bash: cargo test\n✅ Read: src/lib.rs\n\
+         🤖 fixed\n✅ Bash: git status",
+    );
+    let folded = display_lines(&transcript, true, "");
+    let texts: Vec<&str> = folded.iter().map(|line| line.text.as_str()).collect();
+    assert_eq!(
+        texts,
+        vec![
+            "👤 fix the build",
+            "▸ 3 tool calls, 1 failed",
+            "🤖 fixed",
+            "✅ Bash: git status",
+        ]
+    );
+    assert_eq!(folded[1].hidden, 3);
+
+    assert_eq!(display_lines(&transcript, false, "").len(), 6);
+    assert_eq!(display_lines(&transcript, true, "LIB.RS").len(), 6);
+}
+
+#[test]
+fn matches_ignore_ascii_case_and_respect_char_boundaries() {
+    assert_eq!(
+        match_ranges("Cargo cargo CARGO", "cargo"),
+        vec![(0, 5), (6, 11), (12, 17)]
+    );
+    assert_eq!(match_ranges("🤖 ran cargo", "cargo"), vec![(9, 14)]);
+    assert_eq!(match_ranges("aaa", "aa"), vec![(0, 2)]);
+    assert!(match_ranges("anything", "").is_empty());
+}
+
+#[test]
+fn keys_filter_search_and_open_summaries() {
+    let root = temp_root("app");
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("2026-04")).unwrap();
+    let filler: String = (0..40).map(|n| format!("🤖 step {n}\n")).collect();
+    fs::write(
+        root.join("2026-04/2026-04-01T09-00-00-codex.md"),
+        format!("📋 Project: /work/faber\n👤 start\n{filler}👤 the launchd needle\n"),
+    )
+    .unwrap();
+    fs::write(
+        root.join("2026-04/2026-04-03T10-00-00-claude.md"),
+        "📋 Project: /work/cassio\n👤 hello\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("2026-04/2026-04-01.daily.md"),
+        "# Daily 2026-04-01\n",
+    )
+    .unwrap();
+
+    let mut app = App::new(root.clone(), session_rows(&root).unwrap());
+    assert_eq!(app.title, "2026-04/2026-04-03T10-00-00-claude.md");
+
+    type_keys(&mut app, "ftool:codex");
+    app.handle_key(KeyEvent::from(KeyCode::Enter));
+    assert_eq!(app.input, Input::None);
+    assert_eq!(app.title, "2026-04/2026-04-01T09-00-00-codex.md");
+
+    type_keys(&mut app, "/NEEDLE");
+    assert_eq!(app.focus, Focus::Pane);
+    assert_eq!(app.matches, vec![42]);
+    // 43 rows in a 20-row pane: the last screen, with the match in view.
+    assert_eq!(app.scroll, 23);
+    type_keys(&mut app, "X");
+    assert!(app.matches.is_empty());
+    app.handle_key(KeyEvent::from(KeyCode::Backspace));
+    assert_eq!(app.matches, vec![42]);
+
+    app.handle_key(KeyEvent::from(KeyCode::Enter));
+    type_keys(&mut app, "d");
+    assert_eq!(app.title, "Daily summary 2026-04-01");
+    type_keys(&mut app, "w");
+    assert_eq!(
+        app.status,
+        "No weekly summary for 2026-W14 (run `cassio compact weeklies`)"
+    );
+    type_keys(&mut app, "t");
+    assert_eq!(app.title, "2026-04/2026-04-01T09-00-00-codex.md");
+
+    let mut terminal = Terminal::new(TestBackend::new(100, 12)).unwrap();
+    terminal.draw(|frame| app.render(frame)).unwrap();
+    let screen: String = terminal
+        .backend()
+        .buffer()
+        .content()
+        .iter()
+        .map(|cell| cell.symbol())
+        .collect();
+    assert!(screen.contains("Sessions 1/2"));
+    assert!(screen.contains("2026-04-01 codex"));
+    assert!(screen.contains("q quit"));
+
+    assert!(app.handle_key(KeyEvent::from(KeyCode::Char('q'))));
+    let _ = fs::remove_dir_all(&root);
+}
+
+#[test]
+fn thinking_and_tool_input_fold_with_their_runs() {
+    let transcript = lines(
+        "👤 why is it slow\n💭 check the index\nthen the scan\n🤖 looking\n\
+         🔧 Bash: {\"command\":\"cargo test\"}\n❌ Bash: 1 failed\n   test it_runs ... FAILED\n\
+         \n🤖 done",
+    );
+    let folded = display_lines(&transcript, true, "");
+    let texts: Vec<&str> = folded.iter().map(|line| line.text.as_str()).collect();
+    assert_eq!(
+        texts,
+        vec![
+            "👤 why is it slow",
+            "▸ thinking, 2 lines",
+            "🤖 looking",
+            "▸ 1 tool call, 1 failed",
+            "",
+            "🤖 done",
+        ]
+    );
+    assert_eq!((folded[1].hidden, folded[3].hidden), (2, 3));
+    assert_eq!(
+        display_lines(&transcript, true, "scan")[1].text,
+        "💭 check the index"
+    );
+}
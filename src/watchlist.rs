@@ -0,0 +1,267 @@
+//! Saved searches and new-hit alerts (`cassio watchlist run`).
+//!
+//! Each `[[saved_search]]` entry is a `cassio search` query run over the whole
+//! archive. The hits seen by the previous run are kept per search in
+//! `.cassio/watchlist.json`, so a run reports only what is new: lines that
+//! appeared in a new or rewritten transcript since then. Batch runs check the
+//! watchlist after writing (and indexing) each archive.
+//!
+//! A hit is identified by its transcript's root-relative path and a hash of the
+//! matching line, not by line number, so a transcript that grows or gains a
+//! footer does not re-report its earlier hits.
+//!
+//! # TRADE-OFFS
+//!
+//! - The first run of a saved search reports every existing hit: there is no
+//!   "last run" to compare with, and a historical match of a critical query is
+//!   still worth seeing once.
+//! - Identical lines in one transcript collapse into one hit. Repeating a
+//!   matched line is not news.
+//! - The state only holds the current hits; a hit that disappears (its
+//!   transcript was deleted) and later reappears is reported again.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::config::SavedSearchConfig;
+use crate::error::CassioError;
+use crate::manifest::STATE_DIR;
+use crate::search::{SearchOptions, Speaker, search};
+
+const WATCHLIST_FILE: &str = "watchlist.json";
+/// New hits printed per saved search; the rest are counted.
+const PRINTED_HITS: usize = 20;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WatchlistReport {
+    pub searches: Vec<SavedSearchResult>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SavedSearchResult {
+    pub name: String,
+    pub query: String,
+    pub critical: bool,
+    /// Hits for the query across the archive.
+    pub total: usize,
+    /// Hits not seen by the previous run, in search order.
+    pub new_hits: Vec<WatchHit>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WatchHit {
+    /// Root-relative transcript path.
+    pub path: String,
+    pub line: usize,
+    pub text: String,
+}
+
+impl WatchlistReport {
+    /// Names of critical saved searches with new hits.
+    pub fn critical_matches(&self) -> Vec<&str> {
+        self.searches
+            .iter()
+            .filter(|search| search.critical && !search.new_hits.is_empty())
+            .map(|search| search.name.as_str())
+            .collect()
+    }
+
+    pub fn new_hit_count(&self) -> usize {
+        self.searches
+            .iter()
+            .map(|search| search.new_hits.len())
+            .sum()
+    }
+}
+
+/// Hit keys seen by the last run, per saved search name.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct WatchlistState {
+    #[serde(default)]
+    searches: BTreeMap<String, BTreeSet<String>>,
+}
+
+pub fn watchlist_path(root: &Path) -> PathBuf {
+    root.join(STATE_DIR).join(WATCHLIST_FILE)
+}
+
+/// Run every saved search over `root` and report the hits that are new since
+/// the last run. Unless `dry_run`, the current hits become the new baseline.
+pub fn run_watchlist(
+    root: &Path,
+    searches: &[SavedSearchConfig],
+    dry_run: bool,
+) -> Result<WatchlistReport, CassioError> {
+    let mut names = BTreeSet::new();
+    for saved in searches {
+        if saved.name.trim().is_empty() {
+            return Err(CassioError::Other(
+                "[[saved_search]] entries need a name".into(),
+            ));
+        }
+        if !names.insert(saved.name.as_str()) {
+            return Err(CassioError::Other(format!(
+                "Duplicate [[saved_search]] name '{}'",
+                saved.name
+            )));
+        }
+    }
+
+    let mut state = load_state(root)?;
+    let mut report = WatchlistReport {
+        searches: Vec::new(),
+    };
+    for saved in searches {
+        let options = search_options(saved)?;
+        let hits = search(root, &saved.query, &options)?;
+        let seen = state.searches.get(&saved.name);
+        let mut current = BTreeSet::new();
+        let mut new_hits = Vec::new();
+        for hit in hits {
+            let path = hit
+                .path
+                .strip_prefix(root)
+                .unwrap_or(&hit.path)
+                .to_string_lossy()
+                .replace('\\', "/");
+            let key = hit_key(&path, &hit.text);
+            if !current.insert(key.clone()) {
+                continue;
+            }
+            if !seen.is_some_and(|seen| seen.contains(&key)) {
+                new_hits.push(WatchHit {
+                    path,
+                    line: hit.line,
+                    text: hit.text,
+                });
+            }
+        }
+        report.searches.push(SavedSearchResult {
+            name: saved.name.clone(),
+            query: saved.query.clone(),
+            critical: saved.critical,
+            total: current.len(),
+            new_hits,
+        });
+        state.searches.insert(saved.name.clone(), current);
+    }
+    // Searches removed from the config drop their state.
+    state
+        .searches
+        .retain(|name, _| names.contains(name.as_str()));
+    if !dry_run {
+        save_state(root, &state)?;
+    }
+    Ok(report)
+}
+
+fn search_options(saved: &SavedSearchConfig) -> Result<SearchOptions, CassioError> {
+    let speaker = saved
+        .speaker
+        .as_deref()
+        .map(str::parse::<Speaker>)
+        .transpose()
+        .map_err(|e| CassioError::Other(format!("saved search '{}': {e}", saved.name)))?;
+    Ok(SearchOptions {
+        from: None,
+        to: None,
+        tool: saved.tool.clone(),
+        project: saved.project.clone(),
+        session_model: None,
+        speaker,
+        limit: usize::MAX,
+        summaries_only: false,
+        include_training: false,
+        include_paths: false,
+        json: false,
+        regex: saved.regex,
+        case_sensitive: saved.case_sensitive,
+        context: 0,
+        files_with_matches: false,
+        // Counting mode visits every file instead of stopping at the limit.
+        count: true,
+        oldest_first: false,
+        semantic: None,
+        training_root: None,
+        scan: false,
+        hybrid: false,
+        rerank_model: None,
+        sessions: false,
+    })
+}
+
+fn hit_key(path: &str, text: &str) -> String {
+    let digest = Sha256::digest(text.trim().as_bytes());
+    let hash: String = digest.iter().take(8).map(|b| format!("{b:02x}")).collect();
+    format!("{path}#{hash}")
+}
+
+fn load_state(root: &Path) -> Result<WatchlistState, CassioError> {
+    let path = watchlist_path(root);
+    if !path.exists() {
+        return Ok(WatchlistState::default());
+    }
+    let content = fs::read_to_string(&path)?;
+    serde_json::from_str(&content).map_err(|e| {
+        CassioError::Other(format!(
+            "Failed to parse watchlist state {}: {e}",
+            path.display()
+        ))
+    })
+}
+
+fn save_state(root: &Path, state: &WatchlistState) -> Result<(), CassioError> {
+    let path = watchlist_path(root);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut json = serde_json::to_string_pretty(state)?;
+    json.push('\n');
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, json)?;
+    fs::rename(&tmp, &path)?;
+    Ok(())
+}
+
+/// The report as text: one block per saved search with new hits.
+pub fn render_report(report: &WatchlistReport) -> String {
+    let mut out = String::new();
+    for search in &report.searches {
+        if search.new_hits.is_empty() {
+            continue;
+        }
+        out.push_str(&format!(
+            "watchlist: {} new hit{} for {}{} ({} total)\n",
+            search.new_hits.len(),
+            if search.new_hits.len() == 1 { "" } else { "s" },
+            search.name,
+            if search.critical { " [critical]" } else { "" },
+            search.total,
+        ));
+        for hit in search.new_hits.iter().take(PRINTED_HITS) {
+            out.push_str(&format!("  {}:{}: {}\n", hit.path, hit.line, hit.text));
+        }
+        if search.new_hits.len() > PRINTED_HITS {
+            out.push_str(&format!(
+                "  ... and {} more\n",
+                search.new_hits.len() - PRINTED_HITS
+            ));
+        }
+    }
+    if out.is_empty() {
+        out.push_str(&format!(
+            "watchlist: no new hits ({} saved search{})\n",
+            report.searches.len(),
+            if report.searches.len() == 1 { "" } else { "es" },
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+#[path = "watchlist_test.rs"]
+mod tests;
@@ -0,0 +1,103 @@
+//! Drives `cassio mcp` over stdio the way an MCP client does.
+
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use serde_json::{Value, json};
+
+fn archive() -> PathBuf {
+    let root = std::env::temp_dir().join(format!("cassio_mcp_stdio_{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(root.join("2026-04")).unwrap();
+    fs::write(
+        root.join("2026-04/2026-04-10T10-00-00-codex.md"),
+        "📋 Project: /home/u/acme\n\
+         👤 deploy with export AWS_SECRET_ACCESS_KEY=abcd1234abcd1234abcd1234abcd1234abcd1234\n\
+         🤖 deploy finished\n",
+    )
+    .unwrap();
+    fs::write(
+        root.join("2026-04/2026-04-10.daily.md"),
+        "# 2026-04-10\nshipped\n",
+    )
+    .unwrap();
+    root
+}
+
+#[test]
+fn serves_tools_over_stdio() {
+    let root = archive();
+    let mut child = Command::new(env!("CARGO_BIN_EXE_cassio"))
+        .arg("--detached")
+        .arg("-o")
+        .arg(&root)
+        .arg("mcp")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+
+    let requests = [
+        json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {
+            "protocolVersion": "2025-06-18",
+            "capabilities": {},
+            "clientInfo": {"name": "test", "version": "0"},
+        }}),
+        json!({"jsonrpc": "2.0", "method": "notifications/initialized"}),
+        json!({"jsonrpc": "2.0", "id": 2, "method": "tools/list"}),
+        json!({"jsonrpc": "2.0", "id": 3, "method": "tools/call", "params": {
+            "name": "search_transcripts",
+            "arguments": {"query": "deploy", "speaker": "user"},
+        }}),
+        json!({"jsonrpc": "2.0", "id": 4, "method": "tools/call", "params": {
+            "name": "get_daily",
+            "arguments": {"date": "2026-04-10"},
+        }}),
+        json!({"jsonrpc": "2.0", "id": 5, "method": "tools/call", "params": {
+            "name": "summary_stats",
+            "arguments": {},
+        }}),
+    ];
+    let mut stdin = child.stdin.take().unwrap();
+    for request in &requests {
+        writeln!(stdin, "{request}").unwrap();
+    }
+    drop(stdin);
+
+    let responses: Vec<Value> = BufReader::new(child.stdout.take().unwrap())
+        .lines()
+        .map(|line| serde_json::from_str(&line.unwrap()).unwrap())
+        .collect();
+    assert!(child.wait().unwrap().success());
+
+    // One response per request; the notification gets none.
+    let ids: Vec<i64> = responses
+        .iter()
+        .map(|r| r["id"].as_i64().unwrap())
+        .collect();
+    assert_eq!(ids, vec![1, 2, 3, 4, 5]);
+    assert_eq!(responses[0]["result"]["protocolVersion"], "2025-06-18");
+    assert_eq!(responses[1]["result"]["tools"].as_array().unwrap().len(), 6);
+
+    let text = |index: usize| {
+        responses[index]["result"]["content"][0]["text"]
+            .as_str()
+            .unwrap()
+    };
+    let search: Value = serde_json::from_str(text(2)).unwrap();
+    let hits = search["hits"].as_array().unwrap();
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0]["path"], "2026-04/2026-04-10T10-00-00-codex.md");
+    assert!(hits[0]["text"].as_str().unwrap().contains("[REDACTED]"));
+    assert!(!text(2).contains("abcd1234"));
+
+    assert_eq!(text(3), "# 2026-04-10\nshipped\n");
+    let stats: Value = serde_json::from_str(text(4)).unwrap();
+    assert_eq!(stats["totals"]["sessions"], 1);
+    assert_eq!(stats["months"][0]["tool"], "codex");
+
+    let _ = fs::remove_dir_all(&root);
+}